@@ -0,0 +1,2 @@
+-- Preferred language for emails (BCP 47 primary subtag, e.g. 'en', 'vi')
+ALTER TABLE users ADD COLUMN locale VARCHAR(10) NOT NULL DEFAULT 'en';
@@ -0,0 +1,13 @@
+-- Append-only record of administrative actions.
+-- No foreign keys: entries must outlive the users they mention.
+CREATE TABLE audit_logs (
+    id UUID PRIMARY KEY,
+    actor_id UUID,
+    action VARCHAR(100) NOT NULL,
+    target_id UUID,
+    details JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_audit_logs_target_id ON audit_logs (target_id);
+CREATE INDEX idx_audit_logs_created_at ON audit_logs (created_at);
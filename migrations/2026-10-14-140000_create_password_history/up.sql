@@ -0,0 +1,9 @@
+-- Previous Argon2 hashes per user, checked to stop recent passwords being reused
+CREATE TABLE password_history (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    password_hash TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_password_history_user_id_created_at ON password_history (user_id, created_at DESC);
@@ -0,0 +1,2 @@
+-- When the password was last set; NULL for accounts that never had one
+ALTER TABLE users ADD COLUMN password_changed_at TIMESTAMPTZ;
@@ -0,0 +1,4 @@
+-- Mobile number in E.164 form; codes are only texted once it is verified
+ALTER TABLE users
+    ADD COLUMN phone VARCHAR(20),
+    ADD COLUMN phone_verified BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1 @@
+ALTER TABLE users DROP COLUMN phone_code_expires_at, DROP COLUMN phone_code;
@@ -0,0 +1,4 @@
+-- Pending phone verification; stored like confirmation_code
+ALTER TABLE users
+    ADD COLUMN phone_code VARCHAR(64),
+    ADD COLUMN phone_code_expires_at TIMESTAMPTZ;
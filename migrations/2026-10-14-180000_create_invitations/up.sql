@@ -0,0 +1,12 @@
+-- Single-use signup invitations created by admins. Only a hash of the token
+-- is stored; the token itself is shown once, when the invitation is created.
+CREATE TABLE invitations (
+    id UUID PRIMARY KEY,
+    token_hash VARCHAR(64) NOT NULL UNIQUE,
+    role VARCHAR(20),
+    created_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    expires_at TIMESTAMPTZ NOT NULL,
+    used_at TIMESTAMPTZ,
+    used_by UUID REFERENCES users(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,2 @@
+-- Entries under legal hold are kept past the retention period
+ALTER TABLE audit_logs ADD COLUMN legal_hold BOOLEAN NOT NULL DEFAULT FALSE;
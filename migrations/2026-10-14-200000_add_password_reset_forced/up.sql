@@ -0,0 +1,3 @@
+-- Set by an admin-forced reset until the user next sets a password, which
+-- is then exempt from the minimum change interval
+ALTER TABLE users ADD COLUMN password_reset_forced BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,7 @@
+-- When each user was last warned that their account is about to be disabled
+-- for inactivity. Cleared when the user logs in or is disabled, so a row
+-- always belongs to the user's current idle spell.
+CREATE TABLE inactivity_warnings (
+    user_id UUID PRIMARY KEY REFERENCES users(id) ON DELETE CASCADE,
+    warned_at TIMESTAMPTZ NOT NULL
+);
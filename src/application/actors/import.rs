@@ -1,11 +1,13 @@
 // Import the AuthRepository trait which provides database operations for user management
-use crate::domain::repositories::AuthRepository;
+use crate::domain::{entities::User, repositories::AuthRepository, value_objects::UserRole};
+use crate::shared::i18n::Locale;
 // Import Ractor framework components:
 // - Actor: The base trait that all actors must implement
 // - ActorProcessingErr: Error type for actor processing failures
 // - ActorRef: A reference to an actor that can be used to send messages
 use ractor::{Actor, ActorProcessingErr, ActorRef};
 use std::sync::Arc;
+use tokio::sync::{mpsc::UnboundedSender, OwnedSemaphorePermit};
 
 /// UserCreationActor is responsible for creating a single user in the database.
 ///
@@ -23,6 +25,10 @@ pub struct UserCreationActor<R: AuthRepository + 'static> {
     /// Arc (Atomic Reference Counted) allows multiple actors to safely share
     /// the same repository instance without copying
     auth_repo: Arc<R>,
+    /// Where to report the created user, tagged with its CSV row
+    created: Option<(usize, UnboundedSender<(usize, User)>)>,
+    /// Import slot taken by this creation; released when the actor stops
+    permit: Option<OwnedSemaphorePermit>,
 }
 
 impl<R: AuthRepository + 'static> UserCreationActor<R> {
@@ -34,7 +40,21 @@ impl<R: AuthRepository + 'static> UserCreationActor<R> {
     /// # Returns
     /// A new actor instance ready to be spawned
     pub fn new(auth_repo: Arc<R>) -> Self {
-        Self { auth_repo }
+        Self { auth_repo, created: None, permit: None }
+    }
+
+    /// Hold `permit` for as long as the actor lives, so whoever limits
+    /// concurrent creations gets it back once this one is done, failed or not
+    pub fn holding(mut self, permit: OwnedSemaphorePermit) -> Self {
+        self.permit = Some(permit);
+        self
+    }
+
+    /// Send the user to `created` as `(row, user)` once it exists; nothing is
+    /// sent when the email was already taken
+    pub fn reporting_to(mut self, row: usize, created: UnboundedSender<(usize, User)>) -> Self {
+        self.created = Some((row, created));
+        self
     }
 }
 
@@ -52,6 +72,8 @@ pub struct UserCreationMsg {
     pub name: String,
     /// The pre-hashed password (hashing happens before sending the message)
     pub password_hash: String,
+    /// Role the user is created with
+    pub role: UserRole,
 }
 
 /// Implementation of the Actor trait for UserCreationActor
@@ -133,19 +155,28 @@ impl<R: AuthRepository + 'static> Actor for UserCreationActor<R> {
         if exists.is_none() {
             // Call the repository to create the user in the database
             // The password is already hashed, so we pass it directly
-            self.auth_repo
+            let user = self
+                .auth_repo
                 .create_user(
                     &msg.email,
                     &msg.name,
                     Some(msg.password_hash.clone()), // Pass existing option
                     None,                            // confirmation_code
                     None,                            // expires_at
+                    Locale::default().as_str(),
+                    msg.role,
                 )
                 .await
                 .map_err(|e| ActorProcessingErr::from(e.to_string()))?;
 
             // Log success for monitoring and debugging
             tracing::info!("Actor: Successfully created user: {}", msg.email);
+
+            // Hand the user back to whoever publishes the import's events;
+            // a closed channel only means nobody is listening
+            if let Some((row, created)) = &self.created {
+                let _ = created.send((*row, user));
+            }
         } else {
             // User already exists, skip creation and log it
             tracing::info!("Actor: User already exists: {}", msg.email);
@@ -0,0 +1,11 @@
+/// Authentication commands (write operations)
+///
+/// Commands that issue, rotate or revoke credentials and sessions.
+pub mod refresh;
+pub mod revoke_session;
+pub mod verify_phone;
+
+// Re-export command types
+pub use refresh::{RefreshError, RefreshTokenCommand};
+pub use revoke_session::{RevokeSessionCommand, SessionError};
+pub use verify_phone::{PhoneVerificationError, SendPhoneCodeCommand, VerifyPhoneCommand};
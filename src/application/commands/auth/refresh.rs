@@ -0,0 +1,119 @@
+use crate::{
+    application::dto::auth::{AuthResponse, UserInfo},
+    domain::{
+        entities::RefreshToken,
+        repositories::{AuthRepository, AuthRepositoryError},
+    },
+    shared::{
+        telemetry::{record_outcome, record_user_id},
+        utils::jwt::JwtManager,
+    },
+};
+use std::sync::Arc;
+
+#[derive(Debug, thiserror::Error)]
+pub enum RefreshError {
+    #[error("Invalid refresh token")]
+    InvalidToken,
+
+    #[error("User account is inactive")]
+    AccountInactive,
+
+    #[error("Repository error: {0}")]
+    RepositoryError(String),
+
+    #[error("Token creation failed: {0}")]
+    TokenCreationError(String),
+}
+
+/// Trades a refresh token for a new access/refresh pair. The presented token
+/// is revoked in the same step, so each one can be used only once.
+pub struct RefreshTokenCommand<R: AuthRepository> {
+    auth_repo: Arc<R>,
+    jwt_manager: Arc<JwtManager>,
+}
+
+impl<R: AuthRepository> RefreshTokenCommand<R> {
+    pub fn new(auth_repo: Arc<R>, jwt_manager: Arc<JwtManager>) -> Self {
+        Self { auth_repo, jwt_manager }
+    }
+
+    #[tracing::instrument(
+        name = "use_case.refresh",
+        skip_all,
+        fields(
+            user_id = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+        )
+    )]
+    pub async fn execute(&self, refresh_token: &str) -> Result<AuthResponse, RefreshError> {
+        record_outcome(self.run(refresh_token).await)
+    }
+
+    async fn run(&self, refresh_token: &str) -> Result<AuthResponse, RefreshError> {
+        let claims = self
+            .jwt_manager
+            .verify_token(refresh_token)
+            .map_err(|_| RefreshError::InvalidToken)?;
+        if claims.token_type != "refresh" {
+            return Err(RefreshError::InvalidToken);
+        }
+        let user_id: uuid::Uuid = claims.sub.parse().map_err(|_| RefreshError::InvalidToken)?;
+        record_user_id(user_id);
+
+        let token_hash = crate::shared::utils::hash_token(refresh_token);
+        let stored = self
+            .auth_repo
+            .find_refresh_token(&token_hash)
+            .await
+            .map_err(|e| RefreshError::RepositoryError(e.to_string()))?
+            .filter(|t| t.is_valid() && t.user_id == user_id)
+            .ok_or(RefreshError::InvalidToken)?;
+
+        let user = self
+            .auth_repo
+            .find_by_id(stored.user_id)
+            .await
+            .map_err(|e| RefreshError::RepositoryError(e.to_string()))?
+            .ok_or(RefreshError::InvalidToken)?;
+        if !user.is_active {
+            return Err(RefreshError::AccountInactive);
+        }
+
+        // Only one of two requests racing with the same token gets past this
+        self.auth_repo.revoke_refresh_token(&token_hash).await.map_err(|e| match e {
+            AuthRepositoryError::TokenNotFound => RefreshError::InvalidToken,
+            _ => RefreshError::RepositoryError(e.to_string()),
+        })?;
+
+        let role = user.role.to_string();
+        let access_token = self
+            .jwt_manager
+            .issue_access_token_for_role(user_id, &role)
+            .map_err(|e| RefreshError::TokenCreationError(e.to_string()))?;
+        let new_refresh_token = self
+            .jwt_manager
+            .issue_refresh_token_for_role(user_id, &role)
+            .map_err(|e| RefreshError::TokenCreationError(e.to_string()))?;
+
+        let new_hash = crate::shared::utils::hash_token(&new_refresh_token.token);
+        self.auth_repo
+            .save_refresh_token(&RefreshToken::new(user_id, new_hash, new_refresh_token.expires_at))
+            .await
+            .map_err(|e| RefreshError::RepositoryError(e.to_string()))?;
+
+        Ok(AuthResponse {
+            access_token: access_token.token,
+            refresh_token: new_refresh_token.token,
+            token_type: "Bearer".to_string(),
+            expires_in: self.jwt_manager.get_access_token_expiry_seconds_for_role(&role),
+            access_token_expires_at: access_token.expires_at.to_rfc3339(),
+            refresh_token_expires_at: new_refresh_token.expires_at.to_rfc3339(),
+            user: UserInfo {
+                id: user.id.as_uuid().to_string(),
+                email: user.email.as_str().to_string(),
+                name: user.name.clone(),
+            },
+        })
+    }
+}
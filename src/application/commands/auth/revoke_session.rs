@@ -0,0 +1,49 @@
+use crate::{
+    domain::repositories::{AuthRepository, AuthRepositoryError},
+    shared::telemetry::record_outcome,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SessionError {
+    /// No active session with that id belongs to the user
+    #[error("Session not found")]
+    NotFound,
+
+    #[error("Repository error: {0}")]
+    RepositoryError(String),
+}
+
+/// Command for users revoking one of their own sessions (active refresh
+/// tokens). Scoped to the caller's user id, so another user's session looks
+/// the same as one that does not exist.
+pub struct RevokeSessionCommand<R: AuthRepository> {
+    auth_repo: Arc<R>,
+}
+
+impl<R: AuthRepository> RevokeSessionCommand<R> {
+    pub fn new(auth_repo: Arc<R>) -> Self {
+        Self { auth_repo }
+    }
+
+    /// End one of the user's sessions; its refresh token stops working
+    #[tracing::instrument(
+        name = "use_case.revoke_session",
+        skip_all,
+        fields(user_id = %user_id, session_id = %session_id, outcome = tracing::field::Empty)
+    )]
+    pub async fn execute(&self, user_id: Uuid, session_id: Uuid) -> Result<(), SessionError> {
+        record_outcome(self.run(user_id, session_id).await)
+    }
+
+    async fn run(&self, user_id: Uuid, session_id: Uuid) -> Result<(), SessionError> {
+        self.auth_repo
+            .revoke_user_refresh_token(user_id, session_id)
+            .await
+            .map_err(|e| match e {
+                AuthRepositoryError::TokenNotFound => SessionError::NotFound,
+                _ => SessionError::RepositoryError(e.to_string()),
+            })
+    }
+}
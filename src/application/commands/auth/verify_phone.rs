@@ -0,0 +1,276 @@
+use crate::{
+    application::services::{resend::ResendLimiter, sms::SmsSender},
+    domain::{repositories::AuthRepository, value_objects::PhoneNumber},
+    shared::{telemetry::record_outcome, utils::code_hash::CodeHasher},
+};
+use std::sync::Arc;
+use tracing::error;
+use uuid::Uuid;
+
+#[derive(Debug, thiserror::Error)]
+pub enum PhoneVerificationError {
+    #[error("User not found")]
+    UserNotFound,
+
+    #[error("Invalid phone number: expected international format, e.g. +84901234567")]
+    InvalidPhone,
+
+    #[error("Invalid verification code")]
+    InvalidCode,
+
+    #[error("Verification code expired")]
+    CodeExpired,
+
+    #[error("Please wait before requesting another code")]
+    TooManyRequests { retry_after_secs: u64 },
+
+    #[error("Text messaging is not configured")]
+    SmsUnavailable,
+
+    #[error("Repository error: {0}")]
+    RepositoryError(String),
+
+    #[error("Failed to send text message: {0}")]
+    SmsError(String),
+}
+
+/// Texts a verification code to the number a user wants to add
+pub struct SendPhoneCodeCommand<R: AuthRepository> {
+    auth_repo: Arc<R>,
+    sms_sender: Option<Arc<dyn SmsSender>>,
+    code_expiry: i64,
+    limiter: Arc<ResendLimiter>,
+    code_hasher: Arc<CodeHasher>,
+}
+
+impl<R: AuthRepository> SendPhoneCodeCommand<R> {
+    pub fn new(auth_repo: Arc<R>, code_expiry: i64, limiter: Arc<ResendLimiter>) -> Self {
+        Self { auth_repo, sms_sender: None, code_expiry, limiter, code_hasher: Arc::default() }
+    }
+
+    /// Deliver codes through `sender`; without one every request fails
+    pub fn with_sms(mut self, sender: Arc<dyn SmsSender>) -> Self {
+        self.sms_sender = Some(sender);
+        self
+    }
+
+    /// Store codes in the form `hasher` gives them instead of as sent
+    pub fn with_code_hasher(mut self, hasher: Arc<CodeHasher>) -> Self {
+        self.code_hasher = hasher;
+        self
+    }
+
+    #[tracing::instrument(
+        name = "use_case.send_phone_code",
+        skip_all,
+        fields(user_id = %user_id, outcome = tracing::field::Empty)
+    )]
+    pub async fn execute(
+        &self,
+        user_id: Uuid,
+        phone: String,
+    ) -> Result<(), PhoneVerificationError> {
+        record_outcome(self.run(user_id, phone).await)
+    }
+
+    async fn run(&self, user_id: Uuid, phone: String) -> Result<(), PhoneVerificationError> {
+        let phone = PhoneNumber::parse(phone).map_err(|_| PhoneVerificationError::InvalidPhone)?;
+        let sender = self.sms_sender.as_ref().ok_or(PhoneVerificationError::SmsUnavailable)?;
+
+        let mut user = self
+            .auth_repo
+            .find_by_id(user_id)
+            .await
+            .map_err(|e| PhoneVerificationError::RepositoryError(e.to_string()))?
+            .ok_or(PhoneVerificationError::UserNotFound)?;
+
+        // Same budget as emailed codes, so texts cannot be sent without limit
+        self.limiter.acquire(&user.id).await.map_err(|retry_after_secs| {
+            PhoneVerificationError::TooManyRequests { retry_after_secs }
+        })?;
+
+        let code = crate::shared::utils::generate_confirmation_code();
+        let expires_at = chrono::Utc::now() + chrono::Duration::seconds(self.code_expiry);
+        user.set_phone(phone.clone(), self.code_hasher.stored_form(&code), expires_at);
+
+        self.auth_repo
+            .update_user(&user)
+            .await
+            .map_err(|e| PhoneVerificationError::RepositoryError(e.to_string()))?;
+
+        let body = format!("Your phone verification code is: {}", code);
+        if let Err(e) = sender.send(phone.as_str(), &body).await {
+            error!("Failed to text phone verification code via {}: {}", sender.provider(), e);
+            return Err(PhoneVerificationError::SmsError(e.to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Confirms a user's phone number with the code texted to it
+pub struct VerifyPhoneCommand<R: AuthRepository> {
+    auth_repo: Arc<R>,
+    code_hasher: Arc<CodeHasher>,
+}
+
+impl<R: AuthRepository> VerifyPhoneCommand<R> {
+    pub fn new(auth_repo: Arc<R>) -> Self {
+        Self { auth_repo, code_hasher: Arc::default() }
+    }
+
+    /// Look codes up in the form `hasher` stored them
+    pub fn with_code_hasher(mut self, hasher: Arc<CodeHasher>) -> Self {
+        self.code_hasher = hasher;
+        self
+    }
+
+    #[tracing::instrument(
+        name = "use_case.verify_phone",
+        skip_all,
+        fields(user_id = %user_id, outcome = tracing::field::Empty)
+    )]
+    pub async fn execute(&self, user_id: Uuid, code: String) -> Result<(), PhoneVerificationError> {
+        record_outcome(self.run(user_id, code).await)
+    }
+
+    async fn run(&self, user_id: Uuid, code: String) -> Result<(), PhoneVerificationError> {
+        let user = self
+            .auth_repo
+            .find_by_id(user_id)
+            .await
+            .map_err(|e| PhoneVerificationError::RepositoryError(e.to_string()))?
+            .ok_or(PhoneVerificationError::UserNotFound)?;
+
+        match (&user.phone_code, user.phone_code_expires_at) {
+            (Some(stored), Some(expires_at)) if self.code_hasher.matches(stored, &code) => {
+                if chrono::Utc::now() > expires_at {
+                    return Err(PhoneVerificationError::CodeExpired);
+                }
+            },
+            _ => return Err(PhoneVerificationError::InvalidCode),
+        }
+
+        // The check above only picks the error; the code is spent by an update
+        // that re-checks it, so of two requests replaying one code only one wins
+        let consumed = self
+            .auth_repo
+            .consume_phone_code(user_id, &self.code_hasher.stored_form(&code))
+            .await
+            .map_err(|e| PhoneVerificationError::RepositoryError(e.to_string()))?;
+        if !consumed {
+            return Err(PhoneVerificationError::InvalidCode);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        application::services::sms::MockSmsSender,
+        domain::{
+            entities::User,
+            repositories::{auth::MockAuthRepository, cache::MockCacheRepository},
+            value_objects::Email,
+        },
+    };
+    use std::{sync::Mutex, time::Duration};
+
+    /// Repository over a single user that keeps whatever is saved
+    fn repo(user: Arc<Mutex<User>>) -> MockAuthRepository {
+        let mut repo = MockAuthRepository::new();
+        let stored = user.clone();
+        repo.expect_find_by_id()
+            .returning(move |_| Ok(Some(stored.lock().unwrap().clone())));
+        let consumed = user.clone();
+        repo.expect_consume_phone_code().returning(move |_, code| {
+            let mut user = consumed.lock().unwrap();
+            let live = user.phone_code.as_deref() == Some(code)
+                && user.phone_code_expires_at.is_some_and(|at| at > chrono::Utc::now());
+            if live {
+                user.verify_phone();
+            }
+            Ok(live)
+        });
+        repo.expect_update_user().returning(move |saved| {
+            *user.lock().unwrap() = saved.clone();
+            Ok(saved.clone())
+        });
+        repo
+    }
+
+    fn limiter() -> Arc<ResendLimiter> {
+        let mut cache = MockCacheRepository::new();
+        cache.expect_increment().returning(|_, ttl| Ok((1, ttl)));
+        Arc::new(ResendLimiter::new(Arc::new(cache), Duration::ZERO, 5))
+    }
+
+    #[tokio::test]
+    async fn texted_code_verifies_the_normalized_number() {
+        let lan = User::new(Email::parse("lan@example.com").unwrap(), "Lan".into()).unwrap();
+        let user_id = *lan.id.as_uuid();
+        let user = Arc::new(Mutex::new(lan));
+        let texted = Arc::new(Mutex::new(None::<(String, String)>));
+        let mut sms = MockSmsSender::new();
+        let outbox = texted.clone();
+        sms.expect_send().times(1).returning(move |to, body| {
+            *outbox.lock().unwrap() = Some((to.to_string(), body.to_string()));
+            Ok(())
+        });
+
+        SendPhoneCodeCommand::new(Arc::new(repo(user.clone())), 600, limiter())
+            .with_sms(Arc::new(sms))
+            .execute(user_id, "+84 90-123-4567".into())
+            .await
+            .unwrap();
+        let (to, body) = texted.lock().unwrap().clone().unwrap();
+        let code = body.rsplit(' ').next().unwrap().to_string();
+        assert_eq!(to, "+84901234567");
+        assert!(user.lock().unwrap().verified_phone().is_none());
+
+        let verify = VerifyPhoneCommand::new(Arc::new(repo(user.clone())));
+        assert!(matches!(
+            verify.execute(user_id, "wrong".into()).await,
+            Err(PhoneVerificationError::InvalidCode)
+        ));
+        verify.execute(user_id, code.clone()).await.unwrap();
+
+        let verified = user.lock().unwrap().clone();
+        assert_eq!(verified.verified_phone().map(PhoneNumber::as_str), Some("+84901234567"));
+        assert!(verified.phone_code.is_none());
+        // The code is spent
+        assert!(verify.execute(user_id, code).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn expired_code_is_refused() {
+        let mut lan = User::new(Email::parse("lan@example.com").unwrap(), "Lan".into()).unwrap();
+        let phone = PhoneNumber::parse("+84901234567").unwrap();
+        lan.set_phone(phone, "code1234".into(), chrono::Utc::now() - chrono::Duration::seconds(1));
+        let user_id = *lan.id.as_uuid();
+
+        let result = VerifyPhoneCommand::new(Arc::new(repo(Arc::new(Mutex::new(lan)))))
+            .execute(user_id, "code1234".into())
+            .await;
+
+        assert!(matches!(result, Err(PhoneVerificationError::CodeExpired)));
+    }
+
+    #[tokio::test]
+    async fn invalid_numbers_and_missing_sms_are_refused_before_any_lookup() {
+        let use_case =
+            SendPhoneCodeCommand::new(Arc::new(MockAuthRepository::new()), 600, limiter());
+
+        assert!(matches!(
+            use_case.execute(Uuid::new_v4(), "0901234567".into()).await,
+            Err(PhoneVerificationError::InvalidPhone)
+        ));
+        assert!(matches!(
+            use_case.execute(Uuid::new_v4(), "+84901234567".into()).await,
+            Err(PhoneVerificationError::SmsUnavailable)
+        ));
+    }
+}
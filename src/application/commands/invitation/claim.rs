@@ -0,0 +1,43 @@
+use crate::{
+    domain::{
+        entities::Invitation,
+        repositories::{user::RepositoryError, InvitationRepository},
+    },
+    shared::utils::hash_token,
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Command for spending an invitation on a registration (Write operation)
+///
+/// `execute` claims the invitation in one update, so two registrations
+/// cannot share it. The registration then records who used it, or releases
+/// it again if it failed.
+pub struct ClaimInvitationCommand {
+    invitations: Arc<dyn InvitationRepository>,
+}
+
+impl ClaimInvitationCommand {
+    pub fn new(invitations: Arc<dyn InvitationRepository>) -> Self {
+        Self { invitations }
+    }
+
+    /// The invitation behind `token`, if it was still usable
+    pub async fn execute(&self, token: &str) -> Result<Option<Invitation>, RepositoryError> {
+        self.invitations.claim(&hash_token(token)).await
+    }
+
+    /// Make `invitation` usable again after the registration failed
+    pub async fn release(&self, invitation: &Invitation) {
+        if let Err(e) = self.invitations.release(invitation.id).await {
+            tracing::warn!("Failed to release invitation {}: {}", invitation.id, e);
+        }
+    }
+
+    /// Record that `user_id` registered with `invitation`
+    pub async fn complete(&self, invitation: &Invitation, user_id: Uuid) {
+        if let Err(e) = self.invitations.set_used_by(invitation.id, user_id).await {
+            tracing::warn!("Failed to record who used invitation {}: {}", invitation.id, e);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use crate::{
+    application::dto::InvitationResponseDto,
+    domain::{entities::Invitation, repositories::InvitationRepository, value_objects::UserRole},
+    shared::{
+        telemetry::record_outcome,
+        utils::{generate_confirmation_code, hash_token},
+        AppError,
+    },
+};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+/// Longest lifetime an invitation may be given
+pub const MAX_INVITATION_TTL: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+
+/// Command for an admin inviting someone to register (Write operation)
+///
+/// The token is returned once and only its hash is stored, like refresh
+/// tokens.
+pub struct CreateInvitationCommand {
+    invitations: Arc<dyn InvitationRepository>,
+    default_ttl: Duration,
+}
+
+impl CreateInvitationCommand {
+    pub fn new(invitations: Arc<dyn InvitationRepository>, default_ttl: Duration) -> Self {
+        Self { invitations, default_ttl }
+    }
+
+    #[tracing::instrument(
+        name = "use_case.create_invitation",
+        skip_all,
+        fields(actor_id = ?actor_id, outcome = tracing::field::Empty)
+    )]
+    pub async fn execute(
+        &self,
+        role: Option<&str>,
+        expires_in_secs: Option<u64>,
+        actor_id: Option<Uuid>,
+    ) -> Result<InvitationResponseDto, AppError> {
+        record_outcome(self.run(role, expires_in_secs, actor_id).await)
+    }
+
+    async fn run(
+        &self,
+        role: Option<&str>,
+        expires_in_secs: Option<u64>,
+        actor_id: Option<Uuid>,
+    ) -> Result<InvitationResponseDto, AppError> {
+        let role = role
+            .map(|r| {
+                UserRole::parse(r).ok_or_else(|| {
+                    AppError::Validation(format!(
+                        "Invalid role: '{}'. Must be 'admin', 'editor', or 'viewer'",
+                        r
+                    ))
+                })
+            })
+            .transpose()?;
+
+        let ttl = expires_in_secs.map_or(self.default_ttl, Duration::from_secs);
+        if ttl.is_zero() || ttl > MAX_INVITATION_TTL {
+            return Err(AppError::Validation(format!(
+                "expires_in_secs must be between 1 and {}",
+                MAX_INVITATION_TTL.as_secs()
+            )));
+        }
+        let ttl = chrono::Duration::from_std(ttl).map_err(|e| AppError::Internal(e.into()))?;
+
+        let token = generate_confirmation_code();
+        let invitation =
+            Invitation::new(hash_token(&token), role, actor_id, chrono::Utc::now() + ttl);
+        self.invitations
+            .create(&invitation)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!(e)))?;
+
+        Ok(InvitationResponseDto {
+            id: invitation.id.to_string(),
+            token,
+            role: invitation.role.map(|r| r.to_string()),
+            expires_at: invitation.expires_at.to_rfc3339(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repositories::invitation::MockInvitationRepository;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[tokio::test]
+    async fn stores_only_the_token_hash_with_the_requested_role_and_lifetime() {
+        let actor = Uuid::new_v4();
+        let stored = Arc::new(std::sync::Mutex::new(None));
+        let mut repo = MockInvitationRepository::new();
+        let sink = stored.clone();
+        repo.expect_create().times(1).returning(move |invitation| {
+            *sink.lock().unwrap() = Some(invitation.clone());
+            Ok(())
+        });
+
+        let response = CreateInvitationCommand::new(Arc::new(repo), 7 * DAY)
+            .execute(Some("editor"), Some(3600), Some(actor))
+            .await
+            .unwrap();
+
+        let invitation = stored.lock().unwrap().clone().unwrap();
+        assert_eq!(invitation.token_hash, hash_token(&response.token));
+        assert_ne!(invitation.token_hash, response.token);
+        assert_eq!(invitation.role, Some(UserRole::Editor));
+        assert_eq!(invitation.created_by, Some(actor));
+        let lifetime = invitation.expires_at - invitation.created_at;
+        assert!((lifetime - chrono::Duration::hours(1)).num_seconds().abs() <= 1);
+        assert_eq!(response.role.as_deref(), Some("editor"));
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_roles_and_out_of_range_lifetimes() {
+        for (role, expires_in_secs) in
+            [(Some("owner"), None), (None, Some(0)), (None, Some(MAX_INVITATION_TTL.as_secs() + 1))]
+        {
+            let result =
+                CreateInvitationCommand::new(Arc::new(MockInvitationRepository::new()), 7 * DAY)
+                    .execute(role, expires_in_secs, None)
+                    .await;
+            assert!(matches!(result, Err(AppError::Validation(_))), "{:?}", result);
+        }
+    }
+}
@@ -0,0 +1,9 @@
+/// Invitation commands (write operations)
+///
+/// Admins create invitations; registration claims them.
+pub mod claim;
+pub mod create;
+
+// Re-export command types
+pub use claim::ClaimInvitationCommand;
+pub use create::CreateInvitationCommand;
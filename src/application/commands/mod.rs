@@ -1,4 +1,13 @@
 // Commands (write operations) - CQRS pattern
+pub mod auth;
+pub mod invitation;
 pub mod user;
 
-pub use user::{CreateUserCommand, UpdateUserCommand};
+pub use auth::{
+    PhoneVerificationError, RefreshError, RefreshTokenCommand, RevokeSessionCommand,
+    SendPhoneCodeCommand, SessionError, VerifyPhoneCommand,
+};
+pub use invitation::{ClaimInvitationCommand, CreateInvitationCommand};
+pub use user::{
+    CreateUserCommand, DeactivateUsersCommand, ForcePasswordResetCommand, UpdateUserCommand,
+};
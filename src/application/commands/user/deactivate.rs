@@ -0,0 +1,161 @@
+use crate::{
+    application::dto::DeactivateUsersResponseDto,
+    domain::{
+        entities::AuditEntry,
+        repositories::{audit::AuditRepository, AuthRepository},
+    },
+    shared::{telemetry::record_outcome, AppError},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Audit action recorded for each user deactivated in bulk
+pub const USER_DEACTIVATED_ACTION: &str = "user.deactivated";
+
+/// Most users a single request may deactivate
+pub const MAX_DEACTIVATE_BATCH: usize = 100;
+
+/// Command for deactivating many users at once, e.g. a compromised batch
+///
+/// Users are marked inactive and their refresh tokens revoked in one
+/// transaction. Access tokens already issued stay valid until they expire.
+pub struct DeactivateUsersCommand<R: AuthRepository> {
+    auth_repo: Arc<R>,
+    audit_repo: Arc<dyn AuditRepository>,
+}
+
+impl<R: AuthRepository> DeactivateUsersCommand<R> {
+    pub fn new(auth_repo: Arc<R>, audit_repo: Arc<dyn AuditRepository>) -> Self {
+        Self { auth_repo, audit_repo }
+    }
+
+    /// `actor_id` is the authenticated admin; they cannot include themselves.
+    /// The batch is rejected as a whole if any id is malformed.
+    #[tracing::instrument(
+        name = "use_case.deactivate_users",
+        skip_all,
+        fields(
+            count = user_ids.len(),
+            actor_id = ?actor_id,
+            outcome = tracing::field::Empty,
+        )
+    )]
+    pub async fn execute(
+        &self,
+        user_ids: &[String],
+        actor_id: Option<Uuid>,
+    ) -> Result<DeactivateUsersResponseDto, AppError> {
+        record_outcome(self.run(user_ids, actor_id).await)
+    }
+
+    async fn run(
+        &self,
+        user_ids: &[String],
+        actor_id: Option<Uuid>,
+    ) -> Result<DeactivateUsersResponseDto, AppError> {
+        if user_ids.is_empty() {
+            return Err(AppError::Validation("user_ids must not be empty".to_string()));
+        }
+        if user_ids.len() > MAX_DEACTIVATE_BATCH {
+            return Err(AppError::Validation(format!(
+                "At most {} users can be deactivated at once",
+                MAX_DEACTIVATE_BATCH
+            )));
+        }
+
+        let mut ids = Vec::with_capacity(user_ids.len());
+        for raw in user_ids {
+            let id = Uuid::parse_str(raw)
+                .map_err(|_| AppError::Validation(format!("Invalid user ID: {}", raw)))?;
+            if !ids.contains(&id) {
+                ids.push(id);
+            }
+        }
+        if actor_id.is_some_and(|actor| ids.contains(&actor)) {
+            return Err(AppError::Validation("Cannot deactivate your own account".to_string()));
+        }
+
+        let deactivated = self
+            .auth_repo
+            .deactivate_users(&ids)
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!(e)))?;
+        tracing::info!("Deactivated {} of {} requested users", deactivated.len(), ids.len());
+
+        let details = serde_json::json!({ "batch_size": deactivated.len() });
+        audit_deactivations(self.audit_repo.as_ref(), &deactivated, actor_id, details).await;
+
+        let not_found = ids.iter().filter(|id| !deactivated.contains(id)).map(Uuid::to_string);
+        Ok(DeactivateUsersResponseDto {
+            deactivated: deactivated.iter().map(Uuid::to_string).collect(),
+            not_found: not_found.collect(),
+        })
+    }
+}
+
+/// Record a `USER_DEACTIVATED_ACTION` entry with `details` for each of the
+/// `deactivated` users, by `actor_id` if an admin did it. The deactivation
+/// is already committed, so failures are logged, not surfaced.
+pub(crate) async fn audit_deactivations(
+    audit_repo: &dyn AuditRepository,
+    deactivated: &[Uuid],
+    actor_id: Option<Uuid>,
+    details: serde_json::Value,
+) {
+    for user_id in deactivated {
+        let entry =
+            AuditEntry::new(actor_id, USER_DEACTIVATED_ACTION, Some(*user_id), details.clone());
+        if let Err(e) = audit_repo.record(&entry).await {
+            tracing::error!(user_id = %user_id, "Failed to audit deactivation: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repositories::{audit::MockAuditRepository, auth::MockAuthRepository};
+
+    fn use_case(
+        auth: MockAuthRepository,
+        audit: MockAuditRepository,
+    ) -> DeactivateUsersCommand<MockAuthRepository> {
+        DeactivateUsersCommand::new(Arc::new(auth), Arc::new(audit))
+    }
+
+    #[tokio::test]
+    async fn audits_each_deactivated_user_and_reports_unknown_ids() {
+        let (known, unknown) = (Uuid::new_v4(), Uuid::new_v4());
+        let mut auth = MockAuthRepository::new();
+        auth.expect_deactivate_users()
+            .withf(move |ids| ids == [known, unknown])
+            .times(1)
+            .returning(move |_| Ok(vec![known]));
+        let mut audit = MockAuditRepository::new();
+        audit
+            .expect_record()
+            .withf(move |e| e.action == USER_DEACTIVATED_ACTION && e.target_id == Some(known))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let ids = [known.to_string(), unknown.to_string(), known.to_string()];
+        let result = use_case(auth, audit).execute(&ids, None).await.unwrap();
+
+        assert_eq!(result.deactivated, vec![known.to_string()]);
+        assert_eq!(result.not_found, vec![unknown.to_string()]);
+    }
+
+    #[tokio::test]
+    async fn rejects_unsafe_batches_before_touching_the_repository() {
+        let actor = Uuid::new_v4();
+        let oversized: Vec<String> =
+            (0..=MAX_DEACTIVATE_BATCH).map(|_| Uuid::new_v4().to_string()).collect();
+
+        for ids in [vec![], oversized, vec!["not-a-uuid".to_string()], vec![actor.to_string()]] {
+            let result = use_case(MockAuthRepository::new(), MockAuditRepository::new())
+                .execute(&ids, Some(actor))
+                .await;
+            assert!(matches!(result, Err(AppError::Validation(_))), "{:?}", result);
+        }
+    }
+}
@@ -4,10 +4,14 @@
 /// Each command is responsible for validating input and coordinating
 /// with the domain layer to execute business logic.
 pub mod create;
+pub mod deactivate;
+pub mod password_reset;
 pub mod update;
 
 // Re-export command types
 pub use create::CreateUserCommand;
+pub use deactivate::DeactivateUsersCommand;
+pub use password_reset::ForcePasswordResetCommand;
 pub use update::UpdateUserCommand;
 
 // Backward compatibility (deprecated)
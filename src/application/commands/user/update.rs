@@ -22,21 +22,13 @@ impl<R: UserRepository> UpdateUserCommand<R> {
         // Validate input
         dto.validate().map_err(|e| AppError::Validation(e.to_string()))?;
 
-        // Get existing user
-        let mut user = self
+        // Only fields present in the request are written
+        let updated_user = self
             .user_repository
-            .find_by_id(user_id)
+            .patch(user_id, &dto.into_changes()?)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("User {} not found", user_id)))?;
 
-        // Update fields if provided
-        if let Some(name) = dto.name {
-            user.update_name(name).map_err(|e| AppError::Validation(e.to_string()))?;
-        }
-
-        // Save updated user
-        let updated_user = self.user_repository.update(&user).await?;
-
         tracing::info!("User updated successfully: {}", user_id);
 
         Ok(updated_user)
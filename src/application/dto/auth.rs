@@ -14,6 +14,11 @@ pub struct RegisterRequest {
 
     #[validate(length(min = 1, max = 255, message = "Name must be between 1 and 255 characters"))]
     pub name: String,
+
+    /// Token from an admin's invitation; required while registration is
+    /// closed, and sets the role of the new account
+    #[serde(default)]
+    pub invite_token: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
@@ -56,15 +61,52 @@ pub struct RegisterResponse {
     pub user: UserInfo,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct VerifyEmailResponse {
+    pub verified: bool,
+    /// The account has no password yet; the client should continue to
+    /// `POST /api/auth/password` with the same code
+    pub requires_password_setup: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct AuthResponse {
+    /// Left out when tokens are delivered as cookies only
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub access_token: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
     pub refresh_token: String,
     pub token_type: String,
     pub expires_in: i64,
+    /// RFC 3339 instant at which `access_token` expires
+    pub access_token_expires_at: String,
+    /// RFC 3339 instant at which `refresh_token` expires
+    pub refresh_token_expires_at: String,
     pub user: UserInfo,
 }
 
+/// Where login and refresh put the issued tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TokenDelivery {
+    /// HttpOnly cookies only, for browsers; the body carries no tokens
+    Cookie,
+    /// Response body only, for API clients; no cookies are set
+    Body,
+    #[default]
+    Both,
+}
+
+impl TokenDelivery {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "cookie" => Some(Self::Cookie),
+            "body" => Some(Self::Body),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct UserInfo {
     pub id: String,
@@ -74,21 +116,71 @@ pub struct UserInfo {
 
 #[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct RefreshTokenRequest {
+    /// Omit to use the `refresh_token` cookie
     #[validate(length(min = 1, message = "Refresh token is required"))]
-    pub refresh_token: String,
+    pub refresh_token: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+/// One of the caller's signed-in sessions, i.e. an active refresh token
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SessionDto {
+    pub id: String,
+    pub created_at: String,
+    pub expires_at: String,
+}
+
+/// Every field may be omitted, as may the whole body
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate, ToSchema)]
 pub struct LogoutRequest {
+    /// Session to end; defaults to the `refresh_token` cookie
     pub refresh_token: Option<String>,
+    /// End every session of the caller instead
+    #[serde(default)]
     pub logout_all: bool,
 }
 
+/// Where a one-time code is delivered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationChannel {
+    #[default]
+    Email,
+    /// Text message to the user's verified phone; falls back to email for
+    /// users without one
+    Sms,
+}
+
+impl NotificationChannel {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "email" => Some(Self::Email),
+            "sms" => Some(Self::Sms),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct SendPhoneCodeRequest {
+    /// International number; spaces, dashes and parentheses are ignored
+    #[validate(length(min = 1, max = 32, message = "Phone must be between 1 and 32 characters"))]
+    #[schema(example = "+84 90 123 4567")]
+    pub phone: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct VerifyPhoneRequest {
+    #[validate(length(min = 6, message = "Code must be at least 6 characters"))]
+    pub code: String,
+}
+
 #[derive(Debug, Deserialize, Validate, ToSchema)]
 pub struct ForgotPasswordRequest {
     #[validate(email)]
     #[schema(example = "user@example.com")]
     pub email: String,
+    /// Overrides the server's default channel for this code
+    pub channel: Option<NotificationChannel>,
 }
 
 #[derive(Debug, Deserialize, Validate, ToSchema)]
@@ -3,11 +3,13 @@
 /// DTOs define the structure of data sent to and from the API.
 /// Organized by domain for better maintainability.
 pub mod auth;
+pub mod pagination;
 pub mod role;
 pub mod user;
 
 // Re-export commonly used DTOs
 pub use auth::*;
+pub use pagination::*;
 pub use role::*;
 pub use user::*;
 
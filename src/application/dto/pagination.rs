@@ -0,0 +1,56 @@
+use crate::shared::AppError;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Page sizes shared by every list endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageSizeLimits {
+    /// Applied when a request omits `page_size`
+    pub default: i64,
+    /// Larger requests are clamped to this
+    pub max: i64,
+}
+
+impl Default for PageSizeLimits {
+    fn default() -> Self {
+        Self { default: 10, max: 100 }
+    }
+}
+
+impl PageSizeLimits {
+    /// Page size to use for a request; oversized pages are clamped rather
+    /// than rejected.
+    pub fn resolve(&self, requested: Option<i64>) -> Result<i64, AppError> {
+        match requested {
+            None => Ok(self.default.min(self.max)),
+            Some(size) if size < 1 => {
+                Err(AppError::Validation("Page size must be >= 1".to_string()))
+            },
+            Some(size) => Ok(size.min(self.max)),
+        }
+    }
+}
+
+/// Pagination details returned alongside list results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct PaginationMeta {
+    pub page: i64,
+    /// Page size actually applied, after clamping to the configured maximum
+    pub page_size: i64,
+    pub total: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn omitted_page_size_uses_the_default_and_large_ones_are_clamped() {
+        let limits = PageSizeLimits { default: 25, max: 50 };
+
+        assert_eq!(limits.resolve(None).unwrap(), 25);
+        assert_eq!(limits.resolve(Some(7)).unwrap(), 7);
+        assert_eq!(limits.resolve(Some(500)).unwrap(), 50);
+        assert!(matches!(limits.resolve(Some(0)), Err(AppError::Validation(_))));
+    }
+}
@@ -1,4 +1,7 @@
-use crate::domain::entities::User;
+use crate::{
+    domain::{entities::User, repositories::UserChanges},
+    shared::{i18n::Locale, AppError},
+};
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 use validator::Validate;
@@ -17,14 +20,140 @@ pub struct CreateUserDto {
     pub name: String,
 }
 
-/// DTO for updating a user
-#[derive(Debug, Deserialize, Validate, ToSchema)]
+/// DTO for partially updating a user
+///
+/// Omitted fields are left unchanged. An explicit `null` clears a field
+/// where that is allowed: `locale` reverts to the default, while `name`
+/// cannot be cleared.
+#[derive(Debug, Default, Deserialize, Validate, ToSchema)]
 #[schema(example = json!({
     "name": "Updated User01"
 }))]
 pub struct UpdateUserDto {
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<String>)]
     #[validate(length(min = 1, max = 255))]
-    pub name: Option<String>,
+    pub name: Option<Option<String>>,
+
+    #[serde(default, deserialize_with = "present")]
+    #[schema(value_type = Option<String>, nullable, example = "es")]
+    pub locale: Option<Option<String>>,
+}
+
+impl UpdateUserDto {
+    /// Validated, normalised column changes for the repository
+    pub fn into_changes(self) -> Result<UserChanges, AppError> {
+        Ok(UserChanges {
+            name: match self.name {
+                None => None,
+                Some(None) => return Err(AppError::Validation("Name cannot be cleared".into())),
+                Some(Some(name)) => Some(
+                    User::normalize_name(&name).map_err(|e| AppError::Validation(e.to_string()))?,
+                ),
+            },
+            locale: match self.locale {
+                None => None,
+                Some(None) => Some(Locale::default().to_string()),
+                Some(Some(tag)) => Some(
+                    Locale::from_tag(&tag)
+                        .ok_or_else(|| AppError::Validation("Unsupported locale".into()))?
+                        .to_string(),
+                ),
+            },
+        })
+    }
+}
+
+/// Distinguish a field sent as `null` (`Some(None)`) from one left out (`None`)
+fn present<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+/// Request to deactivate a batch of users
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct DeactivateUsersDto {
+    /// Ids of the users to deactivate
+    pub user_ids: Vec<String>,
+}
+
+/// Request to invite someone to register
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CreateInvitationDto {
+    /// Role the invitee registers with; the default role when omitted
+    #[schema(example = "editor")]
+    pub role: Option<String>,
+    /// Lifetime in seconds; the configured default when omitted
+    pub expires_in_secs: Option<u64>,
+}
+
+/// A newly created invitation. The token is not stored and cannot be shown
+/// again.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct InvitationResponseDto {
+    pub id: String,
+    /// Pass as `invite_token` when registering
+    pub token: String,
+    pub role: Option<String>,
+    /// RFC 3339 timestamp
+    pub expires_at: String,
+}
+
+/// Outcome of a bulk deactivation
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct DeactivateUsersResponseDto {
+    /// Users now inactive with every session revoked
+    pub deactivated: Vec<String>,
+    /// Requested ids that matched no user
+    pub not_found: Vec<String>,
+}
+
+/// What importing a CSV would do, from a `dry_run` that wrote nothing
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportReportDto {
+    /// Data rows read, not counting the header
+    pub rows: usize,
+    /// Rows that would create a user
+    pub created: usize,
+    /// Valid rows whose email is already registered; an import skips them
+    pub skipped: usize,
+    /// Rows that cannot be imported
+    pub errors: Vec<ImportRowErrorDto>,
+}
+
+/// Why one CSV row cannot be imported
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ImportRowErrorDto {
+    /// 1-based data row, not counting the header
+    pub row: usize,
+    pub error: String,
+}
+
+/// Where a background import has got to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportJobState {
+    Running,
+    Completed,
+    /// Stopped at a bad row or an internal error; see `error`
+    Failed,
+}
+
+/// Progress of one background import, as returned when it starts and when
+/// its status is polled
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ImportJobDto {
+    pub job_id: String,
+    pub state: ImportJobState,
+    /// Data rows in the file, not counting the header
+    pub total: usize,
+    /// Rows handed off for creation so far; all of them once completed
+    pub processed: usize,
+    /// Why the import failed
+    pub error: Option<String>,
 }
 
 /// DTO for user response
@@ -33,6 +162,12 @@ pub struct UserResponseDto {
     pub id: String,
     pub email: String,
     pub name: String,
+    /// E.164 phone number, if one has been added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phone: Option<String>,
+    pub phone_verified: bool,
+    /// When the user last logged in; null until their first login
+    pub last_login: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
@@ -43,6 +178,9 @@ impl From<User> for UserResponseDto {
             id: user.id.to_string(),
             email: user.email.to_string(),
             name: user.name,
+            phone: user.phone.map(|p| p.as_str().to_string()),
+            phone_verified: user.is_phone_verified,
+            last_login: user.last_login.map(|t| t.to_rfc3339()),
             created_at: user.created_at.to_rfc3339(),
             updated_at: user.updated_at.to_rfc3339(),
         }
@@ -55,8 +193,31 @@ impl From<&User> for UserResponseDto {
             id: user.id.to_string(),
             email: user.email.to_string(),
             name: user.name.clone(),
+            phone: user.phone.as_ref().map(|p| p.as_str().to_string()),
+            phone_verified: user.is_phone_verified,
+            last_login: user.last_login.map(|t| t.to_rfc3339()),
             created_at: user.created_at.to_rfc3339(),
             updated_at: user.updated_at.to_rfc3339(),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(json: &str) -> UpdateUserDto {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn update_dto_tells_omitted_fields_from_null() {
+        let dto = parse(r#"{"name":"Lan"}"#);
+        assert_eq!(dto.name, Some(Some("Lan".to_string())));
+        assert_eq!(dto.locale, None);
+
+        let dto = parse(r#"{"locale":null}"#);
+        assert_eq!(dto.name, None);
+        assert_eq!(dto.locale, Some(None));
+    }
+}
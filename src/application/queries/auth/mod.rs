@@ -0,0 +1,5 @@
+/// Authentication queries (read operations)
+pub mod sessions;
+
+// Re-export query types
+pub use sessions::ListSessionsQuery;
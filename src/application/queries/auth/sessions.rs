@@ -0,0 +1,44 @@
+use crate::{
+    application::dto::auth::SessionDto,
+    domain::repositories::AuthRepository,
+    shared::{telemetry::record_outcome, AppError},
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+/// Query for users listing their own sessions (active refresh tokens)
+pub struct ListSessionsQuery<R: AuthRepository> {
+    auth_repo: Arc<R>,
+}
+
+impl<R: AuthRepository> ListSessionsQuery<R> {
+    pub fn new(auth_repo: Arc<R>) -> Self {
+        Self { auth_repo }
+    }
+
+    /// The user's active sessions, oldest first
+    #[tracing::instrument(
+        name = "use_case.list_sessions",
+        skip_all,
+        fields(user_id = %user_id, outcome = tracing::field::Empty)
+    )]
+    pub async fn execute(&self, user_id: Uuid) -> Result<Vec<SessionDto>, AppError> {
+        record_outcome(self.run(user_id).await)
+    }
+
+    async fn run(&self, user_id: Uuid) -> Result<Vec<SessionDto>, AppError> {
+        let tokens =
+            self.auth_repo.list_active_refresh_tokens(user_id).await.map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Failed to list sessions: {}", e))
+            })?;
+
+        Ok(tokens
+            .into_iter()
+            .map(|token| SessionDto {
+                id: token.id.to_string(),
+                created_at: token.created_at.to_rfc3339(),
+                expires_at: token.expires_at.to_rfc3339(),
+            })
+            .collect())
+    }
+}
@@ -1,4 +1,9 @@
 // Queries (read operations) - CQRS pattern
+pub mod auth;
 pub mod user;
 
-pub use user::{GetUserQuery, ListUsersQuery, UserFilters, UserStatistics, UserStatisticsQuery};
+pub use auth::ListSessionsQuery;
+pub use user::{
+    ExportUsersQuery, GetUserQuery, ListUsersQuery, UserFilters, UserStatistics,
+    UserStatisticsQuery,
+};
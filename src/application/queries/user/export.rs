@@ -0,0 +1,151 @@
+use crate::{
+    domain::{
+        entities::User,
+        repositories::user_repository::{UserFilter, UserRepository},
+    },
+    shared::AppError,
+};
+use futures::{stream::BoxStream, StreamExt};
+use std::sync::Arc;
+
+/// Columns written to the export, in order. Credentials and confirmation
+/// codes are deliberately absent.
+pub const EXPORT_COLUMNS: [&str; 10] = [
+    "id",
+    "email",
+    "name",
+    "role",
+    "is_active",
+    "email_verified",
+    "locale",
+    "last_login",
+    "created_at",
+    "updated_at",
+];
+
+/// Users encoded per chunk of the response body
+const ROWS_PER_CHUNK: usize = 100;
+
+/// Query for exporting users as CSV (Read operation - streamed)
+pub struct ExportUsersQuery<R: UserRepository> {
+    user_repository: Arc<R>,
+}
+
+impl<R: UserRepository> ExportUsersQuery<R> {
+    pub fn new(user_repository: Arc<R>) -> Self {
+        Self { user_repository }
+    }
+
+    /// CSV chunks: the header row, then the matching users a batch at a
+    /// time as they are read from the database. A failure part-way through
+    /// ends the stream with an error after the rows already sent.
+    pub fn execute(&self, filter: &UserFilter) -> BoxStream<'static, Result<Vec<u8>, AppError>> {
+        let header = futures::stream::once(async { encode(std::iter::once(EXPORT_COLUMNS)) });
+
+        let rows = self.user_repository.stream(filter).ready_chunks(ROWS_PER_CHUNK).map(|batch| {
+            let users = batch.into_iter().collect::<Result<Vec<User>, _>>()?;
+            encode(users.iter().map(record))
+        });
+
+        header
+            .chain(rows)
+            .inspect(|chunk| {
+                if let Err(e) = chunk {
+                    tracing::error!("User export aborted: {:?}", e);
+                }
+            })
+            .boxed()
+    }
+}
+
+fn record(user: &User) -> [String; 10] {
+    [
+        user.id.to_string(),
+        user.email.to_string(),
+        user.name.clone(),
+        user.role.to_string(),
+        user.is_active.to_string(),
+        user.is_email_verified.to_string(),
+        user.locale.clone(),
+        user.last_login.map(|t| t.to_rfc3339()).unwrap_or_default(),
+        user.created_at.to_rfc3339(),
+        user.updated_at.to_rfc3339(),
+    ]
+    .map(escape_formula)
+}
+
+/// Spreadsheets evaluate cells starting with these as formulas
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Prefix `'` to a cell a spreadsheet would otherwise run as a formula
+fn escape_formula(cell: String) -> String {
+    if cell.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", cell)
+    } else {
+        cell
+    }
+}
+
+fn encode<I, T>(records: I) -> Result<Vec<u8>, AppError>
+where
+    I: IntoIterator<Item = T>,
+    T: IntoIterator,
+    T::Item: AsRef<[u8]>,
+{
+    let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+    for record in records {
+        writer.write_record(record).map_err(|e| AppError::Internal(e.into()))?;
+    }
+    writer
+        .into_inner()
+        .map_err(|e| AppError::Internal(anyhow::anyhow!(e.to_string())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{repositories::user::MockUserRepository, value_objects::Email};
+
+    async fn collect(use_case: &ExportUsersQuery<MockUserRepository>) -> String {
+        let chunks: Vec<_> = use_case.execute(&UserFilter::default()).collect().await;
+        let bytes: Vec<u8> = chunks.into_iter().flat_map(Result::unwrap).collect();
+        String::from_utf8(bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn writes_header_then_one_row_per_user() {
+        let mut repo = MockUserRepository::new();
+        repo.expect_stream().returning(|_| {
+            let users = ["a@example.com", "b@example.com"].map(|email| {
+                Ok(User::new(Email::parse(email).unwrap(), "Name, Jr.".to_string()).unwrap())
+            });
+            futures::stream::iter(users).boxed()
+        });
+
+        let csv = collect(&ExportUsersQuery::new(Arc::new(repo))).await;
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], EXPORT_COLUMNS.join(","));
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].contains(",a@example.com,\"Name, Jr.\",viewer,false,false,en,,"));
+    }
+
+    #[tokio::test]
+    async fn cells_that_look_like_formulas_are_escaped() {
+        let mut repo = MockUserRepository::new();
+        repo.expect_stream().returning(|_| {
+            let users =
+                ["=HYPERLINK(\"http://evil\")", "+1", "-2", "@SUM(A1)", "Plain"].map(|name| {
+                    let email = Email::parse("x@example.com").unwrap();
+                    Ok(User::new(email, name.to_string()).unwrap())
+                });
+            futures::stream::iter(users).boxed()
+        });
+
+        let csv = collect(&ExportUsersQuery::new(Arc::new(repo))).await;
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let names: Vec<String> = reader.records().map(|r| r.unwrap()[2].to_string()).collect();
+
+        assert_eq!(names, ["'=HYPERLINK(\"http://evil\")", "'+1", "'-2", "'@SUM(A1)", "Plain"]);
+    }
+}
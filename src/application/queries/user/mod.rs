@@ -2,11 +2,13 @@
 ///
 /// Queries represent read operations that don't modify state.
 /// They are optimized for data retrieval and can be cached.
+pub mod export;
 pub mod get;
 pub mod list;
 pub mod statistics;
 
 // Re-export query types
+pub use export::ExportUsersQuery;
 pub use get::GetUserQuery;
 pub use list::{ListUsersQuery, UserFilters};
 pub use statistics::{UserStatistics, UserStatisticsQuery};
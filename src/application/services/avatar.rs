@@ -0,0 +1,7 @@
+/// Renders the default avatar shown for users who have not uploaded one
+#[cfg_attr(test, mockall::automock)]
+pub trait AvatarRenderer: Send + Sync {
+    /// An SVG document for the user with `email`; the same address must
+    /// always give the same image
+    fn svg(&self, email: &str) -> String;
+}
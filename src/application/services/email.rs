@@ -1,33 +1,76 @@
-use crate::shared::errors::AppError;
+use crate::shared::{errors::AppError, i18n, i18n::Locale};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::net::IpAddr;
 
 #[derive(Debug, Clone)]
 pub struct Recipient {
     pub email: String,
     pub name: String,
+    /// Language the email is rendered in
+    pub locale: Locale,
 }
 
 #[derive(Debug, Clone)]
 pub enum EmailType {
-    Welcome(String),       // Name
-    Confirmation(String),  // Code
-    PasswordReset(String), // Code (was Token, but now Code for forgot pass flow)
+    Welcome(String),                      // Name
+    Confirmation(String, Option<String>), // Code, verification link
+    /// Same as `Confirmation`, for a code sent again on request
+    ConfirmationResent(String, Option<String>), // Code, verification link
+    PasswordReset(String),                // Code (was Token, but now Code for forgot pass flow)
+    /// Security alert after too many failed logins
+    AccountLocked(IpAddr, DateTime<Utc>), // IP of the locking attempt, when it was locked
+    /// Notice that an unused account is about to be disabled
+    InactivityWarning(DateTime<Utc>), // When it will be disabled
 }
 
 impl EmailType {
-    pub fn subject(&self) -> String {
+    pub fn subject(&self, locale: Locale) -> String {
+        let key = match self {
+            EmailType::Welcome(_) => "email.welcome.subject",
+            EmailType::Confirmation(..) | EmailType::ConfirmationResent(..) => {
+                "email.confirmation.subject"
+            },
+            EmailType::PasswordReset(_) => "email.password_reset.subject",
+            EmailType::AccountLocked(..) => "email.account_locked.subject",
+            EmailType::InactivityWarning(_) => "email.inactivity_warning.subject",
+        };
+        i18n::t(locale, key).to_string()
+    }
+
+    /// Label naming the kind of email, without its contents
+    pub fn kind(&self) -> &'static str {
         match self {
-            EmailType::Welcome(_) => "Welcome to Axum Backend!".to_string(),
-            EmailType::Confirmation(_) => "Confirm your registration".to_string(),
-            EmailType::PasswordReset(_) => "Reset your password".to_string(),
+            EmailType::Welcome(_) => "welcome",
+            EmailType::Confirmation(..) => "confirmation",
+            EmailType::ConfirmationResent(..) => "confirmation_resent",
+            EmailType::PasswordReset(_) => "password_reset",
+            EmailType::AccountLocked(..) => "account_locked",
+            EmailType::InactivityWarning(_) => "inactivity_warning",
         }
     }
 
     pub fn body(&self) -> String {
         match self {
             EmailType::Welcome(name) => format!("Hello {}, welcome to our platform!", name),
-            EmailType::Confirmation(code) => format!("Your confirmation code is: {}", code),
+            EmailType::Confirmation(code, link) | EmailType::ConfirmationResent(code, link) => {
+                match link {
+                    Some(link) => {
+                        format!("Your confirmation code is: {}\nOr confirm here: {}", code, link)
+                    },
+                    None => format!("Your confirmation code is: {}", code),
+                }
+            },
             EmailType::PasswordReset(code) => format!("Your password reset code is: {}", code),
+            EmailType::AccountLocked(ip, locked_at) => format!(
+                "Your account was locked at {} after repeated failed logins, the last from {}",
+                locked_at.to_rfc3339(),
+                ip
+            ),
+            EmailType::InactivityWarning(disable_at) => format!(
+                "Your account will be disabled at {} unless you log in before then",
+                disable_at.to_rfc3339()
+            ),
         }
     }
 }
@@ -36,4 +79,9 @@ impl EmailType {
 #[async_trait]
 pub trait EmailService: Send + Sync {
     async fn send(&self, recipient: Recipient, email_type: EmailType) -> Result<(), AppError>;
+
+    /// Name of the delivery backend, used to label metrics
+    fn provider(&self) -> &'static str {
+        "custom"
+    }
 }
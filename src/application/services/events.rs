@@ -0,0 +1,164 @@
+use crate::{
+    domain::events::{DomainEvent, EventEnvelope},
+    shared::{errors::AppError, telemetry::TraceContext},
+};
+use async_trait::async_trait;
+
+/// Events an [`EventBatch`] holds before publishing them, unless told otherwise
+pub const DEFAULT_BATCH_SIZE: usize = 100;
+
+/// A serialized event, ready for the broker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutboundEvent {
+    pub subject: &'static str,
+    pub payload: Vec<u8>,
+}
+
+/// Outbound port for domain events
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    /// Publish a serialized payload on `subject`
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), AppError>;
+
+    /// Publish `events` in the order given. The default sends them one at a
+    /// time; transports that can send several messages at once override it.
+    async fn publish_batch(&self, events: Vec<OutboundEvent>) -> Result<(), AppError> {
+        for event in events {
+            self.publish(event.subject, event.payload).await?;
+        }
+        Ok(())
+    }
+}
+
+/// Serialize `event` as JSON and publish it on its own subject, tagged with
+/// the current trace context if there is one.
+pub async fn publish_event<E: DomainEvent + Sync>(
+    publisher: &dyn EventPublisher,
+    event: &E,
+) -> Result<(), AppError> {
+    let outbound = serialize(event)?;
+    publisher.publish(outbound.subject, outbound.payload).await
+}
+
+fn serialize<E: DomainEvent>(event: &E) -> Result<OutboundEvent, AppError> {
+    let envelope =
+        EventEnvelope { traceparent: TraceContext::current().map(|ctx| ctx.inject()), event };
+    let payload = serde_json::to_vec(&envelope)
+        .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to serialize event: {}", e)))?;
+    Ok(OutboundEvent { subject: event.subject(), payload })
+}
+
+/// Coalesces events for bulk operations and publishes them `max_size` at a
+/// time, in the order they were pushed.
+///
+/// Call [`flush`](Self::flush) when done; events still pending when the
+/// batch is dropped are not sent.
+pub struct EventBatch<'a> {
+    publisher: &'a dyn EventPublisher,
+    max_size: usize,
+    pending: Vec<OutboundEvent>,
+}
+
+impl<'a> EventBatch<'a> {
+    pub fn new(publisher: &'a dyn EventPublisher, max_size: usize) -> Self {
+        let max_size = max_size.max(1);
+        Self { publisher, max_size, pending: Vec::with_capacity(max_size) }
+    }
+
+    /// Queue `event`, publishing the batch once it is full
+    pub async fn push<E: DomainEvent>(&mut self, event: &E) -> Result<(), AppError> {
+        self.pending.push(serialize(event)?);
+        if self.pending.len() >= self.max_size {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    /// Publish whatever is pending
+    pub async fn flush(&mut self) -> Result<(), AppError> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+        let events = std::mem::replace(&mut self.pending, Vec::with_capacity(self.max_size));
+        self.publisher.publish_batch(events).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::events::v2::UserRoleChanged;
+    use crate::domain::value_objects::UserRole;
+    use std::sync::{Arc, Mutex};
+
+    fn event() -> UserRoleChanged {
+        UserRoleChanged {
+            user_id: uuid::Uuid::new_v4(),
+            old_role: UserRole::Viewer,
+            new_role: UserRole::Editor,
+            actor_id: None,
+            occurred_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Publish `event` and return the payload handed to the publisher
+    async fn published(event: &UserRoleChanged) -> EventEnvelope<UserRoleChanged> {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let mut publisher = MockEventPublisher::new();
+        let capture = sent.clone();
+        publisher.expect_publish().returning(move |_, payload| {
+            *capture.lock().unwrap() = payload;
+            Ok(())
+        });
+
+        publish_event(&publisher, event).await.unwrap();
+        let payload = sent.lock().unwrap().clone();
+        serde_json::from_slice(&payload).unwrap()
+    }
+
+    #[tokio::test]
+    async fn events_carry_the_current_traceparent_next_to_their_fields() {
+        let ctx = TraceContext::new_root();
+        let event = event();
+
+        let envelope = ctx.scope(published(&event)).await;
+
+        assert_eq!(envelope.traceparent, Some(ctx.inject()));
+        assert_eq!(envelope.event, event);
+    }
+
+    #[tokio::test]
+    async fn events_outside_a_trace_have_no_traceparent() {
+        let event = event();
+        assert_eq!(published(&event).await.traceparent, None);
+    }
+
+    #[tokio::test]
+    async fn batch_publishes_full_groups_then_the_remainder_in_push_order() {
+        let batches = Arc::new(Mutex::new(Vec::new()));
+        let mut publisher = MockEventPublisher::new();
+        let capture = batches.clone();
+        publisher.expect_publish_batch().times(3).returning(move |events| {
+            capture.lock().unwrap().push(events);
+            Ok(())
+        });
+        let events: Vec<_> = (0..5).map(|_| event()).collect();
+
+        let mut batch = EventBatch::new(&publisher, 2);
+        for event in &events {
+            batch.push(event).await.unwrap();
+        }
+        batch.flush().await.unwrap();
+        batch.flush().await.unwrap();
+
+        let batches = batches.lock().unwrap();
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 2, 1]);
+        let sent: Vec<UserRoleChanged> = batches
+            .iter()
+            .flatten()
+            .map(|e| serde_json::from_slice::<EventEnvelope<_>>(&e.payload).unwrap().event)
+            .collect();
+        assert_eq!(sent, events);
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{
+    application::dto::user::{ImportJobDto, ImportJobState},
+    domain::repositories::cache::CacheRepository,
+};
+use std::{sync::Arc, time::Duration};
+use uuid::Uuid;
+
+/// How long a job's state is kept after its last update
+pub const JOB_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Cache key holding the latest state of one background import
+pub fn import_job_key(job_id: &str) -> String {
+    format!("import:{}:status", job_id)
+}
+
+/// Progress of background imports, kept in the `CacheRepository` for `ttl`
+/// after each update so a finished job can still be polled for a while.
+///
+/// Like the other cache-backed state, jobs are per node with the in-memory
+/// cache and cannot be followed at all with caching disabled. A failed write
+/// is only logged; the import itself carries on.
+pub struct ImportJobs {
+    cache: Arc<dyn CacheRepository>,
+    ttl: Duration,
+}
+
+impl ImportJobs {
+    pub fn new(cache: Arc<dyn CacheRepository>, ttl: Duration) -> Self {
+        Self { cache, ttl }
+    }
+
+    /// Record a new running job over `total` rows
+    pub async fn start(&self, total: usize) -> ImportJobDto {
+        let job = ImportJobDto {
+            job_id: Uuid::new_v4().to_string(),
+            state: ImportJobState::Running,
+            total,
+            processed: 0,
+            error: None,
+        };
+        self.save(&job).await;
+        job
+    }
+
+    /// Store the job's latest state
+    pub async fn save(&self, job: &ImportJobDto) {
+        let key = import_job_key(&job.job_id);
+        let value = match serde_json::to_string(job) {
+            Ok(value) => value,
+            Err(e) => {
+                tracing::warn!("Failed to serialize import job {}: {}", job.job_id, e);
+                return;
+            },
+        };
+        if let Err(e) = self.cache.set(&key, &value, self.ttl).await {
+            tracing::warn!("Failed to save import job {}: {}", key, e);
+        }
+    }
+
+    /// Latest state of the job, or `None` if it is unknown, expired or could
+    /// not be read
+    pub async fn get(&self, job_id: &Uuid) -> Option<ImportJobDto> {
+        let key = import_job_key(&job_id.to_string());
+        match self.cache.get(&key).await {
+            Ok(value) => serde_json::from_str(&value?).ok(),
+            Err(e) => {
+                tracing::warn!("Import job lookup failed for {}: {}", key, e);
+                None
+            },
+        }
+    }
+}
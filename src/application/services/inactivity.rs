@@ -0,0 +1,281 @@
+use crate::{
+    application::{
+        commands::user::deactivate::audit_deactivations,
+        services::email::{EmailService, EmailType, Recipient},
+    },
+    domain::{
+        entities::User,
+        repositories::{audit::AuditRepository, AuthRepository},
+    },
+    shared::{i18n::Locale, AppError},
+};
+use chrono::{DateTime, Utc};
+use std::{sync::Arc, time::Duration};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Counter of accounts disabled for going unused too long
+pub const INACTIVE_USERS_DISABLED_TOTAL: &str = "inactive_users_disabled_total";
+
+/// Disables accounts nobody has logged into for `disable_after`, counting
+/// from sign-up for users who never logged in. Admins are never disabled,
+/// so the policy cannot lock every admin out.
+///
+/// Disabling goes through `deactivate_idle_users`, so refresh tokens are
+/// revoked with it and each user gets the same audit entry as a bulk
+/// deactivation. With a warning configured, users are emailed once as they
+/// come within the warning period of being disabled, and only disabled a
+/// full warning period after their warning was sent.
+///
+/// Nothing coordinates runs across nodes, and several nodes would send
+/// duplicate warnings and audit entries, so enable it on one node only.
+pub struct InactiveAccounts<R: AuthRepository> {
+    auth_repo: Arc<R>,
+    audit_repo: Arc<dyn AuditRepository>,
+    disable_after: Duration,
+    batch_size: usize,
+    warning: Option<(Arc<dyn EmailService>, Duration)>,
+}
+
+impl<R: AuthRepository> InactiveAccounts<R> {
+    pub fn new(
+        auth_repo: Arc<R>,
+        audit_repo: Arc<dyn AuditRepository>,
+        disable_after: Duration,
+        batch_size: usize,
+    ) -> Self {
+        Self {
+            auth_repo,
+            audit_repo,
+            disable_after,
+            batch_size: batch_size.max(1),
+            warning: None,
+        }
+    }
+
+    /// Email users `before` their account is disabled
+    pub fn with_warning(mut self, email_service: Arc<dyn EmailService>, before: Duration) -> Self {
+        self.warning = Some((email_service, before));
+        self
+    }
+
+    /// Disable every active account last seen more than `disable_after`
+    /// before `now`, `batch_size` per transaction. With a warning, users
+    /// warned less than the warning period ago are left for a later run.
+    /// Returns the ids disabled.
+    pub async fn disable_stale(&self, now: DateTime<Utc>) -> Result<Vec<Uuid>, AppError> {
+        let cutoff = now - chrono_duration(self.disable_after)?;
+        let warned_before = match &self.warning {
+            Some((_, before)) => Some(now - chrono_duration(*before)?),
+            None => None,
+        };
+
+        let mut disabled = Vec::new();
+        loop {
+            let deactivated = self
+                .auth_repo
+                .deactivate_idle_users(cutoff, warned_before, self.batch_size)
+                .await
+                .map_err(internal)?;
+            metrics::counter!(INACTIVE_USERS_DISABLED_TOTAL).increment(deactivated.len() as u64);
+            let details = serde_json::json!({ "reason": "inactive" });
+            audit_deactivations(self.audit_repo.as_ref(), &deactivated, None, details).await;
+            let last_batch = deactivated.len() < self.batch_size;
+            disabled.extend(deactivated);
+            if last_batch {
+                return Ok(disabled);
+            }
+        }
+    }
+
+    /// Email every user within the warning period of being disabled who has
+    /// not been warned since they were last seen, and record the warning.
+    /// Users already inside the period when the policy is switched on are
+    /// warned on the first run. Returns how many were emailed.
+    pub async fn warn_upcoming(&self, now: DateTime<Utc>) -> Result<usize, AppError> {
+        let Some((email_service, before)) = &self.warning else {
+            return Ok(0);
+        };
+        let entered = now - chrono_duration(self.disable_after.saturating_sub(*before))?;
+        // Disabling waits a full warning period from now, however long ago
+        // the user entered it
+        let disable_at = now + chrono_duration(*before)?;
+
+        let mut warned = 0;
+        loop {
+            let users = self
+                .auth_repo
+                .find_unwarned_idle_users(entered, self.batch_size)
+                .await
+                .map_err(internal)?;
+            for user in &users {
+                self.send_warning(email_service.as_ref(), user, disable_at).await;
+            }
+            let ids: Vec<Uuid> = users.iter().map(|user| *user.id.as_uuid()).collect();
+            self.auth_repo.record_inactivity_warnings(&ids, now).await.map_err(internal)?;
+            warned += users.len();
+            if users.len() < self.batch_size {
+                return Ok(warned);
+            }
+        }
+    }
+
+    /// Warn and disable every `interval` until `token` is cancelled. A
+    /// failed run is logged and retried at the next tick.
+    pub async fn run(self, interval: Duration, token: CancellationToken) {
+        let mut ticks = tokio::time::interval(interval);
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => return,
+                _ = ticks.tick() => {},
+            }
+            let now = Utc::now();
+            match self.warn_upcoming(now).await {
+                Ok(0) => {},
+                Ok(warned) => tracing::info!("Warned {} inactive users", warned),
+                Err(e) => tracing::error!("Inactive account warning failed: {}", e),
+            }
+            match self.disable_stale(now).await {
+                Ok(disabled) if disabled.is_empty() => {},
+                Ok(disabled) => tracing::info!("Disabled {} inactive accounts", disabled.len()),
+                Err(e) => tracing::error!("Disabling inactive accounts failed: {}", e),
+            }
+        }
+    }
+
+    /// A failed warning is logged; the account is disabled on time either way
+    async fn send_warning(
+        &self,
+        email_service: &dyn EmailService,
+        user: &User,
+        disable_at: DateTime<Utc>,
+    ) {
+        let recipient = Recipient {
+            email: user.email.as_str().to_string(),
+            name: user.name.clone(),
+            locale: Locale::from_tag(&user.locale).unwrap_or_default(),
+        };
+        if let Err(e) =
+            email_service.send(recipient, EmailType::InactivityWarning(disable_at)).await
+        {
+            tracing::error!("Failed to send inactivity warning to user {}: {}", user.id, e);
+        }
+    }
+}
+
+fn chrono_duration(duration: Duration) -> Result<chrono::Duration, AppError> {
+    chrono::Duration::from_std(duration).map_err(|e| AppError::Internal(e.into()))
+}
+
+fn internal(e: impl std::error::Error + Send + Sync + 'static) -> AppError {
+    AppError::Internal(anyhow::anyhow!(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        application::{
+            commands::user::deactivate::USER_DEACTIVATED_ACTION, services::email::MockEmailService,
+        },
+        domain::{
+            repositories::{audit::MockAuditRepository, auth::MockAuthRepository},
+            value_objects::Email,
+        },
+    };
+
+    const DAY: Duration = Duration::from_secs(86_400);
+
+    fn user(address: &str, last_login: Option<DateTime<Utc>>) -> User {
+        let mut user = User::new(Email::parse(address).unwrap(), "Idle".to_string()).unwrap();
+        user.last_login = last_login;
+        user
+    }
+
+    #[tokio::test]
+    async fn disables_stale_users_in_batches_and_audits_each() {
+        let now = Utc::now();
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+
+        let mut auth = MockAuthRepository::new();
+        let mut batches = vec![ids[..2].to_vec(), ids[2..].to_vec()].into_iter();
+        auth.expect_deactivate_idle_users()
+            .withf(move |before, warned_before, limit| {
+                *before == now - chrono::Duration::days(30)
+                    && warned_before.is_none()
+                    && *limit == 2
+            })
+            .times(2)
+            .returning(move |_, _, _| Ok(batches.next().unwrap_or_default()));
+        let mut audit = MockAuditRepository::new();
+        audit
+            .expect_record()
+            .withf(|e| e.action == USER_DEACTIVATED_ACTION && e.actor_id.is_none())
+            .times(3)
+            .returning(|_| Ok(()));
+
+        let inactive = InactiveAccounts::new(Arc::new(auth), Arc::new(audit), DAY * 30, 2);
+
+        assert_eq!(inactive.disable_stale(now).await.unwrap(), ids);
+    }
+
+    #[tokio::test]
+    async fn with_a_warning_only_users_warned_a_full_period_ago_are_disabled() {
+        let now = Utc::now();
+        let mut auth = MockAuthRepository::new();
+        auth.expect_deactivate_idle_users()
+            .withf(move |before, warned_before, _| {
+                *before == now - chrono::Duration::days(30)
+                    && *warned_before == Some(now - chrono::Duration::days(7))
+            })
+            .times(1)
+            .returning(|_, _, _| Ok(Vec::new()));
+
+        let inactive = InactiveAccounts::new(
+            Arc::new(auth),
+            Arc::new(MockAuditRepository::new()),
+            DAY * 30,
+            10,
+        )
+        .with_warning(Arc::new(MockEmailService::new()), DAY * 7);
+
+        assert!(inactive.disable_stale(now).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn warns_unwarned_users_in_the_warning_period_and_records_it() {
+        let now = Utc::now();
+        let warned = user("lan@example.com", Some(now - chrono::Duration::days(40)));
+        let warned_id = *warned.id.as_uuid();
+
+        let mut auth = MockAuthRepository::new();
+        auth.expect_find_unwarned_idle_users()
+            .withf(move |before, limit| *before == now - chrono::Duration::days(23) && *limit == 10)
+            .times(1)
+            .returning(move |_, _| Ok(vec![warned.clone()]));
+        auth.expect_record_inactivity_warnings()
+            .withf(move |ids, at| ids == [warned_id] && *at == now)
+            .times(1)
+            .returning(|_, _| Ok(()));
+        let mut email = MockEmailService::new();
+        email
+            .expect_send()
+            .withf(move |recipient, kind| {
+                recipient.email == "lan@example.com"
+                    && matches!(kind, EmailType::InactivityWarning(at)
+                        if *at == now + chrono::Duration::days(7))
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let inactive = InactiveAccounts::new(
+            Arc::new(auth),
+            Arc::new(MockAuditRepository::new()),
+            DAY * 30,
+            10,
+        )
+        .with_warning(Arc::new(email), DAY * 7);
+
+        assert_eq!(inactive.warn_upcoming(now).await.unwrap(), 1);
+    }
+}
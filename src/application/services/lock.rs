@@ -0,0 +1,344 @@
+use crate::domain::repositories::cache::{CacheError, CacheRepository};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Cache key holding the owner token of the lock called `name`
+pub fn lock_key(name: &str) -> String {
+    format!("lock:{}", name)
+}
+
+/// What `DistributedLock` does when the cache backing it cannot be reached
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LockPolicy {
+    /// Fail the acquisition, and with it the work the lock protects
+    #[default]
+    Strict,
+    /// Go ahead without the lock, logging a warning; the work must be safe
+    /// to race, e.g. guarded by database constraints
+    BestEffort,
+}
+
+impl LockPolicy {
+    /// `strict` or `best_effort`, ignoring case
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "strict" => Some(Self::Strict),
+            "best_effort" => Some(Self::BestEffort),
+            _ => None,
+        }
+    }
+}
+
+/// Mutual exclusion between holders sharing a `CacheRepository`.
+///
+/// Every cache backend so far lives inside the process, so holders only
+/// exclude each other within one node; excluding other nodes needs a shared
+/// backend.
+///
+/// A held lock is a cache entry with a TTL, so a crashed holder cannot block
+/// others forever. While the guard is alive a background task extends that
+/// TTL every third of it, so long critical sections keep the lock; the
+/// entry only lapses once the holder stops renewing it.
+pub struct DistributedLock {
+    cache: Arc<dyn CacheRepository>,
+    ttl: Duration,
+    policy: LockPolicy,
+}
+
+impl DistributedLock {
+    pub fn new(cache: Arc<dyn CacheRepository>, ttl: Duration) -> Self {
+        Self { cache, ttl, policy: LockPolicy::default() }
+    }
+
+    /// Choose what happens when the cache is unreachable
+    pub fn with_policy(mut self, policy: LockPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Take the lock called `name`, or `None` if someone else holds it.
+    ///
+    /// Under `LockPolicy::BestEffort` a cache failure yields a guard that
+    /// holds nothing (see `LockGuard::is_degraded`) instead of an error.
+    pub async fn try_acquire(&self, name: &str) -> Result<Option<LockGuard>, CacheError> {
+        let key = lock_key(name);
+        let token = Uuid::new_v4().to_string();
+        match self.cache.set_nx(&key, &token, self.ttl).await {
+            Ok(true) => {},
+            Ok(false) => return Ok(None),
+            Err(e) if self.policy == LockPolicy::BestEffort => {
+                tracing::warn!(
+                    "Distributed lock {} unavailable, continuing without it: {}",
+                    key,
+                    e
+                );
+                return Ok(Some(LockGuard::degraded()));
+            },
+            Err(e) => return Err(e),
+        }
+
+        let lost = Arc::new(AtomicBool::new(false));
+        let renewal = tokio::spawn(renew(
+            self.cache.clone(),
+            key.clone(),
+            token.clone(),
+            self.ttl,
+            lost.clone(),
+        ));
+        Ok(Some(LockGuard {
+            lease: Some(Lease { cache: self.cache.clone(), key, token, renewal }),
+            lost,
+            released: false,
+        }))
+    }
+}
+
+/// Extend the lease until the task is aborted or ownership is lost
+async fn renew(
+    cache: Arc<dyn CacheRepository>,
+    key: String,
+    token: String,
+    ttl: Duration,
+    lost: Arc<AtomicBool>,
+) {
+    let mut interval = tokio::time::interval((ttl / 3).max(Duration::from_millis(1)));
+    // The first tick completes immediately, right after acquiring
+    interval.tick().await;
+    loop {
+        interval.tick().await;
+        match cache.extend_if_equals(&key, &token, ttl).await {
+            Ok(true) => {},
+            Ok(false) => {
+                tracing::warn!("Lost distributed lock {}; no longer renewing it", key);
+                lost.store(true, Ordering::SeqCst);
+                return;
+            },
+            // Retry on the next tick; the lease may still be valid
+            Err(e) => tracing::warn!("Failed to renew distributed lock {}: {}", key, e),
+        }
+    }
+}
+
+/// The cache entry behind a held lock and the task renewing it
+struct Lease {
+    cache: Arc<dyn CacheRepository>,
+    key: String,
+    token: String,
+    renewal: JoinHandle<()>,
+}
+
+/// Proof of holding a `DistributedLock`; dropping it stops renewal and
+/// releases the lock in the background
+pub struct LockGuard {
+    /// `None` when the lock was skipped under `LockPolicy::BestEffort`
+    lease: Option<Lease>,
+    lost: Arc<AtomicBool>,
+    released: bool,
+}
+
+impl LockGuard {
+    fn degraded() -> Self {
+        Self { lease: None, lost: Arc::default(), released: false }
+    }
+
+    /// `false` once a renewal found the lock expired or taken by another
+    /// holder; work protected by it should then stop
+    pub fn is_held(&self) -> bool {
+        !self.lost.load(Ordering::SeqCst)
+    }
+
+    /// `true` when the cache was unreachable and no lock was actually taken
+    pub fn is_degraded(&self) -> bool {
+        self.lease.is_none()
+    }
+
+    /// Release now, returning whether the lock was still ours. A degraded
+    /// guard has nothing to release and reports `true`.
+    pub async fn release(mut self) -> Result<bool, CacheError> {
+        self.released = true;
+        let Some(lease) = &self.lease else {
+            return Ok(true);
+        };
+        lease.renewal.abort();
+        lease.cache.delete_if_equals(&lease.key, &lease.token).await
+    }
+}
+
+impl Drop for LockGuard {
+    fn drop(&mut self) {
+        let Some(lease) = &self.lease else {
+            return;
+        };
+        lease.renewal.abort();
+        if self.released || !self.is_held() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let (cache, key, token) = (lease.cache.clone(), lease.key.clone(), lease.token.clone());
+        runtime.spawn(async move {
+            if let Err(e) = cache.delete_if_equals(&key, &token).await {
+                tracing::warn!("Failed to release distributed lock {}: {}", key, e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repositories::cache::MockCacheRepository;
+    use async_trait::async_trait;
+    use std::{collections::HashMap, sync::Mutex, time::Instant};
+
+    /// Map-backed cache honouring TTLs, like the real backends
+    #[derive(Default)]
+    struct TtlCache(Mutex<HashMap<String, (String, Instant)>>);
+
+    impl TtlCache {
+        fn live(&self, key: &str) -> Option<String> {
+            let map = self.0.lock().unwrap();
+            map.get(key).filter(|(_, exp)| *exp > Instant::now()).map(|(v, _)| v.clone())
+        }
+    }
+
+    #[async_trait]
+    impl CacheRepository for TtlCache {
+        async fn get(&self, key: &str) -> Result<Option<String>, CacheError> {
+            Ok(self.live(key))
+        }
+
+        async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<(), CacheError> {
+            let entry = (value.to_string(), Instant::now() + ttl);
+            self.0.lock().unwrap().insert(key.to_string(), entry);
+            Ok(())
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), CacheError> {
+            self.0.lock().unwrap().remove(key);
+            Ok(())
+        }
+
+        async fn set_nx(&self, key: &str, value: &str, ttl: Duration) -> Result<bool, CacheError> {
+            if self.live(key).is_some() {
+                return Ok(false);
+            }
+            self.set(key, value, ttl).await.map(|()| true)
+        }
+
+        async fn delete_if_equals(&self, key: &str, expected: &str) -> Result<bool, CacheError> {
+            if self.live(key).as_deref() != Some(expected) {
+                return Ok(false);
+            }
+            self.delete(key).await.map(|()| true)
+        }
+
+        async fn extend_if_equals(
+            &self,
+            key: &str,
+            expected: &str,
+            ttl: Duration,
+        ) -> Result<bool, CacheError> {
+            if self.live(key).as_deref() != Some(expected) {
+                return Ok(false);
+            }
+            self.set(key, expected, ttl).await.map(|()| true)
+        }
+
+        async fn increment(
+            &self,
+            _key: &str,
+            _ttl: Duration,
+        ) -> Result<(u64, Duration), CacheError> {
+            Err(CacheError::Backend("locks never count".to_string()))
+        }
+    }
+
+    const TTL: Duration = Duration::from_millis(150);
+
+    #[tokio::test]
+    async fn lock_stays_held_through_a_critical_section_longer_than_its_ttl() {
+        let cache = Arc::new(TtlCache::default());
+        let lock = DistributedLock::new(cache.clone(), TTL);
+        let guard = lock.try_acquire("import").await.unwrap().unwrap();
+
+        tokio::time::sleep(TTL * 5).await;
+
+        assert!(guard.is_held());
+        assert!(lock.try_acquire("import").await.unwrap().is_none());
+        assert!(guard.release().await.unwrap());
+        assert!(lock.try_acquire("import").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn dropping_the_guard_stops_renewal_and_releases() {
+        let cache = Arc::new(TtlCache::default());
+        let lock = DistributedLock::new(cache.clone(), Duration::from_secs(60));
+        let guard = lock.try_acquire("import").await.unwrap().unwrap();
+
+        drop(guard);
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        assert_eq!(cache.live(&lock_key("import")), None);
+    }
+
+    #[tokio::test]
+    async fn renewal_stops_once_another_holder_owns_the_lock() {
+        let cache = Arc::new(TtlCache::default());
+        let lock = DistributedLock::new(cache.clone(), TTL);
+        let guard = lock.try_acquire("import").await.unwrap().unwrap();
+
+        // Simulate the lease lapsing and another node taking over
+        cache.set(&lock_key("import"), "other-node", TTL * 10).await.unwrap();
+        tokio::time::sleep(TTL).await;
+
+        assert!(!guard.is_held());
+        assert!(!guard.release().await.unwrap());
+        assert_eq!(cache.live(&lock_key("import")).as_deref(), Some("other-node"));
+    }
+
+    fn unreachable_cache() -> Arc<MockCacheRepository> {
+        let mut cache = MockCacheRepository::new();
+        cache
+            .expect_set_nx()
+            .returning(|_, _, _| Err(CacheError::Backend("connection refused".to_string())));
+        Arc::new(cache)
+    }
+
+    #[tokio::test]
+    async fn strict_lock_fails_when_the_cache_is_down() {
+        let lock = DistributedLock::new(unreachable_cache(), TTL);
+
+        assert!(matches!(lock.try_acquire("register").await, Err(CacheError::Backend(_))));
+    }
+
+    #[tokio::test]
+    async fn best_effort_lock_proceeds_without_the_cache() {
+        // The mock has no other expectations, so any renewal or release
+        // reaching the cache would fail the test
+        let lock =
+            DistributedLock::new(unreachable_cache(), TTL).with_policy(LockPolicy::BestEffort);
+
+        let guard = lock.try_acquire("register").await.unwrap().unwrap();
+        assert!(guard.is_degraded());
+        assert!(guard.is_held());
+        tokio::time::sleep(TTL).await;
+        assert!(guard.release().await.unwrap());
+        assert!(lock.try_acquire("register").await.unwrap().is_some(), "nothing was locked");
+    }
+
+    #[test]
+    fn policy_parses_both_modes() {
+        assert_eq!(LockPolicy::parse("strict"), Some(LockPolicy::Strict));
+        assert_eq!(LockPolicy::parse(" Best_Effort "), Some(LockPolicy::BestEffort));
+        assert_eq!(LockPolicy::parse("lenient"), None);
+    }
+}
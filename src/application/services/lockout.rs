@@ -0,0 +1,103 @@
+use crate::{domain::repositories::cache::CacheRepository, shared::utils::hash_token};
+use chrono::{DateTime, Utc};
+use std::{sync::Arc, time::Duration};
+
+/// Cache key counting failed logins for `email` in the current window
+pub fn login_failures_key(email: &str) -> String {
+    format!("login:{}:failures", subject(email))
+}
+
+/// Cache key marking `email` locked; holds the Unix time the lock ends
+pub fn login_lock_key(email: &str) -> String {
+    format!("login:{}:locked", subject(email))
+}
+
+/// Keys are per submitted address, hashed so they do not hold it
+fn subject(email: &str) -> String {
+    hash_token(&email.trim().to_lowercase())
+}
+
+/// Locks an email address out of login for `duration` once `max_failures`
+/// logins for it fail within `window`.
+///
+/// Failures count whether or not an account has the address, so an unknown
+/// address locks just like a known one and lockouts reveal nothing about
+/// which addresses are registered.
+///
+/// State lives in the `CacheRepository` like `ResendLimiter`'s, so locks are
+/// per node with the in-memory cache and disappear when caching is
+/// disabled. Cache failures let the login through rather than lock users
+/// out.
+pub struct LoginLockout {
+    cache: Arc<dyn CacheRepository>,
+    max_failures: u32,
+    window: Duration,
+    duration: Duration,
+}
+
+impl LoginLockout {
+    pub fn new(
+        cache: Arc<dyn CacheRepository>,
+        max_failures: u32,
+        window: Duration,
+        duration: Duration,
+    ) -> Self {
+        Self { cache, max_failures: max_failures.max(1), window, duration }
+    }
+
+    /// Seconds until `email` unlocks, or `None` if it is not locked
+    pub async fn locked_for(&self, email: &str) -> Option<u64> {
+        let until = match self.cache.get(&login_lock_key(email)).await {
+            Ok(value) => value?.parse::<i64>().ok()?,
+            Err(e) => {
+                tracing::warn!("Login lock lookup failed, allowing login: {}", e);
+                return None;
+            },
+        };
+        let now = Utc::now().timestamp();
+        (until > now).then(|| u64::try_from(until - now).unwrap_or(0).max(1))
+    }
+
+    /// Count a failed login. Returns when the account was locked if this
+    /// failure is the one that locked it; only one concurrent caller sees that.
+    pub async fn record_failure(&self, email: &str) -> Option<DateTime<Utc>> {
+        let failures_key = login_failures_key(email);
+        match self.cache.increment(&failures_key, self.window).await {
+            Ok((count, _)) if count >= u64::from(self.max_failures) => {},
+            Ok(_) => return None,
+            Err(e) => {
+                tracing::warn!("Failed to count failed login {}: {}", failures_key, e);
+                return None;
+            },
+        }
+
+        let now = Utc::now();
+        let until = now.timestamp().saturating_add(secs(self.duration)).to_string();
+        match self.cache.set_nx(&login_lock_key(email), &until, self.duration).await {
+            Ok(true) => {
+                // The next window starts from zero once the lock ends
+                if let Err(e) = self.cache.delete(&failures_key).await {
+                    tracing::warn!("Failed to clear failed logins {}: {}", failures_key, e);
+                }
+                Some(now)
+            },
+            Ok(false) => None,
+            Err(e) => {
+                tracing::warn!("Failed to lock {}: {}", failures_key, e);
+                None
+            },
+        }
+    }
+
+    /// Forget failed logins, e.g. after a successful one
+    pub async fn reset(&self, email: &str) {
+        let key = login_failures_key(email);
+        if let Err(e) = self.cache.delete(&key).await {
+            tracing::warn!("Failed to clear failed logins {}: {}", key, e);
+        }
+    }
+}
+
+fn secs(duration: Duration) -> i64 {
+    i64::try_from(duration.as_secs()).unwrap_or(i64::MAX)
+}
@@ -2,12 +2,32 @@
 ///
 /// Services encapsulate complex business logic that spans multiple use cases
 /// or requires coordination between different domain entities.
+pub mod audit_retention;
 pub mod auth;
+pub mod avatar;
 pub mod email;
+pub mod events;
+pub mod import_jobs;
+pub mod inactivity;
+pub mod lock;
+pub mod lockout;
+pub mod resend;
+pub mod role;
+pub mod sms;
 pub mod user;
 
 // Re-export for convenience
+pub use audit_retention::AuditRetention;
 pub use auth::AuthService;
+pub use avatar::AvatarRenderer;
+pub use events::EventPublisher;
+pub use import_jobs::ImportJobs;
+pub use inactivity::InactiveAccounts;
+pub use lock::{DistributedLock, LockGuard, LockPolicy};
+pub use lockout::LoginLockout;
+pub use resend::ResendLimiter;
+pub use role::RoleResolver;
+pub use sms::SmsSender;
 pub use user::UserService;
 
 // Backward compatibility (deprecated)
@@ -0,0 +1,228 @@
+use crate::domain::{repositories::cache::CacheRepository, value_objects::UserId};
+use chrono::Utc;
+use std::{sync::Arc, time::Duration};
+
+/// Period over which `max_per_hour` is counted
+const WINDOW: Duration = Duration::from_secs(3600);
+
+/// Cache key marking that a code was just emailed to the user; holds the
+/// Unix time at which the cooldown ends
+pub fn resend_cooldown_key(user_id: &UserId) -> String {
+    format!("user:{}:resend:cooldown", user_id)
+}
+
+/// Cache key counting codes emailed in the current window
+pub fn resend_count_key(user_id: &UserId) -> String {
+    format!("user:{}:resend:count", user_id)
+}
+
+/// Throttles how often a confirmation or reset code can be emailed to the
+/// same user: at most one per `cooldown`, and `max_per_hour` per hour.
+///
+/// State lives in the `CacheRepository`, so limits are per node with the
+/// in-memory cache and disappear when caching is disabled. Every check is a
+/// single atomic cache operation, so concurrent requests cannot slip past
+/// either limit. Cache failures let the send through rather than lock users
+/// out.
+pub struct ResendLimiter {
+    cache: Arc<dyn CacheRepository>,
+    cooldown: Duration,
+    max_per_hour: u32,
+}
+
+impl ResendLimiter {
+    pub fn new(cache: Arc<dyn CacheRepository>, cooldown: Duration, max_per_hour: u32) -> Self {
+        Self { cache, cooldown, max_per_hour }
+    }
+
+    /// Record a send for `user_id`, or return how many seconds the caller
+    /// must wait before another one is allowed.
+    pub async fn acquire(&self, user_id: &UserId) -> Result<(), u64> {
+        let now = Utc::now().timestamp();
+        let cooldown_key = resend_cooldown_key(user_id);
+        let until = (now + secs(self.cooldown)).to_string();
+
+        // Claiming the cooldown first means a refused send is never counted
+        if !self.cooldown.is_zero() && !self.claim(&cooldown_key, &until).await {
+            let until = self.read(&cooldown_key).await.and_then(|v| v.parse::<i64>().ok());
+            return Err(until.map_or(1, |until| seconds_until(until, now)));
+        }
+
+        match self.cache.increment(&resend_count_key(user_id), WINDOW).await {
+            Ok((count, remaining)) if count > u64::from(self.max_per_hour) => {
+                // Over the cap, so this send does not start a cooldown either
+                if let Err(e) = self.cache.delete_if_equals(&cooldown_key, &until).await {
+                    tracing::warn!("Failed to clear resend cooldown {}: {}", cooldown_key, e);
+                }
+                Err(remaining.as_secs().max(1))
+            },
+            Ok(_) => Ok(()),
+            Err(e) => {
+                tracing::warn!("Resend count update failed, allowing send: {}", e);
+                Ok(())
+            },
+        }
+    }
+
+    /// Forget the user's cooldown and counter, e.g. once they have verified
+    pub async fn reset(&self, user_id: &UserId) {
+        for key in [resend_cooldown_key(user_id), resend_count_key(user_id)] {
+            if let Err(e) = self.cache.delete(&key).await {
+                tracing::warn!("Failed to clear resend limit {}: {}", key, e);
+            }
+        }
+    }
+
+    async fn read(&self, key: &str) -> Option<String> {
+        self.cache.get(key).await.unwrap_or_else(|e| {
+            tracing::warn!("Resend limit lookup failed, allowing send: {}", e);
+            None
+        })
+    }
+
+    /// Start the cooldown unless one is running; cache failures allow it
+    async fn claim(&self, key: &str, until: &str) -> bool {
+        self.cache.set_nx(key, until, self.cooldown).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to record resend cooldown, allowing send: {}", e);
+            true
+        })
+    }
+}
+
+fn secs(duration: Duration) -> i64 {
+    i64::try_from(duration.as_secs()).unwrap_or(i64::MAX)
+}
+
+/// Whole seconds from `now` to `until`, never less than one
+fn seconds_until(until: i64, now: i64) -> u64 {
+    u64::try_from(until.saturating_sub(now)).unwrap_or(0).max(1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repositories::cache::CacheError;
+    use async_trait::async_trait;
+    use std::{collections::HashMap, sync::Mutex};
+
+    /// Map-backed cache; expiry is encoded in the values under test
+    #[derive(Default)]
+    struct MapCache(Mutex<HashMap<String, String>>);
+
+    #[async_trait]
+    impl CacheRepository for MapCache {
+        async fn get(&self, key: &str) -> Result<Option<String>, CacheError> {
+            Ok(self.0.lock().unwrap().get(key).cloned())
+        }
+
+        async fn set(&self, key: &str, value: &str, _ttl: Duration) -> Result<(), CacheError> {
+            self.0.lock().unwrap().insert(key.to_string(), value.to_string());
+            Ok(())
+        }
+
+        async fn delete(&self, key: &str) -> Result<(), CacheError> {
+            self.0.lock().unwrap().remove(key);
+            Ok(())
+        }
+
+        async fn set_nx(&self, key: &str, value: &str, _ttl: Duration) -> Result<bool, CacheError> {
+            let mut map = self.0.lock().unwrap();
+            if map.contains_key(key) {
+                return Ok(false);
+            }
+            map.insert(key.to_string(), value.to_string());
+            Ok(true)
+        }
+
+        async fn delete_if_equals(&self, key: &str, expected: &str) -> Result<bool, CacheError> {
+            let mut map = self.0.lock().unwrap();
+            if map.get(key).is_some_and(|v| v == expected) {
+                map.remove(key);
+                return Ok(true);
+            }
+            Ok(false)
+        }
+
+        async fn extend_if_equals(
+            &self,
+            key: &str,
+            expected: &str,
+            _ttl: Duration,
+        ) -> Result<bool, CacheError> {
+            Ok(self.0.lock().unwrap().get(key).is_some_and(|v| v == expected))
+        }
+
+        async fn increment(&self, key: &str, ttl: Duration) -> Result<(u64, Duration), CacheError> {
+            let mut map = self.0.lock().unwrap();
+            let count = map.get(key).map_or(0, |v| v.parse::<u64>().unwrap()) + 1;
+            map.insert(key.to_string(), count.to_string());
+            Ok((count, ttl))
+        }
+    }
+
+    fn limiter(cooldown_secs: u64, max_per_hour: u32) -> ResendLimiter {
+        ResendLimiter::new(
+            Arc::new(MapCache::default()),
+            Duration::from_secs(cooldown_secs),
+            max_per_hour,
+        )
+    }
+
+    #[tokio::test]
+    async fn second_send_within_cooldown_is_refused() {
+        let limiter = limiter(60, 5);
+        let user = UserId::new();
+
+        assert_eq!(limiter.acquire(&user).await, Ok(()));
+        let wait = limiter.acquire(&user).await.unwrap_err();
+        assert!((59..=60).contains(&wait), "{}", wait);
+        assert_eq!(limiter.acquire(&UserId::new()).await, Ok(()));
+    }
+
+    #[tokio::test]
+    async fn hourly_cap_applies_without_cooldown() {
+        let limiter = limiter(0, 2);
+        let user = UserId::new();
+
+        assert_eq!(limiter.acquire(&user).await, Ok(()));
+        assert_eq!(limiter.acquire(&user).await, Ok(()));
+        assert!(limiter.acquire(&user).await.unwrap_err() > 3500);
+    }
+
+    /// Fire `attempts` simultaneous sends for one user, returning how many got through
+    async fn concurrent_successes(limiter: ResendLimiter, attempts: usize) -> usize {
+        let limiter = Arc::new(limiter);
+        let user = UserId::new();
+        let attempts = (0..attempts).map(|_| {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire(&user).await.is_ok() })
+        });
+
+        let mut allowed = 0;
+        for attempt in attempts {
+            allowed += usize::from(attempt.await.unwrap());
+        }
+        allowed
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_sends_never_exceed_the_hourly_cap() {
+        assert_eq!(concurrent_successes(limiter(0, 3), 40).await, 3);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_sends_within_cooldown_admit_exactly_one() {
+        assert_eq!(concurrent_successes(limiter(60, 5), 40).await, 1);
+    }
+
+    #[tokio::test]
+    async fn reset_clears_cooldown_and_counter() {
+        let limiter = limiter(60, 1);
+        let user = UserId::new();
+        limiter.acquire(&user).await.unwrap();
+
+        limiter.reset(&user).await;
+
+        assert_eq!(limiter.acquire(&user).await, Ok(()));
+    }
+}
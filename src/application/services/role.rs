@@ -0,0 +1,126 @@
+use crate::{
+    domain::{
+        repositories::{cache::CacheRepository, user::UserRepository},
+        value_objects::{UserId, UserRole},
+    },
+    shared::AppError,
+};
+use std::{sync::Arc, time::Duration};
+
+/// Cache key holding a user's current role
+pub fn role_cache_key(user_id: &UserId) -> String {
+    format!("user:{}:role", user_id)
+}
+
+/// Looks up a user's current role, caching it so authorization checks do
+/// not hit the database on every request.
+///
+/// Roles are not embedded in access tokens: a role change takes effect on
+/// the next request once `UpdateUserRoleUseCase` drops the cached entry.
+pub struct RoleResolver {
+    users: Arc<dyn UserRepository>,
+    cache: Arc<dyn CacheRepository>,
+    ttl: Duration,
+}
+
+impl RoleResolver {
+    pub fn new(
+        users: Arc<dyn UserRepository>,
+        cache: Arc<dyn CacheRepository>,
+        ttl: Duration,
+    ) -> Self {
+        Self { users, cache, ttl }
+    }
+
+    /// `None` when the user no longer exists.
+    pub async fn resolve(&self, user_id: UserId) -> Result<Option<UserRole>, AppError> {
+        let key = role_cache_key(&user_id);
+
+        match self.cache.get(&key).await {
+            Ok(Some(cached)) => {
+                if let Some(role) = UserRole::parse(&cached) {
+                    return Ok(Some(role));
+                }
+            },
+            Ok(None) => {},
+            Err(e) => tracing::warn!("Role cache lookup failed, using database: {}", e),
+        }
+
+        let Some(user) = self.users.find_by_id(user_id).await? else {
+            return Ok(None);
+        };
+
+        if let Err(e) = self.cache.set(&key, &user.role.to_string(), self.ttl).await {
+            tracing::warn!("Failed to cache role: {}", e);
+        }
+        Ok(Some(user.role))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        entities::User,
+        repositories::{
+            cache::{CacheError, MockCacheRepository},
+            user::MockUserRepository,
+        },
+        value_objects::Email,
+    };
+
+    fn user() -> User {
+        User::new(Email::parse("role@example.com").unwrap(), "Role".to_string()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn cached_role_skips_repository() {
+        let mut cache = MockCacheRepository::new();
+        cache.expect_get().returning(|_| Ok(Some("admin".to_string())));
+
+        let resolver = RoleResolver::new(
+            Arc::new(MockUserRepository::new()),
+            Arc::new(cache),
+            Duration::from_secs(60),
+        );
+
+        assert_eq!(resolver.resolve(UserId::new()).await.unwrap(), Some(UserRole::Admin));
+    }
+
+    #[tokio::test]
+    async fn miss_loads_role_and_caches_it() {
+        let user = user();
+        let user_id = user.id;
+        let key = role_cache_key(&user_id);
+        let mut users = MockUserRepository::new();
+        users.expect_find_by_id().times(1).returning(move |_| Ok(Some(user.clone())));
+        let mut cache = MockCacheRepository::new();
+        cache.expect_get().returning(|_| Ok(None));
+        cache
+            .expect_set()
+            .withf(move |k, v, ttl| k == key && v == "viewer" && *ttl == Duration::from_secs(60))
+            .times(1)
+            .returning(|_, _, _| Ok(()));
+
+        let resolver = RoleResolver::new(Arc::new(users), Arc::new(cache), Duration::from_secs(60));
+
+        assert_eq!(resolver.resolve(user_id).await.unwrap(), Some(UserRole::Viewer));
+    }
+
+    #[tokio::test]
+    async fn cache_failure_falls_back_to_repository() {
+        let user = user();
+        let user_id = user.id;
+        let mut users = MockUserRepository::new();
+        users.expect_find_by_id().returning(move |_| Ok(Some(user.clone())));
+        let mut cache = MockCacheRepository::new();
+        cache.expect_get().returning(|_| Err(CacheError::Backend("down".to_string())));
+        cache
+            .expect_set()
+            .returning(|_, _, _| Err(CacheError::Backend("down".to_string())));
+
+        let resolver = RoleResolver::new(Arc::new(users), Arc::new(cache), Duration::from_secs(60));
+
+        assert_eq!(resolver.resolve(user_id).await.unwrap(), Some(UserRole::Viewer));
+    }
+}
@@ -0,0 +1,15 @@
+use crate::shared::errors::AppError;
+use async_trait::async_trait;
+
+/// Sends text messages, e.g. password reset codes
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait SmsSender: Send + Sync {
+    /// Send `body` to `to`, an E.164 phone number
+    async fn send(&self, to: &str, body: &str) -> Result<(), AppError>;
+
+    /// Name of the delivery backend, used in logs
+    fn provider(&self) -> &'static str {
+        "custom"
+    }
+}
@@ -63,7 +63,18 @@ impl From<RepositoryError> for AppError {
     fn from(err: RepositoryError) -> Self {
         match err {
             RepositoryError::NotFound => AppError::NotFound("Resource not found".to_string()),
-            RepositoryError::DuplicateEmail(msg) => AppError::Validation(msg),
+            RepositoryError::DuplicateEmail(msg) => {
+                tracing::debug!("Unique violation: {}", msg);
+                AppError::Conflict("Email already exists".to_string())
+            },
+            RepositoryError::ForeignKeyViolation(msg) => {
+                tracing::debug!("Foreign key violation: {}", msg);
+                AppError::Validation("Referenced resource does not exist".to_string())
+            },
+            RepositoryError::SerializationFailure(msg) => {
+                tracing::warn!("Serialization failure: {}", msg);
+                AppError::ServiceUnavailable("Database busy, please retry".to_string())
+            },
             RepositoryError::Database(msg) => {
                 tracing::error!("Database error: {}", msg);
                 AppError::Internal(anyhow::anyhow!("Database error"))
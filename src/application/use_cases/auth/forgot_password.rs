@@ -1,6 +1,18 @@
 use crate::{
-    application::services::email::{EmailService, EmailType, Recipient},
+    application::{
+        dto::auth::NotificationChannel,
+        services::{
+            email::{EmailService, EmailType, Recipient},
+            resend::ResendLimiter,
+            sms::SmsSender,
+        },
+    },
     domain::{repositories::AuthRepository, value_objects::Email},
+    shared::{
+        i18n::Locale,
+        telemetry::{email_fingerprint, record_outcome, record_user_id},
+        utils::code_hash::CodeHasher,
+    },
 };
 use std::sync::Arc;
 use tracing::error;
@@ -13,17 +25,27 @@ pub enum ForgotPasswordError {
     #[error("Invalid email format")]
     InvalidEmail,
 
+    #[error("Please wait before requesting another code")]
+    TooManyRequests { retry_after_secs: u64 },
+
     #[error("Repository error: {0}")]
     RepositoryError(String),
 
     #[error("Failed to send email: {0}")]
     EmailError(String),
+
+    #[error("Failed to send text message: {0}")]
+    SmsError(String),
 }
 
 pub struct ForgotPasswordUseCase<R: AuthRepository> {
     auth_repo: Arc<R>,
     email_service: Arc<dyn EmailService>,
     confirm_code_expiry: i64,
+    limiter: Arc<ResendLimiter>,
+    code_hasher: Arc<CodeHasher>,
+    sms_sender: Option<Arc<dyn SmsSender>>,
+    default_channel: NotificationChannel,
 }
 
 impl<R: AuthRepository> ForgotPasswordUseCase<R> {
@@ -31,11 +53,56 @@ impl<R: AuthRepository> ForgotPasswordUseCase<R> {
         auth_repo: Arc<R>,
         email_service: Arc<dyn EmailService>,
         confirm_code_expiry: i64,
+        limiter: Arc<ResendLimiter>,
     ) -> Self {
-        Self { auth_repo, email_service, confirm_code_expiry }
+        Self {
+            auth_repo,
+            email_service,
+            confirm_code_expiry,
+            limiter,
+            code_hasher: Arc::default(),
+            sms_sender: None,
+            default_channel: NotificationChannel::Email,
+        }
+    }
+
+    /// Store codes in the form `hasher` gives them instead of as sent
+    pub fn with_code_hasher(mut self, hasher: Arc<CodeHasher>) -> Self {
+        self.code_hasher = hasher;
+        self
+    }
+
+    /// Allow codes to be texted to users with a verified phone, and choose
+    /// the channel used when a request does not name one
+    pub fn with_sms(mut self, sender: Arc<dyn SmsSender>, default: NotificationChannel) -> Self {
+        self.sms_sender = Some(sender);
+        self.default_channel = default;
+        self
+    }
+
+    #[tracing::instrument(
+        name = "use_case.forgot_password",
+        skip_all,
+        fields(
+            email = %email_fingerprint(&email),
+            user_id = tracing::field::Empty,
+            channel = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+        )
+    )]
+    pub async fn execute(
+        &self,
+        email: String,
+        channel: Option<NotificationChannel>,
+    ) -> Result<String, ForgotPasswordError> {
+        record_outcome(self.run(email, channel).await)
     }
 
-    pub async fn execute(&self, email: String) -> Result<String, ForgotPasswordError> {
+    async fn run(
+        &self,
+        email: String,
+        channel: Option<NotificationChannel>,
+    ) -> Result<String, ForgotPasswordError> {
         let email_vo = Email::parse(&email).map_err(|_| ForgotPasswordError::InvalidEmail)?;
 
         // Find user
@@ -45,6 +112,11 @@ impl<R: AuthRepository> ForgotPasswordUseCase<R> {
             .await
             .map_err(|e| ForgotPasswordError::RepositoryError(e.to_string()))?
             .ok_or(ForgotPasswordError::UserNotFound)?;
+        record_user_id(user.id);
+
+        self.limiter.acquire(&user.id).await.map_err(|retry_after_secs| {
+            ForgotPasswordError::TooManyRequests { retry_after_secs }
+        })?;
 
         // Generate Confirmation Code (CSPRNG, 8-char alphanumeric)
         let confirmation_code = crate::shared::utils::generate_confirmation_code();
@@ -52,15 +124,35 @@ impl<R: AuthRepository> ForgotPasswordUseCase<R> {
         let expires_at = chrono::Utc::now() + chrono::Duration::seconds(self.confirm_code_expiry);
 
         // Update user
-        user.set_confirmation_code(confirmation_code.clone(), expires_at);
+        user.set_confirmation_code(self.code_hasher.stored_form(&confirmation_code), expires_at);
 
         self.auth_repo
             .update_user(&user)
             .await
             .map_err(|e| ForgotPasswordError::RepositoryError(e.to_string()))?;
 
+        // Text the code when asked to and possible; otherwise email it
+        let sms = match channel.unwrap_or(self.default_channel) {
+            NotificationChannel::Sms => self.sms_sender.as_ref().zip(user.verified_phone()),
+            NotificationChannel::Email => None,
+        };
+        tracing::Span::current().record("channel", if sms.is_some() { "sms" } else { "email" });
+
+        if let Some((sender, phone)) = sms {
+            let body = EmailType::PasswordReset(confirmation_code).body();
+            if let Err(e) = sender.send(phone.as_str(), &body).await {
+                error!("Failed to text confirmation code via {}: {}", sender.provider(), e);
+                return Err(ForgotPasswordError::SmsError(e.to_string()));
+            }
+            return Ok("Confirmation code sent to your phone.".to_string());
+        }
+
         // Send confirmation email
-        let recipient = Recipient { email: email_vo.as_str().to_string(), name: user.name.clone() };
+        let recipient = Recipient {
+            email: email_vo.as_str().to_string(),
+            name: user.name.clone(),
+            locale: Locale::from_tag(&user.locale).unwrap_or_default(),
+        };
 
         if let Err(e) = self
             .email_service
@@ -75,3 +167,81 @@ impl<R: AuthRepository> ForgotPasswordUseCase<R> {
         Ok("Confirmation code sent to your email.".to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        application::services::{email::MockEmailService, sms::MockSmsSender},
+        domain::{
+            entities::User,
+            repositories::{auth::MockAuthRepository, cache::MockCacheRepository},
+            value_objects::PhoneNumber,
+        },
+    };
+    use std::time::Duration;
+
+    const PHONE: &str = "+84901234567";
+
+    fn repo(phone_verified: bool) -> MockAuthRepository {
+        let mut user = User::new(Email::parse("lan@example.com").unwrap(), "Lan".into()).unwrap();
+        user.phone = Some(PhoneNumber::parse(PHONE).unwrap());
+        user.is_phone_verified = phone_verified;
+        let mut repo = MockAuthRepository::new();
+        repo.expect_find_by_email().returning(move |_| Ok(Some(user.clone())));
+        repo.expect_update_user().returning(|user| Ok(user.clone()));
+        repo
+    }
+
+    fn limiter() -> Arc<ResendLimiter> {
+        let mut cache = MockCacheRepository::new();
+        cache.expect_increment().returning(|_, ttl| Ok((1, ttl)));
+        Arc::new(ResendLimiter::new(Arc::new(cache), Duration::ZERO, 5))
+    }
+
+    /// Request a code with SMS as the default channel, expecting it on
+    /// exactly one of the two senders
+    async fn request_code(
+        phone_verified: bool,
+        channel: Option<NotificationChannel>,
+        texted: bool,
+    ) -> String {
+        let mut email = MockEmailService::new();
+        email
+            .expect_send()
+            .withf(|_, kind| matches!(kind, EmailType::PasswordReset(_)))
+            .times(usize::from(!texted))
+            .returning(|_, _| Ok(()));
+        let mut sms = MockSmsSender::new();
+        sms.expect_send()
+            .withf(|to, body| to == PHONE && body.starts_with("Your password reset code is: "))
+            .times(usize::from(texted))
+            .returning(|_, _| Ok(()));
+        sms.expect_provider().return_const("mock");
+
+        ForgotPasswordUseCase::new(Arc::new(repo(phone_verified)), Arc::new(email), 600, limiter())
+            .with_sms(Arc::new(sms), NotificationChannel::Sms)
+            .execute("lan@example.com".into(), channel)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn texts_the_code_on_the_default_sms_channel() {
+        let message = request_code(true, None, true).await;
+
+        assert_eq!(message, "Confirmation code sent to your phone.");
+    }
+
+    #[tokio::test]
+    async fn request_can_choose_email_over_the_default() {
+        request_code(true, Some(NotificationChannel::Email), false).await;
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_email_without_a_verified_phone() {
+        let message = request_code(false, Some(NotificationChannel::Sms), false).await;
+
+        assert_eq!(message, "Confirmation code sent to your email.");
+    }
+}
@@ -1,10 +1,29 @@
 use crate::{
-    application::dto::auth::{AuthResponse, UserInfo},
-    domain::{entities::RefreshToken, repositories::AuthRepository},
-    shared::utils::{jwt::JwtManager, password::PasswordManager},
+    application::{
+        dto::auth::{AuthResponse, UserInfo},
+        services::{
+            email::{EmailService, EmailType, Recipient},
+            LoginLockout,
+        },
+    },
+    domain::{
+        entities::{RefreshToken, User},
+        repositories::AuthRepository,
+    },
+    shared::{
+        i18n::Locale,
+        tasks::TaskRegistry,
+        telemetry::{email_fingerprint, record_outcome, record_user_id},
+        utils::{
+            code_hash::CodeHasher,
+            jwt::JwtManager,
+            password::{PasswordManager, Peppers},
+        },
+    },
 };
 
-use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use std::{net::IpAddr, sync::Arc};
 
 #[derive(Debug, thiserror::Error)]
 pub enum LoginError {
@@ -14,36 +33,229 @@ pub enum LoginError {
     #[error("User account is inactive")]
     AccountInactive,
 
+    #[error("Account is locked after too many failed logins, try again later")]
+    AccountLocked { retry_after_secs: u64 },
+
     #[error("Repository error: {0}")]
     RepositoryError(String),
 
     #[error("Token creation failed: {0}")]
     TokenCreationError(String),
+
+    #[error("Maximum number of active sessions reached")]
+    SessionLimitReached,
+}
+
+/// What a login does when the user already has the maximum number of sessions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SessionLimitPolicy {
+    /// Revoke the oldest refresh tokens to make room
+    #[default]
+    EvictOldest,
+    /// Refuse the login until a session ends
+    Reject,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct SessionLimit {
+    max_active: usize,
+    policy: SessionLimitPolicy,
 }
 
 pub struct LoginUseCase<R: AuthRepository> {
     auth_repo: Arc<R>,
     jwt_manager: Arc<JwtManager>,
+    session_limit: Option<SessionLimit>,
+    peppers: Arc<Peppers>,
+    code_hasher: Arc<CodeHasher>,
+    lockout: Option<Arc<LoginLockout>>,
+    lockout_alerts: Option<Arc<dyn EmailService>>,
+    tasks: Arc<TaskRegistry>,
 }
 
 impl<R: AuthRepository> LoginUseCase<R> {
     pub fn new(auth_repo: Arc<R>, jwt_manager: Arc<JwtManager>) -> Self {
-        Self { auth_repo, jwt_manager }
+        Self {
+            auth_repo,
+            jwt_manager,
+            session_limit: None,
+            peppers: Arc::default(),
+            code_hasher: Arc::default(),
+            lockout: None,
+            lockout_alerts: None,
+            tasks: Arc::default(),
+        }
+    }
+
+    /// Lock accounts after repeated failed logins
+    pub fn with_lockout(mut self, lockout: Arc<LoginLockout>) -> Self {
+        self.lockout = Some(lockout);
+        self
+    }
+
+    /// Email the user when failed logins lock their account
+    pub fn with_lockout_alerts(mut self, email_service: Arc<dyn EmailService>) -> Self {
+        self.lockout_alerts = Some(email_service);
+        self
+    }
+
+    /// Send lockout alerts under `tasks`; one still sending at shutdown is
+    /// abandoned and logged
+    pub fn with_tasks(mut self, tasks: Arc<TaskRegistry>) -> Self {
+        self.tasks = tasks;
+        self
+    }
+
+    /// Look codes up in the form `hasher` stored them
+    pub fn with_code_hasher(mut self, hasher: Arc<CodeHasher>) -> Self {
+        self.code_hasher = hasher;
+        self
+    }
+
+    /// Verify against any configured pepper. A password hashed with a retired
+    /// pepper, or none, is rehashed with the current one on successful login.
+    pub fn with_peppers(mut self, peppers: Arc<Peppers>) -> Self {
+        self.peppers = peppers;
+        self
+    }
+
+    /// Cap concurrent sessions (active refresh tokens) per user
+    pub fn with_session_limit(mut self, max_active: u32, policy: SessionLimitPolicy) -> Self {
+        self.session_limit = Some(SessionLimit { max_active: max_active as usize, policy });
+        self
+    }
+
+    /// Store the new session, first making room for it or refusing it if
+    /// the user is at the session limit. The count and the save happen
+    /// together in the repository, so parallel logins cannot overshoot.
+    async fn save_session(&self, token: &RefreshToken) -> Result<(), LoginError> {
+        let Some(limit) = self.session_limit else {
+            return self
+                .auth_repo
+                .save_refresh_token(token)
+                .await
+                .map_err(|e| LoginError::RepositoryError(e.to_string()));
+        };
+
+        let evict_oldest = limit.policy == SessionLimitPolicy::EvictOldest;
+        let evicted = self
+            .auth_repo
+            .save_refresh_token_within_limit(token, limit.max_active, evict_oldest)
+            .await
+            .map_err(|e| LoginError::RepositoryError(e.to_string()))?
+            .ok_or(LoginError::SessionLimitReached)?;
+        if evicted > 0 {
+            tracing::info!("Evicted {} oldest session(s) for user {}", evicted, token.user_id);
+        }
+        Ok(())
+    }
+
+    /// Count a failed login for `email`, telling `user`, the account with the
+    /// address if there is one, when it locked them out
+    async fn record_failure(
+        &self,
+        email: &str,
+        user: Option<&User>,
+        client_ip: IpAddr,
+    ) -> LoginError {
+        let Some(lockout) = &self.lockout else {
+            return LoginError::InvalidCredentials;
+        };
+        let Some(locked_at) = lockout.record_failure(email).await else {
+            return LoginError::InvalidCredentials;
+        };
+
+        if let Some(user) = user {
+            tracing::warn!(%client_ip, "Locked user {} after repeated failed logins", user.id);
+            self.send_lockout_alert(user, client_ip, locked_at);
+        }
+        LoginError::AccountLocked { retry_after_secs: lockout.locked_for(email).await.unwrap_or(1) }
     }
 
+    /// Sent in the background, so the locking response does not wait on email
+    /// delivery; a failed alert is logged and the account stays locked
+    fn send_lockout_alert(&self, user: &User, client_ip: IpAddr, locked_at: DateTime<Utc>) {
+        let Some(email_service) = self.lockout_alerts.clone() else {
+            return;
+        };
+        let recipient = Recipient {
+            email: user.email.as_str().to_string(),
+            name: user.name.clone(),
+            locale: Locale::from_tag(&user.locale).unwrap_or_default(),
+        };
+        let user_id = user.id;
+        self.tasks.spawn("lockout_alert", move |token| async move {
+            let alert =
+                email_service.send(recipient, EmailType::AccountLocked(client_ip, locked_at));
+            tokio::select! {
+                result = alert => {
+                    if let Err(e) = result {
+                        tracing::error!("Failed to send lockout alert to user {}: {}", user_id, e);
+                    }
+                },
+                _ = token.cancelled() => {
+                    tracing::warn!("Lockout alert to user {} not sent: shutting down", user_id);
+                },
+            }
+        });
+    }
+
+    /// Store a hash keyed with the current pepper. Not a password change, so
+    /// `password_changed_at` is left alone; failure keeps the old hash working.
+    async fn upgrade_password_hash(&self, user: &mut User, hash: String) {
+        user.password_hash = Some(hash);
+        match self.auth_repo.update_user(user).await {
+            Ok(_) => tracing::info!("Rehashed password for user {} with current pepper", user.id),
+            Err(e) => tracing::warn!("Failed to rehash password for user {}: {}", user.id, e),
+        }
+    }
+
+    #[tracing::instrument(
+        name = "use_case.login",
+        skip_all,
+        fields(
+            email = %email_fingerprint(&email),
+            method = if code.is_some() { "code" } else { "password" },
+            user_id = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+        )
+    )]
     pub async fn execute(
         &self,
         email: String,
         password: Option<String>,
         code: Option<String>,
+        client_ip: IpAddr,
     ) -> Result<AuthResponse, LoginError> {
+        record_outcome(self.run(email, password, code, client_ip).await)
+    }
+
+    async fn run(
+        &self,
+        email: String,
+        password: Option<String>,
+        code: Option<String>,
+        client_ip: IpAddr,
+    ) -> Result<AuthResponse, LoginError> {
+        // Refused before the credentials are checked, so guessing stops too.
+        // Locks are per address, so this holds for unknown addresses as well.
+        if let Some(lockout) = &self.lockout {
+            if let Some(retry_after_secs) = lockout.locked_for(&email).await {
+                return Err(LoginError::AccountLocked { retry_after_secs });
+            }
+        }
+
         // Find user by email
-        let mut user = self // Mut because we might consume code
+        let user = self
             .auth_repo
             .find_by_email(&email)
             .await
-            .map_err(|e| LoginError::RepositoryError(e.to_string()))?
-            .ok_or(LoginError::InvalidCredentials)?;
+            .map_err(|e| LoginError::RepositoryError(e.to_string()))?;
+        let Some(mut user) = user else {
+            // Counted like a wrong password, so unknown addresses lock too
+            return Err(self.record_failure(&email, None, client_ip).await);
+        };
+        record_user_id(user.id);
 
         // Check if account is active
         // For code login, maybe allow inactive if it's the verification step?
@@ -54,73 +266,90 @@ impl<R: AuthRepository> LoginUseCase<R> {
 
         let mut credentials_valid = false;
 
-        // Check Code
+        // Check Code. Until a password is set, the code left over from
+        // verification is reserved for password setup and cannot log in.
         if let Some(c) = code {
-            let code_matches =
-                user.confirmation_code.as_ref().is_some_and(|user_code| user_code == &c);
-            let not_expired = user
-                .confirmation_code_expires_at
-                .is_some_and(|expires_at| chrono::Utc::now() <= expires_at);
-
-            if code_matches && not_expired {
-                credentials_valid = true;
-                // Consume code to prevent replay
-                user.confirmation_code = None;
-                user.confirmation_code_expires_at = None;
-                self.auth_repo
-                    .update_user(&user)
+            if user.password_hash.is_some() {
+                // Validated and cleared in one update, so a replay fails
+                credentials_valid = self
+                    .auth_repo
+                    .consume_confirmation_code(
+                        *user.id.as_uuid(),
+                        &self.code_hasher.stored_form(&c),
+                    )
                     .await
                     .map_err(|e| LoginError::RepositoryError(e.to_string()))?;
+                if credentials_valid {
+                    user.confirmation_code = None;
+                    user.confirmation_code_expires_at = None;
+                }
             }
         }
         // Check Password if code didn't validate (or wasn't provided)
         else if let Some(p) = password {
-            if let Some(hash) = &user.password_hash {
-                if PasswordManager::verify(&p, hash).unwrap_or(false) {
+            if let Some(hash) = user.password_hash.clone() {
+                let peppers = self.peppers.clone();
+                // `None` for a wrong password, else the replacement hash if one is due
+                let verified = tokio::task::spawn_blocking(move || {
+                    if !PasswordManager::verify_peppered(&p, &hash, &peppers).unwrap_or(false) {
+                        return None;
+                    }
+                    let rehash = PasswordManager::needs_rehash(&hash, &peppers)
+                        .then(|| PasswordManager::hash_peppered(&p, &peppers).ok())
+                        .flatten();
+                    Some(rehash)
+                })
+                .await
+                .map_err(|e| LoginError::RepositoryError(e.to_string()))?;
+
+                if let Some(rehash) = verified {
                     credentials_valid = true;
+                    if let Some(new_hash) = rehash {
+                        self.upgrade_password_hash(&mut user, new_hash).await;
+                    }
                 }
             }
         }
 
         if !credentials_valid {
-            return Err(LoginError::InvalidCredentials);
+            return Err(self.record_failure(&email, Some(&user), client_ip).await);
+        }
+        if let Some(lockout) = &self.lockout {
+            lockout.reset(&email).await;
         }
 
-        // Update last login
-        self.auth_repo
-            .update_last_login(*user.id.as_uuid())
-            .await
-            .map_err(|e| LoginError::RepositoryError(e.to_string()))?;
-
-        // Generate tokens
+        // Generate tokens, with lifetimes that may depend on the user's role
+        let role = user.role.to_string();
         let access_token = self
             .jwt_manager
-            .create_access_token(*user.id.as_uuid())
+            .issue_access_token_for_role(*user.id.as_uuid(), &role)
             .map_err(|e| LoginError::TokenCreationError(e.to_string()))?;
 
         let refresh_token = self
             .jwt_manager
-            .create_refresh_token(*user.id.as_uuid())
+            .issue_refresh_token_for_role(*user.id.as_uuid(), &role)
             .map_err(|e| LoginError::TokenCreationError(e.to_string()))?;
 
         // Store refresh token (hash before storing to protect against DB breach)
-        let token_hash = crate::shared::utils::hash_token(&refresh_token);
-        let refresh_token_entity = RefreshToken::new(
-            *user.id.as_uuid(),
-            token_hash,
-            chrono::Utc::now() + self.jwt_manager.get_refresh_token_expiry(),
-        );
+        let token_hash = crate::shared::utils::hash_token(&refresh_token.token);
+        let refresh_token_entity =
+            RefreshToken::new(*user.id.as_uuid(), token_hash, refresh_token.expires_at);
 
+        self.save_session(&refresh_token_entity).await?;
+
+        // Update last login
         self.auth_repo
-            .save_refresh_token(&refresh_token_entity)
+            .update_last_login(*user.id.as_uuid())
             .await
             .map_err(|e| LoginError::RepositoryError(e.to_string()))?;
 
         Ok(AuthResponse {
-            access_token,
-            refresh_token,
+            access_token: access_token.token,
+            refresh_token: refresh_token.token,
             token_type: "Bearer".to_string(),
-            expires_in: self.jwt_manager.get_access_token_expiry_seconds(),
+            expires_in: self.jwt_manager.get_access_token_expiry_seconds_for_role(&role),
+            access_token_expires_at: access_token.expires_at.to_rfc3339(),
+            refresh_token_expires_at: refresh_token.expires_at.to_rfc3339(),
             user: UserInfo {
                 id: user.id.as_uuid().to_string(),
                 email: user.email.as_str().to_string(),
@@ -129,3 +358,57 @@ impl<R: AuthRepository> LoginUseCase<R> {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{repositories::auth::MockAuthRepository, value_objects::Email},
+        shared::telemetry::spans::capture::SpanCapture,
+    };
+
+    fn jwt_manager() -> Arc<JwtManager> {
+        Arc::new(
+            JwtManager::new(
+                "test_secret_that_is_long_enough_32chars".to_string(),
+                3600,
+                86400,
+                "test-issuer".to_string(),
+                "test-audience".to_string(),
+            )
+            .unwrap(),
+        )
+    }
+
+    #[tokio::test]
+    async fn login_span_records_the_user_and_outcome_but_not_the_address() {
+        let mut user = User::new(Email::parse("lan@example.com").unwrap(), "Lan".into()).unwrap();
+        user.is_active = true;
+        let user_id = user.id.to_string();
+        let mut repo = MockAuthRepository::new();
+        repo.expect_find_by_email().returning(move |_| Ok(Some(user.clone())));
+        let capture = SpanCapture::default();
+        let _guard = capture.install();
+
+        let result = LoginUseCase::new(Arc::new(repo), jwt_manager())
+            .execute(
+                "lan@example.com".into(),
+                Some("hunter2hunter2".into()),
+                None,
+                std::net::Ipv4Addr::LOCALHOST.into(),
+            )
+            .await;
+
+        assert!(matches!(result, Err(LoginError::InvalidCredentials)));
+        let spans = capture.spans();
+        let span = spans.iter().find(|s| s.name == "use_case.login").unwrap();
+        assert_eq!(span.fields["outcome"], "InvalidCredentials");
+        assert_eq!(span.fields["user_id"], user_id);
+        assert_eq!(span.fields["method"], "password");
+        assert_eq!(
+            span.fields["email"],
+            crate::shared::telemetry::email_fingerprint("lan@example.com")
+        );
+        assert!(span.fields.values().all(|v| !v.contains("lan@") && !v.contains("hunter2")));
+    }
+}
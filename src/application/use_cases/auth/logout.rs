@@ -1,4 +1,7 @@
-use crate::domain::repositories::{AuthRepository, AuthRepositoryError};
+use crate::{
+    domain::repositories::{AuthRepository, AuthRepositoryError},
+    shared::telemetry::record_outcome,
+};
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -21,7 +24,18 @@ impl<R: AuthRepository> LogoutUseCase<R> {
     }
 
     /// Logout from current session (revoke specific refresh token)
+    #[tracing::instrument(
+        name = "use_case.logout",
+        skip_all,
+        fields(
+            outcome = tracing::field::Empty,
+        )
+    )]
     pub async fn execute(&self, refresh_token: &str) -> Result<(), LogoutError> {
+        record_outcome(self.run(refresh_token).await)
+    }
+
+    async fn run(&self, refresh_token: &str) -> Result<(), LogoutError> {
         // Hash the raw token to match the stored hash
         let token_hash = crate::shared::utils::hash_token(refresh_token);
         self.auth_repo.revoke_refresh_token(&token_hash).await.map_err(|e| match e {
@@ -31,7 +45,19 @@ impl<R: AuthRepository> LogoutUseCase<R> {
     }
 
     /// Logout from all sessions (revoke all user's refresh tokens)
+    #[tracing::instrument(
+        name = "use_case.logout_all",
+        skip_all,
+        fields(
+            user_id = %user_id,
+            outcome = tracing::field::Empty,
+        )
+    )]
     pub async fn execute_all(&self, user_id: Uuid) -> Result<(), LogoutError> {
+        record_outcome(self.run_all(user_id).await)
+    }
+
+    async fn run_all(&self, user_id: Uuid) -> Result<(), LogoutError> {
         self.auth_repo
             .revoke_all_user_tokens(user_id)
             .await
@@ -7,8 +7,8 @@ pub mod set_password;
 pub mod verify_email;
 
 pub use forgot_password::ForgotPasswordUseCase;
-pub use login::LoginUseCase;
-pub use logout::LogoutUseCase;
+pub use login::{LoginError, LoginUseCase, SessionLimitPolicy};
+pub use logout::{LogoutError, LogoutUseCase};
 pub use register::RegisterUseCase;
 pub use set_password::SetPasswordUseCase;
 pub use verify_email::VerifyEmailUseCase;
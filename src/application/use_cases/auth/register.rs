@@ -1,11 +1,21 @@
 use crate::{
     application::{
+        commands::ClaimInvitationCommand,
         dto::auth::{RegisterResponse, UserInfo},
-        services::email::{EmailService, EmailType, Recipient},
+        services::{
+            email::{EmailService, EmailType, Recipient},
+            DistributedLock, LockGuard,
+        },
     },
     domain::{
-        repositories::{AuthRepository, AuthRepositoryError},
-        value_objects::Email,
+        entities::{Invitation, User},
+        repositories::{AuthRepository, AuthRepositoryError, InvitationRepository},
+        value_objects::{DisposableDomains, Email, EmailDomainPolicy, UserRole},
+    },
+    shared::{
+        i18n::Locale,
+        telemetry::{email_fingerprint, record_outcome, record_user_id},
+        utils::{code_hash::CodeHasher, hash_token, verification_link::VerificationLinks},
     },
 };
 use std::sync::Arc;
@@ -19,6 +29,21 @@ pub enum RegisterError {
     #[error("Invalid email format")]
     InvalidEmail,
 
+    #[error("Registration is not open to addresses at {0}")]
+    EmailDomainNotAllowed(String),
+
+    #[error("Disposable email addresses are not accepted")]
+    DisposableEmail,
+
+    #[error("Invitation is invalid, expired or already used")]
+    InvalidInvitation,
+
+    #[error("A registration for this email is already in progress")]
+    RegistrationInProgress,
+
+    #[error("{0}")]
+    InvalidName(String),
+
     #[error("Password hashing failed: {0}")]
     PasswordHashError(String),
 
@@ -36,6 +61,13 @@ pub struct RegisterUseCase<R: AuthRepository> {
     auth_repo: Arc<R>,
     email_service: Arc<dyn EmailService>,
     confirm_code_expiry: i64,
+    default_role: UserRole,
+    code_hasher: Arc<CodeHasher>,
+    domain_policy: EmailDomainPolicy,
+    disposable_domains: Option<Arc<DisposableDomains>>,
+    invitations: Option<ClaimInvitationCommand>,
+    verification_links: Option<Arc<VerificationLinks>>,
+    lock: Option<Arc<DistributedLock>>,
 }
 
 impl<R: AuthRepository> RegisterUseCase<R> {
@@ -43,14 +75,146 @@ impl<R: AuthRepository> RegisterUseCase<R> {
         auth_repo: Arc<R>,
         email_service: Arc<dyn EmailService>,
         confirm_code_expiry: i64,
+        default_role: UserRole,
     ) -> Self {
-        Self { auth_repo, email_service, confirm_code_expiry }
+        Self {
+            auth_repo,
+            email_service,
+            confirm_code_expiry,
+            default_role,
+            code_hasher: Arc::default(),
+            domain_policy: EmailDomainPolicy::default(),
+            disposable_domains: None,
+            invitations: None,
+            verification_links: None,
+            lock: None,
+        }
+    }
+
+    /// Refuse addresses whose domain `policy` does not permit
+    pub fn with_domain_policy(mut self, policy: EmailDomainPolicy) -> Self {
+        self.domain_policy = policy;
+        self
+    }
+
+    /// Refuse addresses at throwaway mailbox providers
+    pub fn with_disposable_domains(mut self, domains: Arc<DisposableDomains>) -> Self {
+        self.disposable_domains = Some(domains);
+        self
+    }
+
+    /// Accept invitation tokens; without this every token is refused
+    pub fn with_invitations(mut self, invitations: Arc<dyn InvitationRepository>) -> Self {
+        self.invitations = Some(ClaimInvitationCommand::new(invitations));
+        self
+    }
+
+    /// Put a verification link next to the code in the confirmation email
+    pub fn with_verification_links(mut self, links: Arc<VerificationLinks>) -> Self {
+        self.verification_links = Some(links);
+        self
     }
 
+    /// Store codes in the form `hasher` gives them instead of as sent
+    pub fn with_code_hasher(mut self, hasher: Arc<CodeHasher>) -> Self {
+        self.code_hasher = hasher;
+        self
+    }
+
+    /// Hold `lock` per address while registering, so concurrent requests for
+    /// one address do not both create the user and email a code
+    pub fn with_lock(mut self, lock: Arc<DistributedLock>) -> Self {
+        self.lock = Some(lock);
+        self
+    }
+
+    #[tracing::instrument(
+        name = "use_case.register",
+        skip_all,
+        fields(
+            email = %email_fingerprint(&email),
+            locale = %locale,
+            invited = invite_token.is_some(),
+            user_id = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+        )
+    )]
     pub async fn execute(
         &self,
         email: String,
         name: String,
+        locale: Locale,
+        invite_token: Option<String>,
+    ) -> Result<RegisterResponse, RegisterError> {
+        record_outcome(self.run(email, name, locale, invite_token).await)
+    }
+
+    /// Claim the invitation behind `token`, so no one else can register with it
+    async fn claim_invitation(&self, token: &str) -> Result<Invitation, RegisterError> {
+        let invitations = self.invitations.as_ref().ok_or(RegisterError::InvalidInvitation)?;
+        invitations
+            .execute(token)
+            .await
+            .map_err(|e| RegisterError::RepositoryError(e.to_string()))?
+            .ok_or(RegisterError::InvalidInvitation)
+    }
+
+    /// The address and normalized name, if both are acceptable and no user
+    /// has the address yet
+    async fn check_new_user(
+        &self,
+        email: &str,
+        name: &str,
+    ) -> Result<(Email, String), RegisterError> {
+        let email = Email::parse(email).map_err(|_| RegisterError::InvalidEmail)?;
+        if !self.domain_policy.permits(&email) {
+            return Err(RegisterError::EmailDomainNotAllowed(email.domain().to_string()));
+        }
+        if self.disposable_domains.as_ref().is_some_and(|d| d.contains(&email)) {
+            return Err(RegisterError::DisposableEmail);
+        }
+        let name =
+            User::normalize_name(name).map_err(|e| RegisterError::InvalidName(e.to_string()))?;
+
+        if (self
+            .auth_repo
+            .find_by_email(email.as_str())
+            .await
+            .map_err(|e| RegisterError::RepositoryError(e.to_string()))?)
+        .is_some()
+        {
+            return Err(RegisterError::EmailAlreadyExists);
+        }
+        Ok((email, name))
+    }
+
+    /// Take the registration lock on `email`; `None` when there is no lock
+    async fn lock_email(&self, email: &str) -> Result<Option<LockGuard>, RegisterError> {
+        let Some(lock) = &self.lock else {
+            return Ok(None);
+        };
+        // Hashed so the cache keys do not hold addresses
+        let name = format!("register:{}", hash_token(&email.trim().to_lowercase()));
+        lock.try_acquire(&name)
+            .await
+            .map_err(|e| RegisterError::RepositoryError(e.to_string()))?
+            .map(Some)
+            .ok_or(RegisterError::RegistrationInProgress)
+    }
+
+    /// Hand back an invitation claimed for a registration that failed
+    async fn release_invitation(&self, invitation: &Invitation) {
+        if let Some(invitations) = &self.invitations {
+            invitations.release(invitation).await;
+        }
+    }
+
+    async fn run(
+        &self,
+        email: String,
+        name: String,
+        locale: Locale,
+        invite_token: Option<String>,
     ) -> Result<RegisterResponse, RegisterError> {
         // Return type might change to simple check?
         // Instructions: "user call register api, in this api, we need send confirm code"
@@ -65,19 +229,27 @@ impl<R: AuthRepository> RegisterUseCase<R> {
         // Let's change return type to Result<(), RegisterError> or Result<String, RegisterError>.
         // But `AuthResponse` is defined in DTO.
 
-        // Validate email format
-        let email_vo = Email::parse(&email).map_err(|_| RegisterError::InvalidEmail)?;
-
-        // Check if user already exists
-        if (self
-            .auth_repo
-            .find_by_email(email_vo.as_str())
-            .await
-            .map_err(|e| RegisterError::RepositoryError(e.to_string()))?)
-        .is_some()
-        {
-            return Err(RegisterError::EmailAlreadyExists);
-        }
+        // The invitation is checked before anything else, so a caller let past
+        // closed registration by a bogus token learns nothing about the email
+        let invitation = match invite_token {
+            Some(token) => Some(self.claim_invitation(&token).await?),
+            None => None,
+        };
+        let checked = match self.lock_email(&email).await {
+            Ok(guard) => self.check_new_user(&email, &name).await.map(|checked| (guard, checked)),
+            Err(e) => Err(e),
+        };
+        // The guard is held until registration finishes
+        let (_guard, (email_vo, name)) = match checked {
+            Ok(checked) => checked,
+            Err(e) => {
+                if let Some(invitation) = &invitation {
+                    self.release_invitation(invitation).await;
+                }
+                return Err(e);
+            },
+        };
+        let role = invitation.as_ref().and_then(|i| i.role).unwrap_or(self.default_role);
 
         // Generate Confirmation Code (CSPRNG, 8-char alphanumeric)
         let confirmation_code = crate::shared::utils::generate_confirmation_code();
@@ -91,21 +263,43 @@ impl<R: AuthRepository> RegisterUseCase<R> {
                 email_vo.as_str(),
                 &name,
                 None, // No password
-                Some(confirmation_code.clone()),
+                Some(self.code_hasher.stored_form(&confirmation_code)),
                 Some(expires_at),
+                locale.as_str(),
+                role,
             )
-            .await
-            .map_err(|e| match e {
-                AuthRepositoryError::EmailAlreadyExists => RegisterError::EmailAlreadyExists,
-                _ => RegisterError::RepositoryError(e.to_string()),
-            })?;
+            .await;
+        let user = match user {
+            Ok(user) => user,
+            Err(e) => {
+                if let Some(invitation) = &invitation {
+                    self.release_invitation(invitation).await;
+                }
+                return Err(match e {
+                    AuthRepositoryError::EmailAlreadyExists => RegisterError::EmailAlreadyExists,
+                    _ => RegisterError::RepositoryError(e.to_string()),
+                });
+            },
+        };
+        record_user_id(user.id);
+        if let (Some(invitation), Some(invitations)) = (&invitation, &self.invitations) {
+            invitations.complete(invitation, *user.id.as_uuid()).await;
+        }
 
         // Send confirmation email
-        let recipient = Recipient { email: email_vo.as_str().to_string(), name: user.name.clone() };
+        let link = self
+            .verification_links
+            .as_ref()
+            .map(|l| l.link_for(*user.id.as_uuid(), email_vo.as_str()));
+        let recipient = Recipient {
+            email: email_vo.as_str().to_string(),
+            name: user.name.clone(),
+            locale: Locale::from_tag(&user.locale).unwrap_or_default(),
+        };
 
         if let Err(e) = self
             .email_service
-            .send(recipient, EmailType::Confirmation(confirmation_code))
+            .send(recipient, EmailType::Confirmation(confirmation_code, link))
             .await
         {
             error!("Failed to send confirmation email: {}", e);
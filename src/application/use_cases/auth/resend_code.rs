@@ -1,6 +1,14 @@
 use crate::{
-    application::services::email::{EmailService, EmailType, Recipient},
+    application::services::{
+        email::{EmailService, EmailType, Recipient},
+        resend::ResendLimiter,
+    },
     domain::{repositories::AuthRepository, value_objects::Email},
+    shared::{
+        i18n::Locale,
+        telemetry::{email_fingerprint, record_outcome, record_user_id},
+        utils::{code_hash::CodeHasher, verification_link::VerificationLinks},
+    },
 };
 use std::sync::Arc;
 use tracing::error;
@@ -16,6 +24,9 @@ pub enum ResendConfirmCodeError {
     #[error("User already verified")]
     UserAlreadyVerified,
 
+    #[error("Please wait before requesting another code")]
+    TooManyRequests { retry_after_secs: u64 },
+
     #[error("Repository error: {0}")]
     RepositoryError(String),
 
@@ -27,6 +38,9 @@ pub struct ResendConfirmCodeUseCase<R: AuthRepository> {
     auth_repo: Arc<R>,
     email_service: Arc<dyn EmailService>,
     confirm_code_expiry: i64,
+    limiter: Arc<ResendLimiter>,
+    code_hasher: Arc<CodeHasher>,
+    verification_links: Option<Arc<VerificationLinks>>,
 }
 
 impl<R: AuthRepository> ResendConfirmCodeUseCase<R> {
@@ -34,11 +48,44 @@ impl<R: AuthRepository> ResendConfirmCodeUseCase<R> {
         auth_repo: Arc<R>,
         email_service: Arc<dyn EmailService>,
         confirm_code_expiry: i64,
+        limiter: Arc<ResendLimiter>,
     ) -> Self {
-        Self { auth_repo, email_service, confirm_code_expiry }
+        Self {
+            auth_repo,
+            email_service,
+            confirm_code_expiry,
+            limiter,
+            code_hasher: Arc::default(),
+            verification_links: None,
+        }
     }
 
+    /// Put a fresh verification link next to the code
+    pub fn with_verification_links(mut self, links: Arc<VerificationLinks>) -> Self {
+        self.verification_links = Some(links);
+        self
+    }
+
+    /// Store codes in the form `hasher` gives them instead of as sent
+    pub fn with_code_hasher(mut self, hasher: Arc<CodeHasher>) -> Self {
+        self.code_hasher = hasher;
+        self
+    }
+
+    #[tracing::instrument(
+        name = "use_case.resend_code",
+        skip_all,
+        fields(
+            email = %email_fingerprint(&email),
+            user_id = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+        )
+    )]
     pub async fn execute(&self, email: String) -> Result<String, ResendConfirmCodeError> {
+        record_outcome(self.run(email).await)
+    }
+
+    async fn run(&self, email: String) -> Result<String, ResendConfirmCodeError> {
         let email_vo = Email::parse(&email).map_err(|_| ResendConfirmCodeError::InvalidEmail)?;
 
         // Find user
@@ -48,19 +95,24 @@ impl<R: AuthRepository> ResendConfirmCodeUseCase<R> {
             .await
             .map_err(|e| ResendConfirmCodeError::RepositoryError(e.to_string()))?
             .ok_or(ResendConfirmCodeError::UserNotFound)?;
+        record_user_id(user.id);
 
         // Check verification status
         if user.is_email_verified {
             return Err(ResendConfirmCodeError::UserAlreadyVerified);
         }
 
+        self.limiter.acquire(&user.id).await.map_err(|retry_after_secs| {
+            ResendConfirmCodeError::TooManyRequests { retry_after_secs }
+        })?;
+
         // Generate Confirmation Code (CSPRNG, 8-char alphanumeric)
         let confirmation_code = crate::shared::utils::generate_confirmation_code();
 
         let expires_at = chrono::Utc::now() + chrono::Duration::seconds(self.confirm_code_expiry);
 
         // Update user
-        user.set_confirmation_code(confirmation_code.clone(), expires_at);
+        user.set_confirmation_code(self.code_hasher.stored_form(&confirmation_code), expires_at);
 
         self.auth_repo
             .update_user(&user)
@@ -68,11 +120,19 @@ impl<R: AuthRepository> ResendConfirmCodeUseCase<R> {
             .map_err(|e| ResendConfirmCodeError::RepositoryError(e.to_string()))?;
 
         // Send confirmation email
-        let recipient = Recipient { email: email_vo.as_str().to_string(), name: user.name.clone() };
+        let link = self
+            .verification_links
+            .as_ref()
+            .map(|l| l.link_for(*user.id.as_uuid(), email_vo.as_str()));
+        let recipient = Recipient {
+            email: email_vo.as_str().to_string(),
+            name: user.name.clone(),
+            locale: Locale::from_tag(&user.locale).unwrap_or_default(),
+        };
 
         if let Err(e) = self
             .email_service
-            .send(recipient, EmailType::Confirmation(confirmation_code))
+            .send(recipient, EmailType::ConfirmationResent(confirmation_code, link))
             .await
         {
             error!("Failed to send confirmation email: {}", e);
@@ -1,8 +1,18 @@
 use crate::{
-    domain::{repositories::AuthRepository, value_objects::Email},
-    shared::utils::password::PasswordManager,
+    domain::{
+        repositories::{AuthRepository, PasswordHistoryRepository},
+        value_objects::{Email, PasswordRuleViolation, PasswordRules},
+    },
+    shared::{
+        telemetry::{email_fingerprint, record_outcome, record_user_id},
+        utils::{
+            code_hash::CodeHasher,
+            password::{PasswordManager, Peppers},
+        },
+    },
 };
-use std::sync::Arc;
+use chrono::{DateTime, Utc};
+use std::{sync::Arc, time::Duration};
 
 #[derive(Debug, thiserror::Error)]
 pub enum SetPasswordError {
@@ -18,6 +28,15 @@ pub enum SetPasswordError {
     #[error("Confirmation code expired")]
     CodeExpired,
 
+    #[error("Password was used recently, choose a different one")]
+    PasswordReused,
+
+    #[error("{0}")]
+    PasswordRejected(#[from] PasswordRuleViolation),
+
+    #[error("Password was changed too recently, try again later")]
+    ChangedTooRecently { retry_after_secs: u64 },
+
     #[error("Password hashing failed: {0}")]
     PasswordHashError(String),
 
@@ -25,20 +44,93 @@ pub enum SetPasswordError {
     RepositoryError(String),
 }
 
+/// Sets a password with a valid confirmation code. The new password may not
+/// match the current one or any of the last `history_size` set; zero turns
+/// the check off.
+///
+/// This is the self-service path (register and forgot-password). Passwords
+/// written elsewhere, such as by imports, skip both the reuse check and the
+/// minimum change interval; so does one completing an admin-forced reset.
+///
+/// The history entry is written after the password, and failing to write it
+/// fails the request; build both repositories on one transaction so the
+/// password is not changed without it.
 pub struct SetPasswordUseCase<R: AuthRepository> {
     auth_repo: Arc<R>,
+    history: Arc<dyn PasswordHistoryRepository>,
+    history_size: usize,
+    min_change_interval: Duration,
+    rules: PasswordRules,
+    peppers: Arc<Peppers>,
+    code_hasher: Arc<CodeHasher>,
 }
 
 impl<R: AuthRepository> SetPasswordUseCase<R> {
-    pub fn new(auth_repo: Arc<R>) -> Self {
-        Self { auth_repo }
+    pub fn new(
+        auth_repo: Arc<R>,
+        history: Arc<dyn PasswordHistoryRepository>,
+        history_size: usize,
+    ) -> Self {
+        Self {
+            auth_repo,
+            history,
+            history_size,
+            min_change_interval: Duration::ZERO,
+            rules: PasswordRules::default(),
+            peppers: Arc::default(),
+            code_hasher: Arc::default(),
+        }
+    }
+
+    /// Look codes up in the form `hasher` stored them
+    pub fn with_code_hasher(mut self, hasher: Arc<CodeHasher>) -> Self {
+        self.code_hasher = hasher;
+        self
+    }
+
+    /// Key new hashes with the current pepper; retired ones still verify
+    /// during the reuse check
+    pub fn with_peppers(mut self, peppers: Arc<Peppers>) -> Self {
+        self.peppers = peppers;
+        self
+    }
+
+    /// Refuse a change within `interval` of the previous one. Setting the
+    /// first password is never refused.
+    pub fn with_min_change_interval(mut self, interval: Duration) -> Self {
+        self.min_change_interval = interval;
+        self
+    }
+
+    /// Refuse new passwords that break any of `rules`
+    pub fn with_password_rules(mut self, rules: PasswordRules) -> Self {
+        self.rules = rules;
+        self
     }
 
+    #[tracing::instrument(
+        name = "use_case.set_password",
+        skip_all,
+        fields(
+            email = %email_fingerprint(&email),
+            user_id = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+        )
+    )]
     pub async fn execute(
         &self,
         email: String,
         code: String,
         new_password: String,
+    ) -> Result<String, SetPasswordError> {
+        record_outcome(self.run(email, code, new_password).await)
+    }
+
+    async fn run(
+        &self,
+        email: String,
+        code: String,
+        new_password: String,
     ) -> Result<String, SetPasswordError> {
         let email_vo = Email::parse(&email).map_err(|_| SetPasswordError::InvalidEmail)?;
 
@@ -48,10 +140,11 @@ impl<R: AuthRepository> SetPasswordUseCase<R> {
             .await
             .map_err(|e| SetPasswordError::RepositoryError(e.to_string()))?
             .ok_or(SetPasswordError::UserNotFound)?;
+        record_user_id(user.id);
 
         // Check code
         match &user.confirmation_code {
-            Some(c) if c == &code => {
+            Some(stored) if self.code_hasher.matches(stored, &code) => {
                 if let Some(expires_at) = user.confirmation_code_expires_at {
                     if chrono::Utc::now() > expires_at {
                         return Err(SetPasswordError::CodeExpired);
@@ -63,15 +156,54 @@ impl<R: AuthRepository> SetPasswordUseCase<R> {
             _ => return Err(SetPasswordError::InvalidCode),
         }
 
-        // Hash password
-        let password_hash =
-            tokio::task::spawn_blocking(move || PasswordManager::hash(&new_password))
+        self.rules.check(&new_password, &email_vo)?;
+
+        // An admin-forced reset must be completable straight away
+        if let Some(changed_at) = user.password_changed_at.filter(|_| !user.password_reset_forced) {
+            let wait = self.wait_after(changed_at, chrono::Utc::now());
+            if wait > 0 {
+                return Err(SetPasswordError::ChangedTooRecently { retry_after_secs: wait });
+            }
+        }
+
+        if self.history_size > 0 {
+            let mut previous = self
+                .history
+                .recent(user.id, self.history_size)
                 .await
-                .map_err(|e| SetPasswordError::PasswordHashError(e.to_string()))?
-                .map_err(|e| SetPasswordError::PasswordHashError(e.to_string()))?;
+                .map_err(|e| SetPasswordError::RepositoryError(e.to_string()))?;
+            // Accounts from before password history have only the current hash
+            if let Some(current) = &user.password_hash {
+                if !previous.contains(current) {
+                    previous.push(current.clone());
+                }
+            }
+
+            let candidate = new_password.clone();
+            let peppers = self.peppers.clone();
+            let reused = tokio::task::spawn_blocking(move || {
+                previous.iter().any(|hash| {
+                    PasswordManager::verify_peppered(&candidate, hash, &peppers).unwrap_or(false)
+                })
+            })
+            .await
+            .map_err(|e| SetPasswordError::PasswordHashError(e.to_string()))?;
+            if reused {
+                return Err(SetPasswordError::PasswordReused);
+            }
+        }
+
+        // Hash password
+        let peppers = self.peppers.clone();
+        let password_hash = tokio::task::spawn_blocking(move || {
+            PasswordManager::hash_peppered(&new_password, &peppers)
+        })
+        .await
+        .map_err(|e| SetPasswordError::PasswordHashError(e.to_string()))?
+        .map_err(|e| SetPasswordError::PasswordHashError(e.to_string()))?;
 
         // Set password and clear code (now we can clear it, as password is set)
-        user.set_password(password_hash);
+        user.set_password(password_hash.clone());
         user.confirmation_code = None;
         user.confirmation_code_expires_at = None;
         user.is_active = true;
@@ -82,6 +214,80 @@ impl<R: AuthRepository> SetPasswordUseCase<R> {
             .await
             .map_err(|e| SetPasswordError::RepositoryError(e.to_string()))?;
 
+        if self.history_size > 0 {
+            self.history
+                .record(user.id, &password_hash, self.history_size)
+                .await
+                .map_err(|e| SetPasswordError::RepositoryError(e.to_string()))?;
+        }
+
         Ok("Password set successfully.".to_string())
     }
+
+    /// Seconds from `now` until a password changed at `changed_at` may be
+    /// changed again. An interval too large to add to `changed_at` never
+    /// elapses.
+    fn wait_after(&self, changed_at: DateTime<Utc>, now: DateTime<Utc>) -> u64 {
+        let allowed_at = chrono::Duration::from_std(self.min_change_interval)
+            .ok()
+            .and_then(|interval| changed_at.checked_add_signed(interval));
+        match allowed_at {
+            Some(allowed_at) => u64::try_from((allowed_at - now).num_seconds()).unwrap_or(0),
+            None => self.min_change_interval.as_secs(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        entities::User,
+        repositories::{
+            auth::MockAuthRepository, password_history::MockPasswordHistoryRepository,
+            user::RepositoryError,
+        },
+    };
+
+    fn use_case(interval: Duration) -> SetPasswordUseCase<MockAuthRepository> {
+        SetPasswordUseCase::new(
+            Arc::new(MockAuthRepository::new()),
+            Arc::new(MockPasswordHistoryRepository::new()),
+            0,
+        )
+        .with_min_change_interval(interval)
+    }
+
+    #[test]
+    fn intervals_past_the_calendar_never_elapse_instead_of_panicking() {
+        let now = Utc::now();
+        let changed_at = now - chrono::Duration::hours(1);
+
+        assert_eq!(use_case(Duration::from_secs(7200)).wait_after(changed_at, now), 3600);
+        assert_eq!(use_case(Duration::from_secs(60)).wait_after(changed_at, now), 0);
+        assert_eq!(use_case(Duration::MAX).wait_after(changed_at, now), u64::MAX);
+        let huge = Duration::from_secs(i64::MAX as u64 / 1000);
+        assert_eq!(use_case(huge).wait_after(changed_at, now), huge.as_secs());
+    }
+
+    #[tokio::test]
+    async fn failing_to_record_history_fails_the_change() {
+        let mut user = User::new(Email::parse("lan@example.com").unwrap(), "Lan".into()).unwrap();
+        user.set_confirmation_code("123456".into(), Utc::now() + chrono::Duration::hours(1));
+        let mut auth = MockAuthRepository::new();
+        auth.expect_find_by_email().returning(move |_| Ok(Some(user.clone())));
+        auth.expect_update_user().times(1).returning(|user| Ok(user.clone()));
+        let mut history = MockPasswordHistoryRepository::new();
+        history.expect_recent().returning(|_, _| Ok(Vec::new()));
+        history
+            .expect_record()
+            .times(1)
+            .returning(|_, _, _| Err(RepositoryError::Database("disk full".into())));
+
+        let result = SetPasswordUseCase::new(Arc::new(auth), Arc::new(history), 5)
+            .execute("lan@example.com".into(), "123456".into(), "N3w-Passw0rd!".into())
+            .await;
+
+        assert!(matches!(result, Err(SetPasswordError::RepositoryError(_))));
+    }
 }
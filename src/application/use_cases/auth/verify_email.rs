@@ -1,4 +1,14 @@
-use crate::domain::{repositories::AuthRepository, value_objects::Email};
+use crate::{
+    application::{dto::auth::VerifyEmailResponse, services::resend::ResendLimiter},
+    domain::{entities::User, repositories::AuthRepository, value_objects::Email},
+    shared::{
+        telemetry::{email_fingerprint, record_outcome, record_user_id},
+        utils::{
+            code_hash::CodeHasher,
+            verification_link::{LinkError, VerificationLinks},
+        },
+    },
+};
 use std::sync::Arc;
 use tracing::error;
 
@@ -16,20 +26,71 @@ pub enum VerifyEmailError {
     #[error("Confirmation code expired")]
     CodeExpired,
 
+    #[error("Invalid verification link")]
+    InvalidLink,
+
+    #[error("Verification link expired")]
+    LinkExpired,
+
     #[error("Repository error: {0}")]
     RepositoryError(String),
 }
 
 pub struct VerifyEmailUseCase<R: AuthRepository> {
     auth_repo: Arc<R>,
+    resend_limiter: Arc<ResendLimiter>,
+    code_hasher: Arc<CodeHasher>,
+    verification_links: Option<Arc<VerificationLinks>>,
+}
+
+impl From<LinkError> for VerifyEmailError {
+    fn from(e: LinkError) -> Self {
+        match e {
+            LinkError::Invalid => Self::InvalidLink,
+            LinkError::Expired => Self::LinkExpired,
+        }
+    }
 }
 
 impl<R: AuthRepository> VerifyEmailUseCase<R> {
-    pub fn new(auth_repo: Arc<R>) -> Self {
-        Self { auth_repo }
+    pub fn new(auth_repo: Arc<R>, resend_limiter: Arc<ResendLimiter>) -> Self {
+        Self { auth_repo, resend_limiter, code_hasher: Arc::default(), verification_links: None }
+    }
+
+    /// Accept links signed by `links`; without this every link is refused
+    pub fn with_verification_links(mut self, links: Arc<VerificationLinks>) -> Self {
+        self.verification_links = Some(links);
+        self
+    }
+
+    /// Look codes up in the form `hasher` stored them
+    pub fn with_code_hasher(mut self, hasher: Arc<CodeHasher>) -> Self {
+        self.code_hasher = hasher;
+        self
     }
 
-    pub async fn execute(&self, email: String, code: String) -> Result<String, VerifyEmailError> {
+    #[tracing::instrument(
+        name = "use_case.verify_email",
+        skip_all,
+        fields(
+            email = %email_fingerprint(&email),
+            user_id = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+        )
+    )]
+    pub async fn execute(
+        &self,
+        email: String,
+        code: String,
+    ) -> Result<VerifyEmailResponse, VerifyEmailError> {
+        record_outcome(self.run(email, code).await)
+    }
+
+    async fn run(
+        &self,
+        email: String,
+        code: String,
+    ) -> Result<VerifyEmailResponse, VerifyEmailError> {
         let email_vo = Email::parse(&email).map_err(|_| VerifyEmailError::InvalidEmail)?;
 
         let mut user = self
@@ -38,10 +99,11 @@ impl<R: AuthRepository> VerifyEmailUseCase<R> {
             .await
             .map_err(|e| VerifyEmailError::RepositoryError(e.to_string()))?
             .ok_or(VerifyEmailError::UserNotFound)?;
+        record_user_id(user.id);
 
         // Check if code matches
         match &user.confirmation_code {
-            Some(c) if c == &code => {
+            Some(stored) if self.code_hasher.matches(stored, &code) => {
                 // Check expiry
                 if let Some(expires_at) = user.confirmation_code_expires_at {
                     if chrono::Utc::now() > expires_at {
@@ -55,7 +117,61 @@ impl<R: AuthRepository> VerifyEmailUseCase<R> {
             _ => return Err(VerifyEmailError::InvalidCode),
         }
 
-        // Verify user
+        // A user with a password needs the code for nothing else, so spend it
+        // now; otherwise it stays valid for the password setup step
+        if user.password_hash.is_some() {
+            let consumed = self
+                .auth_repo
+                .consume_confirmation_code(*user.id.as_uuid(), &self.code_hasher.stored_form(&code))
+                .await
+                .map_err(|e| VerifyEmailError::RepositoryError(e.to_string()))?;
+            if !consumed {
+                return Err(VerifyEmailError::InvalidCode);
+            }
+            user.confirmation_code = None;
+            user.confirmation_code_expires_at = None;
+        }
+
+        self.mark_verified(user).await
+    }
+
+    /// Verify the address a link from the confirmation email was issued for.
+    ///
+    /// The link expires on its own schedule, not the code's, and leaves the
+    /// code alone so it can still be used to set a password.
+    #[tracing::instrument(
+        name = "use_case.verify_email_link",
+        skip_all,
+        fields(user_id = tracing::field::Empty, outcome = tracing::field::Empty)
+    )]
+    pub async fn execute_link(&self, token: &str) -> Result<VerifyEmailResponse, VerifyEmailError> {
+        record_outcome(self.run_link(token).await)
+    }
+
+    async fn run_link(&self, token: &str) -> Result<VerifyEmailResponse, VerifyEmailError> {
+        let links = self.verification_links.as_ref().ok_or(VerifyEmailError::InvalidLink)?;
+        let token = links.parse(token)?;
+
+        let user = self
+            .auth_repo
+            .find_by_id(token.user_id)
+            .await
+            .map_err(|e| VerifyEmailError::RepositoryError(e.to_string()))?
+            .ok_or(VerifyEmailError::InvalidLink)?;
+        record_user_id(user.id);
+        links.verify(&token, user.email.as_str(), chrono::Utc::now())?;
+
+        // Opening the link twice is harmless
+        if user.is_email_verified {
+            return Ok(VerifyEmailResponse {
+                verified: true,
+                requires_password_setup: user.password_hash.is_none(),
+            });
+        }
+        self.mark_verified(user).await
+    }
+
+    async fn mark_verified(&self, mut user: User) -> Result<VerifyEmailResponse, VerifyEmailError> {
         user.verify_email();
 
         self.auth_repo
@@ -63,6 +179,12 @@ impl<R: AuthRepository> VerifyEmailUseCase<R> {
             .await
             .map_err(|e| VerifyEmailError::RepositoryError(e.to_string()))?;
 
-        Ok("Email verified successfully.".to_string())
+        // Codes sent while unverified should not count against later flows
+        self.resend_limiter.reset(&user.id).await;
+
+        Ok(VerifyEmailResponse {
+            verified: true,
+            requires_password_setup: user.password_hash.is_none(),
+        })
     }
 }
@@ -5,8 +5,8 @@ pub mod user;
 
 // Re-export for backward compatibility
 pub use auth::{
-    ForgotPasswordUseCase, LoginUseCase, LogoutUseCase, RegisterUseCase, ResendConfirmCodeUseCase,
-    SetPasswordUseCase, VerifyEmailUseCase,
+    ForgotPasswordUseCase, LoginError, LoginUseCase, LogoutError, LogoutUseCase, RegisterUseCase,
+    ResendConfirmCodeUseCase, SessionLimitPolicy, SetPasswordUseCase, VerifyEmailUseCase,
 };
 pub use user::{
     CreateUserUseCase, GetUserRoleUseCase, GetUserUseCase, ImportUsersUseCase, ListUsersUseCase,
@@ -1,7 +1,10 @@
 use crate::{
     application::dto::CreateUserDto,
     domain::{entities::User, repositories::user_repository::UserRepository, value_objects::Email},
-    shared::AppError,
+    shared::{
+        telemetry::{email_fingerprint, record_outcome, record_user_id},
+        AppError,
+    },
 };
 use std::sync::Arc;
 use validator::Validate;
@@ -16,7 +19,20 @@ impl<R: UserRepository> CreateUserUseCase<R> {
         Self { user_repository }
     }
 
+    #[tracing::instrument(
+        name = "use_case.create_user",
+        skip_all,
+        fields(
+            email = %email_fingerprint(&dto.email),
+            user_id = tracing::field::Empty,
+            outcome = tracing::field::Empty,
+        )
+    )]
     pub async fn execute(&self, dto: CreateUserDto) -> Result<User, AppError> {
+        record_outcome(self.run(dto).await)
+    }
+
+    async fn run(&self, dto: CreateUserDto) -> Result<User, AppError> {
         // Validate input
         dto.validate().map_err(|e| AppError::Validation(e.to_string()))?;
 
@@ -26,7 +42,7 @@ impl<R: UserRepository> CreateUserUseCase<R> {
 
         // Check if user already exists
         if self.user_repository.exists_by_email(&email).await? {
-            return Err(AppError::Validation(format!(
+            return Err(AppError::Conflict(format!(
                 "User with email {} already exists",
                 dto.email
             )));
@@ -38,6 +54,7 @@ impl<R: UserRepository> CreateUserUseCase<R> {
 
         // Save to repository
         let saved_user = self.user_repository.save(&user).await?;
+        record_user_id(saved_user.id);
 
         tracing::info!("User created successfully: {}", saved_user.id);
 
@@ -2,7 +2,7 @@ use crate::{
     domain::{
         entities::User, repositories::user_repository::UserRepository, value_objects::UserId,
     },
-    shared::AppError,
+    shared::{telemetry::record_outcome, AppError},
 };
 use std::sync::Arc;
 use uuid::Uuid;
@@ -17,7 +17,19 @@ impl<R: UserRepository> GetUserUseCase<R> {
         Self { user_repository }
     }
 
+    #[tracing::instrument(
+        name = "use_case.get_user",
+        skip_all,
+        fields(
+            user_id = %user_id,
+            outcome = tracing::field::Empty,
+        )
+    )]
     pub async fn execute(&self, user_id: &str) -> Result<User, AppError> {
+        record_outcome(self.run(user_id).await)
+    }
+
+    async fn run(&self, user_id: &str) -> Result<User, AppError> {
         // Parse UUID
         let uuid = Uuid::parse_str(user_id)
             .map_err(|_| AppError::Validation("Invalid user ID format".to_string()))?;
@@ -1,59 +1,263 @@
 use crate::{
-    application::actors::user_import_actor::{UserCreationActor, UserCreationMsg},
-    domain::repositories::AuthRepository,
-    shared::utils::password::PasswordManager,
+    application::{
+        actors::user_import_actor::{UserCreationActor, UserCreationMsg},
+        dto::user::{ImportJobDto, ImportJobState, ImportReportDto, ImportRowErrorDto},
+        services::{
+            events::{EventBatch, EventPublisher},
+            ImportJobs,
+        },
+    },
+    domain::{
+        entities::User,
+        events::v2::UserCreated,
+        repositories::AuthRepository,
+        value_objects::{Email, UserRole},
+    },
+    shared::{
+        tasks::TaskRegistry,
+        telemetry::record_outcome,
+        utils::password::{PasswordManager, Peppers},
+    },
 };
 use ractor::Actor;
 use serde::Deserialize;
-use std::sync::Arc;
+use std::{collections::HashSet, sync::Arc};
 use thiserror::Error;
+use tokio::sync::{mpsc, Semaphore};
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// User creations an import runs at once unless told otherwise
+pub const DEFAULT_CONCURRENCY: usize = 4;
+
+/// Columns every import must have. Columns are matched by header name,
+/// ignoring case and surrounding spaces, so they may come in any order.
+pub const REQUIRED_COLUMNS: [&str; 3] = ["email", "name", "password"];
 
 #[derive(Debug, Deserialize)]
 pub struct CsvUserRecord {
     pub email: String,
     pub name: String,
     pub password: String,
+    /// Optional column; absent or blank gives the default role
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+impl CsvUserRecord {
+    /// Check the row can be imported, returning the role to create it with
+    fn validate(&self) -> Result<UserRole, String> {
+        Email::parse(&self.email).map_err(|e| format!("invalid email '{}': {}", self.email, e))?;
+        match self.role.as_deref().map(str::trim) {
+            None | Some("") => Ok(UserRole::default()),
+            Some(role) => UserRole::parse(role).ok_or_else(|| format!("invalid role '{}'", role)),
+        }
+    }
+}
+
+/// Reader over `csv_data` with lowercased headers, once every required
+/// column is known to be present
+fn reader(csv_data: &[u8]) -> Result<csv::Reader<&[u8]>, ImportUsersError> {
+    let mut rdr = csv::ReaderBuilder::new().trim(csv::Trim::Headers).from_reader(csv_data);
+    let headers: csv::StringRecord = rdr
+        .headers()
+        .map_err(|e| ImportUsersError::CsvError(e.to_string()))?
+        .iter()
+        .map(str::to_ascii_lowercase)
+        .collect();
+    let missing: Vec<String> = REQUIRED_COLUMNS
+        .iter()
+        .filter(|column| !headers.iter().any(|header| header == **column))
+        .map(|column| column.to_string())
+        .collect();
+    if !missing.is_empty() {
+        return Err(ImportUsersError::MissingColumns(missing));
+    }
+    rdr.set_headers(headers);
+    Ok(rdr)
 }
 
 #[derive(Debug, Error)]
 pub enum ImportUsersError {
     #[error("CSV parsing error: {0}")]
     CsvError(String),
+    #[error("CSV is missing required column(s): {}", .0.join(", "))]
+    MissingColumns(Vec<String>),
     #[error("Repository error: {0}")]
     RepositoryError(String),
     #[error("Internal error: {0}")]
     Internal(String),
     #[error("Actor error: {0}")]
     ActorError(String),
+    #[error("Import cancelled by server shutdown")]
+    Cancelled,
 }
 
 pub struct ImportUsersUseCase<R: AuthRepository + 'static> {
     auth_repo: Arc<R>,
+    peppers: Arc<Peppers>,
+    events: Option<(Arc<dyn EventPublisher>, usize)>,
+    /// One permit per user creation in flight
+    in_flight: Arc<Semaphore>,
+    jobs: Option<Arc<ImportJobs>>,
+    tasks: Arc<TaskRegistry>,
 }
 
 impl<R: AuthRepository + 'static> ImportUsersUseCase<R> {
     pub fn new(auth_repo: Arc<R>) -> Self {
-        Self { auth_repo }
+        Self {
+            auth_repo,
+            peppers: Arc::default(),
+            events: None,
+            in_flight: Arc::new(Semaphore::new(DEFAULT_CONCURRENCY)),
+            jobs: None,
+            tasks: Arc::default(),
+        }
+    }
+
+    /// Run at most `limit` user creations at once, so a large import cannot
+    /// take every database connection. Rows past the limit wait, unhashed,
+    /// until an earlier creation finishes.
+    pub fn with_concurrency(mut self, limit: usize) -> Self {
+        self.in_flight = Arc::new(Semaphore::new(limit.max(1)));
+        self
+    }
+
+    /// Publish a `UserCreated` event per imported user, `batch_size` per
+    /// publish and in CSV order
+    pub fn with_events(mut self, publisher: Arc<dyn EventPublisher>, batch_size: usize) -> Self {
+        self.events = Some((publisher, batch_size));
+        self
+    }
+
+    /// Key imported hashes with the current pepper
+    pub fn with_peppers(mut self, peppers: Arc<Peppers>) -> Self {
+        self.peppers = peppers;
+        self
+    }
+
+    /// Track background imports in `jobs`; without it `start` is refused
+    pub fn with_jobs(mut self, jobs: Arc<ImportJobs>) -> Self {
+        self.jobs = Some(jobs);
+        self
+    }
+
+    /// Run background imports under `tasks`, so shutdown cancels them and
+    /// marks their jobs failed rather than leaving them running
+    pub fn with_tasks(mut self, tasks: Arc<TaskRegistry>) -> Self {
+        self.tasks = tasks;
+        self
     }
 
+    #[tracing::instrument(
+        name = "use_case.import_users",
+        skip_all,
+        fields(
+            bytes = csv_data.len(),
+            outcome = tracing::field::Empty,
+        )
+    )]
     pub async fn execute(&self, csv_data: &[u8]) -> Result<usize, ImportUsersError> {
-        let mut rdr = csv::Reader::from_reader(csv_data);
+        record_outcome(self.run(csv_data, None).await)
+    }
+
+    /// Import `csv_data` in the background and return the job to poll with
+    /// `status`. Missing columns are refused straight away; a bad row or an
+    /// internal error later fails the job instead.
+    pub async fn start(
+        self: &Arc<Self>,
+        csv_data: Vec<u8>,
+    ) -> Result<ImportJobDto, ImportUsersError> {
+        let Some(jobs) = self.jobs.clone() else {
+            return Err(ImportUsersError::Internal("Background imports are not enabled".into()));
+        };
+        let total = reader(&csv_data)?.records().count();
+        let job = jobs.start(total).await;
+
+        let this = self.clone();
+        let tracked = job.clone();
+        self.tasks.spawn("user_import", move |token| async move {
+            this.run_job(&csv_data, &jobs, tracked, token).await
+        });
+        Ok(job)
+    }
+
+    /// Latest state of a background import, or `None` if the job is unknown
+    /// or finished too long ago
+    pub async fn status(&self, job_id: &Uuid) -> Option<ImportJobDto> {
+        self.jobs.as_ref()?.get(job_id).await
+    }
+
+    #[tracing::instrument(
+        name = "use_case.import_users_job",
+        skip_all,
+        fields(
+            job_id = %job.job_id,
+            outcome = tracing::field::Empty,
+        )
+    )]
+    async fn run_job(
+        &self,
+        csv_data: &[u8],
+        jobs: &ImportJobs,
+        mut job: ImportJobDto,
+        token: CancellationToken,
+    ) {
+        let result = tokio::select! {
+            biased;
+            () = token.cancelled() => Err(ImportUsersError::Cancelled),
+            result = self.run(csv_data, Some((jobs, &mut job))) => result,
+        };
+        match record_outcome(result) {
+            Ok(_) => job.state = ImportJobState::Completed,
+            Err(e) => {
+                job.state = ImportJobState::Failed;
+                job.error = Some(e.to_string());
+            },
+        }
+        jobs.save(&job).await;
+    }
+
+    /// With a `job`, its progress is saved as each row is handed off
+    async fn run(
+        &self,
+        csv_data: &[u8],
+        mut job: Option<(&ImportJobs, &mut ImportJobDto)>,
+    ) -> Result<usize, ImportUsersError> {
+        let mut rdr = reader(csv_data)?;
         let mut count = 0;
         let mut handles = Vec::new();
+        let (created_tx, mut created_rx) = mpsc::unbounded_channel();
 
         for result in rdr.deserialize::<CsvUserRecord>() {
             let record = result.map_err(|e| ImportUsersError::CsvError(e.to_string()))?;
+            let role = record
+                .validate()
+                .map_err(|e| ImportUsersError::CsvError(format!("row {}: {}", count + 1, e)))?;
+
+            // Backpressure: wait for a free slot before doing any work on the row
+            let permit = self
+                .in_flight
+                .clone()
+                .acquire_owned()
+                .await
+                .map_err(|e| ImportUsersError::Internal(e.to_string()))?;
 
             // Hash password — Argon2 is CPU-heavy, run off the async executor
             let password = record.password.clone();
-            let password_hash =
-                tokio::task::spawn_blocking(move || PasswordManager::hash(&password))
-                    .await
-                    .map_err(|e| ImportUsersError::Internal(e.to_string()))?
-                    .map_err(|e| ImportUsersError::Internal(e.to_string()))?;
+            let peppers = self.peppers.clone();
+            let password_hash = tokio::task::spawn_blocking(move || {
+                PasswordManager::hash_peppered(&password, &peppers)
+            })
+            .await
+            .map_err(|e| ImportUsersError::Internal(e.to_string()))?
+            .map_err(|e| ImportUsersError::Internal(e.to_string()))?;
 
             // Spawn a new actor (process) for every user
-            let actor_impl = UserCreationActor::new(self.auth_repo.clone());
+            let mut actor_impl = UserCreationActor::new(self.auth_repo.clone()).holding(permit);
+            if self.events.is_some() {
+                actor_impl = actor_impl.reporting_to(count, created_tx.clone());
+            }
             let (actor_ref, handle) = Actor::spawn(None, actor_impl, ())
                 .await
                 .map_err(|e| ImportUsersError::ActorError(e.to_string()))?;
@@ -64,11 +268,16 @@ impl<R: AuthRepository + 'static> ImportUsersUseCase<R> {
                     email: record.email,
                     name: record.name,
                     password_hash,
+                    role,
                 })
                 .map_err(|e| ImportUsersError::ActorError(e.to_string()))?;
 
             handles.push(handle);
             count += 1;
+            if let Some((jobs, job)) = job.as_mut() {
+                job.processed = count;
+                jobs.save(job).await;
+            }
         }
 
         // Wait for all actors to finish processing
@@ -76,6 +285,306 @@ impl<R: AuthRepository + 'static> ImportUsersUseCase<R> {
             handle.await.map_err(|e| ImportUsersError::ActorError(e.to_string()))?;
         }
 
+        let mut created = Vec::new();
+        while let Ok(entry) = created_rx.try_recv() {
+            created.push(entry);
+        }
+        created.sort_by_key(|(row, _)| *row);
+        self.publish_created(created.into_iter().map(|(_, user)| user)).await;
+
         Ok(count)
     }
+
+    /// Check every row as an import would, without creating users or
+    /// publishing events. Unlike an import, a bad row does not stop the
+    /// check; each one is listed in the report.
+    #[tracing::instrument(
+        name = "use_case.import_users_dry_run",
+        skip_all,
+        fields(
+            bytes = csv_data.len(),
+            outcome = tracing::field::Empty,
+        )
+    )]
+    pub async fn dry_run(&self, csv_data: &[u8]) -> Result<ImportReportDto, ImportUsersError> {
+        record_outcome(self.run_dry(csv_data).await)
+    }
+
+    async fn run_dry(&self, csv_data: &[u8]) -> Result<ImportReportDto, ImportUsersError> {
+        let mut rdr = reader(csv_data)?;
+        let mut report = ImportReportDto { rows: 0, created: 0, skipped: 0, errors: Vec::new() };
+        let mut seen = HashSet::new();
+
+        for result in rdr.deserialize::<CsvUserRecord>() {
+            report.rows += 1;
+            let row = report.rows;
+            let checked = result.map_err(|e| e.to_string()).and_then(|record| {
+                record.validate()?;
+                if !seen.insert(record.email.to_ascii_lowercase()) {
+                    return Err(format!("duplicate email '{}'", record.email));
+                }
+                Ok(record)
+            });
+            let record = match checked {
+                Ok(record) => record,
+                Err(error) => {
+                    report.errors.push(ImportRowErrorDto { row, error });
+                    continue;
+                },
+            };
+
+            let existing = self
+                .auth_repo
+                .find_by_email(&record.email)
+                .await
+                .map_err(|e| ImportUsersError::RepositoryError(e.to_string()))?;
+            match existing {
+                Some(_) => report.skipped += 1,
+                None => report.created += 1,
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Announce the users an import created. Failures are only logged: the
+    /// users exist either way, as with role change events.
+    async fn publish_created(&self, users: impl Iterator<Item = User>) {
+        let Some((publisher, batch_size)) = &self.events else {
+            return;
+        };
+
+        let mut batch = EventBatch::new(publisher.as_ref(), *batch_size);
+        for user in users {
+            let event = UserCreated {
+                user_id: *user.id.as_uuid(),
+                role: user.role,
+                occurred_at: user.created_at,
+            };
+            if let Err(e) = batch.push(&event).await {
+                tracing::warn!("Failed to publish imported users: {:?}", e);
+            }
+        }
+        if let Err(e) = batch.flush().await {
+            tracing::warn!("Failed to publish imported users: {:?}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        application::services::{
+            events::{MockEventPublisher, OutboundEvent},
+            import_jobs::JOB_TTL,
+        },
+        domain::{
+            events::{v2::USER_CREATED, EventEnvelope},
+            repositories::{auth::MockAuthRepository, cache::MockCacheRepository},
+            value_objects::Email,
+        },
+    };
+    use std::{collections::HashMap, sync::Mutex, time::Duration};
+
+    #[tokio::test]
+    async fn import_publishes_created_users_in_batches_in_csv_order() {
+        let emails: Vec<_> = (0..5).map(|i| format!("user{}@example.com", i)).collect();
+        let ids_by_email = Arc::new(Mutex::new(HashMap::new()));
+        let mut repo = MockAuthRepository::new();
+        repo.expect_find_by_email().returning(|_| Ok(None));
+        let created = ids_by_email.clone();
+        repo.expect_create_user().times(5).returning(move |email, name, _, _, _, _, _| {
+            let user = User::new(Email::parse(email).unwrap(), name.to_string()).unwrap();
+            created.lock().unwrap().insert(email.to_string(), *user.id.as_uuid());
+            Ok(user)
+        });
+
+        let batches: Arc<Mutex<Vec<Vec<OutboundEvent>>>> = Arc::default();
+        let capture = batches.clone();
+        let mut publisher = MockEventPublisher::new();
+        publisher.expect_publish_batch().times(3).returning(move |events| {
+            capture.lock().unwrap().push(events);
+            Ok(())
+        });
+        publisher.expect_publish().never();
+
+        let csv: String = emails
+            .iter()
+            .enumerate()
+            .map(|(i, email)| format!("{},User {},password{}\n", email, i, i))
+            .fold("email,name,password\n".to_string(), |csv, row| csv + &row);
+        let count = ImportUsersUseCase::new(Arc::new(repo))
+            .with_events(Arc::new(publisher), 2)
+            .execute(csv.as_bytes())
+            .await
+            .unwrap();
+
+        assert_eq!(count, 5);
+        let batches = batches.lock().unwrap();
+        assert_eq!(batches.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 2, 1]);
+        let published: Vec<_> = batches
+            .iter()
+            .flatten()
+            .map(|event| {
+                assert_eq!(event.subject, USER_CREATED);
+                serde_json::from_slice::<EventEnvelope<UserCreated>>(&event.payload)
+                    .unwrap()
+                    .event
+                    .user_id
+            })
+            .collect();
+        let ids_by_email = ids_by_email.lock().unwrap();
+        let expected: Vec<_> = emails.iter().map(|email| ids_by_email[email]).collect();
+        assert_eq!(published, expected);
+    }
+
+    /// Repository that records the email, name and role of each created user
+    fn recording_repo(created: Arc<Mutex<Vec<(String, String, UserRole)>>>) -> MockAuthRepository {
+        let mut repo = MockAuthRepository::new();
+        repo.expect_find_by_email().returning(|_| Ok(None));
+        repo.expect_create_user().returning(move |email, name, _, _, _, _, role| {
+            created.lock().unwrap().push((email.to_string(), name.to_string(), role));
+            let mut user = User::new(Email::parse(email).unwrap(), name.to_string()).unwrap();
+            user.role = role;
+            Ok(user)
+        });
+        repo
+    }
+
+    #[tokio::test]
+    async fn columns_are_matched_by_header_in_any_order() {
+        let created = Arc::new(Mutex::new(Vec::new()));
+        let csv = " Password ,ROLE,email,name\n\
+                   secret1,editor,lan@example.com,Lan\n\
+                   secret2,,minh@example.com,Minh\n";
+
+        let count = ImportUsersUseCase::new(Arc::new(recording_repo(created.clone())))
+            .execute(csv.as_bytes())
+            .await
+            .unwrap();
+
+        assert_eq!(count, 2);
+        let mut created = created.lock().unwrap().clone();
+        created.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            created,
+            vec![
+                ("lan@example.com".to_string(), "Lan".to_string(), UserRole::Editor),
+                ("minh@example.com".to_string(), "Minh".to_string(), UserRole::default()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_required_headers_are_named_before_any_user_is_created() {
+        let mut repo = MockAuthRepository::new();
+        repo.expect_create_user().never();
+
+        let result = ImportUsersUseCase::new(Arc::new(repo))
+            .execute(b"email,role\nlan@example.com,viewer\n")
+            .await;
+
+        assert!(
+            matches!(&result, Err(ImportUsersError::MissingColumns(columns)) if columns == &["name", "password"]),
+            "{:?}",
+            result
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "CSV is missing required column(s): name, password"
+        );
+    }
+
+    #[tokio::test]
+    async fn unknown_roles_are_rejected_with_their_row() {
+        let mut repo = MockAuthRepository::new();
+        repo.expect_create_user().never();
+
+        let result = ImportUsersUseCase::new(Arc::new(repo))
+            .execute(b"email,name,password,role\nlan@example.com,Lan,secret1,owner\n")
+            .await;
+
+        assert!(
+            matches!(&result, Err(ImportUsersError::CsvError(msg)) if msg == "row 1: invalid role 'owner'"),
+            "{:?}",
+            result
+        );
+    }
+
+    #[tokio::test]
+    async fn dry_run_reports_rows_without_creating_users() {
+        let mut repo = MockAuthRepository::new();
+        repo.expect_find_by_email().returning(|email| {
+            Ok((email == "old@example.com")
+                .then(|| User::new(Email::parse(email).unwrap(), "Old".to_string()).unwrap()))
+        });
+        repo.expect_create_user().never();
+        let mut publisher = MockEventPublisher::new();
+        publisher.expect_publish_batch().never();
+        publisher.expect_publish().never();
+
+        let csv = "email,name,password,role\n\
+                   new@example.com,New,secret1,editor\n\
+                   old@example.com,Old,secret2,\n\
+                   bad@example.com,Bad,secret3,owner\n\
+                   NEW@example.com,Again,secret4,\n";
+        let report = ImportUsersUseCase::new(Arc::new(repo))
+            .with_events(Arc::new(publisher), 10)
+            .dry_run(csv.as_bytes())
+            .await
+            .unwrap();
+
+        assert_eq!((report.rows, report.created, report.skipped), (4, 1, 1));
+        let errors: Vec<_> = report.errors.iter().map(|e| (e.row, e.error.as_str())).collect();
+        assert_eq!(
+            errors,
+            vec![(3, "invalid role 'owner'"), (4, "duplicate email 'NEW@example.com'")]
+        );
+    }
+
+    /// Cache keeping whatever is stored in it, so job updates can be read back
+    fn job_cache() -> MockCacheRepository {
+        let entries: Arc<Mutex<HashMap<String, String>>> = Arc::default();
+        let mut cache = MockCacheRepository::new();
+        let stored = entries.clone();
+        cache.expect_set().returning(move |key, value, _| {
+            stored.lock().unwrap().insert(key.to_string(), value.to_string());
+            Ok(())
+        });
+        cache
+            .expect_get()
+            .returning(move |key| Ok(entries.lock().unwrap().get(key).cloned()));
+        cache
+    }
+
+    #[tokio::test]
+    async fn background_import_cancelled_by_shutdown_is_marked_failed() {
+        let mut repo = MockAuthRepository::new();
+        repo.expect_create_user().never();
+        let tasks = Arc::new(TaskRegistry::new());
+        let use_case = Arc::new(
+            ImportUsersUseCase::new(Arc::new(repo))
+                .with_jobs(Arc::new(ImportJobs::new(Arc::new(job_cache()), JOB_TTL)))
+                .with_tasks(tasks.clone()),
+        );
+        tasks.shutdown(Duration::from_secs(1)).await;
+
+        let csv = b"email,name,password\nlan@example.com,Lan,secret1\n".to_vec();
+        let job = use_case.start(csv).await.unwrap();
+        let job_id = Uuid::parse_str(&job.job_id).unwrap();
+        let job = tokio::time::timeout(Duration::from_secs(1), async {
+            loop {
+                match use_case.status(&job_id).await {
+                    Some(job) if job.state != ImportJobState::Running => break job,
+                    _ => tokio::task::yield_now().await,
+                }
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(job.state, ImportJobState::Failed);
+        assert_eq!(job.error.as_deref(), Some("Import cancelled by server shutdown"));
+    }
 }
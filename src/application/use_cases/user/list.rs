@@ -1,35 +1,119 @@
 use crate::{
+    application::dto::{PageSizeLimits, PaginationMeta},
     domain::{entities::User, repositories::user_repository::UserRepository},
-    shared::AppError,
+    shared::{telemetry::record_outcome, AppError},
 };
 use std::sync::Arc;
 
 /// Use case for listing users with pagination
 pub struct ListUsersUseCase<R: UserRepository> {
     user_repository: Arc<R>,
+    page_sizes: PageSizeLimits,
 }
 
 impl<R: UserRepository> ListUsersUseCase<R> {
-    pub fn new(user_repository: Arc<R>) -> Self {
-        Self { user_repository }
+    pub fn new(user_repository: Arc<R>, page_sizes: PageSizeLimits) -> Self {
+        Self { user_repository, page_sizes }
     }
 
-    pub async fn execute(&self, page: i64, page_size: i64) -> Result<Vec<User>, AppError> {
+    /// A missing `page_size` gets the configured default and oversized pages
+    /// are clamped to the maximum; the returned metadata carries the page
+    /// size actually used.
+    #[tracing::instrument(
+        name = "use_case.list_users",
+        skip_all,
+        fields(
+            page = page,
+            page_size = ?page_size,
+            outcome = tracing::field::Empty,
+        )
+    )]
+    pub async fn execute(
+        &self,
+        page: i64,
+        page_size: Option<i64>,
+    ) -> Result<(Vec<User>, PaginationMeta), AppError> {
+        record_outcome(self.run(page, page_size).await)
+    }
+
+    async fn run(
+        &self,
+        page: i64,
+        page_size: Option<i64>,
+    ) -> Result<(Vec<User>, PaginationMeta), AppError> {
         // Validate pagination parameters
         if page < 1 {
             return Err(AppError::Validation("Page must be >= 1".to_string()));
         }
 
-        if !(1..=100).contains(&page_size) {
-            return Err(AppError::Validation("Page size must be between 1 and 100".to_string()));
-        }
-
-        let offset = (page - 1) * page_size;
+        let page_size = self.page_sizes.resolve(page_size)?;
+        let offset = (page - 1).saturating_mul(page_size);
 
         // Fetch users
         let users = self.user_repository.list_paginated(page_size, offset).await?;
+        let total = self.user_repository.count().await?;
         tracing::info!("Listed {} users (page {})", users.len(), page);
 
-        Ok(users)
+        Ok((users, PaginationMeta { page, page_size, total }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repositories::user::MockUserRepository;
+    use mockall::predicate::eq;
+
+    fn use_case(repo: MockUserRepository) -> ListUsersUseCase<MockUserRepository> {
+        ListUsersUseCase::new(Arc::new(repo), PageSizeLimits { default: 20, max: 100 })
+    }
+
+    #[tokio::test]
+    async fn clamps_page_size_to_configured_max() {
+        let mut repo = MockUserRepository::new();
+        repo.expect_list_paginated()
+            .with(eq(100), eq(100))
+            .times(1)
+            .returning(|_, _| Ok(vec![]));
+        repo.expect_count().returning(|| Ok(250));
+
+        let (_, meta) = use_case(repo).execute(2, Some(1_000_000)).await.unwrap();
+
+        assert_eq!(meta, PaginationMeta { page: 2, page_size: 100, total: 250 });
+    }
+
+    #[tokio::test]
+    async fn keeps_page_size_within_limit() {
+        let mut repo = MockUserRepository::new();
+        repo.expect_list_paginated().with(eq(10), eq(0)).returning(|_, _| Ok(vec![]));
+        repo.expect_count().returning(|| Ok(0));
+
+        let (_, meta) = use_case(repo).execute(1, Some(10)).await.unwrap();
+
+        assert_eq!(meta.page_size, 10);
+    }
+
+    #[tokio::test]
+    async fn applies_default_page_size_when_omitted() {
+        let mut repo = MockUserRepository::new();
+        repo.expect_list_paginated().with(eq(20), eq(20)).returning(|_, _| Ok(vec![]));
+        repo.expect_count().returning(|| Ok(0));
+
+        let (_, meta) = use_case(repo).execute(2, None).await.unwrap();
+
+        assert_eq!(meta.page_size, 20);
+    }
+
+    #[tokio::test]
+    async fn rejects_non_positive_page_and_page_size() {
+        for (page, page_size) in [(0, Some(10)), (-1, None), (1, Some(0)), (1, Some(-5))] {
+            let result = use_case(MockUserRepository::new()).execute(page, page_size).await;
+            assert!(
+                matches!(result, Err(AppError::Validation(_))),
+                "page={} page_size={:?}",
+                page,
+                page_size
+            );
+        }
     }
 }
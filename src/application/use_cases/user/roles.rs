@@ -1,11 +1,27 @@
 use crate::{
-    application::dto::{RolePermissions, RoleResponse},
+    application::{
+        dto::{RolePermissions, RoleResponse},
+        services::{
+            events::{publish_event, EventPublisher},
+            role::role_cache_key,
+        },
+    },
     domain::{
-        repositories::user_repository::UserRepository,
+        entities::AuditEntry,
+        events::v2::UserRoleChanged,
+        repositories::{
+            audit::AuditRepository, cache::CacheRepository, user_repository::UserRepository,
+        },
         value_objects::{UserId, UserRole},
     },
+    shared::telemetry::record_outcome,
 };
+use chrono::Utc;
 use std::sync::Arc;
+use uuid::Uuid;
+
+/// Audit action recorded for role changes
+pub const ROLE_CHANGED_ACTION: &str = "user.role_changed";
 
 /// Use case for getting a user's role
 pub struct GetUserRoleUseCase<R: UserRepository> {
@@ -17,7 +33,19 @@ impl<R: UserRepository> GetUserRoleUseCase<R> {
         Self { user_repo }
     }
 
+    #[tracing::instrument(
+        name = "use_case.get_role",
+        skip_all,
+        fields(
+            user_id = %user_id,
+            outcome = tracing::field::Empty,
+        )
+    )]
     pub async fn execute(&self, user_id: &str) -> Result<RoleResponse, GetRoleError> {
+        record_outcome(self.run(user_id).await)
+    }
+
+    async fn run(&self, user_id: &str) -> Result<RoleResponse, GetRoleError> {
         // Parse user ID
         let user_id = UserId::from_string(user_id).map_err(|_| GetRoleError::InvalidUserId)?;
 
@@ -44,19 +72,52 @@ impl<R: UserRepository> GetUserRoleUseCase<R> {
 }
 
 /// Use case for updating a user's role
+///
+/// An actual change drops the cached role, so existing access tokens pick
+/// it up on their next request, and is audited and announced as
+/// `UserRoleChanged`. Setting the role a user already has is a no-op.
 pub struct UpdateUserRoleUseCase<R: UserRepository> {
     user_repo: Arc<R>,
+    audit_repo: Arc<dyn AuditRepository>,
+    cache: Arc<dyn CacheRepository>,
+    events: Arc<dyn EventPublisher>,
 }
 
 impl<R: UserRepository> UpdateUserRoleUseCase<R> {
-    pub fn new(user_repo: Arc<R>) -> Self {
-        Self { user_repo }
+    pub fn new(
+        user_repo: Arc<R>,
+        audit_repo: Arc<dyn AuditRepository>,
+        cache: Arc<dyn CacheRepository>,
+        events: Arc<dyn EventPublisher>,
+    ) -> Self {
+        Self { user_repo, audit_repo, cache, events }
     }
 
+    /// `actor_id` is the authenticated user making the change.
+    #[tracing::instrument(
+        name = "use_case.update_role",
+        skip_all,
+        fields(
+            user_id = %user_id,
+            role = %new_role,
+            actor_id = ?actor_id,
+            outcome = tracing::field::Empty,
+        )
+    )]
     pub async fn execute(
         &self,
         user_id: &str,
         new_role: &str,
+        actor_id: Option<Uuid>,
+    ) -> Result<RoleResponse, UpdateRoleError> {
+        record_outcome(self.run(user_id, new_role, actor_id).await)
+    }
+
+    async fn run(
+        &self,
+        user_id: &str,
+        new_role: &str,
+        actor_id: Option<Uuid>,
     ) -> Result<RoleResponse, UpdateRoleError> {
         // Parse user ID
         let user_id = UserId::from_string(user_id).map_err(|_| UpdateRoleError::InvalidUserId)?;
@@ -74,6 +135,7 @@ impl<R: UserRepository> UpdateUserRoleUseCase<R> {
             .ok_or(UpdateRoleError::UserNotFound)?;
 
         // Update role
+        let old_role = user.role;
         user.role = role;
 
         // Save user
@@ -83,6 +145,28 @@ impl<R: UserRepository> UpdateUserRoleUseCase<R> {
             .await
             .map_err(|e| UpdateRoleError::Repository(e.to_string()))?;
 
+        if old_role != role {
+            let event = UserRoleChanged {
+                user_id: *updated_user.id.as_uuid(),
+                old_role,
+                new_role: role,
+                actor_id,
+                occurred_at: Utc::now(),
+            };
+
+            // Built on the request's transaction, a failure here also undoes the save
+            self.audit(&event).await?;
+
+            // Until this succeeds the old role is served for up to the cache TTL
+            if let Err(e) = self.cache.delete(&role_cache_key(&updated_user.id)).await {
+                tracing::error!(user_id = %updated_user.id, "Failed to invalidate cached role: {}", e);
+            }
+
+            if let Err(e) = publish_event(self.events.as_ref(), &event).await {
+                tracing::warn!(user_id = %event.user_id, "Failed to publish role change: {:?}", e);
+            }
+        }
+
         // Build response
         Ok(RoleResponse {
             user_id: updated_user.id.to_string(),
@@ -95,6 +179,19 @@ impl<R: UserRepository> UpdateUserRoleUseCase<R> {
             },
         })
     }
+
+    async fn audit(&self, event: &UserRoleChanged) -> Result<(), UpdateRoleError> {
+        let entry = AuditEntry::new(
+            event.actor_id,
+            ROLE_CHANGED_ACTION,
+            Some(event.user_id),
+            serde_json::json!({ "old_role": event.old_role, "new_role": event.new_role }),
+        );
+        self.audit_repo
+            .record(&entry)
+            .await
+            .map_err(|e| UpdateRoleError::Repository(format!("Failed to audit role change: {}", e)))
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -118,3 +215,160 @@ pub enum UpdateRoleError {
     #[error("Repository error: {0}")]
     Repository(String),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        application::services::events::MockEventPublisher,
+        domain::{
+            entities::User,
+            events::v2::USER_ROLE_CHANGED,
+            repositories::{
+                audit::MockAuditRepository, cache::MockCacheRepository, user::MockUserRepository,
+            },
+            value_objects::Email,
+        },
+    };
+
+    fn viewer() -> User {
+        User::new(Email::parse("viewer@example.com").unwrap(), "Viewer".to_string()).unwrap()
+    }
+
+    fn users_returning(user: User) -> MockUserRepository {
+        let mut repo = MockUserRepository::new();
+        repo.expect_find_by_id().returning(move |_| Ok(Some(user.clone())));
+        repo.expect_save().returning(|u| Ok(u.clone()));
+        repo
+    }
+
+    fn invalidating_cache() -> MockCacheRepository {
+        let mut cache = MockCacheRepository::new();
+        cache.expect_delete().times(1).returning(|_| Ok(()));
+        cache
+    }
+
+    fn use_case(
+        users: MockUserRepository,
+        audit: MockAuditRepository,
+        cache: MockCacheRepository,
+        events: MockEventPublisher,
+    ) -> UpdateUserRoleUseCase<MockUserRepository> {
+        UpdateUserRoleUseCase::new(
+            Arc::new(users),
+            Arc::new(audit),
+            Arc::new(cache),
+            Arc::new(events),
+        )
+    }
+
+    #[tokio::test]
+    async fn role_change_is_audited_and_published() {
+        let user = viewer();
+        let user_id = *user.id.as_uuid();
+        let actor_id = Uuid::new_v4();
+
+        let mut audit = MockAuditRepository::new();
+        audit
+            .expect_record()
+            .withf(move |entry| {
+                entry.action == ROLE_CHANGED_ACTION
+                    && entry.actor_id == Some(actor_id)
+                    && entry.target_id == Some(user_id)
+                    && entry.details
+                        == serde_json::json!({ "old_role": "viewer", "new_role": "admin" })
+            })
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let mut events = MockEventPublisher::new();
+        events
+            .expect_publish()
+            .withf(move |subject, payload| {
+                let event: UserRoleChanged = serde_json::from_slice(payload).unwrap();
+                subject == USER_ROLE_CHANGED
+                    && event.user_id == user_id
+                    && event.old_role == UserRole::Viewer
+                    && event.new_role == UserRole::Admin
+                    && event.actor_id == Some(actor_id)
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+
+        let mut cache = MockCacheRepository::new();
+        cache
+            .expect_delete()
+            .withf(move |key| key == format!("user:{}:role", user_id))
+            .times(1)
+            .returning(|_| Ok(()));
+
+        let response = use_case(users_returning(user), audit, cache, events)
+            .execute(&user_id.to_string(), "admin", Some(actor_id))
+            .await
+            .unwrap();
+
+        assert_eq!(response.role, "admin");
+    }
+
+    #[tokio::test]
+    async fn unchanged_role_emits_nothing() {
+        let user = viewer();
+        let user_id = user.id.to_string();
+
+        // Mocks without expectations panic if called
+        let result = use_case(
+            users_returning(user),
+            MockAuditRepository::new(),
+            MockCacheRepository::new(),
+            MockEventPublisher::new(),
+        )
+        .execute(&user_id, "viewer", None)
+        .await;
+
+        assert!(result.is_ok());
+    }
+
+    #[tokio::test]
+    async fn publish_failure_does_not_fail_the_update() {
+        let user = viewer();
+        let user_id = user.id.to_string();
+
+        let mut audit = MockAuditRepository::new();
+        audit.expect_record().times(1).returning(|_| Ok(()));
+        let mut events = MockEventPublisher::new();
+        events.expect_publish().returning(|_, _| {
+            Err(crate::shared::AppError::Internal(anyhow::anyhow!("broker down")))
+        });
+
+        let result = use_case(users_returning(user), audit, invalidating_cache(), events)
+            .execute(&user_id, "editor", None)
+            .await;
+
+        assert_eq!(result.unwrap().role, "editor");
+    }
+
+    #[tokio::test]
+    async fn audit_failure_fails_the_update_before_anything_is_announced() {
+        let user = viewer();
+        let user_id = user.id.to_string();
+
+        let mut audit = MockAuditRepository::new();
+        audit.expect_record().times(1).returning(|_| {
+            Err(crate::domain::repositories::user::RepositoryError::Database(
+                "audit_logs unavailable".to_string(),
+            ))
+        });
+
+        // Neither the cache nor the publisher may be touched
+        let result = use_case(
+            users_returning(user),
+            audit,
+            MockCacheRepository::new(),
+            MockEventPublisher::new(),
+        )
+        .execute(&user_id, "editor", None)
+        .await;
+
+        assert!(matches!(result, Err(UpdateRoleError::Repository(_))));
+    }
+}
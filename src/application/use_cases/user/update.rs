@@ -3,13 +3,13 @@ use crate::{
     domain::{
         entities::User, repositories::user_repository::UserRepository, value_objects::UserId,
     },
-    shared::AppError,
+    shared::{telemetry::record_outcome, AppError},
 };
 use std::sync::Arc;
 use uuid::Uuid;
 use validator::Validate;
 
-/// Use case for updating a user
+/// Use case for partially updating a user
 pub struct UpdateUserUseCase<R: UserRepository> {
     user_repository: Arc<R>,
 }
@@ -19,7 +19,21 @@ impl<R: UserRepository> UpdateUserUseCase<R> {
         Self { user_repository }
     }
 
+    /// Only fields present in `dto` are written; see `UpdateUserDto` for
+    /// which fields accept `null`.
+    #[tracing::instrument(
+        name = "use_case.update_user",
+        skip_all,
+        fields(
+            user_id = %user_id,
+            outcome = tracing::field::Empty,
+        )
+    )]
     pub async fn execute(&self, user_id: &str, dto: UpdateUserDto) -> Result<User, AppError> {
+        record_outcome(self.run(user_id, dto).await)
+    }
+
+    async fn run(&self, user_id: &str, dto: UpdateUserDto) -> Result<User, AppError> {
         // Validate input
         dto.validate().map_err(|e| AppError::Validation(e.to_string()))?;
 
@@ -29,23 +43,69 @@ impl<R: UserRepository> UpdateUserUseCase<R> {
 
         let user_id = UserId::from_uuid(uuid);
 
-        // Find existing user
-        let mut user = self
+        let changes = dto.into_changes()?;
+
+        let updated_user = self
             .user_repository
-            .find_by_id(user_id)
+            .patch(user_id, &changes)
             .await?
             .ok_or_else(|| AppError::NotFound(format!("User with ID {} not found", user_id)))?;
 
-        // Update name if provided
-        if let Some(name) = dto.name {
-            user.update_name(name).map_err(|e| AppError::Validation(e.to_string()))?;
+        tracing::info!("User updated successfully: {}", updated_user.id);
+
+        Ok(updated_user)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        repositories::{user::MockUserRepository, UserChanges},
+        Email,
+    };
+    use mockall::predicate::{always, eq};
+
+    const ID: &str = "6f1c1c2e-63a4-4d7b-9d56-0d1f4c8f6d11";
+
+    fn user() -> User {
+        User::new(Email::parse("lan@example.com").unwrap(), "Lan".to_string()).unwrap()
+    }
+
+    async fn run(dto: UpdateUserDto, expected: Option<UserChanges>) -> Result<User, AppError> {
+        let mut repo = MockUserRepository::new();
+        if let Some(expected) = expected {
+            repo.expect_patch()
+                .with(always(), eq(expected))
+                .times(1)
+                .returning(|_, _| Ok(Some(user())));
         }
+        UpdateUserUseCase::new(Arc::new(repo)).execute(ID, dto).await
+    }
 
-        // Save updated user
-        let updated_user = self.user_repository.save(&user).await?;
+    #[tokio::test]
+    async fn writes_only_the_fields_that_were_sent() {
+        let dto = UpdateUserDto { name: Some(Some("  Mai ".into())), ..Default::default() };
+        let expected = UserChanges { name: Some("Mai".into()), locale: None };
 
-        tracing::info!("User updated successfully: {}", updated_user.id);
+        assert!(run(dto, Some(expected)).await.is_ok());
+    }
 
-        Ok(updated_user)
+    #[tokio::test]
+    async fn null_locale_resets_to_default() {
+        let dto = UpdateUserDto { locale: Some(None), ..Default::default() };
+        let expected = UserChanges { name: None, locale: Some("en".into()) };
+
+        assert!(run(dto, Some(expected)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn rejects_clearing_name_and_unknown_locales() {
+        for dto in [
+            UpdateUserDto { name: Some(None), ..Default::default() },
+            UpdateUserDto { locale: Some(Some("klingon".into())), ..Default::default() },
+        ] {
+            assert!(matches!(run(dto, None).await, Err(AppError::Validation(_))));
+        }
     }
 }
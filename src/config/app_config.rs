@@ -1,23 +1,157 @@
-use crate::config::database::DatabaseConfig;
+use crate::application::dto::{auth::TokenDelivery, PageSizeLimits};
+use crate::application::services::LockPolicy;
+use crate::config::{
+    audit::AuditRetentionConfig,
+    cache::CacheConfig,
+    cache_control::CacheControlConfig,
+    database::DatabaseConfig,
+    email::EmailConfig,
+    events::EventTransport,
+    features::{self, Features},
+    inactivity::InactivityConfig,
+    lockout::LockoutConfig,
+    metrics::MetricsConfig,
+    nats::NatsConfig,
+    runtime::RuntimeConfig,
+    sms::SmsConfig,
+};
+use crate::domain::value_objects::{
+    BreachedPasswords, DisposableDomains, EmailDomainPolicy, PasswordRules, UserRole,
+};
+use crate::shared::rate_limiter::RateLimitAlgorithm;
+use crate::shared::utils::{
+    code_hash::{self, CodeHasher},
+    jwt::{ExpiryOverride, DEFAULT_LEEWAY_SECS},
+    password::Peppers,
+};
+use ipnet::IpNet;
+use std::collections::HashMap;
 use std::env;
+use std::net::{IpAddr, SocketAddr};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct AppConfig {
     pub database_url: String,
     pub server_host: String,
     pub server_port: u16,
+    /// Addresses listened on instead of `server_host:server_port`, one
+    /// listener each; set via `BIND_ADDRESSES`
+    pub bind_addresses: Vec<SocketAddr>,
+    /// Idle time allowed between requests on a kept-alive connection
+    pub http_keep_alive_timeout: Duration,
+    /// Requests served at once before new ones are shed with 503
+    pub max_concurrent_requests: usize,
+    /// How long open connections, and then background tasks, get to finish
+    /// on shutdown before they are aborted
+    pub shutdown_timeout: Duration,
+    /// Header fields a request may carry before it gets 431
+    pub max_request_headers: usize,
+    /// Total bytes of header names and values allowed before 431
+    pub max_request_header_bytes: usize,
+    /// Tokio worker and blocking thread counts
+    pub runtime: RuntimeConfig,
     pub jwt_secret: String,
     pub jwt_access_expiry: i64,
     pub jwt_refresh_expiry: i64,
+    /// Lifetimes replacing the two above for users of a role, keyed by role
+    /// name; set via `JWT_ACCESS_EXPIRY_<ROLE>`/`JWT_REFRESH_EXPIRY_<ROLE>`
+    pub jwt_role_expiry: HashMap<String, ExpiryOverride>,
     pub jwt_issuer: String,
+    /// Reject tokens whose `iss` is not `jwt_issuer`; turned off only while
+    /// migrating between issuer names
+    pub jwt_validate_issuer: bool,
+    /// Audience stamped into issued tokens; always accepted
     pub jwt_audience: String,
+    /// Further audiences accepted on incoming tokens
+    pub jwt_accepted_audiences: Vec<String>,
+    /// Clock skew tolerated when validating `exp`/`nbf`/`iat`, in seconds
+    pub jwt_leeway: u64,
     pub confirm_code_expiry: i64,
+    /// Seconds a verification link stays valid; set apart from
+    /// `confirm_code_expiry` since links are often opened much later
+    pub verification_link_expiry: i64,
+    /// Where verification links point; the token is appended as `?token=`
+    pub verification_link_url: String,
+    /// Keyed hash applied to confirmation codes before they are stored;
+    /// unkeyed stores them as sent
+    pub confirmation_code_hasher: CodeHasher,
     pub rust_log: String,
     pub is_production: bool,
     pub cookie_secure: bool,
+    /// Where tokens go when a request does not say (`X-Token-Delivery`)
+    pub token_delivery: TokenDelivery,
+    /// Serve Swagger UI and the OpenAPI document
+    pub swagger_enabled: bool,
     pub rate_limit_per_second: u64,
     pub rate_limit_burst_size: u32,
+    /// How bursts within the rate above are counted
+    pub rate_limit_algorithm: RateLimitAlgorithm,
+    /// Peers whose `X-Forwarded-For`/`X-Real-IP` headers are believed
+    pub trusted_proxies: Vec<IpNet>,
+    /// `page_size` applied on list endpoints when a request omits it
+    pub default_page_size: i64,
+    /// Upper bound for `page_size` on list endpoints; larger requests are clamped
+    pub max_page_size: i64,
+    /// Active refresh tokens allowed per user; `None` means unlimited
+    pub max_sessions_per_user: Option<u32>,
+    /// Refuse logins over the cap instead of evicting the oldest session
+    pub session_limit_reject: bool,
+    /// Previous passwords a new one may not match; 0 disables the check
+    pub password_history_size: usize,
+    /// Minimum time between self-service password changes; zero allows any
+    pub password_min_change_interval: Duration,
+    /// Secret mixed into password hashes; the first is current, the rest
+    /// only verify until their hashes are upgraded on login
+    pub password_peppers: Peppers,
+    /// Optional checks against passwords derived from the email or known
+    /// from breaches
+    pub password_rules: PasswordRules,
+    /// Let anyone register; when false only admins can create accounts
+    /// through `register`
+    pub registration_open: bool,
+    /// Lifetime of an admin's invitation when they do not choose one
+    pub invitation_ttl: Duration,
+    /// Role given to self-registered users
+    pub default_user_role: UserRole,
+    /// Email domains self-registration is limited to, or refused for
+    pub email_domain_policy: EmailDomainPolicy,
+    /// Throwaway mailbox providers refused at registration; `None` when
+    /// `BLOCK_DISPOSABLE_EMAILS` is off
+    pub disposable_email_domains: Option<Arc<DisposableDomains>>,
+    /// How long a user's role may be served from cache
+    pub role_cache_ttl: Duration,
+    /// Minimum gap between confirmation/reset codes emailed to one user
+    pub resend_cooldown: Duration,
+    /// Codes that may be emailed to one user per hour
+    pub resend_max_per_hour: u32,
+    /// Locking accounts after repeated failed logins
+    pub lockout: LockoutConfig,
+    /// User creations a CSV import runs at once; keep it below
+    /// `DB_MAX_CONNECTIONS` so requests still get connections during imports
+    pub import_max_concurrency: usize,
+    /// CSV file the user import endpoint reads
+    pub import_csv_path: PathBuf,
+    /// Time each readiness dependency check may take before it counts as down
+    pub health_check_timeout: Duration,
+    /// Optional subsystems switched on or off via `FEATURE_*`
+    pub features: Features,
     pub db_config: DatabaseConfig,
+    pub cache_config: CacheConfig,
+    /// What distributed locks, such as the per-address registration lock, do
+    /// when the cache backing them is unreachable
+    pub lock_policy: LockPolicy,
+    pub cache_control: CacheControlConfig,
+    pub audit_retention: AuditRetentionConfig,
+    /// Disabling accounts nobody has logged into for a long time
+    pub inactivity: InactivityConfig,
+    pub metrics_config: MetricsConfig,
+    pub nats_config: NatsConfig,
+    pub event_transport: EventTransport,
+    pub email_config: EmailConfig,
+    pub sms_config: SmsConfig,
 }
 
 impl AppConfig {
@@ -25,14 +159,59 @@ impl AppConfig {
         // Load .env file if it exists
         dotenvy::dotenv().ok();
 
-        Ok(Self {
+        let features = Features::from_env()?;
+        let server_port = env::var("SERVER_PORT")
+            .unwrap_or_else(|_| "3000".to_string())
+            .parse()
+            .map_err(|_| ConfigError::InvalidPort)?;
+        let server_host = env::var("SERVER_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
+        let verification_link_url = env::var("VERIFICATION_LINK_URL")
+            .ok()
+            .filter(|v| !v.trim().is_empty())
+            .unwrap_or_else(|| {
+                format!("http://{}:{}/api/auth/verify-link", server_host, server_port)
+            });
+
+        let config = Self {
             database_url: env::var("DATABASE_URL")
                 .map_err(|_| ConfigError::MissingEnvVar("DATABASE_URL".to_string()))?,
-            server_host: env::var("SERVER_HOST").unwrap_or_else(|_| "127.0.0.1".to_string()),
-            server_port: env::var("SERVER_PORT")
-                .unwrap_or_else(|_| "3000".to_string())
+            server_host,
+            server_port,
+            bind_addresses: parse_bind_addresses(
+                &env::var("BIND_ADDRESSES").unwrap_or_default(),
+                server_port,
+            )?,
+            http_keep_alive_timeout: Duration::from_secs(
+                env::var("HTTP_KEEP_ALIVE_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "75".to_string())
+                    .parse()
+                    .map_err(|_| ConfigError::InvalidServerLimit("HTTP_KEEP_ALIVE_TIMEOUT_SECS"))?,
+            ),
+            max_concurrent_requests: env::var("MAX_CONCURRENT_REQUESTS")
+                .unwrap_or_else(|_| "1024".to_string())
+                .parse()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or(ConfigError::InvalidServerLimit("MAX_CONCURRENT_REQUESTS"))?,
+            shutdown_timeout: Duration::from_secs(
+                env::var("SHUTDOWN_TIMEOUT_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .map_err(|_| ConfigError::InvalidServerLimit("SHUTDOWN_TIMEOUT_SECS"))?,
+            ),
+            max_request_headers: env::var("MAX_REQUEST_HEADERS")
+                .unwrap_or_else(|_| "100".to_string())
+                .parse()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or(ConfigError::InvalidServerLimit("MAX_REQUEST_HEADERS"))?,
+            max_request_header_bytes: env::var("MAX_REQUEST_HEADER_BYTES")
+                .unwrap_or_else(|_| "32768".to_string())
                 .parse()
-                .map_err(|_| ConfigError::InvalidPort)?,
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or(ConfigError::InvalidServerLimit("MAX_REQUEST_HEADER_BYTES"))?,
+            runtime: RuntimeConfig::from_env()?,
             jwt_secret: env::var("JWT_SECRET")
                 .map_err(|_| ConfigError::MissingEnvVar("JWT_SECRET".to_string()))?,
             jwt_access_expiry: env::var("JWT_ACCESS_EXPIRY")
@@ -43,17 +222,48 @@ impl AppConfig {
                 .unwrap_or_else(|_| "604800".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidTokenExpiry)?,
+            jwt_role_expiry: role_token_expiry()?,
             jwt_issuer: env::var("JWT_ISSUER").unwrap_or_else(|_| "axum-backend".to_string()),
+            jwt_validate_issuer: env::var("JWT_VALIDATE_ISSUER")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(true),
             jwt_audience: env::var("JWT_AUDIENCE")
                 .unwrap_or_else(|_| "axum-backend-api".to_string()),
+            jwt_accepted_audiences: env::var("JWT_ACCEPTED_AUDIENCES")
+                .unwrap_or_default()
+                .split(',')
+                .map(str::trim)
+                .filter(|aud| !aud.is_empty())
+                .map(String::from)
+                .collect(),
+            jwt_leeway: match env::var("JWT_LEEWAY_SECS") {
+                Ok(v) => v.parse().map_err(|_| ConfigError::InvalidJwtLeeway)?,
+                Err(_) => DEFAULT_LEEWAY_SECS,
+            },
             confirm_code_expiry: env::var("CONFIRMATION_CODE_EXPIRY")
                 .unwrap_or_else(|_| "60".to_string())
                 .parse()
                 .map_err(|_| ConfigError::InvalidTokenExpiry)?,
+            verification_link_expiry: env::var("VERIFICATION_LINK_EXPIRY")
+                .unwrap_or_else(|_| "86400".to_string())
+                .parse()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .ok_or(ConfigError::InvalidTokenExpiry)?,
+            verification_link_url,
+            confirmation_code_hasher: match env::var("CONFIRMATION_CODE_HASH_KEY") {
+                Ok(key) if key.len() >= code_hash::MIN_KEY_LEN => CodeHasher::new(Some(&key)),
+                Ok(key) if !key.is_empty() => return Err(ConfigError::InvalidCodeHashKey),
+                _ => CodeHasher::default(),
+            },
             rust_log: env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
             is_production: env::var("ENVIRONMENT")
                 .unwrap_or_else(|_| "development".to_string())
                 .eq_ignore_ascii_case("production"),
+            token_delivery: match env::var("TOKEN_DELIVERY") {
+                Ok(v) => TokenDelivery::parse(&v).ok_or(ConfigError::InvalidTokenDelivery(v))?,
+                Err(_) => TokenDelivery::default(),
+            },
             cookie_secure: env::var("COOKIE_SECURE")
                 .map(|v| v == "true" || v == "1")
                 .unwrap_or_else(|_| {
@@ -61,6 +271,13 @@ impl AppConfig {
                         .unwrap_or_else(|_| "development".to_string())
                         .eq_ignore_ascii_case("production")
                 }),
+            swagger_enabled: env::var("ENABLE_SWAGGER")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or_else(|_| {
+                    !env::var("ENVIRONMENT")
+                        .unwrap_or_else(|_| "development".to_string())
+                        .eq_ignore_ascii_case("production")
+                }),
             rate_limit_per_second: env::var("RATE_LIMIT_PER_SECOND")
                 .unwrap_or_else(|_| "2".to_string())
                 .parse()
@@ -69,13 +286,284 @@ impl AppConfig {
                 .unwrap_or_else(|_| "5".to_string())
                 .parse()
                 .unwrap_or(5),
+            rate_limit_algorithm: match env::var("RATE_LIMIT_ALGORITHM") {
+                Ok(v) => RateLimitAlgorithm::parse(&v)
+                    .ok_or(ConfigError::InvalidRateLimitAlgorithm(v))?,
+                Err(_) => RateLimitAlgorithm::default(),
+            },
+            trusted_proxies: parse_trusted_proxies(
+                &env::var("TRUSTED_PROXIES").unwrap_or_default(),
+            )?,
+            default_page_size: match env::var("DEFAULT_PAGE_SIZE") {
+                Ok(v) => v
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or(ConfigError::InvalidPageSize("DEFAULT_PAGE_SIZE"))?,
+                Err(_) => 10,
+            },
+            max_page_size: match env::var("MAX_PAGE_SIZE") {
+                Ok(v) => v
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or(ConfigError::InvalidPageSize("MAX_PAGE_SIZE"))?,
+                Err(_) => 100,
+            },
+            max_sessions_per_user: match env::var("MAX_SESSIONS_PER_USER") {
+                Ok(v) => match v.parse::<u32>() {
+                    Ok(0) => None,
+                    Ok(n) => Some(n),
+                    Err(_) => return Err(ConfigError::InvalidSessionLimit),
+                },
+                Err(_) => None,
+            },
+            session_limit_reject: match env::var("SESSION_LIMIT_POLICY").as_deref() {
+                Ok("reject") => true,
+                Ok("evict") | Err(_) => false,
+                Ok(_) => return Err(ConfigError::InvalidSessionLimit),
+            },
+            password_history_size: env::var("PASSWORD_HISTORY_SIZE")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .map_err(|_| ConfigError::InvalidServerLimit("PASSWORD_HISTORY_SIZE"))?,
+            password_min_change_interval: Duration::from_secs(
+                env::var("PASSWORD_MIN_CHANGE_INTERVAL_SECS")
+                    .unwrap_or_else(|_| "0".to_string())
+                    .parse()
+                    .map_err(|_| {
+                        ConfigError::InvalidServerLimit("PASSWORD_MIN_CHANGE_INTERVAL_SECS")
+                    })?,
+            ),
+            password_peppers: Peppers::parse(&env::var("PASSWORD_PEPPERS").unwrap_or_default())
+                .map_err(|e| ConfigError::InvalidPepper(e.to_string()))?,
+            password_rules: PasswordRules {
+                reject_email: env::var("REJECT_EMAIL_PASSWORDS")
+                    .map(|v| v == "true" || v == "1")
+                    .unwrap_or(false),
+                breached: breached_passwords()?,
+            },
+            registration_open: env::var("REGISTRATION_OPEN")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(true),
+            invitation_ttl: Duration::from_secs(
+                env::var("INVITATION_TTL_SECS")
+                    .unwrap_or_else(|_| "604800".to_string())
+                    .parse()
+                    .ok()
+                    .filter(|secs| *secs > 0)
+                    .ok_or(ConfigError::InvalidServerLimit("INVITATION_TTL_SECS"))?,
+            ),
+            default_user_role: match env::var("DEFAULT_USER_ROLE") {
+                Ok(v) => UserRole::parse(v.trim()).ok_or(ConfigError::InvalidUserRole(v))?,
+                Err(_) => UserRole::default(),
+            },
+            email_domain_policy: EmailDomainPolicy::new(
+                list_var("ALLOWED_EMAIL_DOMAINS"),
+                list_var("DENIED_EMAIL_DOMAINS"),
+            )
+            .map_err(|e| ConfigError::InvalidEmailDomain(e.to_string()))?,
+            disposable_email_domains: disposable_email_domains()?,
+            role_cache_ttl: Duration::from_secs(
+                env::var("ROLE_CACHE_TTL_SECS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .map_err(|_| ConfigError::InvalidServerLimit("ROLE_CACHE_TTL_SECS"))?,
+            ),
+            resend_cooldown: Duration::from_secs(
+                env::var("RESEND_COOLDOWN_SECS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .map_err(|_| ConfigError::InvalidServerLimit("RESEND_COOLDOWN_SECS"))?,
+            ),
+            resend_max_per_hour: env::var("RESEND_MAX_PER_HOUR")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or(ConfigError::InvalidServerLimit("RESEND_MAX_PER_HOUR"))?,
+            lockout: LockoutConfig::from_env()?,
+            import_max_concurrency: env::var("IMPORT_MAX_CONCURRENCY")
+                .unwrap_or_else(|_| "4".to_string())
+                .parse()
+                .ok()
+                .filter(|n| *n > 0)
+                .ok_or(ConfigError::InvalidServerLimit("IMPORT_MAX_CONCURRENCY"))?,
+            import_csv_path: env::var("IMPORT_CSV_PATH")
+                .ok()
+                .filter(|path| !path.trim().is_empty())
+                .unwrap_or_else(|| "import/users.csv".to_string())
+                .into(),
+            health_check_timeout: Duration::from_millis(
+                env::var("HEALTH_CHECK_TIMEOUT_MS")
+                    .unwrap_or_else(|_| "2000".to_string())
+                    .parse()
+                    .ok()
+                    .filter(|ms| *ms > 0)
+                    .ok_or(ConfigError::InvalidServerLimit("HEALTH_CHECK_TIMEOUT_MS"))?,
+            ),
             db_config: DatabaseConfig::from_env(),
-        })
+            cache_config: CacheConfig::from_env()?,
+            lock_policy: match env::var("LOCK_POLICY") {
+                Ok(v) => LockPolicy::parse(&v).ok_or(ConfigError::InvalidLockPolicy(v))?,
+                Err(_) => LockPolicy::default(),
+            },
+            cache_control: CacheControlConfig::from_env()?,
+            audit_retention: AuditRetentionConfig::from_env()?,
+            inactivity: InactivityConfig::from_env()?,
+            metrics_config: MetricsConfig::from_env()?,
+            nats_config: NatsConfig::from_env(),
+            event_transport: EventTransport::from_env()?,
+            // SMTP settings are irrelevant, and not validated, when email is off
+            email_config: if features.email {
+                EmailConfig::from_env()?
+            } else {
+                EmailConfig::default()
+            },
+            sms_config: SmsConfig::from_env()?,
+            features,
+        };
+
+        if config.default_page_size > config.max_page_size {
+            return Err(ConfigError::DefaultPageSizeTooLarge);
+        }
+        Ok(config)
+    }
+
+    /// Page sizes shared by every list endpoint
+    pub fn page_size_limits(&self) -> PageSizeLimits {
+        PageSizeLimits { default: self.default_page_size, max: self.max_page_size }
     }
 
     pub fn server_address(&self) -> String {
         format!("{}:{}", self.server_host, self.server_port)
     }
+
+    /// Every address to listen on: `bind_addresses`, or the single
+    /// `server_address` when none are set
+    pub fn listen_addresses(&self) -> Result<Vec<SocketAddr>, std::net::AddrParseError> {
+        if self.bind_addresses.is_empty() {
+            Ok(vec![self.server_address().parse()?])
+        } else {
+            Ok(self.bind_addresses.clone())
+        }
+    }
+}
+
+/// Per-role token lifetimes from `JWT_ACCESS_EXPIRY_ADMIN` and friends;
+/// only roles with at least one variable set get an entry
+fn role_token_expiry() -> Result<HashMap<String, ExpiryOverride>, ConfigError> {
+    let seconds = |var: String| match env::var(var) {
+        Ok(v) => v
+            .parse::<i64>()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .map(Some)
+            .ok_or(ConfigError::InvalidTokenExpiry),
+        Err(_) => Ok(None),
+    };
+
+    let mut overrides = HashMap::new();
+    for role in UserRole::all() {
+        let suffix = role.to_string().to_uppercase();
+        let expiry = ExpiryOverride {
+            access: seconds(format!("JWT_ACCESS_EXPIRY_{}", suffix))?,
+            refresh: seconds(format!("JWT_REFRESH_EXPIRY_{}", suffix))?,
+        };
+        if expiry != ExpiryOverride::default() {
+            overrides.insert(role.to_string(), expiry);
+        }
+    }
+    Ok(overrides)
+}
+
+/// Domains bundled for when `DISPOSABLE_EMAIL_DOMAINS_FILE` is unset
+const BUNDLED_DISPOSABLE_DOMAINS: &str = include_str!("disposable_domains.txt");
+
+/// The list read from `DISPOSABLE_EMAIL_DOMAINS_FILE`, or the bundled one,
+/// if `BLOCK_DISPOSABLE_EMAILS` is on. The file is read once at startup, so
+/// an edited list takes effect on restart.
+fn disposable_email_domains() -> Result<Option<Arc<DisposableDomains>>, ConfigError> {
+    // A misspelt value fails startup rather than silently leaving it off
+    let enabled = match env::var("BLOCK_DISPOSABLE_EMAILS") {
+        Ok(v) if !v.trim().is_empty() => features::parse_flag(&v)
+            .ok_or(ConfigError::InvalidFeatureFlag("BLOCK_DISPOSABLE_EMAILS"))?,
+        _ => false,
+    };
+    if !enabled {
+        return Ok(None);
+    }
+
+    let list = match env::var("DISPOSABLE_EMAIL_DOMAINS_FILE") {
+        Ok(path) if !path.trim().is_empty() => std::fs::read_to_string(path.trim())
+            .map_err(|e| ConfigError::InvalidDisposableDomainsFile(e.to_string()))?,
+        _ => BUNDLED_DISPOSABLE_DOMAINS.to_string(),
+    };
+    Ok(Some(Arc::new(DisposableDomains::parse(&list))))
+}
+
+const BUNDLED_BREACHED_PASSWORDS: &str = include_str!("breached_passwords.txt");
+
+fn breached_passwords() -> Result<Option<Arc<BreachedPasswords>>, ConfigError> {
+    let enabled = env::var("BLOCK_BREACHED_PASSWORDS")
+        .map(|v| v == "true" || v == "1")
+        .unwrap_or(false);
+    if !enabled {
+        return Ok(None);
+    }
+
+    let list = match env::var("BREACHED_PASSWORDS_FILE") {
+        Ok(path) if !path.trim().is_empty() => std::fs::read_to_string(path.trim())
+            .map_err(|e| ConfigError::InvalidBreachedPasswordsFile(e.to_string()))?,
+        _ => BUNDLED_BREACHED_PASSWORDS.to_string(),
+    };
+    Ok(Some(Arc::new(BreachedPasswords::parse(&list))))
+}
+
+/// Non-empty entries of a comma-separated variable; none when unset
+fn list_var(name: &str) -> Vec<String> {
+    env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Parse a comma-separated list of socket addresses; a bare IP listens on
+/// `default_port`, so `0.0.0.0,::` serves both stacks on `SERVER_PORT`
+pub fn parse_bind_addresses(raw: &str, default_port: u16) -> Result<Vec<SocketAddr>, ConfigError> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .parse::<SocketAddr>()
+                .or_else(|_| {
+                    entry
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .parse::<IpAddr>()
+                        .map(|ip| SocketAddr::new(ip, default_port))
+                })
+                .map_err(|_| ConfigError::InvalidBindAddress(entry.to_string()))
+        })
+        .collect()
+}
+
+/// Parse a comma-separated list of IPs and CIDR ranges; bare IPs match only themselves.
+pub fn parse_trusted_proxies(raw: &str) -> Result<Vec<IpNet>, ConfigError> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            entry
+                .parse::<IpNet>()
+                .or_else(|_| entry.parse::<IpAddr>().map(IpNet::from))
+                .map_err(|_| ConfigError::InvalidTrustedProxy(entry.to_string()))
+        })
+        .collect()
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -86,6 +574,144 @@ pub enum ConfigError {
     #[error("Invalid port number")]
     InvalidPort,
 
+    #[error("Invalid {0}: expected a positive integer")]
+    InvalidServerLimit(&'static str),
+
     #[error("Invalid token expiry duration")]
     InvalidTokenExpiry,
+
+    #[error("Invalid JWT_LEEWAY_SECS: expected a non-negative number of seconds")]
+    InvalidJwtLeeway,
+
+    #[error(
+        "Invalid METRICS_DURATION_BUCKETS: expected increasing positive numbers, comma-separated"
+    )]
+    InvalidMetricsBuckets,
+
+    #[error(
+        "Invalid session limit: MAX_SESSIONS_PER_USER must be a number, SESSION_LIMIT_POLICY evict or reject"
+    )]
+    InvalidSessionLimit,
+
+    #[error(
+        "Invalid RATE_LIMIT_ALGORITHM '{0}': expected token_bucket, fixed_window or sliding_window"
+    )]
+    InvalidRateLimitAlgorithm(String),
+
+    #[error("Invalid BIND_ADDRESSES entry: {0}")]
+    InvalidBindAddress(String),
+
+    #[error("Invalid TRUSTED_PROXIES entry: {0}")]
+    InvalidTrustedProxy(String),
+
+    #[error("Invalid {0}: expected a positive integer")]
+    InvalidPageSize(&'static str),
+
+    #[error("DEFAULT_PAGE_SIZE must not exceed MAX_PAGE_SIZE")]
+    DefaultPageSizeTooLarge,
+
+    #[error("Invalid email address in {0}")]
+    InvalidEmailAddress(String),
+
+    #[error("Invalid {0}: {1}")]
+    InvalidEmailSubject(&'static str, String),
+
+    #[error("Invalid {0}: expected true or false")]
+    InvalidFeatureFlag(&'static str),
+
+    #[error("Invalid EVENT_TRANSPORT '{0}': expected nats or postgres")]
+    InvalidEventTransport(String),
+
+    #[error("Invalid PASSWORD_RESET_CHANNEL '{0}': expected email or sms")]
+    InvalidNotificationChannel(String),
+
+    #[error("Invalid TOKEN_DELIVERY '{0}': expected cookie, body or both")]
+    InvalidTokenDelivery(String),
+
+    #[error("Invalid {0} '{1}': expected no-store, or private or public with max-age=<secs>")]
+    InvalidCacheControl(&'static str, String),
+
+    #[error("Invalid CACHE_BACKEND '{0}': expected memory or moka")]
+    InvalidCacheBackend(String),
+
+    #[error("Invalid LOCK_POLICY '{0}': expected strict or best_effort")]
+    InvalidLockPolicy(String),
+
+    #[error("Invalid CONFIRMATION_CODE_HASH_KEY: expected at least 32 bytes")]
+    InvalidCodeHashKey,
+
+    #[error("Invalid PASSWORD_PEPPERS: {0}")]
+    InvalidPepper(String),
+
+    #[error("{0} in ALLOWED_EMAIL_DOMAINS or DENIED_EMAIL_DOMAINS")]
+    InvalidEmailDomain(String),
+
+    #[error("Cannot read DISPOSABLE_EMAIL_DOMAINS_FILE: {0}")]
+    InvalidDisposableDomainsFile(String),
+
+    #[error("Cannot read BREACHED_PASSWORDS_FILE: {0}")]
+    InvalidBreachedPasswordsFile(String),
+
+    #[error("Invalid DEFAULT_USER_ROLE '{0}': expected admin, editor or viewer")]
+    InvalidUserRole(String),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_trusted_proxy_ips_and_ranges() {
+        let proxies = parse_trusted_proxies(" 10.0.0.0/8, 127.0.0.1 ,::1,").unwrap();
+        let expected: Vec<IpNet> = ["10.0.0.0/8", "127.0.0.1/32", "::1/128"]
+            .iter()
+            .map(|n| n.parse().unwrap())
+            .collect();
+        assert_eq!(proxies, expected);
+
+        assert!(parse_trusted_proxies("").unwrap().is_empty());
+        assert!(matches!(
+            parse_trusted_proxies("10.0.0.0/8,proxy.local"),
+            Err(ConfigError::InvalidTrustedProxy(entry)) if entry == "proxy.local"
+        ));
+    }
+
+    #[test]
+    fn parses_bind_addresses_with_and_without_ports() {
+        let addrs = parse_bind_addresses("0.0.0.0, [::]:8080,::1,[fe80::1]", 3000).unwrap();
+        let expected: Vec<SocketAddr> =
+            ["0.0.0.0:3000", "[::]:8080", "[::1]:3000", "[fe80::1]:3000"]
+                .iter()
+                .map(|a| a.parse().unwrap())
+                .collect();
+        assert_eq!(addrs, expected);
+
+        assert!(parse_bind_addresses("", 3000).unwrap().is_empty());
+        assert!(matches!(
+            parse_bind_addresses("0.0.0.0,localhost:80", 3000),
+            Err(ConfigError::InvalidBindAddress(entry)) if entry == "localhost:80"
+        ));
+    }
+
+    #[test]
+    fn bundled_breached_list_refuses_common_passwords() {
+        let bundled = BreachedPasswords::parse(BUNDLED_BREACHED_PASSWORDS);
+        assert!(bundled.len() >= 20);
+        for password in ["password123", "P@ssw0rd", "12345678"] {
+            assert!(bundled.contains(password), "{}", password);
+        }
+        assert!(!bundled.contains("correct horse battery staple"));
+    }
+
+    #[test]
+    fn bundled_disposable_list_covers_well_known_providers() {
+        use crate::domain::value_objects::Email;
+
+        let bundled = DisposableDomains::parse(BUNDLED_DISPOSABLE_DOMAINS);
+        assert!(bundled.len() >= 20);
+        for address in ["a@mailinator.com", "a@guerrillamail.com", "a@yopmail.com"] {
+            assert!(bundled.contains(&Email::parse(address).unwrap()), "{}", address);
+        }
+        assert!(!bundled.contains(&Email::parse("a@gmail.com").unwrap()));
+    }
 }
@@ -0,0 +1,58 @@
+use crate::config::app_config::ConfigError;
+use std::{env, time::Duration};
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// How long audit entries are kept and how the purge removes older ones
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuditRetentionConfig {
+    /// Age past which entries are purged; `None` keeps them forever
+    pub retention: Option<Duration>,
+    /// Pause between purge runs
+    pub purge_interval: Duration,
+    /// Entries deleted per statement, so a large backlog does not hold one
+    /// long-running delete
+    pub batch_size: i64,
+}
+
+impl Default for AuditRetentionConfig {
+    fn default() -> Self {
+        Self { retention: None, purge_interval: Duration::from_secs(3600), batch_size: 1000 }
+    }
+}
+
+impl AuditRetentionConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        Ok(Self {
+            retention: match env::var("AUDIT_LOG_RETENTION_DAYS") {
+                Ok(v) if !v.trim().is_empty() => Some(
+                    v.trim()
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|days| *days > 0)
+                        .map(|days| DAY * days)
+                        .ok_or(ConfigError::InvalidServerLimit("AUDIT_LOG_RETENTION_DAYS"))?,
+                ),
+                _ => defaults.retention,
+            },
+            purge_interval: match env::var("AUDIT_PURGE_INTERVAL_SECS") {
+                Ok(v) => v
+                    .parse()
+                    .ok()
+                    .filter(|secs| *secs > 0)
+                    .map(Duration::from_secs)
+                    .ok_or(ConfigError::InvalidServerLimit("AUDIT_PURGE_INTERVAL_SECS"))?,
+                Err(_) => defaults.purge_interval,
+            },
+            batch_size: match env::var("AUDIT_PURGE_BATCH_SIZE") {
+                Ok(v) => v
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or(ConfigError::InvalidServerLimit("AUDIT_PURGE_BATCH_SIZE"))?,
+                Err(_) => defaults.batch_size,
+            },
+        })
+    }
+}
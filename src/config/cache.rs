@@ -0,0 +1,52 @@
+use crate::config::app_config::ConfigError;
+use std::env;
+
+/// Implementation behind the cache while `FEATURE_CACHE` is on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CacheBackend {
+    /// Unbounded map, expired entries dropped when next read
+    #[default]
+    Memory,
+    /// Bounded `moka` cache with background expiry and LRU eviction; only
+    /// in builds with the `moka` feature
+    #[cfg(feature = "moka")]
+    Moka,
+}
+
+#[derive(Debug, Clone)]
+pub struct CacheConfig {
+    pub backend: CacheBackend,
+    /// Entries the `moka` backend keeps before evicting; ignored otherwise
+    pub max_entries: u64,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self { backend: CacheBackend::default(), max_entries: 10_000 }
+    }
+}
+
+impl CacheConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        Ok(Self {
+            backend: match env::var("CACHE_BACKEND") {
+                Ok(v) => match v.trim().to_ascii_lowercase().as_str() {
+                    "memory" => CacheBackend::Memory,
+                    #[cfg(feature = "moka")]
+                    "moka" => CacheBackend::Moka,
+                    _ => return Err(ConfigError::InvalidCacheBackend(v)),
+                },
+                Err(_) => defaults.backend,
+            },
+            max_entries: match env::var("CACHE_MAX_ENTRIES") {
+                Ok(v) => v
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or(ConfigError::InvalidServerLimit("CACHE_MAX_ENTRIES"))?,
+                Err(_) => defaults.max_entries,
+            },
+        })
+    }
+}
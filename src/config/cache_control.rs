@@ -0,0 +1,38 @@
+use crate::{config::app_config::ConfigError, shared::cache_control::CachePolicy};
+use std::env;
+
+/// `Cache-Control` for GET routes whose responses may be reused. Auth and
+/// admin routes are always `no-store`.
+#[derive(Debug, Clone, Copy)]
+pub struct CacheControlConfig {
+    /// `GET /api/users/:id`
+    pub user_detail: CachePolicy,
+    /// `GET /api/admin/system`
+    pub system_stats: CachePolicy,
+}
+
+impl Default for CacheControlConfig {
+    fn default() -> Self {
+        Self {
+            user_detail: CachePolicy::Private { max_age: 30 },
+            system_stats: CachePolicy::Private { max_age: 5 },
+        }
+    }
+}
+
+impl CacheControlConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        Ok(Self {
+            user_detail: policy("CACHE_CONTROL_USER_DETAIL", defaults.user_detail)?,
+            system_stats: policy("CACHE_CONTROL_SYSTEM_STATS", defaults.system_stats)?,
+        })
+    }
+}
+
+fn policy(var: &'static str, default: CachePolicy) -> Result<CachePolicy, ConfigError> {
+    match env::var(var) {
+        Ok(v) => CachePolicy::parse(&v).ok_or(ConfigError::InvalidCacheControl(var, v)),
+        Err(_) => Ok(default),
+    }
+}
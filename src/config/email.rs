@@ -0,0 +1,241 @@
+use crate::config::app_config::ConfigError;
+use lettre::Address;
+use std::env;
+
+#[derive(Debug, Clone)]
+pub struct EmailConfig {
+    pub smtp_host: String,
+    pub smtp_username: String,
+    pub smtp_password: String,
+    /// Envelope and `From:` address
+    pub from_address: String,
+    /// Shown alongside `from_address`, e.g. `"Acme Accounts" <no-reply@acme.test>`
+    pub from_name: Option<String>,
+    pub reply_to: Option<String>,
+    /// Audit mailbox that silently receives a copy of every message
+    pub bcc: Option<String>,
+    pub subjects: SubjectTemplates,
+}
+
+/// Subject lines replacing the localized defaults, per kind of email. Each
+/// may use `{app_name}` and `{name}`, the recipient's name; `{{` and `}}`
+/// are literal braces.
+#[derive(Debug, Clone)]
+pub struct SubjectTemplates {
+    pub app_name: String,
+    pub welcome: Option<String>,
+    /// Code sent on registration
+    pub confirmation: Option<String>,
+    /// Code sent again on request
+    pub confirmation_resent: Option<String>,
+    pub password_reset: Option<String>,
+    /// Security alert after too many failed logins
+    pub account_locked: Option<String>,
+    /// Notice before an unused account is disabled
+    pub inactivity_warning: Option<String>,
+}
+
+impl Default for SubjectTemplates {
+    fn default() -> Self {
+        Self {
+            app_name: "Axum Backend".to_string(),
+            welcome: None,
+            confirmation: None,
+            confirmation_resent: None,
+            password_reset: None,
+            account_locked: None,
+            inactivity_warning: None,
+        }
+    }
+}
+
+impl SubjectTemplates {
+    /// Fill in a template; it has already been validated at startup
+    pub fn render(&self, template: &str, name: &str) -> String {
+        render_subject(template, |key| match key {
+            "app_name" => Some(self.app_name.as_str()),
+            "name" => Some(name),
+            _ => None,
+        })
+        .unwrap_or_else(|_| template.to_string())
+    }
+
+    fn validate(&self) -> Result<(), ConfigError> {
+        let templates = [
+            ("EMAIL_SUBJECT_WELCOME", &self.welcome),
+            ("EMAIL_SUBJECT_CONFIRMATION", &self.confirmation),
+            ("EMAIL_SUBJECT_CONFIRMATION_RESENT", &self.confirmation_resent),
+            ("EMAIL_SUBJECT_PASSWORD_RESET", &self.password_reset),
+            ("EMAIL_SUBJECT_ACCOUNT_LOCKED", &self.account_locked),
+            ("EMAIL_SUBJECT_INACTIVITY_WARNING", &self.inactivity_warning),
+        ];
+        for (var, template) in templates {
+            if let Some(template) = template {
+                render_subject(template, |key| matches!(key, "app_name" | "name").then_some(""))
+                    .map_err(|reason| ConfigError::InvalidEmailSubject(var, reason))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Substitute `{placeholder}`s in one pass, so values are never re-expanded
+fn render_subject<'a>(
+    template: &str,
+    lookup: impl Fn(&str) -> Option<&'a str>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(i) = rest.find(['{', '}']) {
+        out.push_str(&rest[..i]);
+        let tail = &rest[i..];
+        if let Some(after) = tail.strip_prefix("{{").or_else(|| tail.strip_prefix("}}")) {
+            out.push_str(&tail[..1]);
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix('{') {
+            let end = after.find('}').ok_or_else(|| "unclosed '{'".to_string())?;
+            let key = &after[..end];
+            out.push_str(lookup(key).ok_or_else(|| format!("unknown placeholder {{{}}}", key))?);
+            rest = &after[end + 1..];
+        } else {
+            return Err("unmatched '}'".to_string());
+        }
+    }
+    out.push_str(rest);
+    Ok(out)
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            smtp_host: "127.0.0.1".to_string(),
+            smtp_username: String::new(),
+            smtp_password: String::new(),
+            from_address: "noreply@axum-backend.com".to_string(),
+            from_name: None,
+            reply_to: None,
+            bcc: None,
+            subjects: SubjectTemplates::default(),
+        }
+    }
+}
+
+impl EmailConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let non_empty = |key: &str| env::var(key).ok().filter(|v| !v.trim().is_empty());
+        let defaults = Self::default();
+
+        let config = Self {
+            smtp_host: non_empty("SMTP_HOST").unwrap_or(defaults.smtp_host),
+            smtp_username: non_empty("SMTP_USERNAME")
+                .or_else(|| non_empty("SMTP_USER"))
+                .unwrap_or_default(),
+            smtp_password: non_empty("SMTP_PASSWORD")
+                .or_else(|| non_empty("SMTP_PASS"))
+                .unwrap_or_default(),
+            from_address: non_empty("SMTP_FROM").unwrap_or(defaults.from_address),
+            from_name: non_empty("SMTP_FROM_NAME"),
+            reply_to: non_empty("SMTP_REPLY_TO"),
+            bcc: non_empty("SMTP_BCC"),
+            subjects: SubjectTemplates {
+                app_name: non_empty("APP_NAME").unwrap_or(defaults.subjects.app_name),
+                welcome: non_empty("EMAIL_SUBJECT_WELCOME"),
+                confirmation: non_empty("EMAIL_SUBJECT_CONFIRMATION"),
+                confirmation_resent: non_empty("EMAIL_SUBJECT_CONFIRMATION_RESENT"),
+                password_reset: non_empty("EMAIL_SUBJECT_PASSWORD_RESET"),
+                account_locked: non_empty("EMAIL_SUBJECT_ACCOUNT_LOCKED"),
+                inactivity_warning: non_empty("EMAIL_SUBJECT_INACTIVITY_WARNING"),
+            },
+        };
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Reject sender addresses and subject templates that would only fail
+    /// later, at send time.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        validate_address("SMTP_FROM", &self.from_address)?;
+        if let Some(reply_to) = &self.reply_to {
+            validate_address("SMTP_REPLY_TO", reply_to)?;
+        }
+        if let Some(bcc) = &self.bcc {
+            validate_address("SMTP_BCC", bcc)?;
+        }
+        self.subjects.validate()
+    }
+}
+
+fn validate_address(var: &str, value: &str) -> Result<(), ConfigError> {
+    value
+        .parse::<Address>()
+        .map(|_| ())
+        .map_err(|_| ConfigError::InvalidEmailAddress(var.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_sender_is_valid() {
+        assert!(EmailConfig::default().validate().is_ok());
+    }
+
+    #[test]
+    fn rejects_malformed_from_and_reply_to() {
+        let bad_from =
+            EmailConfig { from_address: "not-an-email".to_string(), ..EmailConfig::default() };
+        assert!(matches!(
+            bad_from.validate(),
+            Err(ConfigError::InvalidEmailAddress(var)) if var == "SMTP_FROM"
+        ));
+
+        let bad_reply_to =
+            EmailConfig { reply_to: Some("support@".to_string()), ..EmailConfig::default() };
+        assert!(matches!(
+            bad_reply_to.validate(),
+            Err(ConfigError::InvalidEmailAddress(var)) if var == "SMTP_REPLY_TO"
+        ));
+
+        let bad_bcc = EmailConfig { bcc: Some("audit".to_string()), ..EmailConfig::default() };
+        assert!(matches!(
+            bad_bcc.validate(),
+            Err(ConfigError::InvalidEmailAddress(var)) if var == "SMTP_BCC"
+        ));
+    }
+
+    #[test]
+    fn subject_templates_render_placeholders_once() {
+        let subjects =
+            SubjectTemplates { app_name: "Acme".to_string(), ..SubjectTemplates::default() };
+
+        assert_eq!(
+            subjects.render("Welcome to {app_name}, {name}!", "Lan"),
+            "Welcome to Acme, Lan!"
+        );
+        assert_eq!(subjects.render("{{{app_name}}}", "Lan"), "{Acme}");
+        assert_eq!(subjects.render("Hi {name}", "{app_name}"), "Hi {app_name}");
+    }
+
+    #[test]
+    fn rejects_malformed_subject_templates() {
+        for template in ["{unknown} code", "Unclosed {app_name", "Stray } brace"] {
+            let config = EmailConfig {
+                subjects: SubjectTemplates {
+                    password_reset: Some(template.to_string()),
+                    ..SubjectTemplates::default()
+                },
+                ..EmailConfig::default()
+            };
+            assert!(
+                matches!(
+                    config.validate(),
+                    Err(ConfigError::InvalidEmailSubject("EMAIL_SUBJECT_PASSWORD_RESET", _))
+                ),
+                "{}",
+                template
+            );
+        }
+    }
+}
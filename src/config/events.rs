@@ -0,0 +1,26 @@
+use crate::config::app_config::ConfigError;
+use std::env;
+
+/// Where domain events are published
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum EventTransport {
+    /// NATS at `NATS_URL`; events are only logged while it is unset
+    #[default]
+    Nats,
+    /// `pg_notify` on the application database, one channel per subject,
+    /// for deployments without a broker
+    Postgres,
+}
+
+impl EventTransport {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        match env::var("EVENT_TRANSPORT") {
+            Ok(v) => match v.trim().to_ascii_lowercase().as_str() {
+                "nats" => Ok(Self::Nats),
+                "postgres" => Ok(Self::Postgres),
+                _ => Err(ConfigError::InvalidEventTransport(v)),
+            },
+            Err(_) => Ok(Self::default()),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::config::app_config::ConfigError;
+use std::env;
+
+/// Optional subsystems that can be switched off without a rebuild.
+///
+/// A disabled subsystem falls back to a no-op implementation and its
+/// configuration is neither read nor validated, so it can be left unset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Features {
+    /// Publish domain events and probe the broker (`FEATURE_NATS`)
+    pub nats: bool,
+    /// Cache per-user roles between requests (`FEATURE_CACHE`)
+    pub cache: bool,
+    /// Deliver email over SMTP; when off, messages are only logged (`FEATURE_EMAIL`)
+    pub email: bool,
+}
+
+impl Default for Features {
+    fn default() -> Self {
+        Self { nats: true, cache: true, email: true }
+    }
+}
+
+impl Features {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Ok(Self {
+            nats: flag("FEATURE_NATS")?,
+            cache: flag("FEATURE_CACHE")?,
+            email: flag("FEATURE_EMAIL")?,
+        })
+    }
+}
+
+/// Every feature is on unless its variable says otherwise
+fn flag(var: &'static str) -> Result<bool, ConfigError> {
+    match env::var(var) {
+        Ok(v) => parse_flag(&v).ok_or(ConfigError::InvalidFeatureFlag(var)),
+        Err(_) => Ok(true),
+    }
+}
+
+/// `true`/`1`/`on` or `false`/`0`/`off`, ignoring case
+pub(crate) fn parse_flag(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "on" => Some(true),
+        "false" | "0" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_common_boolean_spellings() {
+        for on in ["true", "1", "ON", " True "] {
+            assert_eq!(parse_flag(on), Some(true), "{:?}", on);
+        }
+        for off in ["false", "0", "Off"] {
+            assert_eq!(parse_flag(off), Some(false), "{:?}", off);
+        }
+        assert_eq!(parse_flag("maybe"), None);
+        assert_eq!(parse_flag(""), None);
+    }
+}
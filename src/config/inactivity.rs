@@ -0,0 +1,76 @@
+use crate::config::app_config::ConfigError;
+use std::{env, time::Duration};
+
+const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// When unused accounts are disabled, and whether their owners are warned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InactivityConfig {
+    /// Time without a login after which an account is disabled; `None`
+    /// never disables
+    pub disable_after: Option<Duration>,
+    /// How long before disabling the user is emailed; `None` sends no
+    /// warning. Always shorter than `disable_after`.
+    pub warn_before: Option<Duration>,
+    /// Pause between checks
+    pub check_interval: Duration,
+    /// Accounts disabled per transaction, and warned per query
+    pub batch_size: usize,
+}
+
+impl Default for InactivityConfig {
+    fn default() -> Self {
+        Self {
+            disable_after: None,
+            warn_before: None,
+            check_interval: Duration::from_secs(3600),
+            batch_size: 100,
+        }
+    }
+}
+
+impl InactivityConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        let disable_after = days_var("INACTIVE_ACCOUNT_DISABLE_DAYS")?;
+        let warn_before = days_var("INACTIVE_ACCOUNT_WARNING_DAYS")?;
+        if let (Some(disable_after), Some(warn_before)) = (disable_after, warn_before) {
+            if warn_before >= disable_after {
+                return Err(ConfigError::InvalidServerLimit("INACTIVE_ACCOUNT_WARNING_DAYS"));
+            }
+        }
+
+        Ok(Self {
+            disable_after,
+            warn_before,
+            check_interval: match env::var("INACTIVE_ACCOUNT_CHECK_INTERVAL_SECS") {
+                Ok(v) => v.parse().ok().filter(|secs| *secs > 0).map(Duration::from_secs).ok_or(
+                    ConfigError::InvalidServerLimit("INACTIVE_ACCOUNT_CHECK_INTERVAL_SECS"),
+                )?,
+                Err(_) => defaults.check_interval,
+            },
+            batch_size: match env::var("INACTIVE_ACCOUNT_BATCH_SIZE") {
+                Ok(v) => v
+                    .parse()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or(ConfigError::InvalidServerLimit("INACTIVE_ACCOUNT_BATCH_SIZE"))?,
+                Err(_) => defaults.batch_size,
+            },
+        })
+    }
+}
+
+/// Positive number of days from `var`; unset or blank is `None`
+fn days_var(var: &'static str) -> Result<Option<Duration>, ConfigError> {
+    match env::var(var) {
+        Ok(v) if !v.trim().is_empty() => v
+            .trim()
+            .parse::<u32>()
+            .ok()
+            .filter(|days| *days > 0)
+            .map(|days| Some(DAY * days))
+            .ok_or(ConfigError::InvalidServerLimit(var)),
+        _ => Ok(None),
+    }
+}
@@ -0,0 +1,62 @@
+use crate::config::app_config::ConfigError;
+use std::{env, time::Duration};
+
+/// When repeated failed logins lock an account, and whether the user is told
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LockoutConfig {
+    /// Failures within `window` that lock the account; `None` never locks
+    pub max_failures: Option<u32>,
+    /// Period over which failures are counted
+    pub window: Duration,
+    /// How long a locked account stays locked
+    pub duration: Duration,
+    /// Email the user a security alert when their account is locked
+    pub alert_email: bool,
+}
+
+impl Default for LockoutConfig {
+    fn default() -> Self {
+        Self {
+            max_failures: None,
+            window: Duration::from_secs(900),
+            duration: Duration::from_secs(900),
+            alert_email: false,
+        }
+    }
+}
+
+impl LockoutConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let defaults = Self::default();
+        Ok(Self {
+            max_failures: match env::var("LOGIN_MAX_FAILED_ATTEMPTS") {
+                Ok(v) if !v.trim().is_empty() => Some(
+                    v.trim()
+                        .parse()
+                        .ok()
+                        .filter(|n| *n > 0)
+                        .ok_or(ConfigError::InvalidServerLimit("LOGIN_MAX_FAILED_ATTEMPTS"))?,
+                ),
+                _ => defaults.max_failures,
+            },
+            window: secs_var("LOGIN_FAILURE_WINDOW_SECS", defaults.window)?,
+            duration: secs_var("LOGIN_LOCKOUT_SECS", defaults.duration)?,
+            alert_email: env::var("LOCKOUT_ALERT_EMAIL")
+                .map(|v| v == "true" || v == "1")
+                .unwrap_or(defaults.alert_email),
+        })
+    }
+}
+
+/// Positive number of seconds from `var`, else `default`
+fn secs_var(var: &'static str, default: Duration) -> Result<Duration, ConfigError> {
+    match env::var(var) {
+        Ok(v) => v
+            .parse()
+            .ok()
+            .filter(|secs| *secs > 0)
+            .map(Duration::from_secs)
+            .ok_or(ConfigError::InvalidServerLimit(var)),
+        Err(_) => Ok(default),
+    }
+}
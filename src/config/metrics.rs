@@ -0,0 +1,90 @@
+use crate::config::app_config::ConfigError;
+use std::env;
+
+/// Request duration buckets (seconds), matching axum-prometheus' defaults.
+pub const DEFAULT_DURATION_BUCKETS: &[f64] =
+    &[0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+/// Credentials required to scrape `/metrics`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MetricsAuth {
+    /// `Authorization: Bearer <token>`
+    Bearer(String),
+    /// `Authorization: Basic <base64(username:password)>`
+    Basic { username: String, password: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct MetricsConfig {
+    /// `None` leaves `/metrics` open (the default, suitable for private networks).
+    pub auth: Option<MetricsAuth>,
+    /// Histogram buckets for `axum_http_requests_duration_seconds`.
+    pub duration_buckets: Vec<f64>,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self { auth: None, duration_buckets: DEFAULT_DURATION_BUCKETS.to_vec() }
+    }
+}
+
+impl MetricsConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let duration_buckets = match env::var("METRICS_DURATION_BUCKETS") {
+            Ok(raw) if !raw.trim().is_empty() => parse_buckets(&raw)?,
+            _ => DEFAULT_DURATION_BUCKETS.to_vec(),
+        };
+
+        Ok(Self { auth: metrics_auth_from_env(), duration_buckets })
+    }
+}
+
+/// Parse a comma-separated list of strictly increasing, positive bucket bounds.
+fn parse_buckets(raw: &str) -> Result<Vec<f64>, ConfigError> {
+    let buckets = raw
+        .split(',')
+        .map(|b| b.trim().parse::<f64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| ConfigError::InvalidMetricsBuckets)?;
+
+    let positive = buckets.iter().all(|b| b.is_finite() && *b > 0.0);
+    let increasing = buckets.windows(2).all(|w| w[0] < w[1]);
+    if !positive || !increasing {
+        return Err(ConfigError::InvalidMetricsBuckets);
+    }
+
+    Ok(buckets)
+}
+
+/// A bearer token takes precedence over basic credentials when both are set.
+fn metrics_auth_from_env() -> Option<MetricsAuth> {
+    let non_empty = |key: &str| env::var(key).ok().filter(|v| !v.trim().is_empty());
+
+    if let Some(token) = non_empty("METRICS_BEARER_TOKEN") {
+        return Some(MetricsAuth::Bearer(token));
+    }
+
+    match (non_empty("METRICS_BASIC_USERNAME"), non_empty("METRICS_BASIC_PASSWORD")) {
+        (Some(username), Some(password)) => Some(MetricsAuth::Basic { username, password }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_comma_separated_buckets() {
+        assert_eq!(parse_buckets("0.01, 0.1,1,10").unwrap(), vec![0.01, 0.1, 1.0, 10.0]);
+    }
+
+    #[test]
+    fn rejects_malformed_or_unordered_buckets() {
+        assert!(parse_buckets("0.1,abc").is_err());
+        assert!(parse_buckets("0.1,,1").is_err());
+        assert!(parse_buckets("1,0.5").is_err());
+        assert!(parse_buckets("0.1,0.1").is_err());
+        assert!(parse_buckets("-1,1").is_err());
+    }
+}
@@ -1,5 +1,31 @@
 pub mod app_config;
+pub mod audit;
+pub mod cache;
+pub mod cache_control;
 pub mod database;
+pub mod diagnostics;
+pub mod email;
+pub mod events;
+pub mod features;
+pub mod inactivity;
+pub mod lockout;
+pub mod metrics;
+pub mod nats;
+pub mod runtime;
+pub mod sms;
 
-pub use app_config::AppConfig;
+pub use app_config::{parse_trusted_proxies, AppConfig};
+pub use audit::AuditRetentionConfig;
+pub use cache::{CacheBackend, CacheConfig};
+pub use cache_control::CacheControlConfig;
 pub use database::DatabaseConfig;
+pub use diagnostics::REDACTED;
+pub use email::EmailConfig;
+pub use events::EventTransport;
+pub use features::Features;
+pub use inactivity::InactivityConfig;
+pub use lockout::LockoutConfig;
+pub use metrics::{MetricsAuth, MetricsConfig};
+pub use nats::NatsConfig;
+pub use runtime::RuntimeConfig;
+pub use sms::{SmsConfig, TwilioConfig};
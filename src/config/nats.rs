@@ -0,0 +1,31 @@
+use std::env;
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct NatsConfig {
+    /// Broker URL; `None` disables the readiness check entirely.
+    pub url: Option<String>,
+    /// When `false` a NATS outage is reported but does not fail readiness.
+    pub required: bool,
+    pub ping_timeout: Duration,
+}
+
+impl Default for NatsConfig {
+    fn default() -> Self {
+        Self { url: None, required: false, ping_timeout: Duration::from_secs(2) }
+    }
+}
+
+impl NatsConfig {
+    pub fn from_env() -> Self {
+        Self {
+            url: env::var("NATS_URL").ok().filter(|v| !v.trim().is_empty()),
+            required: env::var("NATS_REQUIRED").map(|v| v == "true" || v == "1").unwrap_or(false),
+            ping_timeout: env::var("NATS_PING_TIMEOUT_MS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .map(Duration::from_millis)
+                .unwrap_or(Duration::from_secs(2)),
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::config::app_config::ConfigError;
+use std::env;
+
+/// Thread counts for the Tokio runtime the server runs on
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RuntimeConfig {
+    /// Threads polling async tasks; `None` uses one per CPU core
+    pub worker_threads: Option<usize>,
+    /// Upper bound on threads for blocking work such as password hashing;
+    /// `None` keeps Tokio's default of 512
+    pub max_blocking_threads: Option<usize>,
+}
+
+impl RuntimeConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Ok(Self {
+            worker_threads: count_var("RUNTIME_WORKER_THREADS")?,
+            max_blocking_threads: count_var("RUNTIME_MAX_BLOCKING_THREADS")?,
+        })
+    }
+
+    /// Multi-threaded runtime builder with these thread counts and all
+    /// drivers enabled, as `#[tokio::main]` would set up
+    pub fn builder(&self) -> tokio::runtime::Builder {
+        let mut builder = tokio::runtime::Builder::new_multi_thread();
+        builder.enable_all();
+        if let Some(threads) = self.worker_threads {
+            builder.worker_threads(threads);
+        }
+        if let Some(threads) = self.max_blocking_threads {
+            builder.max_blocking_threads(threads);
+        }
+        builder
+    }
+}
+
+/// Positive count from `var`; unset or blank leaves Tokio's default
+fn count_var(var: &'static str) -> Result<Option<usize>, ConfigError> {
+    match env::var(var) {
+        Ok(v) if !v.trim().is_empty() => v
+            .trim()
+            .parse()
+            .ok()
+            .filter(|n| *n > 0)
+            .map(Some)
+            .ok_or(ConfigError::InvalidServerLimit(var)),
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builder_starts_the_configured_number_of_workers() {
+        let config = RuntimeConfig { worker_threads: Some(3), max_blocking_threads: Some(2) };
+        let runtime = config.builder().build().unwrap();
+
+        assert_eq!(runtime.metrics().num_workers(), 3);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{application::dto::auth::NotificationChannel, config::app_config::ConfigError};
+use std::env;
+
+/// Twilio's REST API, overridable for tests
+pub const TWILIO_API_BASE: &str = "https://api.twilio.com";
+
+#[derive(Debug, Clone, Default)]
+pub struct SmsConfig {
+    /// Channel password reset codes go to when a request does not name one
+    pub default_channel: NotificationChannel,
+    /// `None` leaves SMS unavailable, so every code is emailed
+    pub twilio: Option<TwilioConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TwilioConfig {
+    pub account_sid: String,
+    pub auth_token: String,
+    /// Sending number in E.164 form
+    pub from_number: String,
+    pub api_base: String,
+}
+
+impl SmsConfig {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        let non_empty = |key: &str| env::var(key).ok().filter(|v| !v.trim().is_empty());
+        let required = |key: &'static str| {
+            non_empty(key).ok_or_else(|| ConfigError::MissingEnvVar(key.to_string()))
+        };
+
+        let twilio = match non_empty("TWILIO_ACCOUNT_SID") {
+            Some(account_sid) => Some(TwilioConfig {
+                account_sid,
+                auth_token: required("TWILIO_AUTH_TOKEN")?,
+                from_number: required("TWILIO_FROM_NUMBER")?,
+                api_base: non_empty("TWILIO_API_BASE").unwrap_or_else(|| TWILIO_API_BASE.into()),
+            }),
+            None => None,
+        };
+
+        let default_channel = match non_empty("PASSWORD_RESET_CHANNEL") {
+            Some(v) => {
+                NotificationChannel::parse(&v).ok_or(ConfigError::InvalidNotificationChannel(v))?
+            },
+            None => NotificationChannel::default(),
+        };
+        // SMS by default with nowhere to send it is a misconfiguration
+        if default_channel == NotificationChannel::Sms && twilio.is_none() {
+            return Err(ConfigError::MissingEnvVar("TWILIO_ACCOUNT_SID".to_string()));
+        }
+
+        Ok(Self { default_channel, twilio })
+    }
+}
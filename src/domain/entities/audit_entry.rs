@@ -0,0 +1,39 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A record of an administrative action, kept for compliance and forensics
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: Uuid,
+    /// User who performed the action; `None` for system actions
+    pub actor_id: Option<Uuid>,
+    /// Dotted action name, e.g. `user.role_changed`
+    pub action: String,
+    /// Entity the action was performed on
+    pub target_id: Option<Uuid>,
+    /// Action-specific context, e.g. old and new values
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    /// Kept past the retention period until the hold is lifted
+    pub legal_hold: bool,
+}
+
+impl AuditEntry {
+    pub fn new(
+        actor_id: Option<Uuid>,
+        action: impl Into<String>,
+        target_id: Option<Uuid>,
+        details: serde_json::Value,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            actor_id,
+            action: action.into(),
+            target_id,
+            details,
+            created_at: Utc::now(),
+            legal_hold: false,
+        }
+    }
+}
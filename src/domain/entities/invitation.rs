@@ -0,0 +1,46 @@
+use crate::domain::value_objects::UserRole;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// A single-use invitation to register, created by an admin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Invitation {
+    pub id: Uuid,
+    /// SHA-256 of the token handed to the invitee
+    pub token_hash: String,
+    /// Role the invitee registers with; `None` gives the default role
+    pub role: Option<UserRole>,
+    /// Admin who created it
+    pub created_by: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    /// User who registered with it
+    pub used_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl Invitation {
+    pub fn new(
+        token_hash: String,
+        role: Option<UserRole>,
+        created_by: Option<Uuid>,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            token_hash,
+            role,
+            created_by,
+            expires_at,
+            used_at: None,
+            used_by: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Not yet used and not expired
+    pub fn is_usable(&self) -> bool {
+        self.used_at.is_none() && self.expires_at > Utc::now()
+    }
+}
@@ -1,5 +1,9 @@
+pub mod audit_entry;
+pub mod invitation;
 pub mod refresh_token;
 pub mod user;
 
+pub use audit_entry::AuditEntry;
+pub use invitation::Invitation;
 pub use refresh_token::RefreshToken;
 pub use user::User;
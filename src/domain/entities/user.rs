@@ -1,6 +1,6 @@
 use crate::domain::{
     errors::DomainError,
-    value_objects::{Email, UserId, UserRole},
+    value_objects::{Email, PhoneNumber, UserId, UserRole},
 };
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
@@ -18,24 +18,30 @@ pub struct User {
     pub confirmation_code: Option<String>,
     pub confirmation_code_expires_at: Option<DateTime<Utc>>,
     pub last_login: Option<DateTime<Utc>>,
+    /// When the password was last set, if ever
+    pub password_changed_at: Option<DateTime<Utc>>,
+    /// Mobile number in E.164 form, if the user has given one
+    pub phone: Option<PhoneNumber>,
+    pub is_phone_verified: bool,
+    /// Code texted to `phone` to verify it, stored like `confirmation_code`
+    pub phone_code: Option<String>,
+    pub phone_code_expires_at: Option<DateTime<Utc>>,
+    /// Set by an admin-forced reset until the next password change, which is
+    /// then exempt from the minimum change interval
+    pub password_reset_forced: bool,
+    /// Preferred language for outgoing emails, as a locale tag
+    pub locale: String,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Longest display name accepted, in characters
+pub const MAX_NAME_LENGTH: usize = 255;
+
 impl User {
     /// Create a new user (inactive, no password, with confirmation code)
     pub fn new(email: Email, name: String) -> Result<Self, DomainError> {
-        // Validate name
-        if name.trim().is_empty() {
-            return Err(DomainError::InvalidName);
-        }
-
-        if name.len() > 255 {
-            return Err(DomainError::InvalidUserData(
-                "Name must be less than 255 characters".to_string(),
-            ));
-        }
-
+        let name = Self::normalize_name(&name)?;
         let now = Utc::now();
 
         // 6-digit code generation logic should ideally be in a service, using basic random here or placeholder
@@ -47,7 +53,7 @@ impl User {
         Ok(Self {
             id: UserId::new(),
             email,
-            name: name.trim().to_string(),
+            name,
             password_hash: None,
             role: UserRole::default(),
             is_active: false,
@@ -55,6 +61,13 @@ impl User {
             confirmation_code: None, // Set by `set_confirmation_code`
             confirmation_code_expires_at: None,
             last_login: None,
+            password_changed_at: None,
+            phone: None,
+            is_phone_verified: false,
+            phone_code: None,
+            phone_code_expires_at: None,
+            password_reset_forced: false,
+            locale: "en".to_string(),
             created_at: now,
             updated_at: now,
         })
@@ -79,7 +92,39 @@ impl User {
 
     /// Set password
     pub fn set_password(&mut self, hash: String) {
+        let now = Utc::now();
         self.password_hash = Some(hash);
+        self.password_changed_at = Some(now);
+        self.password_reset_forced = false;
+        self.updated_at = now;
+    }
+
+    /// Require a new password, set with `code`, on an admin's behalf
+    pub fn force_password_reset(&mut self, code: String, expires_at: DateTime<Utc>) {
+        self.set_confirmation_code(code, expires_at);
+        self.password_reset_forced = true;
+    }
+
+    /// The phone number codes may be texted to, once it has been verified
+    pub fn verified_phone(&self) -> Option<&PhoneNumber> {
+        self.phone.as_ref().filter(|_| self.is_phone_verified)
+    }
+
+    /// Replace the phone number with `phone`, unverified until `code` is
+    /// confirmed
+    pub fn set_phone(&mut self, phone: PhoneNumber, code: String, expires_at: DateTime<Utc>) {
+        self.phone = Some(phone);
+        self.is_phone_verified = false;
+        self.phone_code = Some(code);
+        self.phone_code_expires_at = Some(expires_at);
+        self.updated_at = Utc::now();
+    }
+
+    /// Mark the phone number verified and spend its code
+    pub fn verify_phone(&mut self) {
+        self.is_phone_verified = true;
+        self.phone_code = None;
+        self.phone_code_expires_at = None;
         self.updated_at = Utc::now();
     }
 
@@ -96,6 +141,13 @@ impl User {
         confirmation_code: Option<String>,
         confirmation_code_expires_at: Option<DateTime<Utc>>,
         last_login: Option<DateTime<Utc>>,
+        password_changed_at: Option<DateTime<Utc>>,
+        phone: Option<PhoneNumber>,
+        is_phone_verified: bool,
+        phone_code: Option<String>,
+        phone_code_expires_at: Option<DateTime<Utc>>,
+        password_reset_forced: bool,
+        locale: String,
         created_at: DateTime<Utc>,
         updated_at: DateTime<Utc>,
     ) -> Self {
@@ -110,24 +162,41 @@ impl User {
             confirmation_code,
             confirmation_code_expires_at,
             last_login,
+            password_changed_at,
+            phone,
+            is_phone_verified,
+            phone_code,
+            phone_code_expires_at,
+            password_reset_forced,
+            locale,
             created_at,
             updated_at,
         }
     }
 
-    /// Update user name
-    pub fn update_name(&mut self, new_name: String) -> Result<(), DomainError> {
-        if new_name.trim().is_empty() {
-            return Err(DomainError::InvalidName);
+    /// Name with control characters stripped and surrounding whitespace
+    /// trimmed, or an error if nothing is left or it is too long
+    pub fn normalize_name(name: &str) -> Result<String, DomainError> {
+        let name: String = name.chars().filter(|c| !c.is_control()).collect();
+        let name = name.trim();
+
+        if name.is_empty() {
+            return Err(DomainError::InvalidName("name cannot be empty".to_string()));
         }
 
-        if new_name.len() > 255 {
-            return Err(DomainError::InvalidUserData(
-                "Name must be less than 255 characters".to_string(),
-            ));
+        if name.chars().count() > MAX_NAME_LENGTH {
+            return Err(DomainError::InvalidName(format!(
+                "name must be at most {} characters",
+                MAX_NAME_LENGTH
+            )));
         }
 
-        self.name = new_name.trim().to_string();
+        Ok(name.to_string())
+    }
+
+    /// Update user name
+    pub fn update_name(&mut self, new_name: String) -> Result<(), DomainError> {
+        self.name = Self::normalize_name(&new_name)?;
         self.updated_at = Utc::now();
         Ok(())
     }
@@ -158,7 +227,31 @@ mod tests {
         let email = Email::parse("test@example.com").unwrap();
         let result = User::new(email, "   ".to_string());
 
-        assert!(result.is_err());
+        assert!(matches!(result, Err(DomainError::InvalidName(_))));
+    }
+
+    #[test]
+    fn test_name_rejects_overlong_input() {
+        let at_limit = "a".repeat(MAX_NAME_LENGTH);
+        assert_eq!(User::normalize_name(&at_limit).unwrap(), at_limit);
+
+        // Counted in characters, not bytes
+        let multibyte = "é".repeat(MAX_NAME_LENGTH);
+        assert!(User::normalize_name(&multibyte).is_ok());
+
+        let result = User::normalize_name(&"a".repeat(MAX_NAME_LENGTH + 1));
+        assert!(
+            matches!(result, Err(DomainError::InvalidName(ref detail)) if detail.contains("255")),
+            "{:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_name_strips_control_characters() {
+        assert_eq!(User::normalize_name("  Jane\u{0}\u{1b}[31m Doe\n").unwrap(), "Jane[31m Doe");
+        assert_eq!(User::normalize_name("Nguyễn\u{7f} Văn").unwrap(), "Nguyễn Văn");
+        assert!(matches!(User::normalize_name("\t\r\n \u{0}"), Err(DomainError::InvalidName(_))));
     }
 
     #[test]
@@ -170,6 +263,17 @@ mod tests {
         assert_eq!(user.name, "Jane Doe");
     }
 
+    #[test]
+    fn test_set_password_records_change_time() {
+        let email = Email::parse("test@example.com").unwrap();
+        let mut user = User::new(email, "John Doe".to_string()).unwrap();
+        assert!(user.password_changed_at.is_none());
+
+        user.set_password("hash".to_string());
+
+        assert_eq!(user.password_changed_at, Some(user.updated_at));
+    }
+
     #[test]
     fn test_update_email() {
         let email = Email::parse("test@example.com").unwrap();
@@ -4,8 +4,14 @@ pub enum DomainError {
     #[error("Invalid email format: {0}")]
     InvalidEmail(String),
 
-    #[error("Invalid name: name cannot be empty")]
-    InvalidName,
+    #[error("Invalid email domain: {0}")]
+    InvalidEmailDomain(String),
+
+    #[error("Invalid phone number: {0}")]
+    InvalidPhoneNumber(String),
+
+    #[error("Invalid name: {0}")]
+    InvalidName(String),
 
     #[error("Invalid user data: {0}")]
     InvalidUserData(String),
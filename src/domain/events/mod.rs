@@ -0,0 +1,24 @@
+/// Domain events published to the message broker
+///
+/// Payloads are versioned by subject prefix (`events.v2.*`) so consumers can
+/// pin to a schema; breaking changes go into a new version module.
+pub mod v2;
+
+use serde::{Deserialize, Serialize};
+
+/// An event that downstream systems can subscribe to
+pub trait DomainEvent: Serialize {
+    /// Broker subject the event is published on
+    fn subject(&self) -> &'static str;
+}
+
+/// What is published: the event's own fields, plus metadata alongside them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventEnvelope<E> {
+    /// W3C `traceparent` of the operation that raised the event, so
+    /// consumers can continue its trace
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub traceparent: Option<String>,
+    #[serde(flatten)]
+    pub event: E,
+}
@@ -0,0 +1,39 @@
+use super::DomainEvent;
+use crate::domain::value_objects::UserRole;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+pub const USER_ROLE_CHANGED: &str = "events.v2.user.role_changed";
+pub const USER_CREATED: &str = "events.v2.user.created";
+
+/// A user's role was changed by an administrator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserRoleChanged {
+    pub user_id: Uuid,
+    pub old_role: UserRole,
+    pub new_role: UserRole,
+    /// `None` when the change was not made through an authenticated request
+    pub actor_id: Option<Uuid>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl DomainEvent for UserRoleChanged {
+    fn subject(&self) -> &'static str {
+        USER_ROLE_CHANGED
+    }
+}
+
+/// A user account was created; raised by bulk import
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserCreated {
+    pub user_id: Uuid,
+    pub role: UserRole,
+    pub occurred_at: DateTime<Utc>,
+}
+
+impl DomainEvent for UserCreated {
+    fn subject(&self) -> &'static str {
+        USER_CREATED
+    }
+}
@@ -1,5 +1,6 @@
 pub mod entities;
 pub mod errors;
+pub mod events;
 pub mod repositories;
 pub mod value_objects;
 
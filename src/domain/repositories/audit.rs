@@ -0,0 +1,17 @@
+use crate::domain::{entities::AuditEntry, repositories::user::RepositoryError};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+/// Append-only store for audit entries; entries only leave it through the
+/// retention purge
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait AuditRepository: Send + Sync {
+    /// Persist a single entry
+    async fn record(&self, entry: &AuditEntry) -> Result<(), RepositoryError>;
+
+    /// Delete at most `limit` entries created before `cutoff`, oldest
+    /// first, skipping those under legal hold. Returns how many were deleted.
+    async fn purge_before(&self, cutoff: DateTime<Utc>, limit: i64)
+        -> Result<u64, RepositoryError>;
+}
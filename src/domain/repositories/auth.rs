@@ -1,4 +1,7 @@
-use crate::domain::entities::{refresh_token::RefreshToken, user::User};
+use crate::domain::{
+    entities::{refresh_token::RefreshToken, user::User},
+    value_objects::UserRole,
+};
 use async_trait::async_trait;
 
 use uuid::Uuid;
@@ -24,7 +27,11 @@ pub trait AuthRepository: Send + Sync {
     /// Find user by email
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, AuthRepositoryError>;
 
-    /// Create a new user with password hash
+    /// Find user by id
+    async fn find_by_id(&self, user_id: Uuid) -> Result<Option<User>, AuthRepositoryError>;
+
+    /// Create a new user with password hash, preferred locale and initial role
+    #[allow(clippy::too_many_arguments)]
     async fn create_user(
         &self,
         email: &str,
@@ -32,11 +39,31 @@ pub trait AuthRepository: Send + Sync {
         password_hash: Option<String>,
         confirmation_code: Option<String>,
         expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        locale: &str,
+        role: UserRole,
     ) -> Result<User, AuthRepositoryError>;
 
     /// Update user's last login timestamp
     async fn update_last_login(&self, user_id: Uuid) -> Result<(), AuthRepositoryError>;
 
+    /// Clear the user's confirmation code if it equals `code` and has not
+    /// expired, in the same update that checks it. Returns whether the code
+    /// was consumed, so of two requests replaying one code only one succeeds.
+    async fn consume_confirmation_code(
+        &self,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<bool, AuthRepositoryError>;
+
+    /// Mark the user's phone verified and clear its code if the code equals
+    /// `code` and has not expired, in the same update that checks it.
+    /// Returns whether the code was consumed, as `consume_confirmation_code`.
+    async fn consume_phone_code(
+        &self,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<bool, AuthRepositoryError>;
+
     /// Update user entity (generic update)
     async fn update_user(&self, user: &User) -> Result<User, AuthRepositoryError>;
 
@@ -49,12 +76,73 @@ pub trait AuthRepository: Send + Sync {
         token_hash: &str,
     ) -> Result<Option<RefreshToken>, AuthRepositoryError>;
 
+    /// Unrevoked, unexpired refresh tokens for a user, oldest first
+    async fn list_active_refresh_tokens(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<RefreshToken>, AuthRepositoryError>;
+
+    /// Save `token` as one of at most `max_active` active sessions for its
+    /// user. Runs in one transaction holding the user's row lock, so
+    /// concurrent logins cannot both take the last slot. With `evict_oldest`
+    /// the oldest active tokens are revoked to make room and their number
+    /// returned; otherwise a full user gets `None` and nothing is saved.
+    async fn save_refresh_token_within_limit(
+        &self,
+        token: &RefreshToken,
+        max_active: usize,
+        evict_oldest: bool,
+    ) -> Result<Option<usize>, AuthRepositoryError>;
+
     /// Revoke refresh token
     async fn revoke_refresh_token(&self, token_hash: &str) -> Result<(), AuthRepositoryError>;
 
+    /// Revoke the active refresh token `token_id` if it belongs to `user_id`;
+    /// `TokenNotFound` otherwise, whoever the token belongs to
+    async fn revoke_user_refresh_token(
+        &self,
+        user_id: Uuid,
+        token_id: Uuid,
+    ) -> Result<(), AuthRepositoryError>;
+
     /// Revoke all user's refresh tokens (logout from all devices)
     async fn revoke_all_user_tokens(&self, user_id: Uuid) -> Result<(), AuthRepositoryError>;
 
+    /// In one transaction, mark the given users inactive and revoke all their
+    /// refresh tokens. Returns the ids that matched a user; unknown ids are
+    /// skipped.
+    async fn deactivate_users(&self, user_ids: &[Uuid]) -> Result<Vec<Uuid>, AuthRepositoryError>;
+
+    /// Up to `limit` idle users, oldest sign-up first, not yet warned since
+    /// they were last seen. Idle users are active non-admins last seen
+    /// before `before`: at their last login, or at sign-up if they never
+    /// logged in.
+    async fn find_unwarned_idle_users(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+        limit: usize,
+    ) -> Result<Vec<User>, AuthRepositoryError>;
+
+    /// Record that the given users were warned at `at` of being disabled.
+    /// Logging in clears the warning.
+    async fn record_inactivity_warnings(
+        &self,
+        user_ids: &[Uuid],
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), AuthRepositoryError>;
+
+    /// Deactivate up to `limit` users idle since before `before`, as
+    /// `deactivate_users` does. With `warned_before`, only users warned no
+    /// later than that are included. The idle check is repeated in the
+    /// update, so a user who logs in meanwhile stays active. Returns the
+    /// ids deactivated.
+    async fn deactivate_idle_users(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+        warned_before: Option<chrono::DateTime<chrono::Utc>>,
+        limit: usize,
+    ) -> Result<Vec<Uuid>, AuthRepositoryError>;
+
     /// Clean up expired tokens
     async fn cleanup_expired_tokens(&self) -> Result<u64, AuthRepositoryError>;
 }
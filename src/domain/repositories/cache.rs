@@ -0,0 +1,47 @@
+use async_trait::async_trait;
+use std::time::Duration;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CacheError {
+    #[error("Cache backend error: {0}")]
+    Backend(String),
+}
+
+/// Key/value cache with per-entry expiry
+///
+/// Callers treat the cache as an optimisation: a failed lookup should fall
+/// back to the source of truth rather than fail the request.
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait CacheRepository: Send + Sync {
+    /// Value stored under `key`, or `None` if missing or expired
+    async fn get(&self, key: &str) -> Result<Option<String>, CacheError>;
+
+    /// Store `value` under `key`, replacing any previous value
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<(), CacheError>;
+
+    /// Remove `key`; removing a missing key is not an error
+    async fn delete(&self, key: &str) -> Result<(), CacheError>;
+
+    /// Store `value` only if `key` is missing or expired, as one atomic step.
+    /// Returns whether the value was stored.
+    async fn set_nx(&self, key: &str, value: &str, ttl: Duration) -> Result<bool, CacheError>;
+
+    /// Remove `key` only if it currently holds `expected`, as one atomic
+    /// step. Returns whether it was removed.
+    async fn delete_if_equals(&self, key: &str, expected: &str) -> Result<bool, CacheError>;
+
+    /// Reset the expiry of `key` to `ttl` only if it currently holds
+    /// `expected`, as one atomic step. Returns whether it was extended.
+    async fn extend_if_equals(
+        &self,
+        key: &str,
+        expected: &str,
+        ttl: Duration,
+    ) -> Result<bool, CacheError>;
+
+    /// Add one to the counter at `key`, starting a new one that expires
+    /// after `ttl` if it is missing or expired, as one atomic step. Returns
+    /// the new count and the time left before the counter expires.
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<(u64, Duration), CacheError>;
+}
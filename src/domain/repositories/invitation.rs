@@ -0,0 +1,21 @@
+use crate::domain::{entities::Invitation, repositories::user::RepositoryError};
+use async_trait::async_trait;
+use uuid::Uuid;
+
+/// Store for signup invitations, looked up by token hash
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait InvitationRepository: Send + Sync {
+    async fn create(&self, invitation: &Invitation) -> Result<(), RepositoryError>;
+
+    /// Mark the invitation with `token_hash` used, if it is still usable.
+    /// Done in one update so two registrations cannot share an invitation.
+    async fn claim(&self, token_hash: &str) -> Result<Option<Invitation>, RepositoryError>;
+
+    /// Make a claimed invitation usable again after the registration it was
+    /// claimed for failed
+    async fn release(&self, id: Uuid) -> Result<(), RepositoryError>;
+
+    /// Record which user registered with a claimed invitation
+    async fn set_used_by(&self, id: Uuid, user_id: Uuid) -> Result<(), RepositoryError>;
+}
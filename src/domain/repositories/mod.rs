@@ -2,12 +2,20 @@
 ///
 /// These traits define the contracts for data access operations.
 /// Implementations are provided in the infrastructure layer.
+pub mod audit;
 pub mod auth;
+pub mod cache;
+pub mod invitation;
+pub mod password_history;
 pub mod user;
 
 // Re-export repository traits
+pub use audit::AuditRepository;
 pub use auth::{AuthRepository, AuthRepositoryError};
-pub use user::UserRepository;
+pub use cache::{CacheError, CacheRepository};
+pub use invitation::InvitationRepository;
+pub use password_history::PasswordHistoryRepository;
+pub use user::{UserChanges, UserFilter, UserRepository};
 
 // Backward compatibility (deprecated)
 #[deprecated(since = "0.3.0", note = "Use `auth` module instead")]
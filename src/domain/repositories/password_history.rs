@@ -0,0 +1,18 @@
+use crate::domain::{repositories::user::RepositoryError, value_objects::UserId};
+use async_trait::async_trait;
+
+/// Hashes of passwords a user has set, newest first
+#[cfg_attr(test, mockall::automock)]
+#[async_trait]
+pub trait PasswordHistoryRepository: Send + Sync {
+    /// Up to `limit` of the user's most recent password hashes
+    async fn recent(&self, user_id: UserId, limit: usize) -> Result<Vec<String>, RepositoryError>;
+
+    /// Store a newly set hash, keeping only the `keep` most recent
+    async fn record(
+        &self,
+        user_id: UserId,
+        password_hash: &str,
+        keep: usize,
+    ) -> Result<(), RepositoryError>;
+}
@@ -1,11 +1,13 @@
 use crate::domain::{
     entities::User,
-    value_objects::{Email, UserId},
+    value_objects::{Email, UserId, UserRole},
 };
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 
 /// Repository trait for User entity
 /// This is defined in the domain layer but implemented in infrastructure
+#[cfg_attr(test, mockall::automock)]
 #[async_trait]
 pub trait UserRepository: Send + Sync {
     /// Save a new user or update existing
@@ -14,6 +16,13 @@ pub trait UserRepository: Send + Sync {
     /// Update an existing user
     async fn update(&self, user: &User) -> Result<User, RepositoryError>;
 
+    /// Write only the fields set in `changes`; `None` if the user does not exist
+    async fn patch(
+        &self,
+        id: UserId,
+        changes: &UserChanges,
+    ) -> Result<Option<User>, RepositoryError>;
+
     /// Find user by ID
     async fn find_by_id(&self, id: UserId) -> Result<Option<User>, RepositoryError>;
 
@@ -29,6 +38,10 @@ pub trait UserRepository: Send + Sync {
     /// List all users with pagination
     async fn list_paginated(&self, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError>;
 
+    /// Every user matching `filter`, oldest first, read incrementally so the
+    /// full result set is never held in memory
+    fn stream(&self, filter: &UserFilter) -> BoxStream<'static, Result<User, RepositoryError>>;
+
     /// Delete user by ID
     async fn delete(&self, id: UserId) -> Result<bool, RepositoryError>;
 
@@ -36,6 +49,26 @@ pub trait UserRepository: Send + Sync {
     async fn delete_all(&self) -> Result<usize, RepositoryError>;
 }
 
+/// Partial update of a user; `None` leaves the column untouched
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserChanges {
+    pub name: Option<String>,
+    pub locale: Option<String>,
+}
+
+impl UserChanges {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.locale.is_none()
+    }
+}
+
+/// Criteria for selecting users; `None` matches any value
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserFilter {
+    pub role: Option<UserRole>,
+    pub is_active: Option<bool>,
+}
+
 /// Repository-specific errors
 #[derive(Debug, thiserror::Error)]
 pub enum RepositoryError {
@@ -48,21 +81,13 @@ pub enum RepositoryError {
     #[error("Duplicate email: {0}")]
     DuplicateEmail(String),
 
+    #[error("Referenced record does not exist: {0}")]
+    ForeignKeyViolation(String),
+
+    /// Concurrent transactions conflicted; the operation may succeed if retried
+    #[error("Serialization failure: {0}")]
+    SerializationFailure(String),
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
-
-impl From<diesel::result::Error> for RepositoryError {
-    fn from(err: diesel::result::Error) -> Self {
-        match err {
-            diesel::result::Error::NotFound => RepositoryError::NotFound,
-            diesel::result::Error::DatabaseError(kind, info) => match kind {
-                diesel::result::DatabaseErrorKind::UniqueViolation => {
-                    RepositoryError::DuplicateEmail(info.message().to_string())
-                },
-                _ => RepositoryError::Database(info.message().to_string()),
-            },
-            _ => RepositoryError::Internal(err.to_string()),
-        }
-    }
-}
@@ -30,6 +30,16 @@ impl Email {
         &self.0
     }
 
+    /// The part before the `@`
+    pub fn local_part(&self) -> &str {
+        self.0.rsplit_once('@').map_or("", |(local, _)| local)
+    }
+
+    /// The part after the `@`
+    pub fn domain(&self) -> &str {
+        self.0.rsplit_once('@').map_or("", |(_, domain)| domain)
+    }
+
     /// Convert to owned String
     pub fn into_string(self) -> String {
         self.0
@@ -0,0 +1,183 @@
+use crate::domain::{errors::DomainError, value_objects::Email};
+use std::collections::HashSet;
+
+/// One entry of a domain list: `example.com` matches only that domain,
+/// `*.example.com` matches any subdomain of it but not `example.com` itself
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DomainPattern {
+    Exact(String),
+    Subdomains(String),
+}
+
+impl DomainPattern {
+    fn parse(raw: &str) -> Result<Self, DomainError> {
+        let pattern = raw.trim().to_lowercase();
+        let (wildcard, domain) = match pattern.strip_prefix("*.") {
+            Some(domain) => (true, domain),
+            None => (false, pattern.as_str()),
+        };
+        let valid_label = |label: &str| {
+            !label.is_empty() && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        };
+        if !domain.split('.').all(valid_label) {
+            return Err(DomainError::InvalidEmailDomain(raw.trim().to_string()));
+        }
+
+        let domain = domain.to_string();
+        Ok(if wildcard { Self::Subdomains(domain) } else { Self::Exact(domain) })
+    }
+
+    fn matches(&self, domain: &str) -> bool {
+        match self {
+            Self::Exact(d) => domain == d,
+            Self::Subdomains(parent) => domain
+                .strip_suffix(parent.as_str())
+                .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+        }
+    }
+}
+
+/// Which email domains may register.
+///
+/// A domain on the deny list is always refused. When the allow list is
+/// non-empty, only domains on it are accepted; otherwise every domain not
+/// denied is.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EmailDomainPolicy {
+    allowed: Vec<DomainPattern>,
+    denied: Vec<DomainPattern>,
+}
+
+impl EmailDomainPolicy {
+    /// Build from allow and deny lists; entries are matched case-insensitively
+    pub fn new(
+        allowed: impl IntoIterator<Item = impl AsRef<str>>,
+        denied: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<Self, DomainError> {
+        Ok(Self { allowed: parse_patterns(allowed)?, denied: parse_patterns(denied)? })
+    }
+
+    pub fn permits(&self, email: &Email) -> bool {
+        let domain = email.domain();
+        if self.denied.iter().any(|p| p.matches(domain)) {
+            return false;
+        }
+        self.allowed.is_empty() || self.allowed.iter().any(|p| p.matches(domain))
+    }
+}
+
+/// Domains of throwaway mailbox providers.
+///
+/// An address matches when its domain, or any parent of it, is listed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DisposableDomains(HashSet<String>);
+
+impl DisposableDomains {
+    /// Read a list with one domain per line; blank lines and `#` comments
+    /// are ignored
+    pub fn parse(list: &str) -> Self {
+        Self(
+            list.lines()
+                .map(|line| line.split('#').next().unwrap_or_default().trim().to_lowercase())
+                .filter(|domain| !domain.is_empty())
+                .collect(),
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, email: &Email) -> bool {
+        let mut domain = email.domain();
+        loop {
+            if self.0.contains(domain) {
+                return true;
+            }
+            match domain.split_once('.') {
+                Some((_, parent)) => domain = parent,
+                None => return false,
+            }
+        }
+    }
+}
+
+fn parse_patterns(
+    entries: impl IntoIterator<Item = impl AsRef<str>>,
+) -> Result<Vec<DomainPattern>, DomainError> {
+    entries.into_iter().map(|e| DomainPattern::parse(e.as_ref())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn email(address: &str) -> Email {
+        Email::parse(address).unwrap()
+    }
+
+    fn policy(allowed: &[&str], denied: &[&str]) -> EmailDomainPolicy {
+        EmailDomainPolicy::new(allowed, denied).unwrap()
+    }
+
+    #[test]
+    fn open_policy_permits_any_domain() {
+        let open = EmailDomainPolicy::default();
+
+        assert!(open.permits(&email("jane@anywhere.io")));
+    }
+
+    #[test]
+    fn allow_list_admits_only_listed_domains() {
+        let corporate = policy(&["Example.com", "partner.org"], &[]);
+
+        assert!(corporate.permits(&email("jane@example.com")));
+        assert!(corporate.permits(&email("JOHN@PARTNER.ORG")));
+        assert!(!corporate.permits(&email("jane@gmail.com")));
+        assert!(!corporate.permits(&email("jane@eu.example.com")), "subdomains need a wildcard");
+    }
+
+    #[test]
+    fn deny_list_wins_over_allow_list() {
+        let mixed = policy(&["*.example.com"], &["contractors.example.com"]);
+
+        assert!(mixed.permits(&email("jane@eu.example.com")));
+        assert!(!mixed.permits(&email("jane@contractors.example.com")));
+        assert!(!policy(&[], &["spam.test"]).permits(&email("x@spam.test")));
+        assert!(policy(&[], &["spam.test"]).permits(&email("x@example.com")));
+    }
+
+    #[test]
+    fn wildcard_matches_subdomains_at_any_depth_but_not_the_apex() {
+        let subdomains = policy(&["*.example.com"], &[]);
+
+        assert!(subdomains.permits(&email("jane@eu.example.com")));
+        assert!(subdomains.permits(&email("jane@mail.eu.example.com")));
+        assert!(!subdomains.permits(&email("jane@example.com")));
+        assert!(!subdomains.permits(&email("jane@badexample.com")));
+    }
+
+    #[test]
+    fn disposable_list_matches_listed_domains_and_their_subdomains() {
+        let disposable = DisposableDomains::parse(
+            "# throwaway providers\nMailinator.com\n\n  yopmail.com  # popular\n",
+        );
+
+        assert_eq!(disposable.len(), 2);
+        assert!(disposable.contains(&email("spam@mailinator.com")));
+        assert!(disposable.contains(&email("spam@eu.yopmail.com")));
+        assert!(!disposable.contains(&email("jane@example.com")));
+        assert!(!disposable.contains(&email("jane@notmailinator.com")));
+    }
+
+    #[test]
+    fn rejects_malformed_patterns() {
+        for bad in ["", "*", "*.", "example..com", "exa mple.com", "user@example.com", "a.*.com"] {
+            assert!(EmailDomainPolicy::new([bad], Vec::<&str>::new()).is_err(), "{:?}", bad);
+        }
+    }
+}
@@ -1,7 +1,13 @@
 pub mod email;
+pub mod email_domain;
+pub mod password_rules;
+pub mod phone_number;
 pub mod user_id;
 pub mod user_role;
 
 pub use email::Email;
+pub use email_domain::{DisposableDomains, EmailDomainPolicy};
+pub use password_rules::{BreachedPasswords, PasswordRuleViolation, PasswordRules};
+pub use phone_number::PhoneNumber;
 pub use user_id::UserId;
 pub use user_role::UserRole;
@@ -0,0 +1,104 @@
+use super::Email;
+use std::{collections::HashSet, sync::Arc};
+
+/// Passwords known from breach corpora. Entries match exactly, case
+/// included, as breach lists record them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BreachedPasswords(HashSet<String>);
+
+impl BreachedPasswords {
+    /// Read a list with one password per line; blank lines and lines
+    /// starting with `#` are ignored. Passwords may contain `#` themselves,
+    /// so there are no trailing comments.
+    pub fn parse(list: &str) -> Self {
+        Self(
+            list.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(String::from)
+                .collect(),
+        )
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn contains(&self, password: &str) -> bool {
+        self.0.contains(password)
+    }
+}
+
+/// Why a new password was turned down
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum PasswordRuleViolation {
+    #[error("Password must not be the same as your email address")]
+    MatchesEmail,
+
+    #[error("Password has appeared in a data breach, choose a different one")]
+    Breached,
+}
+
+/// Optional checks on a newly chosen password, each off unless configured
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PasswordRules {
+    /// Refuse the address, or the part before its `@`, ignoring case
+    pub reject_email: bool,
+    /// Refuse passwords on this list
+    pub breached: Option<Arc<BreachedPasswords>>,
+}
+
+impl PasswordRules {
+    pub fn check(&self, password: &str, email: &Email) -> Result<(), PasswordRuleViolation> {
+        if self.reject_email
+            && (password.eq_ignore_ascii_case(email.local_part())
+                || password.eq_ignore_ascii_case(email.as_str()))
+        {
+            return Err(PasswordRuleViolation::MatchesEmail);
+        }
+        if self.breached.as_ref().is_some_and(|list| list.contains(password)) {
+            return Err(PasswordRuleViolation::Breached);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn email(address: &str) -> Email {
+        Email::parse(address).unwrap()
+    }
+
+    #[test]
+    fn email_rule_refuses_the_address_and_its_local_part() {
+        let rules = PasswordRules { reject_email: true, breached: None };
+        let lan = email("Lan.Nguyen@example.com");
+
+        assert_eq!(rules.check("LAN.NGUYEN", &lan), Err(PasswordRuleViolation::MatchesEmail));
+        assert_eq!(
+            rules.check("lan.nguyen@example.com", &lan),
+            Err(PasswordRuleViolation::MatchesEmail)
+        );
+        assert_eq!(rules.check("lan.nguyen2024", &lan), Ok(()));
+        assert_eq!(PasswordRules::default().check("lan.nguyen", &lan), Ok(()));
+    }
+
+    #[test]
+    fn breach_list_matches_whole_passwords_exactly() {
+        let list = BreachedPasswords::parse("# leaked\npassword123\n\n  qwerty#1  \n");
+        let rules = PasswordRules { reject_email: false, breached: Some(Arc::new(list)) };
+        let lan = email("lan@example.com");
+
+        assert_eq!(rules.breached.as_ref().map(|list| list.len()), Some(2));
+        assert_eq!(rules.check("password123", &lan), Err(PasswordRuleViolation::Breached));
+        assert_eq!(rules.check("qwerty#1", &lan), Err(PasswordRuleViolation::Breached));
+        assert_eq!(rules.check("Password123", &lan), Ok(()));
+        assert_eq!(rules.check("password1234", &lan), Ok(()));
+    }
+}
@@ -0,0 +1,81 @@
+use crate::domain::errors::DomainError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Digits an E.164 number may have, country code included
+const MIN_DIGITS: usize = 7;
+const MAX_DIGITS: usize = 15;
+
+/// Phone number value object, always held in E.164 form (`+84901234567`)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhoneNumber(String);
+
+impl PhoneNumber {
+    /// Parse an international number, dropping the spaces, dashes, dots and
+    /// parentheses people write it with. A leading `00` is read as `+`;
+    /// numbers without a country code are rejected, as there is no way to
+    /// tell which country they belong to.
+    pub fn parse(phone: impl Into<String>) -> Result<Self, DomainError> {
+        let phone = phone.into();
+        let compact: String =
+            phone.chars().filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')')).collect();
+        let digits = compact
+            .strip_prefix('+')
+            .or_else(|| compact.strip_prefix("00"))
+            .ok_or_else(|| DomainError::InvalidPhoneNumber(phone.clone()))?;
+
+        let valid = (MIN_DIGITS..=MAX_DIGITS).contains(&digits.len())
+            && digits.chars().all(|c| c.is_ascii_digit())
+            && !digits.starts_with('0');
+        if !valid {
+            return Err(DomainError::InvalidPhoneNumber(phone));
+        }
+
+        Ok(Self(format!("+{}", digits)))
+    }
+
+    /// Get the number as a string slice
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for PhoneNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl AsRef<str> for PhoneNumber {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalizes_formatted_numbers_to_e164() {
+        for raw in ["+84 90 123 4567", "+84-90-123-4567", "0084901234567", "+84 (90) 123.4567"] {
+            assert_eq!(PhoneNumber::parse(raw).unwrap().as_str(), "+84901234567", "{}", raw);
+        }
+        assert_eq!(PhoneNumber::parse("+1 415 555 2671").unwrap().as_str(), "+14155552671");
+    }
+
+    #[test]
+    fn rejects_numbers_that_are_not_e164() {
+        for bad in [
+            "",
+            "0901234567",          // national format, no country code
+            "+0901234567",         // country codes never start with 0
+            "+84 90 ABC 4567",     // letters
+            "+123456",             // too short
+            "+1234567890123456",   // more than 15 digits
+            "+84 90 123 4567 ext", // trailing text
+        ] {
+            assert!(PhoneNumber::parse(bad).is_err(), "{:?}", bad);
+        }
+    }
+}
@@ -7,6 +7,9 @@ use std::fmt;
 /// - Admin: Full access (read, write, delete)
 /// - Editor: Can read and write, but cannot delete
 /// - Viewer: Can only read data
+///
+/// Roles form a hierarchy, Admin > Editor > Viewer: each role inherits
+/// everything the roles below it may do.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum UserRole {
@@ -20,19 +23,33 @@ pub enum UserRole {
 }
 
 impl UserRole {
+    /// Position in the hierarchy; higher ranks inherit lower ones
+    fn rank(&self) -> u8 {
+        match self {
+            UserRole::Admin => 2,
+            UserRole::Editor => 1,
+            UserRole::Viewer => 0,
+        }
+    }
+
+    /// Check if this role is `required` or ranks above it
+    pub fn satisfies(&self, required: UserRole) -> bool {
+        self.rank() >= required.rank()
+    }
+
     /// Check if this role can read data
     pub fn can_read(&self) -> bool {
-        matches!(self, UserRole::Admin | UserRole::Editor | UserRole::Viewer)
+        self.satisfies(UserRole::Viewer)
     }
 
     /// Check if this role can write (create/update) data
     pub fn can_write(&self) -> bool {
-        matches!(self, UserRole::Admin | UserRole::Editor)
+        self.satisfies(UserRole::Editor)
     }
 
     /// Check if this role can delete data
     pub fn can_delete(&self) -> bool {
-        matches!(self, UserRole::Admin)
+        self.satisfies(UserRole::Admin)
     }
 
     /// Get all available roles
@@ -89,6 +106,19 @@ mod tests {
         assert!(!viewer.can_delete());
     }
 
+    #[test]
+    fn higher_roles_satisfy_lower_requirements() {
+        assert!(UserRole::Admin.satisfies(UserRole::Editor));
+        assert!(UserRole::Admin.satisfies(UserRole::Viewer));
+        assert!(UserRole::Editor.satisfies(UserRole::Viewer));
+        for role in UserRole::all() {
+            assert!(role.satisfies(role));
+        }
+
+        assert!(!UserRole::Editor.satisfies(UserRole::Admin));
+        assert!(!UserRole::Viewer.satisfies(UserRole::Editor));
+    }
+
     #[test]
     fn test_role_from_str() {
         assert_eq!(UserRole::parse("admin"), Some(UserRole::Admin));
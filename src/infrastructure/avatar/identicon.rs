@@ -0,0 +1,79 @@
+use crate::application::services::AvatarRenderer;
+use sha2::{Digest, Sha256};
+use std::fmt::Write;
+
+/// Cells per side; columns are mirrored around the middle one
+const GRID: usize = 5;
+/// Rendered size of one cell, in SVG user units
+const CELL: usize = 50;
+/// Blank border around the grid, in SVG user units
+const MARGIN: usize = 25;
+
+/// Render a GitHub-style identicon for `email` as an SVG document.
+///
+/// The pattern and colour come from a SHA-256 of the trimmed, lowercased
+/// address, so the same user always gets the same image and no network
+/// access is needed.
+pub fn identicon_svg(email: &str) -> String {
+    let digest = Sha256::digest(email.trim().to_lowercase().as_bytes());
+    let (r, g, b) = (digest[0], digest[1], digest[2]);
+    let size = GRID * CELL + 2 * MARGIN;
+
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}"><rect width="{size}" height="{size}" fill="#f0f0f0"/><g fill="#{r:02x}{g:02x}{b:02x}">"##
+    );
+
+    // One bit per cell of the left half plus the middle column
+    let half = GRID.div_ceil(2);
+    for row in 0..GRID {
+        for col in 0..half {
+            let bit = row * half + col;
+            if digest[3 + bit / 8] >> (bit % 8) & 1 == 0 {
+                continue;
+            }
+            for x in [col, GRID - 1 - col] {
+                let _ = write!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{CELL}" height="{CELL}"/>"#,
+                    MARGIN + x * CELL,
+                    MARGIN + row * CELL
+                );
+                if x == GRID - 1 - x {
+                    break;
+                }
+            }
+        }
+    }
+
+    svg.push_str("</g></svg>");
+    svg
+}
+
+/// `AvatarRenderer` drawing identicons with `identicon_svg`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdenticonRenderer;
+
+impl AvatarRenderer for IdenticonRenderer {
+    fn svg(&self, email: &str) -> String {
+        identicon_svg(email)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_email_yields_same_identicon() {
+        let first = identicon_svg("jane@example.com");
+
+        assert_eq!(first, identicon_svg("jane@example.com"));
+        assert_eq!(first, identicon_svg("  Jane@Example.COM "));
+        assert!(first.starts_with("<svg") && first.ends_with("</svg>"));
+    }
+
+    #[test]
+    fn different_emails_yield_different_identicons() {
+        assert_ne!(identicon_svg("jane@example.com"), identicon_svg("john@example.com"));
+    }
+}
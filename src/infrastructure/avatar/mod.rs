@@ -0,0 +1,3 @@
+pub mod identicon;
+
+pub use identicon::{identicon_svg, IdenticonRenderer};
@@ -0,0 +1,161 @@
+use crate::domain::repositories::cache::{CacheError, CacheRepository};
+use async_trait::async_trait;
+use std::{
+    collections::HashMap,
+    sync::{Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+
+/// Value and the instant it expires
+type Entries = HashMap<String, (String, Instant)>;
+
+/// Process-local cache for single-node deployments
+///
+/// Expired entries are dropped lazily when they are next read.
+#[derive(Default)]
+pub struct InMemoryCacheRepository {
+    entries: Mutex<Entries>,
+}
+
+impl InMemoryCacheRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn entries(&self) -> Result<MutexGuard<'_, Entries>, CacheError> {
+        self.entries
+            .lock()
+            .map_err(|_| CacheError::Backend("cache lock poisoned".to_string()))
+    }
+}
+
+#[async_trait]
+impl CacheRepository for InMemoryCacheRepository {
+    async fn get(&self, key: &str) -> Result<Option<String>, CacheError> {
+        let mut entries = self.entries()?;
+        match entries.get(key) {
+            Some((value, expires_at)) if *expires_at > Instant::now() => Ok(Some(value.clone())),
+            Some(_) => {
+                entries.remove(key);
+                Ok(None)
+            },
+            None => Ok(None),
+        }
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<(), CacheError> {
+        self.entries()?
+            .insert(key.to_string(), (value.to_string(), Instant::now() + ttl));
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), CacheError> {
+        self.entries()?.remove(key);
+        Ok(())
+    }
+
+    async fn set_nx(&self, key: &str, value: &str, ttl: Duration) -> Result<bool, CacheError> {
+        let mut entries = self.entries()?;
+        let now = Instant::now();
+        if entries.get(key).is_some_and(|(_, expires_at)| *expires_at > now) {
+            return Ok(false);
+        }
+        entries.insert(key.to_string(), (value.to_string(), now + ttl));
+        Ok(true)
+    }
+
+    async fn delete_if_equals(&self, key: &str, expected: &str) -> Result<bool, CacheError> {
+        let mut entries = self.entries()?;
+        let matches = entries
+            .get(key)
+            .is_some_and(|(value, expires_at)| value == expected && *expires_at > Instant::now());
+        if matches {
+            entries.remove(key);
+        }
+        Ok(matches)
+    }
+
+    async fn extend_if_equals(
+        &self,
+        key: &str,
+        expected: &str,
+        ttl: Duration,
+    ) -> Result<bool, CacheError> {
+        let mut entries = self.entries()?;
+        let now = Instant::now();
+        match entries.get_mut(key) {
+            Some((value, expires_at)) if value == expected && *expires_at > now => {
+                *expires_at = now + ttl;
+                Ok(true)
+            },
+            _ => Ok(false),
+        }
+    }
+
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<(u64, Duration), CacheError> {
+        let mut entries = self.entries()?;
+        let now = Instant::now();
+        let (count, expires_at) = match entries.get(key) {
+            Some((value, expires_at)) if *expires_at > now => {
+                let count = value
+                    .parse::<u64>()
+                    .map_err(|_| CacheError::Backend(format!("{} is not a counter", key)))?;
+                (count.saturating_add(1), *expires_at)
+            },
+            _ => (1, now + ttl),
+        };
+        entries.insert(key.to_string(), (count.to_string(), expires_at));
+        Ok((count, expires_at - now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn set_get_and_delete_round_trip() {
+        let cache = InMemoryCacheRepository::new();
+        cache.set("k", "v", Duration::from_secs(60)).await.unwrap();
+        assert_eq!(cache.get("k").await.unwrap().as_deref(), Some("v"));
+
+        cache.delete("k").await.unwrap();
+        assert_eq!(cache.get("k").await.unwrap(), None);
+        cache.delete("k").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn conditional_writes_respect_current_value() {
+        let cache = InMemoryCacheRepository::new();
+        assert!(cache.set_nx("k", "a", Duration::from_secs(60)).await.unwrap());
+        assert!(!cache.set_nx("k", "b", Duration::from_secs(60)).await.unwrap());
+
+        assert!(!cache.extend_if_equals("k", "b", Duration::ZERO).await.unwrap());
+        assert!(cache.extend_if_equals("k", "a", Duration::from_secs(60)).await.unwrap());
+        assert!(!cache.delete_if_equals("k", "b").await.unwrap());
+        assert!(cache.delete_if_equals("k", "a").await.unwrap());
+        assert!(cache.set_nx("k", "b", Duration::ZERO).await.unwrap());
+        // An expired entry no longer blocks set_nx
+        assert!(cache.set_nx("k", "c", Duration::from_secs(60)).await.unwrap());
+        assert_eq!(cache.get("k").await.unwrap().as_deref(), Some("c"));
+    }
+
+    #[tokio::test]
+    async fn increment_counts_within_one_expiry() {
+        let cache = InMemoryCacheRepository::new();
+        assert_eq!(cache.increment("n", Duration::from_secs(60)).await.unwrap().0, 1);
+        let (count, remaining) = cache.increment("n", Duration::from_secs(600)).await.unwrap();
+        assert_eq!(count, 2);
+        assert!(remaining <= Duration::from_secs(60));
+
+        cache.set("n", "x", Duration::from_secs(60)).await.unwrap();
+        assert!(cache.increment("n", Duration::from_secs(60)).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_not_returned() {
+        let cache = InMemoryCacheRepository::new();
+        cache.set("k", "v", Duration::ZERO).await.unwrap();
+        assert_eq!(cache.get("k").await.unwrap(), None);
+    }
+}
@@ -0,0 +1,92 @@
+use crate::domain::repositories::cache::{CacheError, CacheRepository};
+use async_trait::async_trait;
+use std::{sync::Arc, time::Duration};
+
+/// Counter of lookups answered from the cache, labelled by `cache`
+pub const CACHE_HITS_TOTAL: &str = "cache_hits_total";
+
+/// Counter of lookups the cache could not answer, labelled by `cache`
+pub const CACHE_MISSES_TOTAL: &str = "cache_misses_total";
+
+/// Counts hits and misses of `get` on the wrapped cache under one name.
+///
+/// Failed lookups are neither; callers fall back to the source of truth
+/// and the backend error is logged there.
+pub struct MeteredCacheRepository {
+    inner: Arc<dyn CacheRepository>,
+    name: &'static str,
+}
+
+impl MeteredCacheRepository {
+    pub fn new(inner: Arc<dyn CacheRepository>, name: &'static str) -> Self {
+        Self { inner, name }
+    }
+}
+
+#[async_trait]
+impl CacheRepository for MeteredCacheRepository {
+    async fn get(&self, key: &str) -> Result<Option<String>, CacheError> {
+        let result = self.inner.get(key).await;
+        match &result {
+            Ok(Some(_)) => metrics::counter!(CACHE_HITS_TOTAL, "cache" => self.name).increment(1),
+            Ok(None) => metrics::counter!(CACHE_MISSES_TOTAL, "cache" => self.name).increment(1),
+            Err(_) => {},
+        }
+        result
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<(), CacheError> {
+        self.inner.set(key, value, ttl).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), CacheError> {
+        self.inner.delete(key).await
+    }
+
+    async fn set_nx(&self, key: &str, value: &str, ttl: Duration) -> Result<bool, CacheError> {
+        self.inner.set_nx(key, value, ttl).await
+    }
+
+    async fn delete_if_equals(&self, key: &str, expected: &str) -> Result<bool, CacheError> {
+        self.inner.delete_if_equals(key, expected).await
+    }
+
+    async fn extend_if_equals(
+        &self,
+        key: &str,
+        expected: &str,
+        ttl: Duration,
+    ) -> Result<bool, CacheError> {
+        self.inner.extend_if_equals(key, expected, ttl).await
+    }
+
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<(u64, Duration), CacheError> {
+        self.inner.increment(key, ttl).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::cache::InMemoryCacheRepository;
+    use axum_prometheus::metrics_exporter_prometheus::PrometheusBuilder;
+
+    #[test]
+    fn cold_then_warm_get_counts_one_miss_and_one_hit() {
+        let recorder = PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        let cache = MeteredCacheRepository::new(Arc::new(InMemoryCacheRepository::new()), "role");
+
+        metrics::with_local_recorder(&recorder, || {
+            futures::executor::block_on(async {
+                assert_eq!(cache.get("user:1:role").await.unwrap(), None);
+                cache.set("user:1:role", "admin", Duration::from_secs(60)).await.unwrap();
+                assert_eq!(cache.get("user:1:role").await.unwrap().as_deref(), Some("admin"));
+            })
+        });
+
+        let rendered = handle.render();
+        assert!(rendered.contains(r#"cache_misses_total{cache="role"} 1"#), "{}", rendered);
+        assert!(rendered.contains(r#"cache_hits_total{cache="role"} 1"#), "{}", rendered);
+    }
+}
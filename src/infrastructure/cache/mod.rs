@@ -1,2 +1,12 @@
-// Cache implementation (Redis or in-memory)
-// To be implemented when needed
+// Cache implementations
+pub mod memory;
+pub mod metered;
+#[cfg(feature = "moka")]
+pub mod moka;
+pub mod noop;
+
+pub use memory::InMemoryCacheRepository;
+pub use metered::MeteredCacheRepository;
+#[cfg(feature = "moka")]
+pub use moka::MokaCacheRepository;
+pub use noop::NoOpCacheRepository;
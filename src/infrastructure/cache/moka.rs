@@ -0,0 +1,260 @@
+use crate::domain::repositories::cache::{CacheError, CacheRepository};
+use async_trait::async_trait;
+use moka::{
+    future::Cache,
+    ops::compute::{CompResult, Op},
+    Expiry,
+};
+use std::time::{Duration, Instant};
+
+#[derive(Clone)]
+struct Entry {
+    value: String,
+    expires_at: Instant,
+}
+
+impl Entry {
+    fn new(value: String, ttl: Duration) -> Self {
+        Self { value, expires_at: Instant::now() + ttl }
+    }
+}
+
+/// Expire each entry at the instant it carries, so updates that keep the
+/// instant (counter increments) keep the original expiry
+struct PerEntryExpiry;
+
+impl Expiry<String, Entry> for PerEntryExpiry {
+    fn expire_after_create(&self, _key: &String, entry: &Entry, now: Instant) -> Option<Duration> {
+        Some(entry.expires_at.saturating_duration_since(now))
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        entry: &Entry,
+        now: Instant,
+        _remaining: Option<Duration>,
+    ) -> Option<Duration> {
+        Some(entry.expires_at.saturating_duration_since(now))
+    }
+}
+
+/// Bounded process-local cache for single-node deployments, backed by
+/// `moka`
+///
+/// Unlike `InMemoryCacheRepository`, expired entries are evicted in the
+/// background and the least recently used ones make room once
+/// `max_entries` is reached.
+pub struct MokaCacheRepository {
+    cache: Cache<String, Entry>,
+}
+
+impl MokaCacheRepository {
+    pub fn new(max_entries: u64) -> Self {
+        Self {
+            cache: Cache::builder().max_capacity(max_entries).expire_after(PerEntryExpiry).build(),
+        }
+    }
+}
+
+#[async_trait]
+impl CacheRepository for MokaCacheRepository {
+    async fn get(&self, key: &str) -> Result<Option<String>, CacheError> {
+        Ok(self.cache.get(key).await.map(|entry| entry.value))
+    }
+
+    async fn set(&self, key: &str, value: &str, ttl: Duration) -> Result<(), CacheError> {
+        self.cache.insert(key.to_string(), Entry::new(value.to_string(), ttl)).await;
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), CacheError> {
+        self.cache.invalidate(key).await;
+        Ok(())
+    }
+
+    async fn set_nx(&self, key: &str, value: &str, ttl: Duration) -> Result<bool, CacheError> {
+        // moka runs at most one initializer per key, so concurrent callers
+        // cannot both see a fresh entry
+        let entry = self
+            .cache
+            .entry(key.to_string())
+            .or_insert(Entry::new(value.to_string(), ttl))
+            .await;
+        Ok(entry.is_fresh())
+    }
+
+    async fn delete_if_equals(&self, key: &str, expected: &str) -> Result<bool, CacheError> {
+        let result = self
+            .cache
+            .entry(key.to_string())
+            .and_compute_with(|current| async move {
+                match current {
+                    Some(entry) if entry.value().value == expected => Op::Remove,
+                    _ => Op::Nop,
+                }
+            })
+            .await;
+        Ok(matches!(result, CompResult::Removed(_)))
+    }
+
+    async fn extend_if_equals(
+        &self,
+        key: &str,
+        expected: &str,
+        ttl: Duration,
+    ) -> Result<bool, CacheError> {
+        let result = self
+            .cache
+            .entry(key.to_string())
+            .and_compute_with(|current| async move {
+                match current {
+                    Some(entry) if entry.value().value == expected => {
+                        Op::Put(Entry::new(expected.to_string(), ttl))
+                    },
+                    _ => Op::Nop,
+                }
+            })
+            .await;
+        Ok(matches!(result, CompResult::ReplacedWith(_)))
+    }
+
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<(u64, Duration), CacheError> {
+        let result = self
+            .cache
+            .entry(key.to_string())
+            .and_compute_with(|current| async move {
+                match current.map(|entry| entry.into_value()) {
+                    // Like Redis INCR, a value that is not a counter is left
+                    // alone and the increment fails
+                    Some(Entry { value, expires_at }) => match value.parse::<u64>() {
+                        Ok(count) => Op::Put(Entry {
+                            value: count.saturating_add(1).to_string(),
+                            expires_at,
+                        }),
+                        Err(_) => Op::Nop,
+                    },
+                    None => Op::Put(Entry::new("1".to_string(), ttl)),
+                }
+            })
+            .await;
+
+        match result {
+            CompResult::Inserted(entry) | CompResult::ReplacedWith(entry) => {
+                let Entry { value, expires_at } = entry.into_value();
+                let count = value
+                    .parse()
+                    .map_err(|_| CacheError::Backend(format!("{} is not a counter", key)))?;
+                Ok((count, expires_at.saturating_duration_since(Instant::now())))
+            },
+            CompResult::Unchanged(_) => {
+                Err(CacheError::Backend(format!("{} is not a counter", key)))
+            },
+            _ => Err(CacheError::Backend(format!("failed to increment {}", key))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[tokio::test]
+    async fn set_get_and_delete_round_trip() {
+        let cache = MokaCacheRepository::new(100);
+        cache.set("k", "v", TTL).await.unwrap();
+        assert_eq!(cache.get("k").await.unwrap().as_deref(), Some("v"));
+
+        cache.set("k", "w", TTL).await.unwrap();
+        assert_eq!(cache.get("k").await.unwrap().as_deref(), Some("w"));
+
+        cache.delete("k").await.unwrap();
+        assert_eq!(cache.get("k").await.unwrap(), None);
+        cache.delete("k").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn entries_expire_after_their_own_ttl() {
+        let cache = MokaCacheRepository::new(100);
+        cache.set("short", "v", Duration::from_millis(50)).await.unwrap();
+        cache.set("long", "v", TTL).await.unwrap();
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        assert_eq!(cache.get("short").await.unwrap(), None);
+        assert_eq!(cache.get("long").await.unwrap().as_deref(), Some("v"));
+        // An expired key can be claimed again
+        assert!(cache.set_nx("short", "again", TTL).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn set_nx_only_stores_missing_keys() {
+        let cache = MokaCacheRepository::new(100);
+        assert!(cache.set_nx("k", "first", TTL).await.unwrap());
+        assert!(!cache.set_nx("k", "second", TTL).await.unwrap());
+        assert_eq!(cache.get("k").await.unwrap().as_deref(), Some("first"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn set_nx_has_a_single_winner_under_contention() {
+        let cache = Arc::new(MokaCacheRepository::new(100));
+        let attempts = (0..32).map(|i| {
+            let cache = cache.clone();
+            tokio::spawn(async move { cache.set_nx("lock", &i.to_string(), TTL).await.unwrap() })
+        });
+
+        let mut winners = 0;
+        for attempt in attempts {
+            winners += usize::from(attempt.await.unwrap());
+        }
+        assert_eq!(winners, 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_increments_are_all_counted() {
+        let cache = Arc::new(MokaCacheRepository::new(100));
+        let increments = (0..50).map(|_| {
+            let cache = cache.clone();
+            tokio::spawn(async move { cache.increment("hits", TTL).await.unwrap().0 })
+        });
+
+        let mut counts = Vec::new();
+        for increment in increments {
+            counts.push(increment.await.unwrap());
+        }
+        counts.sort_unstable();
+        assert_eq!(counts, (1..=50).collect::<Vec<u64>>());
+
+        // Later increments keep the expiry set by the first
+        let (count, remaining) = cache.increment("hits", TTL * 10).await.unwrap();
+        assert_eq!(count, 51);
+        assert!(remaining <= TTL, "{:?}", remaining);
+        assert_eq!(cache.get("hits").await.unwrap().as_deref(), Some("51"));
+    }
+
+    #[tokio::test]
+    async fn incrementing_a_non_counter_fails_and_keeps_the_value() {
+        let cache = MokaCacheRepository::new(100);
+        cache.set("k", "not-a-number", TTL).await.unwrap();
+
+        assert!(matches!(cache.increment("k", TTL).await, Err(CacheError::Backend(_))));
+        assert_eq!(cache.get("k").await.unwrap().as_deref(), Some("not-a-number"));
+    }
+
+    #[tokio::test]
+    async fn conditional_delete_and_extend_leave_other_values() {
+        let cache = MokaCacheRepository::new(100);
+        cache.set("k", "mine", TTL).await.unwrap();
+
+        assert!(!cache.delete_if_equals("k", "theirs").await.unwrap());
+        assert!(!cache.extend_if_equals("k", "theirs", TTL).await.unwrap());
+        assert!(cache.extend_if_equals("k", "mine", TTL).await.unwrap());
+        assert_eq!(cache.get("k").await.unwrap().as_deref(), Some("mine"));
+        assert!(cache.delete_if_equals("k", "mine").await.unwrap());
+        assert_eq!(cache.get("k").await.unwrap(), None);
+        assert!(!cache.delete_if_equals("missing", "mine").await.unwrap());
+    }
+}
@@ -0,0 +1,53 @@
+use crate::domain::repositories::cache::{CacheError, CacheRepository};
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Cache that never stores anything, used when `FEATURE_CACHE` is off so
+/// every lookup falls through to the source of truth.
+#[derive(Debug, Default)]
+pub struct NoOpCacheRepository;
+
+impl NoOpCacheRepository {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl CacheRepository for NoOpCacheRepository {
+    async fn get(&self, _key: &str) -> Result<Option<String>, CacheError> {
+        Ok(None)
+    }
+
+    async fn set(&self, _key: &str, _value: &str, _ttl: Duration) -> Result<(), CacheError> {
+        Ok(())
+    }
+
+    async fn delete(&self, _key: &str) -> Result<(), CacheError> {
+        Ok(())
+    }
+
+    /// Nothing is ever held, so every caller "wins"
+    async fn set_nx(&self, _key: &str, _value: &str, _ttl: Duration) -> Result<bool, CacheError> {
+        Ok(true)
+    }
+
+    async fn delete_if_equals(&self, _key: &str, _expected: &str) -> Result<bool, CacheError> {
+        Ok(false)
+    }
+
+    /// Holders are never told they lost what `set_nx` granted
+    async fn extend_if_equals(
+        &self,
+        _key: &str,
+        _expected: &str,
+        _ttl: Duration,
+    ) -> Result<bool, CacheError> {
+        Ok(true)
+    }
+
+    /// Every counter is always fresh
+    async fn increment(&self, _key: &str, ttl: Duration) -> Result<(u64, Duration), CacheError> {
+        Ok((1, ttl))
+    }
+}
@@ -28,3 +28,53 @@ pub async fn run_migrations(database_url: &str) -> anyhow::Result<()> {
     tracing::info!("Database migrations completed successfully");
     Ok(())
 }
+
+#[derive(diesel::QueryableByName)]
+struct MigrationTable {
+    #[diesel(sql_type = diesel::sql_types::Bool)]
+    present: bool,
+}
+
+#[derive(diesel::QueryableByName)]
+struct AppliedMigration {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    version: String,
+}
+
+/// Count embedded migrations that have not been applied to the database yet.
+///
+/// A database that was never migrated has no `__diesel_schema_migrations`
+/// table, in which case every embedded migration is pending.
+pub async fn pending_migrations(pool: &DbPool) -> anyhow::Result<usize> {
+    use diesel::migration::MigrationSource;
+    use diesel::pg::Pg;
+    use diesel_async::RunQueryDsl;
+    use std::collections::HashSet;
+
+    let mut conn = pool.get().await?;
+
+    let table = diesel::sql_query(
+        "SELECT to_regclass('__diesel_schema_migrations') IS NOT NULL AS present",
+    )
+    .get_result::<MigrationTable>(&mut conn)
+    .await?;
+
+    let applied: HashSet<String> = if table.present {
+        diesel::sql_query("SELECT version FROM __diesel_schema_migrations")
+            .load::<AppliedMigration>(&mut conn)
+            .await?
+            .into_iter()
+            .map(|m| m.version)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+
+    let embedded = MigrationSource::<Pg>::migrations(&MIGRATIONS)
+        .map_err(|e| anyhow::anyhow!("Failed to read embedded migrations: {}", e))?;
+
+    Ok(embedded
+        .iter()
+        .filter(|m| !applied.contains(&m.name().version().to_string()))
+        .count())
+}
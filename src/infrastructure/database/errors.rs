@@ -0,0 +1,68 @@
+use crate::domain::repositories::user::RepositoryError;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+
+/// Classify a Diesel error so callers can answer with a precise status
+/// instead of collapsing every failure into a 500.
+pub fn map_db_error(err: DieselError) -> RepositoryError {
+    match err {
+        DieselError::NotFound => RepositoryError::NotFound,
+        DieselError::DatabaseError(kind, info) => {
+            let message = info.message().to_string();
+            match kind {
+                DatabaseErrorKind::UniqueViolation => RepositoryError::DuplicateEmail(message),
+                DatabaseErrorKind::ForeignKeyViolation => {
+                    RepositoryError::ForeignKeyViolation(message)
+                },
+                DatabaseErrorKind::SerializationFailure => {
+                    RepositoryError::SerializationFailure(message)
+                },
+                _ => RepositoryError::Database(message),
+            }
+        },
+        _ => RepositoryError::Internal(err.to_string()),
+    }
+}
+
+impl From<DieselError> for RepositoryError {
+    fn from(err: DieselError) -> Self {
+        map_db_error(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn db_error(kind: DatabaseErrorKind) -> DieselError {
+        DieselError::DatabaseError(kind, Box::new("constraint failed".to_string()))
+    }
+
+    #[test]
+    fn classifies_constraint_and_conflict_errors() {
+        assert!(matches!(
+            map_db_error(db_error(DatabaseErrorKind::UniqueViolation)),
+            RepositoryError::DuplicateEmail(_)
+        ));
+        assert!(matches!(
+            map_db_error(db_error(DatabaseErrorKind::ForeignKeyViolation)),
+            RepositoryError::ForeignKeyViolation(_)
+        ));
+        assert!(matches!(
+            map_db_error(db_error(DatabaseErrorKind::SerializationFailure)),
+            RepositoryError::SerializationFailure(_)
+        ));
+        assert!(matches!(map_db_error(DieselError::NotFound), RepositoryError::NotFound));
+    }
+
+    #[test]
+    fn other_errors_stay_opaque() {
+        assert!(matches!(
+            map_db_error(db_error(DatabaseErrorKind::CheckViolation)),
+            RepositoryError::Database(_)
+        ));
+        assert!(matches!(
+            map_db_error(DieselError::RollbackTransaction),
+            RepositoryError::Internal(_)
+        ));
+    }
+}
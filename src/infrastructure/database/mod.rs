@@ -1,4 +1,5 @@
 pub mod connection;
+pub mod errors;
 pub mod models; // New: Organized models by domain
 pub mod repositories;
 pub mod schema;
@@ -6,4 +7,5 @@ pub mod transaction;
 
 // Re-export for convenience
 pub use connection::{create_pool, DbPool};
+pub use errors::map_db_error;
 pub use models::{RefreshTokenModel, UserModel};
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{domain::entities::AuditEntry, infrastructure::database::schema::audit_logs};
+
+/// Database model for AuditEntry
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = audit_logs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AuditLogModel {
+    pub id: Uuid,
+    pub actor_id: Option<Uuid>,
+    pub action: String,
+    pub target_id: Option<Uuid>,
+    pub details: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+    pub legal_hold: bool,
+}
+
+impl From<&AuditEntry> for AuditLogModel {
+    fn from(entry: &AuditEntry) -> Self {
+        Self {
+            id: entry.id,
+            actor_id: entry.actor_id,
+            action: entry.action.clone(),
+            target_id: entry.target_id,
+            details: entry.details.clone(),
+            created_at: entry.created_at,
+            legal_hold: entry.legal_hold,
+        }
+    }
+}
+
+impl From<AuditLogModel> for AuditEntry {
+    fn from(model: AuditLogModel) -> Self {
+        Self {
+            id: model.id,
+            actor_id: model.actor_id,
+            action: model.action,
+            target_id: model.target_id,
+            details: model.details,
+            created_at: model.created_at,
+            legal_hold: model.legal_hold,
+        }
+    }
+}
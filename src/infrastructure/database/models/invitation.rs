@@ -0,0 +1,54 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::{
+    domain::{entities::Invitation, value_objects::UserRole},
+    infrastructure::database::schema::invitations,
+};
+
+/// Database model for Invitation
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = invitations)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct InvitationModel {
+    pub id: Uuid,
+    pub token_hash: String,
+    pub role: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub used_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<&Invitation> for InvitationModel {
+    fn from(invitation: &Invitation) -> Self {
+        Self {
+            id: invitation.id,
+            token_hash: invitation.token_hash.clone(),
+            role: invitation.role.map(|r| r.to_string()),
+            created_by: invitation.created_by,
+            expires_at: invitation.expires_at,
+            used_at: invitation.used_at,
+            used_by: invitation.used_by,
+            created_at: invitation.created_at,
+        }
+    }
+}
+
+impl From<InvitationModel> for Invitation {
+    fn from(model: InvitationModel) -> Self {
+        Self {
+            id: model.id,
+            token_hash: model.token_hash,
+            // Written from a `UserRole`, so an unknown value cannot occur
+            role: model.role.as_deref().and_then(UserRole::parse),
+            created_by: model.created_by,
+            expires_at: model.expires_at,
+            used_at: model.used_at,
+            used_by: model.used_by,
+            created_at: model.created_at,
+        }
+    }
+}
@@ -2,13 +2,19 @@
 ///
 /// This module contains all database models (Diesel structs) organized by domain.
 /// Models are separate from domain entities to maintain clean architecture.
+pub mod audit;
 pub mod auth;
 pub mod common;
+pub mod invitation;
+pub mod password_history;
 pub mod user;
 
 // Re-export models for convenience
+pub use audit::AuditLogModel;
 pub use auth::RefreshTokenModel;
-pub use user::UserModel;
+pub use invitation::InvitationModel;
+pub use password_history::PasswordHistoryModel;
+pub use user::{UserChangeset, UserModel};
 
 // Re-export common traits
 pub use common::{HasUuid, SoftDeletable, Timestamped};
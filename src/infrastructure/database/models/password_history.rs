@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+use crate::infrastructure::database::schema::password_history;
+
+/// Database model for a previously set password hash
+#[derive(Debug, Clone, Queryable, Selectable, Insertable)]
+#[diesel(table_name = password_history)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct PasswordHistoryModel {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub password_hash: String,
+    pub created_at: DateTime<Utc>,
+}
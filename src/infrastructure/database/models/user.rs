@@ -4,6 +4,7 @@ use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::infrastructure::database::schema::users;
+use crate::shared::i18n::Locale;
 
 /// Database model for User entity
 ///
@@ -25,6 +26,23 @@ pub struct UserModel {
     pub confirmation_code: Option<String>,
     pub confirmation_code_expires_at: Option<DateTime<Utc>>,
     pub email_verified: bool,
+    pub locale: String,
+    pub password_changed_at: Option<DateTime<Utc>>,
+    pub phone: Option<String>,
+    pub phone_verified: bool,
+    pub phone_code: Option<String>,
+    pub phone_code_expires_at: Option<DateTime<Utc>>,
+    pub password_reset_forced: bool,
+}
+
+/// Columns written by a partial update; `None` fields are left out of the
+/// `SET` clause entirely
+#[derive(Debug, Clone, AsChangeset)]
+#[diesel(table_name = users)]
+pub struct UserChangeset {
+    pub name: Option<String>,
+    pub locale: Option<String>,
+    pub updated_at: DateTime<Utc>,
 }
 
 impl UserModel {
@@ -50,6 +68,13 @@ impl UserModel {
             confirmation_code: None,
             confirmation_code_expires_at: None,
             email_verified: false,
+            locale: Locale::default().to_string(),
+            password_changed_at: None,
+            phone: None,
+            phone_verified: false,
+            phone_code: None,
+            phone_code_expires_at: None,
+            password_reset_forced: false,
         }
     }
 
@@ -0,0 +1,81 @@
+use crate::{
+    domain::{
+        entities::AuditEntry,
+        repositories::{audit::AuditRepository, user::RepositoryError},
+    },
+    infrastructure::database::{
+        models::AuditLogModel,
+        schema::audit_logs,
+        transaction::{ConnectionSource, RequestTransaction},
+        DbPool,
+    },
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+/// PostgreSQL implementation of AuditRepository
+#[derive(Clone)]
+pub struct RepositoryImpl {
+    conns: ConnectionSource,
+}
+
+impl RepositoryImpl {
+    pub fn new(pool: DbPool) -> Self {
+        Self { conns: pool.into() }
+    }
+
+    /// Run every statement in `tx` instead of on pooled connections
+    pub fn in_transaction(tx: RequestTransaction) -> Self {
+        Self { conns: ConnectionSource::Transaction(tx) }
+    }
+}
+
+#[async_trait]
+impl AuditRepository for RepositoryImpl {
+    async fn record(&self, entry: &AuditEntry) -> Result<(), RepositoryError> {
+        let mut conn =
+            self.conns.get().await.map_err(|e| {
+                RepositoryError::Internal(format!("Failed to get connection: {}", e))
+            })?;
+
+        diesel::insert_into(audit_logs::table)
+            .values(AuditLogModel::from(entry))
+            .execute(&mut *conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn purge_before(
+        &self,
+        cutoff: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<u64, RepositoryError> {
+        let mut conn =
+            self.conns.get().await.map_err(|e| {
+                RepositoryError::Internal(format!("Failed to get connection: {}", e))
+            })?;
+
+        let batch: Vec<Uuid> = audit_logs::table
+            .filter(audit_logs::created_at.lt(cutoff))
+            .filter(audit_logs::legal_hold.eq(false))
+            .order(audit_logs::created_at.asc())
+            .limit(limit)
+            .select(audit_logs::id)
+            .load(&mut *conn)
+            .await?;
+        // Re-checked so a hold placed since the select still protects the entry
+        let deleted = diesel::delete(
+            audit_logs::table
+                .filter(audit_logs::id.eq_any(batch))
+                .filter(audit_logs::legal_hold.eq(false)),
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(deleted as u64)
+    }
+}
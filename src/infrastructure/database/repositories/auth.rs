@@ -2,17 +2,19 @@ use crate::{
     domain::{
         entities::{RefreshToken, User},
         repositories::{AuthRepository, AuthRepositoryError},
-        value_objects::{Email, UserId, UserRole},
+        value_objects::{Email, PhoneNumber, UserId, UserRole},
     },
     infrastructure::database::{
         models::{RefreshTokenModel, UserModel},
-        schema::{refresh_tokens, users},
+        schema::{inactivity_warnings, refresh_tokens, users},
+        transaction::{retry_on_conflict, ConnectionSource, RequestTransaction},
         DbPool,
     },
 };
 use async_trait::async_trait;
 use diesel::prelude::*;
-use diesel_async::RunQueryDsl;
+use diesel_async::{scoped_futures::ScopedFutureExt, AsyncConnection, RunQueryDsl};
+use futures::FutureExt;
 use uuid::Uuid;
 
 /// PostgreSQL implementation of AuthRepository
@@ -20,12 +22,17 @@ use uuid::Uuid;
 /// This implementation uses Diesel ORM with async PostgreSQL.
 /// The struct name is generic to avoid coupling to specific database technology.
 pub struct RepositoryImpl {
-    pool: DbPool,
+    conns: ConnectionSource,
 }
 
 impl RepositoryImpl {
     pub fn new(pool: DbPool) -> Self {
-        Self { pool }
+        Self { conns: pool.into() }
+    }
+
+    /// Run every statement in `tx` instead of on pooled connections
+    pub fn in_transaction(tx: RequestTransaction) -> Self {
+        Self { conns: ConnectionSource::Transaction(tx) }
     }
 
     /// Helper: Convert UserModel to domain User entity
@@ -36,6 +43,11 @@ impl RepositoryImpl {
                 model.email
             ))
         })?;
+        let phone = model
+            .phone
+            .map(PhoneNumber::parse)
+            .transpose()
+            .map_err(|e| AuthRepositoryError::DatabaseError(format!("{} in database", e)))?;
         Ok(User::from_existing(
             UserId::from_uuid(model.id),
             email,
@@ -47,6 +59,13 @@ impl RepositoryImpl {
             model.confirmation_code,
             model.confirmation_code_expires_at,
             model.last_login,
+            model.password_changed_at,
+            phone,
+            model.phone_verified,
+            model.phone_code,
+            model.phone_code_expires_at,
+            model.password_reset_forced,
+            model.locale,
             model.created_at,
             model.updated_at,
         ))
@@ -77,18 +96,63 @@ impl RepositoryImpl {
     }
 }
 
+/// Filter on `users` that one query can apply in several places
+type UserFilter =
+    Box<dyn BoxableExpression<users::table, diesel::pg::Pg, SqlType = diesel::sql_types::Bool>>;
+
+/// Active non-admins last seen before `before`, at their last login or else
+/// at sign-up; with `warned_before`, only those warned by then
+fn idle_before(
+    before: chrono::DateTime<chrono::Utc>,
+    warned_before: Option<chrono::DateTime<chrono::Utc>>,
+) -> UserFilter {
+    let idle = users::is_active.eq(true).and(users::role.ne(UserRole::Admin.to_string())).and(
+        users::last_login
+            .assume_not_null()
+            .lt(before)
+            .or(users::last_login.is_null().and(users::created_at.lt(before))),
+    );
+    match warned_before {
+        None => Box::new(idle),
+        Some(warned_before) => Box::new(
+            idle.and(diesel::dsl::exists(
+                inactivity_warnings::table
+                    .filter(inactivity_warnings::user_id.eq(users::id))
+                    .filter(inactivity_warnings::warned_at.le(warned_before)),
+            )),
+        ),
+    }
+}
+
 #[async_trait]
 impl AuthRepository for RepositoryImpl {
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, AuthRepositoryError> {
         let mut conn = self
-            .pool
+            .conns
             .get()
             .await
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
 
         let result = users::table
             .filter(users::email.eq(email))
-            .first::<UserModel>(&mut conn)
+            .first::<UserModel>(&mut *conn)
+            .await
+            .optional()
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        result.map(Self::user_model_to_entity).transpose()
+    }
+
+    async fn find_by_id(&self, user_id: Uuid) -> Result<Option<User>, AuthRepositoryError> {
+        let mut conn = self
+            .conns
+            .get()
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        let result = users::table
+            .filter(users::id.eq(user_id))
+            .first::<UserModel>(&mut *conn)
             .await
             .optional()
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
@@ -103,9 +167,11 @@ impl AuthRepository for RepositoryImpl {
         password_hash: Option<String>,
         confirmation_code: Option<String>,
         expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        locale: &str,
+        role: UserRole,
     ) -> Result<User, AuthRepositoryError> {
         let mut conn = self
-            .pool
+            .conns
             .get()
             .await
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
@@ -120,17 +186,24 @@ impl AuthRepository for RepositoryImpl {
             created_at: now,
             updated_at: now,
             password_hash: password_hash.clone(), // Clone if needed or passed by value
-            role: "viewer".to_string(),
+            role: role.to_string(),
             is_active: false, // Default inactive
             last_login: None,
             confirmation_code: confirmation_code.clone(),
             confirmation_code_expires_at: expires_at,
             email_verified: false,
+            locale: locale.to_string(),
+            password_changed_at: password_hash.as_ref().map(|_| now),
+            phone: None,
+            phone_verified: false,
+            phone_code: None,
+            phone_code_expires_at: None,
+            password_reset_forced: false,
         };
 
         diesel::insert_into(users::table)
             .values(&new_user)
-            .execute(&mut conn)
+            .execute(&mut *conn)
             .await
             .map_err(|e| {
                 if e.to_string().contains("duplicate key")
@@ -149,12 +222,19 @@ impl AuthRepository for RepositoryImpl {
             email_vo,
             name.to_string(),
             password_hash,
-            UserRole::default(),
+            role,
             false,
             false,
             confirmation_code,
             expires_at,
             None,
+            new_user.password_changed_at,
+            None,
+            false,
+            None,
+            None,
+            false,
+            locale.to_string(),
             now,
             now,
         ))
@@ -162,7 +242,7 @@ impl AuthRepository for RepositoryImpl {
 
     async fn update_last_login(&self, user_id: Uuid) -> Result<(), AuthRepositoryError> {
         let mut conn = self
-            .pool
+            .conns
             .get()
             .await
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
@@ -171,36 +251,112 @@ impl AuthRepository for RepositoryImpl {
 
         diesel::update(users::table.filter(users::id.eq(user_id)))
             .set((users::last_login.eq(now), users::updated_at.eq(now)))
-            .execute(&mut conn)
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        // A new idle spell starts now and earns its own warning
+        diesel::delete(inactivity_warnings::table.filter(inactivity_warnings::user_id.eq(user_id)))
+            .execute(&mut *conn)
             .await
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
 
         Ok(())
     }
 
+    async fn consume_confirmation_code(
+        &self,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<bool, AuthRepositoryError> {
+        let mut conn = self
+            .conns
+            .get()
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        let now = chrono::Utc::now();
+
+        let consumed = diesel::update(
+            users::table
+                .filter(users::id.eq(user_id))
+                .filter(users::confirmation_code.eq(code))
+                .filter(users::confirmation_code_expires_at.ge(now)),
+        )
+        .set((
+            users::confirmation_code.eq(None::<String>),
+            users::confirmation_code_expires_at.eq(None::<chrono::DateTime<chrono::Utc>>),
+            users::updated_at.eq(now),
+        ))
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(consumed == 1)
+    }
+
+    async fn consume_phone_code(
+        &self,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<bool, AuthRepositoryError> {
+        let mut conn = self
+            .conns
+            .get()
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        let now = chrono::Utc::now();
+
+        let consumed = diesel::update(
+            users::table
+                .filter(users::id.eq(user_id))
+                .filter(users::phone_code.eq(code))
+                .filter(users::phone_code_expires_at.gt(now)),
+        )
+        .set((
+            users::phone_verified.eq(true),
+            users::phone_code.eq(None::<String>),
+            users::phone_code_expires_at.eq(None::<chrono::DateTime<chrono::Utc>>),
+            users::updated_at.eq(now),
+        ))
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(consumed == 1)
+    }
+
     async fn update_user(&self, user: &User) -> Result<User, AuthRepositoryError> {
         let mut conn = self
-            .pool
+            .conns
             .get()
             .await
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
 
         let now = chrono::Utc::now();
-        let uid = user.id.as_uuid();
+        let uid = *user.id.as_uuid();
 
         diesel::update(users::table.filter(users::id.eq(uid)))
             .set((
                 users::name.eq(&user.name),
                 users::email.eq(user.email.as_str()),
                 users::password_hash.eq(&user.password_hash),
+                users::password_changed_at.eq(user.password_changed_at),
+                users::phone.eq(user.phone.as_ref().map(PhoneNumber::as_str)),
+                users::phone_verified.eq(user.is_phone_verified),
+                users::phone_code.eq(&user.phone_code),
+                users::phone_code_expires_at.eq(user.phone_code_expires_at),
+                users::password_reset_forced.eq(user.password_reset_forced),
                 users::role.eq(user.role.to_string()),
                 users::is_active.eq(user.is_active),
                 users::email_verified.eq(user.is_email_verified),
                 users::confirmation_code.eq(&user.confirmation_code),
                 users::confirmation_code_expires_at.eq(user.confirmation_code_expires_at),
+                users::locale.eq(&user.locale),
                 users::updated_at.eq(now),
             ))
-            .execute(&mut conn)
+            .execute(&mut *conn)
             .await
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
 
@@ -215,7 +371,7 @@ impl AuthRepository for RepositoryImpl {
 
     async fn save_refresh_token(&self, token: &RefreshToken) -> Result<(), AuthRepositoryError> {
         let mut conn = self
-            .pool
+            .conns
             .get()
             .await
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
@@ -224,7 +380,7 @@ impl AuthRepository for RepositoryImpl {
 
         diesel::insert_into(refresh_tokens::table)
             .values(&db_token)
-            .execute(&mut conn)
+            .execute(&mut *conn)
             .await
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
 
@@ -236,14 +392,14 @@ impl AuthRepository for RepositoryImpl {
         token_hash: &str,
     ) -> Result<Option<RefreshToken>, AuthRepositoryError> {
         let mut conn = self
-            .pool
+            .conns
             .get()
             .await
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
 
         let result = refresh_tokens::table
             .filter(refresh_tokens::token_hash.eq(token_hash))
-            .first::<RefreshTokenModel>(&mut conn)
+            .first::<RefreshTokenModel>(&mut *conn)
             .await
             .optional()
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
@@ -251,9 +407,99 @@ impl AuthRepository for RepositoryImpl {
         Ok(result.map(Self::token_model_to_entity))
     }
 
+    async fn list_active_refresh_tokens(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<RefreshToken>, AuthRepositoryError> {
+        let mut conn = self
+            .conns
+            .get()
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        let tokens = refresh_tokens::table
+            .filter(refresh_tokens::user_id.eq(user_id))
+            .filter(refresh_tokens::revoked_at.is_null())
+            .filter(refresh_tokens::expires_at.gt(chrono::Utc::now()))
+            .order(refresh_tokens::created_at.asc())
+            .load::<RefreshTokenModel>(&mut *conn)
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(tokens.into_iter().map(Self::token_model_to_entity).collect())
+    }
+
+    async fn save_refresh_token_within_limit(
+        &self,
+        token: &RefreshToken,
+        max_active: usize,
+        evict_oldest: bool,
+    ) -> Result<Option<usize>, AuthRepositoryError> {
+        let mut conn = self
+            .conns
+            .get()
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        let db_token = Self::token_entity_to_model(token);
+        let user_id = token.user_id;
+
+        retry_on_conflict(&mut *conn, |conn| {
+            let db_token = db_token.clone();
+            async move {
+                conn.transaction(|conn| {
+                    async move {
+                        // Logins for the same user queue here until this one commits
+                        users::table
+                            .select(users::id)
+                            .filter(users::id.eq(user_id))
+                            .for_update()
+                            .first::<Uuid>(conn)
+                            .await?;
+
+                        let now = chrono::Utc::now();
+                        let active: Vec<Uuid> = refresh_tokens::table
+                            .select(refresh_tokens::id)
+                            .filter(refresh_tokens::user_id.eq(user_id))
+                            .filter(refresh_tokens::revoked_at.is_null())
+                            .filter(refresh_tokens::expires_at.gt(now))
+                            .order(refresh_tokens::created_at.asc())
+                            .load(conn)
+                            .await?;
+
+                        let excess = (active.len() + 1).saturating_sub(max_active);
+                        if excess > 0 {
+                            if !evict_oldest {
+                                return Ok(None);
+                            }
+                            diesel::update(
+                                refresh_tokens::table
+                                    .filter(refresh_tokens::id.eq_any(&active[..excess])),
+                            )
+                            .set(refresh_tokens::revoked_at.eq(now))
+                            .execute(conn)
+                            .await?;
+                        }
+
+                        diesel::insert_into(refresh_tokens::table)
+                            .values(&db_token)
+                            .execute(conn)
+                            .await?;
+                        Ok(Some(excess))
+                    }
+                    .scope_boxed()
+                })
+                .await
+            }
+            .boxed()
+        })
+        .await
+        .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))
+    }
+
     async fn revoke_refresh_token(&self, token_hash: &str) -> Result<(), AuthRepositoryError> {
         let mut conn = self
-            .pool
+            .conns
             .get()
             .await
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
@@ -266,7 +512,39 @@ impl AuthRepository for RepositoryImpl {
                 .filter(refresh_tokens::revoked_at.is_null()),
         )
         .set(refresh_tokens::revoked_at.eq(now))
-        .execute(&mut conn)
+        .execute(&mut *conn)
+        .await
+        .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        if rows_affected == 0 {
+            return Err(AuthRepositoryError::TokenNotFound);
+        }
+
+        Ok(())
+    }
+
+    async fn revoke_user_refresh_token(
+        &self,
+        user_id: Uuid,
+        token_id: Uuid,
+    ) -> Result<(), AuthRepositoryError> {
+        let mut conn = self
+            .conns
+            .get()
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        let now = chrono::Utc::now();
+
+        let rows_affected = diesel::update(
+            refresh_tokens::table
+                .filter(refresh_tokens::id.eq(token_id))
+                .filter(refresh_tokens::user_id.eq(user_id))
+                .filter(refresh_tokens::revoked_at.is_null())
+                .filter(refresh_tokens::expires_at.gt(now)),
+        )
+        .set(refresh_tokens::revoked_at.eq(now))
+        .execute(&mut *conn)
         .await
         .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
 
@@ -279,7 +557,7 @@ impl AuthRepository for RepositoryImpl {
 
     async fn revoke_all_user_tokens(&self, user_id: Uuid) -> Result<(), AuthRepositoryError> {
         let mut conn = self
-            .pool
+            .conns
             .get()
             .await
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
@@ -292,16 +570,180 @@ impl AuthRepository for RepositoryImpl {
                 .filter(refresh_tokens::revoked_at.is_null()),
         )
         .set(refresh_tokens::revoked_at.eq(now))
-        .execute(&mut conn)
+        .execute(&mut *conn)
         .await
         .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
 
         Ok(())
     }
 
+    async fn deactivate_users(&self, user_ids: &[Uuid]) -> Result<Vec<Uuid>, AuthRepositoryError> {
+        let mut conn = self
+            .conns
+            .get()
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        let now = chrono::Utc::now();
+
+        retry_on_conflict(&mut *conn, |conn| {
+            let user_ids = user_ids.to_vec();
+            async move {
+                conn.transaction(|conn| {
+                    async move {
+                        let deactivated: Vec<Uuid> =
+                            diesel::update(users::table.filter(users::id.eq_any(&user_ids)))
+                                .set((users::is_active.eq(false), users::updated_at.eq(now)))
+                                .returning(users::id)
+                                .get_results(conn)
+                                .await?;
+
+                        diesel::update(
+                            refresh_tokens::table
+                                .filter(refresh_tokens::user_id.eq_any(&deactivated))
+                                .filter(refresh_tokens::revoked_at.is_null()),
+                        )
+                        .set(refresh_tokens::revoked_at.eq(now))
+                        .execute(conn)
+                        .await?;
+
+                        Ok(deactivated)
+                    }
+                    .scope_boxed()
+                })
+                .await
+            }
+            .boxed()
+        })
+        .await
+        .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))
+    }
+
+    async fn find_unwarned_idle_users(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+        limit: usize,
+    ) -> Result<Vec<User>, AuthRepositoryError> {
+        let mut conn = self
+            .conns
+            .get()
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        let warned = inactivity_warnings::table.filter(inactivity_warnings::user_id.eq(users::id));
+        users::table
+            .filter(idle_before(before, None))
+            .filter(diesel::dsl::not(diesel::dsl::exists(warned)))
+            .order(users::created_at.asc())
+            .limit(i64::try_from(limit).unwrap_or(i64::MAX))
+            .load::<UserModel>(&mut *conn)
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?
+            .into_iter()
+            .map(Self::user_model_to_entity)
+            .collect()
+    }
+
+    async fn record_inactivity_warnings(
+        &self,
+        user_ids: &[Uuid],
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), AuthRepositoryError> {
+        if user_ids.is_empty() {
+            return Ok(());
+        }
+        let mut conn = self
+            .conns
+            .get()
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        let rows: Vec<_> = user_ids
+            .iter()
+            .map(|user_id| {
+                (inactivity_warnings::user_id.eq(*user_id), inactivity_warnings::warned_at.eq(at))
+            })
+            .collect();
+        diesel::insert_into(inactivity_warnings::table)
+            .values(&rows)
+            .on_conflict(inactivity_warnings::user_id)
+            .do_update()
+            .set(inactivity_warnings::warned_at.eq(at))
+            .execute(&mut *conn)
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn deactivate_idle_users(
+        &self,
+        before: chrono::DateTime<chrono::Utc>,
+        warned_before: Option<chrono::DateTime<chrono::Utc>>,
+        limit: usize,
+    ) -> Result<Vec<Uuid>, AuthRepositoryError> {
+        let mut conn = self
+            .conns
+            .get()
+            .await
+            .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
+
+        let now = chrono::Utc::now();
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+
+        retry_on_conflict(&mut *conn, |conn| {
+            async move {
+                conn.transaction(|conn| {
+                    async move {
+                        let candidates: Vec<Uuid> = users::table
+                            .select(users::id)
+                            .filter(idle_before(before, warned_before))
+                            .order(users::created_at.asc())
+                            .limit(limit)
+                            .load(conn)
+                            .await?;
+
+                        let deactivated: Vec<Uuid> = diesel::update(
+                            users::table
+                                .filter(users::id.eq_any(&candidates))
+                                .filter(idle_before(before, warned_before)),
+                        )
+                        .set((users::is_active.eq(false), users::updated_at.eq(now)))
+                        .returning(users::id)
+                        .get_results(conn)
+                        .await?;
+
+                        diesel::update(
+                            refresh_tokens::table
+                                .filter(refresh_tokens::user_id.eq_any(&deactivated))
+                                .filter(refresh_tokens::revoked_at.is_null()),
+                        )
+                        .set(refresh_tokens::revoked_at.eq(now))
+                        .execute(conn)
+                        .await?;
+
+                        diesel::delete(
+                            inactivity_warnings::table
+                                .filter(inactivity_warnings::user_id.eq_any(&deactivated)),
+                        )
+                        .execute(conn)
+                        .await?;
+
+                        Ok(deactivated)
+                    }
+                    .scope_boxed()
+                })
+                .await
+            }
+            .boxed()
+        })
+        .await
+        .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))
+    }
+
     async fn cleanup_expired_tokens(&self) -> Result<u64, AuthRepositoryError> {
         let mut conn = self
-            .pool
+            .conns
             .get()
             .await
             .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
@@ -313,7 +755,7 @@ impl AuthRepository for RepositoryImpl {
                 .filter(refresh_tokens::expires_at.lt(now))
                 .or_filter(refresh_tokens::revoked_at.is_not_null()),
         )
-        .execute(&mut conn)
+        .execute(&mut *conn)
         .await
         .map_err(|e| AuthRepositoryError::DatabaseError(e.to_string()))?;
 
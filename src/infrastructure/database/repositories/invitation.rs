@@ -0,0 +1,90 @@
+use crate::{
+    domain::{
+        entities::Invitation,
+        repositories::{invitation::InvitationRepository, user::RepositoryError},
+    },
+    infrastructure::database::{models::InvitationModel, schema::invitations, DbPool},
+};
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+/// PostgreSQL implementation of InvitationRepository
+#[derive(Clone)]
+pub struct RepositoryImpl {
+    pool: DbPool,
+}
+
+impl RepositoryImpl {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl InvitationRepository for RepositoryImpl {
+    async fn create(&self, invitation: &Invitation) -> Result<(), RepositoryError> {
+        let mut conn =
+            self.pool.get().await.map_err(|e| {
+                RepositoryError::Internal(format!("Failed to get connection: {}", e))
+            })?;
+
+        diesel::insert_into(invitations::table)
+            .values(InvitationModel::from(invitation))
+            .execute(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn claim(&self, token_hash: &str) -> Result<Option<Invitation>, RepositoryError> {
+        let mut conn =
+            self.pool.get().await.map_err(|e| {
+                RepositoryError::Internal(format!("Failed to get connection: {}", e))
+            })?;
+        let now = chrono::Utc::now();
+
+        let claimed = diesel::update(
+            invitations::table
+                .filter(invitations::token_hash.eq(token_hash))
+                .filter(invitations::used_at.is_null())
+                .filter(invitations::expires_at.gt(now)),
+        )
+        .set(invitations::used_at.eq(now))
+        .returning(InvitationModel::as_returning())
+        .get_result(&mut conn)
+        .await
+        .optional()?;
+
+        Ok(claimed.map(Invitation::from))
+    }
+
+    async fn release(&self, id: Uuid) -> Result<(), RepositoryError> {
+        let mut conn =
+            self.pool.get().await.map_err(|e| {
+                RepositoryError::Internal(format!("Failed to get connection: {}", e))
+            })?;
+
+        diesel::update(invitations::table.find(id))
+            .set(invitations::used_at.eq(None::<chrono::DateTime<chrono::Utc>>))
+            .execute(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn set_used_by(&self, id: Uuid, user_id: Uuid) -> Result<(), RepositoryError> {
+        let mut conn =
+            self.pool.get().await.map_err(|e| {
+                RepositoryError::Internal(format!("Failed to get connection: {}", e))
+            })?;
+
+        diesel::update(invitations::table.find(id))
+            .set(invitations::used_by.eq(user_id))
+            .execute(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+}
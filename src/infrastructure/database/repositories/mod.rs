@@ -3,11 +3,17 @@
 /// This module contains concrete implementations of repository traits.
 /// Implementations are organized by domain (user, auth, etc.) rather than
 /// by database technology to avoid coupling.
+pub mod audit;
 pub mod auth;
+pub mod invitation;
+pub mod password_history;
 pub mod user;
 
 // Re-export with descriptive names
+pub use audit::RepositoryImpl as AuditRepositoryImpl;
 pub use auth::RepositoryImpl as AuthRepositoryImpl;
+pub use invitation::RepositoryImpl as InvitationRepositoryImpl;
+pub use password_history::RepositoryImpl as PasswordHistoryRepositoryImpl;
 pub use user::RepositoryImpl as UserRepositoryImpl;
 
 // Backward compatibility (deprecated)
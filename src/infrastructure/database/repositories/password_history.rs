@@ -0,0 +1,98 @@
+use crate::{
+    domain::{
+        repositories::{password_history::PasswordHistoryRepository, user::RepositoryError},
+        value_objects::UserId,
+    },
+    infrastructure::database::{
+        models::PasswordHistoryModel,
+        schema::password_history,
+        transaction::{ConnectionSource, RequestTransaction},
+        DbPool,
+    },
+};
+use async_trait::async_trait;
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use uuid::Uuid;
+
+/// PostgreSQL implementation of PasswordHistoryRepository
+#[derive(Clone)]
+pub struct RepositoryImpl {
+    conns: ConnectionSource,
+}
+
+impl RepositoryImpl {
+    pub fn new(pool: DbPool) -> Self {
+        Self { conns: pool.into() }
+    }
+
+    /// Run every statement in `tx` instead of on pooled connections
+    pub fn in_transaction(tx: RequestTransaction) -> Self {
+        Self { conns: ConnectionSource::Transaction(tx) }
+    }
+}
+
+fn as_limit(n: usize) -> i64 {
+    i64::try_from(n).unwrap_or(i64::MAX)
+}
+
+#[async_trait]
+impl PasswordHistoryRepository for RepositoryImpl {
+    async fn recent(&self, user_id: UserId, limit: usize) -> Result<Vec<String>, RepositoryError> {
+        let mut conn =
+            self.conns.get().await.map_err(|e| {
+                RepositoryError::Internal(format!("Failed to get connection: {}", e))
+            })?;
+
+        let hashes = password_history::table
+            .filter(password_history::user_id.eq(user_id.as_uuid()))
+            .order(password_history::created_at.desc())
+            .limit(as_limit(limit))
+            .select(password_history::password_hash)
+            .load(&mut *conn)
+            .await?;
+
+        Ok(hashes)
+    }
+
+    async fn record(
+        &self,
+        user_id: UserId,
+        password_hash: &str,
+        keep: usize,
+    ) -> Result<(), RepositoryError> {
+        let mut conn =
+            self.conns.get().await.map_err(|e| {
+                RepositoryError::Internal(format!("Failed to get connection: {}", e))
+            })?;
+        let uid = *user_id.as_uuid();
+
+        diesel::insert_into(password_history::table)
+            .values(PasswordHistoryModel {
+                id: Uuid::new_v4(),
+                user_id: uid,
+                password_hash: password_hash.to_string(),
+                created_at: chrono::Utc::now(),
+            })
+            .execute(&mut *conn)
+            .await?;
+
+        // Drop everything older than the newest `keep` entries
+        let kept: Vec<Uuid> = password_history::table
+            .filter(password_history::user_id.eq(uid))
+            .order(password_history::created_at.desc())
+            .limit(as_limit(keep))
+            .select(password_history::id)
+            .load(&mut *conn)
+            .await?;
+        diesel::delete(
+            password_history::table
+                .filter(password_history::user_id.eq(uid))
+                .filter(password_history::id.ne_all(kept)),
+        )
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+}
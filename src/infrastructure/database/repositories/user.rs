@@ -1,14 +1,24 @@
 use crate::{
     domain::{
         entities::User,
-        repositories::user_repository::{RepositoryError, UserRepository},
-        value_objects::{Email, UserId, UserRole},
+        repositories::user_repository::{RepositoryError, UserChanges, UserFilter, UserRepository},
+        value_objects::{Email, PhoneNumber, UserId, UserRole},
+    },
+    infrastructure::database::{
+        map_db_error,
+        models::{UserChangeset, UserModel},
+        schema::users,
+        transaction::{ConnectionSource, RequestTransaction},
+        DbPool,
     },
-    infrastructure::database::{models::UserModel, schema::users, DbPool},
 };
 use async_trait::async_trait;
 use diesel::prelude::*;
 use diesel_async::RunQueryDsl;
+use futures::{stream::BoxStream, StreamExt};
+
+/// Rows read ahead of a slow consumer when streaming
+const STREAM_BUFFER: usize = 256;
 
 /// PostgreSQL implementation of UserRepository
 ///
@@ -16,16 +26,26 @@ use diesel_async::RunQueryDsl;
 /// The struct name is generic to avoid coupling to specific database technology.
 #[derive(Clone)]
 pub struct RepositoryImpl {
-    pool: DbPool,
+    conns: ConnectionSource,
 }
 
 impl RepositoryImpl {
     pub fn new(pool: DbPool) -> Self {
-        Self { pool }
+        Self { conns: pool.into() }
+    }
+
+    /// Run every statement in `tx` instead of on pooled connections
+    pub fn in_transaction(tx: RequestTransaction) -> Self {
+        Self { conns: ConnectionSource::Transaction(tx) }
     }
 
     /// Helper: Convert UserModel to domain User entity
     fn model_to_entity(model: UserModel) -> Result<User, RepositoryError> {
+        let phone = model
+            .phone
+            .map(PhoneNumber::parse)
+            .transpose()
+            .map_err(|e| RepositoryError::Internal(format!("{} from database", e)))?;
         Ok(User::from_existing(
             UserId::from_uuid(model.id),
             Email::parse(&model.email).map_err(|e| {
@@ -39,6 +59,13 @@ impl RepositoryImpl {
             model.confirmation_code,
             model.confirmation_code_expires_at,
             model.last_login,
+            model.password_changed_at,
+            phone,
+            model.phone_verified,
+            model.phone_code,
+            model.phone_code_expires_at,
+            model.password_reset_forced,
+            model.locale,
             model.created_at,
             model.updated_at,
         ))
@@ -59,6 +86,13 @@ impl RepositoryImpl {
             confirmation_code: user.confirmation_code.clone(),
             confirmation_code_expires_at: user.confirmation_code_expires_at,
             email_verified: user.is_email_verified,
+            locale: user.locale.clone(),
+            password_changed_at: user.password_changed_at,
+            phone: user.phone.as_ref().map(|p| p.as_str().to_string()),
+            phone_verified: user.is_phone_verified,
+            phone_code: user.phone_code.clone(),
+            phone_code_expires_at: user.phone_code_expires_at,
+            password_reset_forced: user.password_reset_forced,
         }
     }
 }
@@ -67,7 +101,7 @@ impl RepositoryImpl {
 impl UserRepository for RepositoryImpl {
     async fn save(&self, user: &User) -> Result<User, RepositoryError> {
         let mut conn =
-            self.pool.get().await.map_err(|e| {
+            self.conns.get().await.map_err(|e| {
                 RepositoryError::Internal(format!("Failed to get connection: {}", e))
             })?;
 
@@ -78,96 +112,123 @@ impl UserRepository for RepositoryImpl {
             .on_conflict(users::id)
             .do_update()
             .set(&db_user)
-            .get_result::<UserModel>(&mut conn)
+            .get_result::<UserModel>(&mut *conn)
             .await
-            .map_err(|e| RepositoryError::Internal(e.to_string()))?;
+            .map_err(map_db_error)?;
 
         Self::model_to_entity(result)
     }
 
     async fn update(&self, user: &User) -> Result<User, RepositoryError> {
         let mut conn =
-            self.pool.get().await.map_err(|e| {
+            self.conns.get().await.map_err(|e| {
                 RepositoryError::Internal(format!("Failed to get connection: {}", e))
             })?;
 
         let db_user = Self::entity_to_model(user);
 
-        let result = diesel::update(users::table.filter(users::id.eq(user.id.as_uuid())))
+        let result = diesel::update(users::table.filter(users::id.eq(db_user.id)))
             .set(&db_user)
-            .get_result::<UserModel>(&mut conn)
+            .get_result::<UserModel>(&mut *conn)
             .await
-            .map_err(|e| RepositoryError::Internal(e.to_string()))?;
+            .map_err(map_db_error)?;
 
         Self::model_to_entity(result)
     }
 
+    async fn patch(
+        &self,
+        id: UserId,
+        changes: &UserChanges,
+    ) -> Result<Option<User>, RepositoryError> {
+        // Nothing to write; avoid bumping updated_at for an empty patch
+        if changes.is_empty() {
+            return self.find_by_id(id).await;
+        }
+
+        let mut conn =
+            self.conns.get().await.map_err(|e| {
+                RepositoryError::Internal(format!("Failed to get connection: {}", e))
+            })?;
+
+        let changeset = UserChangeset {
+            name: changes.name.clone(),
+            locale: changes.locale.clone(),
+            updated_at: chrono::Utc::now(),
+        };
+
+        let result = diesel::update(users::table.filter(users::id.eq(id.as_uuid())))
+            .set(&changeset)
+            .get_result::<UserModel>(&mut *conn)
+            .await
+            .optional()
+            .map_err(map_db_error)?;
+
+        result.map(Self::model_to_entity).transpose()
+    }
+
     async fn find_by_id(&self, id: UserId) -> Result<Option<User>, RepositoryError> {
         let mut conn =
-            self.pool.get().await.map_err(|e| {
+            self.conns.get().await.map_err(|e| {
                 RepositoryError::Internal(format!("Failed to get connection: {}", e))
             })?;
 
         let result = users::table
             .filter(users::id.eq(id.as_uuid()))
-            .first::<UserModel>(&mut conn)
+            .first::<UserModel>(&mut *conn)
             .await
             .optional()
-            .map_err(|e| RepositoryError::Internal(e.to_string()))?;
+            .map_err(map_db_error)?;
 
         result.map(Self::model_to_entity).transpose()
     }
 
     async fn find_by_email(&self, email: &Email) -> Result<Option<User>, RepositoryError> {
         let mut conn =
-            self.pool.get().await.map_err(|e| {
+            self.conns.get().await.map_err(|e| {
                 RepositoryError::Internal(format!("Failed to get connection: {}", e))
             })?;
 
         let result = users::table
             .filter(users::email.eq(email.as_str()))
-            .first::<UserModel>(&mut conn)
+            .first::<UserModel>(&mut *conn)
             .await
             .optional()
-            .map_err(|e| RepositoryError::Internal(e.to_string()))?;
+            .map_err(map_db_error)?;
 
         result.map(Self::model_to_entity).transpose()
     }
 
     async fn exists_by_email(&self, email: &Email) -> Result<bool, RepositoryError> {
         let mut conn =
-            self.pool.get().await.map_err(|e| {
+            self.conns.get().await.map_err(|e| {
                 RepositoryError::Internal(format!("Failed to get connection: {}", e))
             })?;
 
         let count: i64 = users::table
             .filter(users::email.eq(email.as_str()))
             .count()
-            .get_result(&mut conn)
+            .get_result(&mut *conn)
             .await
-            .map_err(|e| RepositoryError::Internal(e.to_string()))?;
+            .map_err(map_db_error)?;
 
         Ok(count > 0)
     }
 
     async fn count(&self) -> Result<i64, RepositoryError> {
         let mut conn =
-            self.pool.get().await.map_err(|e| {
+            self.conns.get().await.map_err(|e| {
                 RepositoryError::Internal(format!("Failed to get connection: {}", e))
             })?;
 
-        let count: i64 = users::table
-            .count()
-            .get_result(&mut conn)
-            .await
-            .map_err(|e| RepositoryError::Internal(e.to_string()))?;
+        let count: i64 = users::table.count().get_result(&mut *conn).await.map_err(map_db_error)?;
 
         Ok(count)
     }
 
     async fn list_paginated(&self, limit: i64, offset: i64) -> Result<Vec<User>, RepositoryError> {
         let mut conn =
-            self.pool.get().await.map_err(|e| {
+            self.conns.get().await.map_err(|e| {
                 RepositoryError::Internal(format!("Failed to get connection: {}", e))
             })?;
 
@@ -175,37 +236,80 @@ impl UserRepository for RepositoryImpl {
             .order(users::created_at.desc())
             .limit(limit)
             .offset(offset)
-            .load::<UserModel>(&mut conn)
+            .load::<UserModel>(&mut *conn)
             .await
-            .map_err(|e| RepositoryError::Internal(e.to_string()))?;
+            .map_err(map_db_error)?;
 
         results.into_iter().map(Self::model_to_entity).collect::<Result<Vec<_>, _>>()
     }
 
+    fn stream(&self, filter: &UserFilter) -> BoxStream<'static, Result<User, RepositoryError>> {
+        let conns = self.conns.clone();
+        let filter = filter.clone();
+        let (tx, rx) = tokio::sync::mpsc::channel(STREAM_BUFFER);
+
+        // The connection must outlive the row stream, so a task owns both and
+        // hands rows over a bounded channel; a dropped receiver ends it
+        tokio::spawn(async move {
+            let result: Result<(), RepositoryError> = async {
+                let mut conn = conns.get().await.map_err(|e| {
+                    RepositoryError::Internal(format!("Failed to get connection: {}", e))
+                })?;
+
+                let mut query = users::table.into_boxed();
+                if let Some(role) = filter.role {
+                    query = query.filter(users::role.eq(role.to_string()));
+                }
+                if let Some(is_active) = filter.is_active {
+                    query = query.filter(users::is_active.eq(is_active));
+                }
+
+                let mut rows = query
+                    .order((users::created_at.asc(), users::id.asc()))
+                    .load_stream::<UserModel>(&mut *conn)
+                    .await
+                    .map_err(map_db_error)?;
+                while let Some(row) = rows.next().await {
+                    let user = Self::model_to_entity(row.map_err(map_db_error)?)?;
+                    if tx.send(Ok(user)).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(())
+            }
+            .await;
+
+            if let Err(e) = result {
+                let _ = tx.send(Err(e)).await;
+            }
+        });
+
+        futures::stream::unfold(rx, |mut rx| async move { rx.recv().await.map(|item| (item, rx)) })
+            .boxed()
+    }
+
     async fn delete(&self, id: UserId) -> Result<bool, RepositoryError> {
         let mut conn =
-            self.pool.get().await.map_err(|e| {
+            self.conns.get().await.map_err(|e| {
                 RepositoryError::Internal(format!("Failed to get connection: {}", e))
             })?;
 
         let rows_affected = diesel::delete(users::table.filter(users::id.eq(id.as_uuid())))
-            .execute(&mut conn)
+            .execute(&mut *conn)
             .await
-            .map_err(|e| RepositoryError::Internal(e.to_string()))?;
+            .map_err(map_db_error)?;
 
         Ok(rows_affected > 0)
     }
 
     async fn delete_all(&self) -> Result<usize, RepositoryError> {
         let mut conn =
-            self.pool.get().await.map_err(|e| {
+            self.conns.get().await.map_err(|e| {
                 RepositoryError::Internal(format!("Failed to get connection: {}", e))
             })?;
 
-        let rows_affected = diesel::delete(users::table)
-            .execute(&mut conn)
-            .await
-            .map_err(|e| RepositoryError::Internal(e.to_string()))?;
+        let rows_affected =
+            diesel::delete(users::table).execute(&mut *conn).await.map_err(map_db_error)?;
 
         Ok(rows_affected)
     }
@@ -1,5 +1,49 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    audit_logs (id) {
+        id -> Uuid,
+        actor_id -> Nullable<Uuid>,
+        #[max_length = 100]
+        action -> Varchar,
+        target_id -> Nullable<Uuid>,
+        details -> Jsonb,
+        created_at -> Timestamptz,
+        legal_hold -> Bool,
+    }
+}
+
+diesel::table! {
+    inactivity_warnings (user_id) {
+        user_id -> Uuid,
+        warned_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    invitations (id) {
+        id -> Uuid,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        #[max_length = 20]
+        role -> Nullable<Varchar>,
+        created_by -> Nullable<Uuid>,
+        expires_at -> Timestamptz,
+        used_at -> Nullable<Timestamptz>,
+        used_by -> Nullable<Uuid>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    password_history (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        password_hash -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     refresh_tokens (id) {
         id -> Uuid,
@@ -29,9 +73,28 @@ diesel::table! {
         confirmation_code -> Nullable<Varchar>,
         confirmation_code_expires_at -> Nullable<Timestamptz>,
         email_verified -> Bool,
+        #[max_length = 10]
+        locale -> Varchar,
+        password_changed_at -> Nullable<Timestamptz>,
+        #[max_length = 20]
+        phone -> Nullable<Varchar>,
+        phone_verified -> Bool,
+        #[max_length = 64]
+        phone_code -> Nullable<Varchar>,
+        phone_code_expires_at -> Nullable<Timestamptz>,
+        password_reset_forced -> Bool,
     }
 }
 
+diesel::joinable!(inactivity_warnings -> users (user_id));
+diesel::joinable!(password_history -> users (user_id));
 diesel::joinable!(refresh_tokens -> users (user_id));
 
-diesel::allow_tables_to_appear_in_same_query!(refresh_tokens, users,);
+diesel::allow_tables_to_appear_in_same_query!(
+    audit_logs,
+    inactivity_warnings,
+    invitations,
+    password_history,
+    refresh_tokens,
+    users,
+);
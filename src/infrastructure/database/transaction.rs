@@ -1,11 +1,91 @@
+use crate::infrastructure::database::DbPool;
 use diesel::result::Error as DieselError;
-use diesel_async::pooled_connection::deadpool::Object;
-use diesel_async::AsyncPgConnection;
+use diesel_async::pooled_connection::deadpool::{Object, PoolError};
+use diesel_async::{AnsiTransactionManager, AsyncPgConnection, TransactionManager};
 use futures::future::BoxFuture;
+use std::{
+    ops::{Deref, DerefMut},
+    sync::Arc,
+};
+use tokio::sync::{Mutex, OwnedMutexGuard};
 
 /// Connection type used in the repository
 pub type Conn = Object<AsyncPgConnection>;
 
+/// Transaction held open for the length of one request. Clones share the
+/// connection, so every repository built on it sees the others' writes and
+/// they commit or roll back together.
+#[derive(Clone)]
+pub struct RequestTransaction(Arc<Mutex<Conn>>);
+
+impl RequestTransaction {
+    /// Start a transaction on `conn`
+    pub async fn begin(mut conn: Conn) -> Result<Self, DieselError> {
+        AnsiTransactionManager::begin_transaction(&mut *conn).await?;
+        Ok(Self(Arc::new(Mutex::new(conn))))
+    }
+
+    pub async fn commit(&self) -> Result<(), DieselError> {
+        AnsiTransactionManager::commit_transaction(&mut **self.0.lock().await).await
+    }
+
+    /// The pool's recycle check discards a connection left mid-transaction,
+    /// so a failed rollback does not leak into the next request
+    pub async fn rollback(&self) -> Result<(), DieselError> {
+        AnsiTransactionManager::rollback_transaction(&mut **self.0.lock().await).await
+    }
+}
+
+/// Where a repository gets its connection: a fresh one from the pool per
+/// call, or the request's transaction
+#[derive(Clone)]
+pub enum ConnectionSource {
+    Pool(DbPool),
+    Transaction(RequestTransaction),
+}
+
+impl ConnectionSource {
+    pub async fn get(&self) -> Result<SourcedConn, PoolError> {
+        match self {
+            Self::Pool(pool) => pool.get().await.map(SourcedConn::Pooled),
+            Self::Transaction(tx) => Ok(SourcedConn::Shared(tx.0.clone().lock_owned().await)),
+        }
+    }
+}
+
+impl From<DbPool> for ConnectionSource {
+    fn from(pool: DbPool) -> Self {
+        Self::Pool(pool)
+    }
+}
+
+/// Connection handed out by a `ConnectionSource`; a shared one is locked
+/// to the holder until dropped
+pub enum SourcedConn {
+    Pooled(Conn),
+    Shared(OwnedMutexGuard<Conn>),
+}
+
+impl Deref for SourcedConn {
+    type Target = AsyncPgConnection;
+
+    fn deref(&self) -> &AsyncPgConnection {
+        match self {
+            Self::Pooled(conn) => conn,
+            Self::Shared(conn) => conn,
+        }
+    }
+}
+
+impl DerefMut for SourcedConn {
+    fn deref_mut(&mut self) -> &mut AsyncPgConnection {
+        match self {
+            Self::Pooled(conn) => conn,
+            Self::Shared(conn) => conn,
+        }
+    }
+}
+
 /// Trait for executing database operations within a transaction
 #[async_trait::async_trait]
 pub trait TransactionalRepository {
@@ -15,3 +95,119 @@ pub trait TransactionalRepository {
         E: From<DieselError> + Send,
         R: Send;
 }
+
+/// Attempts after the first before a serialization failure is surfaced
+const MAX_CONFLICT_RETRIES: u32 = 3;
+
+/// Backoff before the first retry; doubled on each further attempt
+const CONFLICT_BACKOFF: std::time::Duration = std::time::Duration::from_millis(10);
+
+/// True for Postgres SQLSTATE 40001 (`serialization_failure`)
+pub fn is_serialization_failure(err: &DieselError) -> bool {
+    matches!(
+        err,
+        DieselError::DatabaseError(diesel::result::DatabaseErrorKind::SerializationFailure, _)
+    )
+}
+
+/// Run `op` against `conn`, retrying a bounded number of times with backoff
+/// when Postgres aborts it with a serialization failure. Any other error, or
+/// a conflict that persists past the last retry, is returned unchanged.
+///
+/// Meant for multi-statement transactions: open the transaction inside
+/// `op`, so a retry starts from a clean slate. A single statement at the
+/// default READ COMMITTED isolation is not aborted this way, so wrapping
+/// one gains nothing.
+pub async fn retry_on_conflict<C, F, R>(conn: &mut C, mut op: F) -> Result<R, DieselError>
+where
+    C: Send,
+    F: for<'a> FnMut(&'a mut C) -> BoxFuture<'a, Result<R, DieselError>> + Send,
+    R: Send,
+{
+    let mut attempt = 0;
+    loop {
+        match op(conn).await {
+            Err(e) if is_serialization_failure(&e) && attempt < MAX_CONFLICT_RETRIES => {
+                let backoff = CONFLICT_BACKOFF * 2u32.pow(attempt);
+                attempt += 1;
+                tracing::warn!(
+                    "Serialization failure, retrying ({}/{}) in {:?}",
+                    attempt,
+                    MAX_CONFLICT_RETRIES,
+                    backoff
+                );
+                tokio::time::sleep(backoff).await;
+            },
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::result::DatabaseErrorKind;
+    use futures::FutureExt;
+
+    fn conflict() -> DieselError {
+        DieselError::DatabaseError(
+            DatabaseErrorKind::SerializationFailure,
+            Box::new("could not serialize access due to concurrent update".to_string()),
+        )
+    }
+
+    #[tokio::test]
+    async fn retries_serialization_failures_until_success() {
+        let mut failures_left = 2;
+
+        let result = retry_on_conflict(&mut failures_left, |left| {
+            async move {
+                if *left > 0 {
+                    *left -= 1;
+                    Err(conflict())
+                } else {
+                    Ok("committed")
+                }
+            }
+            .boxed()
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), "committed");
+        assert_eq!(failures_left, 0);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let mut attempts = 0u32;
+
+        let result: Result<(), _> = retry_on_conflict(&mut attempts, |attempts| {
+            async move {
+                *attempts += 1;
+                Err(conflict())
+            }
+            .boxed()
+        })
+        .await;
+
+        assert!(result.as_ref().is_err_and(is_serialization_failure));
+        assert_eq!(attempts, MAX_CONFLICT_RETRIES + 1);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_other_errors() {
+        let mut attempts = 0u32;
+
+        let result: Result<(), _> = retry_on_conflict(&mut attempts, |attempts| {
+            async move {
+                *attempts += 1;
+                Err(DieselError::NotFound)
+            }
+            .boxed()
+        })
+        .await;
+
+        assert!(matches!(result, Err(DieselError::NotFound)));
+        assert_eq!(attempts, 1);
+    }
+}
@@ -1,41 +1,35 @@
 use crate::application::services::email::{EmailService, EmailType, Recipient};
+use crate::config::{email::SubjectTemplates, EmailConfig};
 use crate::shared::errors::AppError;
 use askama::Template;
 use async_trait::async_trait;
 use lettre::{
     message::{header::ContentType, Mailbox},
     transport::smtp::authentication::Credentials,
-    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    Address, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
 };
 use tracing::{error, info};
 
 #[derive(Clone)]
 pub struct LettreEmailService {
     mailer: AsyncSmtpTransport<Tokio1Executor>,
-    from_email: String,
+    from: Mailbox,
+    reply_to: Option<Mailbox>,
+    bcc: Option<Mailbox>,
+    subjects: SubjectTemplates,
 }
 
 impl LettreEmailService {
-    pub fn new() -> Result<Self, AppError> {
-        let smtp_host = std::env::var("SMTP_HOST").unwrap_or_else(|_| "127.0.0.1".to_string());
-        let smtp_user = std::env::var("SMTP_USERNAME")
-            .or_else(|_| std::env::var("SMTP_USER"))
-            .unwrap_or_default();
-        let smtp_pass = std::env::var("SMTP_PASSWORD")
-            .or_else(|_| std::env::var("SMTP_PASS"))
-            .unwrap_or_default();
-        let from_email =
-            std::env::var("SMTP_FROM").unwrap_or_else(|_| "noreply@axum-backend.com".to_string());
-
-        let creds = Credentials::new(smtp_user, smtp_pass);
+    pub fn new(config: &EmailConfig) -> Result<Self, AppError> {
+        let creds = Credentials::new(config.smtp_username.clone(), config.smtp_password.clone());
 
         // For production, you should use relay() and proper TLS.
         // For development/load testing, we use builder_unencrypted_localhost() or similar if no auth.
         // This is a basic implementation that assumes a standard SMTP server.
-        let mailer = if smtp_host == "127.0.0.1" || smtp_host == "localhost" {
+        let mailer = if config.smtp_host == "127.0.0.1" || config.smtp_host == "localhost" {
             AsyncSmtpTransport::<Tokio1Executor>::unencrypted_localhost()
         } else {
-            AsyncSmtpTransport::<Tokio1Executor>::relay(&smtp_host)
+            AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host)
                 .map_err(|e| {
                     AppError::Internal(anyhow::anyhow!("Failed to build SMTP transport: {}", e))
                 })?
@@ -43,37 +37,66 @@ impl LettreEmailService {
                 .build()
         };
 
-        Ok(Self { mailer, from_email })
+        let from_address = config
+            .from_address
+            .parse::<Address>()
+            .map_err(|e| AppError::Config(format!("Invalid from address: {}", e)))?;
+        let from = Mailbox::new(config.from_name.clone(), from_address);
+
+        let reply_to = config
+            .reply_to
+            .as_deref()
+            .map(|addr| addr.parse::<Mailbox>())
+            .transpose()
+            .map_err(|e| AppError::Config(format!("Invalid reply-to address: {}", e)))?;
+
+        let bcc = config
+            .bcc
+            .as_deref()
+            .map(|addr| addr.parse::<Mailbox>())
+            .transpose()
+            .map_err(|e| AppError::Config(format!("Invalid BCC address: {}", e)))?;
+
+        Ok(Self { mailer, from, reply_to, bcc, subjects: config.subjects.clone() })
     }
-}
 
-#[async_trait]
-impl EmailService for LettreEmailService {
-    async fn send(&self, recipient: Recipient, email_type: EmailType) -> Result<(), AppError> {
+    /// Render and assemble the message without sending it
+    fn build_message(
+        &self,
+        recipient: &Recipient,
+        email_type: &EmailType,
+    ) -> Result<Message, AppError> {
         let to_address = format!("{} <{}>", recipient.name, recipient.email)
             .parse::<Mailbox>()
             .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid email address: {}", e)))?;
 
-        let from_address = self
-            .from_email
-            .parse::<Mailbox>()
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Invalid from address: {}", e)))?;
-
-        let subject = email_type.subject();
+        let template = match email_type {
+            EmailType::Welcome(_) => &self.subjects.welcome,
+            EmailType::Confirmation(..) => &self.subjects.confirmation,
+            EmailType::ConfirmationResent(..) => &self.subjects.confirmation_resent,
+            EmailType::PasswordReset(_) => &self.subjects.password_reset,
+            EmailType::AccountLocked(..) => &self.subjects.account_locked,
+            EmailType::InactivityWarning(_) => &self.subjects.inactivity_warning,
+        };
+        let subject = match template {
+            Some(template) => self.subjects.render(template, &recipient.name),
+            None => email_type.subject(recipient.locale),
+        };
 
         // Render template based on email type
-        let body = match &email_type {
-            EmailType::Welcome(name) => {
-                crate::infrastructure::email::templates::WelcomeTemplate { name: name.clone() }
-                    .render()
-                    .map_err(|e| {
-                        AppError::Internal(anyhow::anyhow!("Failed to render template: {}", e))
-                    })?
-            },
-            EmailType::Confirmation(code) => {
+        let body = match email_type {
+            EmailType::Welcome(name) => crate::infrastructure::email::templates::WelcomeTemplate {
+                name: name.clone(),
+                locale: recipient.locale,
+            }
+            .render()
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to render template: {}", e)))?,
+            EmailType::Confirmation(code, link) | EmailType::ConfirmationResent(code, link) => {
                 crate::infrastructure::email::templates::ConfirmationTemplate {
                     name: recipient.name.clone(),
                     code: code.clone(),
+                    link: link.clone(),
+                    locale: recipient.locale,
                 }
                 .render()
                 .map_err(|e| {
@@ -84,6 +107,30 @@ impl EmailService for LettreEmailService {
                 crate::infrastructure::email::templates::ForgotPasswordTemplate {
                     name: recipient.name.clone(),
                     code: code.clone(),
+                    locale: recipient.locale,
+                }
+                .render()
+                .map_err(|e| {
+                    AppError::Internal(anyhow::anyhow!("Failed to render template: {}", e))
+                })?
+            },
+            EmailType::AccountLocked(ip, locked_at) => {
+                crate::infrastructure::email::templates::AccountLockedTemplate {
+                    name: recipient.name.clone(),
+                    ip: ip.to_string(),
+                    locked_at: locked_at.format("%Y-%m-%d %H:%M:%S UTC").to_string(),
+                    locale: recipient.locale,
+                }
+                .render()
+                .map_err(|e| {
+                    AppError::Internal(anyhow::anyhow!("Failed to render template: {}", e))
+                })?
+            },
+            EmailType::InactivityWarning(disable_at) => {
+                crate::infrastructure::email::templates::InactivityWarningTemplate {
+                    name: recipient.name.clone(),
+                    disable_at: disable_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+                    locale: recipient.locale,
                 }
                 .render()
                 .map_err(|e| {
@@ -92,13 +139,28 @@ impl EmailService for LettreEmailService {
             },
         };
 
-        let email = Message::builder()
-            .from(from_address)
-            .to(to_address)
+        let mut builder = Message::builder().from(self.from.clone()).to(to_address);
+        if let Some(reply_to) = &self.reply_to {
+            builder = builder.reply_to(reply_to.clone());
+        }
+        // Lettre moves Bcc into the SMTP envelope and strips the header, so
+        // the recipient never sees the audit mailbox
+        if let Some(bcc) = &self.bcc {
+            builder = builder.bcc(bcc.clone());
+        }
+
+        builder
             .subject(subject)
             .header(ContentType::TEXT_HTML) // Changed to HTML
             .body(body)
-            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to build email: {}", e)))?;
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to build email: {}", e)))
+    }
+}
+
+#[async_trait]
+impl EmailService for LettreEmailService {
+    async fn send(&self, recipient: Recipient, email_type: EmailType) -> Result<(), AppError> {
+        let email = self.build_message(&recipient, &email_type)?;
 
         match self.mailer.send(email).await {
             Ok(_) => {
@@ -113,4 +175,186 @@ impl EmailService for LettreEmailService {
             },
         }
     }
+
+    fn provider(&self) -> &'static str {
+        "smtp"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::shared::i18n::Locale;
+
+    fn recipient() -> Recipient {
+        Recipient {
+            email: "jane@example.com".to_string(),
+            name: "Jane".to_string(),
+            locale: Locale::default(),
+        }
+    }
+
+    fn welcome(service: &LettreEmailService) -> Message {
+        service
+            .build_message(&recipient(), &EmailType::Welcome("Jane".to_string()))
+            .unwrap()
+    }
+
+    fn formatted(service: &LettreEmailService) -> String {
+        String::from_utf8(welcome(service).formatted()).unwrap()
+    }
+
+    fn envelope_recipients(service: &LettreEmailService) -> Vec<String> {
+        welcome(service).envelope().to().iter().map(|a| a.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn message_uses_configured_sender_and_reply_to() {
+        let config = EmailConfig {
+            from_address: "accounts@acme.test".to_string(),
+            from_name: Some("Acme Accounts".to_string()),
+            reply_to: Some("support@acme.test".to_string()),
+            ..EmailConfig::default()
+        };
+        let service = LettreEmailService::new(&config).unwrap();
+
+        let raw = formatted(&service);
+        assert!(raw.contains("From: \"Acme Accounts\" <accounts@acme.test>"), "{}", raw);
+        assert!(raw.contains("Reply-To: support@acme.test"), "{}", raw);
+    }
+
+    #[tokio::test]
+    async fn message_omits_display_name_and_reply_to_when_unset() {
+        let service = LettreEmailService::new(&EmailConfig::default()).unwrap();
+
+        let raw = formatted(&service);
+        assert!(raw.contains("From: noreply@axum-backend.com"), "{}", raw);
+        assert!(!raw.contains("Reply-To:"), "{}", raw);
+    }
+
+    #[tokio::test]
+    async fn audit_bcc_is_added_to_envelope_only_when_configured() {
+        let config =
+            EmailConfig { bcc: Some("audit@acme.test".to_string()), ..EmailConfig::default() };
+        let service = LettreEmailService::new(&config).unwrap();
+
+        assert_eq!(envelope_recipients(&service), vec!["jane@example.com", "audit@acme.test"]);
+        assert!(
+            !formatted(&service).contains("audit@acme.test"),
+            "Bcc must not leak into headers"
+        );
+
+        let service = LettreEmailService::new(&EmailConfig::default()).unwrap();
+        assert_eq!(envelope_recipients(&service), vec!["jane@example.com"]);
+        assert!(!formatted(&service).contains("Bcc:"));
+    }
+
+    #[tokio::test]
+    async fn message_is_rendered_in_recipient_locale() {
+        let service = LettreEmailService::new(&EmailConfig::default()).unwrap();
+        let recipient = Recipient { locale: Locale::Vi, ..recipient() };
+
+        let message = service
+            .build_message(&recipient, &EmailType::Confirmation("abc123".into(), None))
+            .unwrap();
+
+        // Raw header value, before RFC 2047 encoding of the non-ASCII subject
+        assert_eq!(message.headers().get_raw("Subject"), Some("Xác nhận đăng ký của bạn"));
+    }
+
+    #[tokio::test]
+    async fn configured_subjects_override_localized_defaults() {
+        let config = EmailConfig {
+            subjects: SubjectTemplates {
+                app_name: "Acme".to_string(),
+                confirmation: Some("{app_name}: confirm your account, {name}".to_string()),
+                confirmation_resent: Some("{app_name}: your new code".to_string()),
+                ..SubjectTemplates::default()
+            },
+            ..EmailConfig::default()
+        };
+        let service = LettreEmailService::new(&config).unwrap();
+        let recipient = Recipient { locale: Locale::Vi, ..recipient() };
+        let subject = |email_type: EmailType| {
+            let message = service.build_message(&recipient, &email_type).unwrap();
+            message.headers().get_raw("Subject").map(str::to_string)
+        };
+
+        assert_eq!(
+            subject(EmailType::Confirmation("abc123".into(), None)).as_deref(),
+            Some("Acme: confirm your account, Jane")
+        );
+        assert_eq!(
+            subject(EmailType::ConfirmationResent("abc123".into(), None)).as_deref(),
+            Some("Acme: your new code")
+        );
+        // Kinds without a template keep the localized subject
+        assert_eq!(
+            subject(EmailType::PasswordReset("abc123".into())).as_deref(),
+            Some("Đặt lại mật khẩu của bạn")
+        );
+    }
+
+    #[test]
+    fn templates_render_translated_copy() {
+        let html = crate::infrastructure::email::templates::ConfirmationTemplate {
+            name: "Lan".to_string(),
+            code: "abc123".to_string(),
+            link: None,
+            locale: Locale::Vi,
+        }
+        .render()
+        .unwrap();
+
+        assert!(html.contains("<html lang=\"vi\">"));
+        assert!(html.contains("Xin chào Lan,"));
+        assert!(html.contains("Xác nhận địa chỉ email của bạn"));
+        assert!(!html.contains("Confirm Your Email Address"));
+        assert!(!html.contains("href=\"https://"));
+    }
+
+    #[test]
+    fn confirmation_carries_the_verification_link_when_there_is_one() {
+        let html = crate::infrastructure::email::templates::ConfirmationTemplate {
+            name: "Lan".to_string(),
+            code: "abc123".to_string(),
+            link: Some("https://app.example.com/verify?token=a.1.b&x=1".to_string()),
+            locale: Locale::En,
+        }
+        .render()
+        .unwrap();
+
+        assert!(html.contains("abc123"));
+        assert!(html.contains("href=\"https://app.example.com/verify?token=a.1.b&#38;x=1\""));
+    }
+
+    #[test]
+    fn lockout_alert_shows_the_locking_ip_and_time() {
+        let html = crate::infrastructure::email::templates::AccountLockedTemplate {
+            name: "Lan".to_string(),
+            ip: "203.0.113.7".to_string(),
+            locked_at: "2026-10-14 08:30:00 UTC".to_string(),
+            locale: Locale::En,
+        }
+        .render()
+        .unwrap();
+
+        assert!(html.contains("Account Locked"));
+        assert!(html.contains("203.0.113.7"));
+        assert!(html.contains("2026-10-14 08:30:00 UTC"));
+    }
+
+    #[test]
+    fn inactivity_warning_shows_when_the_account_is_disabled() {
+        let html = crate::infrastructure::email::templates::InactivityWarningTemplate {
+            name: "Lan".to_string(),
+            disable_at: "2026-11-13 08:30 UTC".to_string(),
+            locale: Locale::Vi,
+        }
+        .render()
+        .unwrap();
+
+        assert!(html.contains("Vô hiệu hóa vào"));
+        assert!(html.contains("2026-11-13 08:30 UTC"));
+    }
 }
@@ -0,0 +1,95 @@
+use crate::application::services::email::{EmailService, EmailType, Recipient};
+use crate::shared::errors::AppError;
+use async_trait::async_trait;
+use std::{sync::Arc, time::Instant};
+
+/// Histogram of time spent in `EmailService::send`, labelled by `provider`
+/// and `type`
+pub const EMAIL_SEND_DURATION_SECONDS: &str = "email_send_duration_seconds";
+
+/// Buckets for `email_send_duration_seconds`; SMTP handshakes make sends far
+/// slower than typical requests, so the range reaches past a minute
+pub const EMAIL_SEND_DURATION_BUCKETS: &[f64] =
+    &[0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+
+/// Records how long every send through the wrapped service takes, whether
+/// it succeeds or fails
+pub struct MeteredEmailService {
+    inner: Arc<dyn EmailService>,
+}
+
+impl MeteredEmailService {
+    pub fn new(inner: Arc<dyn EmailService>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl EmailService for MeteredEmailService {
+    async fn send(&self, recipient: Recipient, email_type: EmailType) -> Result<(), AppError> {
+        let kind = email_type.kind();
+        let started = Instant::now();
+        let result = self.inner.send(recipient, email_type).await;
+        metrics::histogram!(
+            EMAIL_SEND_DURATION_SECONDS,
+            "provider" => self.inner.provider(),
+            "type" => kind,
+        )
+        .record(started.elapsed().as_secs_f64());
+        result
+    }
+
+    fn provider(&self) -> &'static str {
+        self.inner.provider()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{infrastructure::email::noop_service::NoOpEmailService, shared::i18n::Locale};
+    use axum_prometheus::metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
+
+    #[test]
+    fn records_send_duration_by_provider_and_type() {
+        let recorder = PrometheusBuilder::new()
+            .set_buckets_for_metric(
+                Matcher::Full(EMAIL_SEND_DURATION_SECONDS.to_string()),
+                EMAIL_SEND_DURATION_BUCKETS,
+            )
+            .unwrap()
+            .build_recorder();
+        let handle = recorder.handle();
+        let service = MeteredEmailService::new(Arc::new(NoOpEmailService::new()));
+        let recipient = Recipient {
+            email: "jane@example.com".to_string(),
+            name: "Jane".to_string(),
+            locale: Locale::default(),
+        };
+
+        metrics::with_local_recorder(&recorder, || {
+            futures::executor::block_on(
+                service.send(recipient, EmailType::Confirmation("ABCD1234".to_string(), None)),
+            )
+        })
+        .unwrap();
+
+        let rendered = handle.render();
+        assert!(
+            rendered.contains("# TYPE email_send_duration_seconds histogram"),
+            "{}",
+            rendered
+        );
+        assert!(
+            rendered.contains(
+                r#"email_send_duration_seconds_count{provider="noop",type="confirmation"} 1"#
+            ),
+            "{}",
+            rendered
+        );
+        // The no-op provider returns at once, well inside the first bucket
+        assert!(rendered.contains(
+            r#"email_send_duration_seconds_bucket{provider="noop",type="confirmation",le="0.01"} 1"#
+        ));
+    }
+}
@@ -1,3 +1,4 @@
 pub mod lettre_service;
+pub mod metered;
 pub mod noop_service;
 pub mod templates;
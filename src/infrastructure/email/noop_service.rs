@@ -18,4 +18,8 @@ impl EmailService for NoOpEmailService {
         info!("(NoOp) Sending {:?} to {} <{}>", email_type, recipient.name, recipient.email);
         Ok(())
     }
+
+    fn provider(&self) -> &'static str {
+        "noop"
+    }
 }
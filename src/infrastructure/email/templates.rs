@@ -1,9 +1,11 @@
+use crate::shared::i18n::{self, Locale};
 use askama::Template;
 
 #[derive(Template)]
 #[template(path = "welcome.html")]
 pub struct WelcomeTemplate {
     pub name: String,
+    pub locale: Locale,
 }
 
 #[derive(Template)]
@@ -11,6 +13,9 @@ pub struct WelcomeTemplate {
 pub struct ConfirmationTemplate {
     pub name: String,
     pub code: String,
+    /// One-click alternative to typing the code
+    pub link: Option<String>,
+    pub locale: Locale,
 }
 
 #[derive(Template)]
@@ -18,4 +23,56 @@ pub struct ConfirmationTemplate {
 pub struct ForgotPasswordTemplate {
     pub name: String,
     pub code: String,
+    pub locale: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "account_locked.html")]
+pub struct AccountLockedTemplate {
+    pub name: String,
+    /// Address the locking attempt came from
+    pub ip: String,
+    /// When the account was locked, already formatted
+    pub locked_at: String,
+    pub locale: Locale,
+}
+
+#[derive(Template)]
+#[template(path = "inactivity_warning.html")]
+pub struct InactivityWarningTemplate {
+    pub name: String,
+    /// When the account will be disabled, already formatted
+    pub disable_at: String,
+    pub locale: Locale,
+}
+
+// Templates look up their copy through `self.t("key")`
+impl WelcomeTemplate {
+    pub fn t(&self, key: &'static str) -> &'static str {
+        i18n::t(self.locale, key)
+    }
+}
+
+impl ConfirmationTemplate {
+    pub fn t(&self, key: &'static str) -> &'static str {
+        i18n::t(self.locale, key)
+    }
+}
+
+impl ForgotPasswordTemplate {
+    pub fn t(&self, key: &'static str) -> &'static str {
+        i18n::t(self.locale, key)
+    }
+}
+
+impl AccountLockedTemplate {
+    pub fn t(&self, key: &'static str) -> &'static str {
+        i18n::t(self.locale, key)
+    }
+}
+
+impl InactivityWarningTemplate {
+    pub fn t(&self, key: &'static str) -> &'static str {
+        i18n::t(self.locale, key)
+    }
 }
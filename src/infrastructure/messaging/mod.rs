@@ -0,0 +1,12 @@
+// Message broker integrations
+#[cfg(feature = "nats")]
+pub mod nats;
+pub mod pg_notify;
+pub mod publisher;
+
+#[cfg(feature = "nats")]
+pub use nats::{ping_nats, NatsClient, NatsProbeError};
+pub use pg_notify::PgNotifyEventPublisher;
+#[cfg(feature = "nats")]
+pub use publisher::NatsEventPublisher;
+pub use publisher::NoOpEventPublisher;
@@ -0,0 +1,56 @@
+use crate::{
+    application::services::events::EventPublisher, infrastructure::database::DbPool,
+    shared::errors::AppError,
+};
+use async_trait::async_trait;
+use diesel::sql_types::Text;
+use diesel_async::RunQueryDsl;
+
+/// Largest payload Postgres accepts in a notification, in bytes
+pub const MAX_NOTIFY_PAYLOAD: usize = 7999;
+
+/// Publishes events as Postgres notifications on the application database.
+///
+/// Each subject is its own channel, so a consumer runs `LISTEN "<subject>"`
+/// (quoted: subjects contain dots). Notifications reach only sessions
+/// listening at the time and are not stored, matching core NATS.
+#[derive(Clone)]
+pub struct PgNotifyEventPublisher {
+    pool: DbPool,
+}
+
+impl PgNotifyEventPublisher {
+    pub fn new(pool: DbPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl EventPublisher for PgNotifyEventPublisher {
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), AppError> {
+        if payload.len() > MAX_NOTIFY_PAYLOAD {
+            return Err(AppError::Internal(anyhow::anyhow!(
+                "Event on {} is {} bytes, over the {} byte notification limit",
+                subject,
+                payload.len(),
+                MAX_NOTIFY_PAYLOAD
+            )));
+        }
+        let payload = String::from_utf8(payload).map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Event on {} is not UTF-8: {}", subject, e))
+        })?;
+
+        let mut conn = self.pool.get().await.map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to publish {}: {}", subject, e))
+        })?;
+        diesel::sql_query("SELECT pg_notify($1, $2)")
+            .bind::<Text, _>(subject)
+            .bind::<Text, _>(payload)
+            .execute(&mut conn)
+            .await
+            .map_err(|e| {
+                AppError::Internal(anyhow::anyhow!("Failed to publish {}: {}", subject, e))
+            })?;
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+#[cfg(feature = "nats")]
+use super::nats::NatsClient;
+use crate::{
+    application::services::events::{EventPublisher, OutboundEvent},
+    shared::errors::AppError,
+};
+use async_trait::async_trait;
+#[cfg(feature = "nats")]
+use std::{sync::Arc, time::Duration};
+use tracing::info;
+
+/// Publishes events to NATS over one connection shared by all clones
+#[cfg(feature = "nats")]
+#[derive(Clone)]
+pub struct NatsEventPublisher {
+    client: Arc<NatsClient>,
+}
+
+#[cfg(feature = "nats")]
+impl NatsEventPublisher {
+    pub fn new(url: impl Into<String>, timeout: Duration) -> Self {
+        Self { client: Arc::new(NatsClient::new(url, timeout)) }
+    }
+}
+
+#[cfg(feature = "nats")]
+#[async_trait]
+impl EventPublisher for NatsEventPublisher {
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), AppError> {
+        self.client.publish(subject, &payload).await.map_err(|e| {
+            AppError::Internal(anyhow::anyhow!("Failed to publish {}: {}", subject, e))
+        })
+    }
+
+    /// One acknowledgement for the whole batch
+    async fn publish_batch(&self, events: Vec<OutboundEvent>) -> Result<(), AppError> {
+        let messages: Vec<_> = events.iter().map(|e| (e.subject, e.payload.as_slice())).collect();
+        self.client.publish_batch(&messages).await.map_err(|e| {
+            AppError::Internal(anyhow::anyhow!(
+                "Failed to publish batch of {} events: {}",
+                events.len(),
+                e
+            ))
+        })
+    }
+}
+
+/// Used when no broker is configured, or the build has no NATS support;
+/// events are only logged
+#[derive(Clone, Default)]
+pub struct NoOpEventPublisher;
+
+impl NoOpEventPublisher {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[async_trait]
+impl EventPublisher for NoOpEventPublisher {
+    async fn publish(&self, subject: &str, payload: Vec<u8>) -> Result<(), AppError> {
+        info!("(NoOp) Publishing {} ({} bytes)", subject, payload.len());
+        Ok(())
+    }
+
+    async fn publish_batch(&self, events: Vec<OutboundEvent>) -> Result<(), AppError> {
+        info!("(NoOp) Publishing batch of {} events", events.len());
+        Ok(())
+    }
+}
@@ -1,8 +1,11 @@
+pub mod avatar;
 pub mod cache;
 pub mod database;
 pub mod email;
 pub mod external_apis;
+pub mod messaging;
 pub mod monitoring;
+pub mod sms;
 
 // Re-export commonly used items
 pub use database::repositories::{AuthRepositoryImpl, UserRepositoryImpl};
@@ -1,7 +1,17 @@
+use crate::infrastructure::email::metered::{
+    EMAIL_SEND_DURATION_BUCKETS, EMAIL_SEND_DURATION_SECONDS,
+};
+use crate::shared::tasks::TaskRegistry;
+use axum_prometheus::{
+    metrics_exporter_prometheus::{BuildError, Matcher, PrometheusBuilder, PrometheusHandle},
+    AXUM_HTTP_REQUESTS_DURATION_SECONDS,
+};
 use serde::Serialize;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::Duration;
 use sysinfo::System;
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
 
 #[derive(Clone)]
 pub struct SystemMonitor {
@@ -44,3 +54,100 @@ impl SystemMonitor {
         SystemMetrics { cpu_usage, total_memory, used_memory, uptime }
     }
 }
+
+/// The installed recorder, the buckets it was built with, and whether the
+/// task keeping it drained has stopped
+struct InstalledRecorder {
+    handle: PrometheusHandle,
+    duration_buckets: Vec<f64>,
+    upkeep: CancellationToken,
+}
+
+static PROMETHEUS_RECORDER: StdMutex<Option<InstalledRecorder>> = StdMutex::new(None);
+
+/// Install the process-wide Prometheus recorder with the given request
+/// duration buckets and return a handle for rendering `/metrics`. Its upkeep
+/// task runs under `tasks`.
+///
+/// `metrics` allows a single global recorder, so only the first call installs
+/// one; later calls (e.g. several routers in one test binary) reuse it and
+/// its buckets, warning if they asked for different ones. A later call also
+/// restarts the upkeep task under its own `tasks` if the registry it ran
+/// under has shut down.
+pub fn install_prometheus_recorder(
+    duration_buckets: &[f64],
+    tasks: &TaskRegistry,
+) -> Result<PrometheusHandle, BuildError> {
+    let mut installed = PROMETHEUS_RECORDER.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(recorder) = installed.as_mut() {
+        if recorder.duration_buckets != duration_buckets {
+            tracing::warn!(
+                "Prometheus recorder already installed with buckets {:?}; ignoring {:?}",
+                recorder.duration_buckets,
+                duration_buckets
+            );
+        }
+        if recorder.upkeep.is_cancelled() {
+            recorder.upkeep = spawn_upkeep(recorder.handle.clone(), tasks);
+        }
+        return Ok(recorder.handle.clone());
+    }
+
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Full(AXUM_HTTP_REQUESTS_DURATION_SECONDS.to_string()),
+            duration_buckets,
+        )?
+        .set_buckets_for_metric(
+            Matcher::Full(EMAIL_SEND_DURATION_SECONDS.to_string()),
+            EMAIL_SEND_DURATION_BUCKETS,
+        )?
+        .install_recorder()?;
+
+    *installed = Some(InstalledRecorder {
+        handle: handle.clone(),
+        duration_buckets: duration_buckets.to_vec(),
+        upkeep: spawn_upkeep(handle.clone(), tasks),
+    });
+    Ok(handle)
+}
+
+/// Drain buffered histogram samples between scrapes until cancelled.
+/// Returns a token cancelled once the task has stopped.
+fn spawn_upkeep(handle: PrometheusHandle, tasks: &TaskRegistry) -> CancellationToken {
+    let stopped = CancellationToken::new();
+    let guard = stopped.clone().drop_guard();
+    tasks.spawn("prometheus_upkeep", move |token| async move {
+        let _guard = guard;
+        loop {
+            tokio::select! {
+                _ = token.cancelled() => break,
+                _ = tokio::time::sleep(Duration::from_secs(5)) => handle.run_upkeep(),
+            }
+        }
+    });
+    stopped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn upkeep_restarts_under_a_new_registry_after_the_first_shuts_down() {
+        let buckets = [0.1, 1.0];
+        let first = TaskRegistry::new();
+        install_prometheus_recorder(&buckets, &first).unwrap();
+        assert_eq!(first.len(), 1);
+
+        // A live upkeep task is reused, whatever buckets are asked for
+        let second = TaskRegistry::new();
+        install_prometheus_recorder(&[5.0], &second).unwrap();
+        assert!(second.is_empty());
+
+        assert!(first.shutdown(Duration::from_secs(1)).await.is_empty());
+        install_prometheus_recorder(&buckets, &second).unwrap();
+        assert_eq!(second.len(), 1);
+        second.shutdown(Duration::from_secs(1)).await;
+    }
+}
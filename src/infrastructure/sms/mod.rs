@@ -0,0 +1,3 @@
+pub mod twilio;
+
+pub use twilio::TwilioSmsSender;
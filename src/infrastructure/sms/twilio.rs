@@ -0,0 +1,144 @@
+use crate::{
+    application::services::sms::SmsSender, config::TwilioConfig, shared::errors::AppError,
+};
+use async_trait::async_trait;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// Longest a send may take, connecting included, so an unresponsive Twilio
+/// fails the request instead of holding it open
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Sends text messages through Twilio's Messages API
+#[derive(Clone)]
+pub struct TwilioSmsSender {
+    client: reqwest::Client,
+    messages_url: String,
+    account_sid: String,
+    auth_token: String,
+    from_number: String,
+}
+
+impl TwilioSmsSender {
+    pub fn new(config: &TwilioConfig) -> Self {
+        // SAFETY: Building only fails if the TLS backend cannot initialise,
+        // which `reqwest::Client::new` would panic on just the same
+        #[allow(clippy::expect_used)]
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .expect("Failed to initialise the HTTP client for Twilio");
+        Self {
+            client,
+            messages_url: format!(
+                "{}/2010-04-01/Accounts/{}/Messages.json",
+                config.api_base.trim_end_matches('/'),
+                config.account_sid
+            ),
+            account_sid: config.account_sid.clone(),
+            auth_token: config.auth_token.clone(),
+            from_number: config.from_number.clone(),
+        }
+    }
+}
+
+#[async_trait]
+impl SmsSender for TwilioSmsSender {
+    async fn send(&self, to: &str, body: &str) -> Result<(), AppError> {
+        let response = self
+            .client
+            .post(&self.messages_url)
+            .basic_auth(&self.account_sid, Some(&self.auth_token))
+            .form(&[("To", to), ("From", self.from_number.as_str()), ("Body", body)])
+            .send()
+            .await
+            .map_err(|e| AppError::Internal(anyhow::anyhow!("Failed to send SMS: {}", e)))?;
+
+        let status = response.status();
+        if status.is_success() {
+            info!("SMS sent via Twilio");
+            return Ok(());
+        }
+        // Twilio explains rejections (bad number, unverified sender) in the body
+        let detail = response.text().await.unwrap_or_default();
+        error!("Twilio rejected SMS: {} {}", status, detail);
+        Err(AppError::Internal(anyhow::anyhow!("Twilio responded {}", status)))
+    }
+
+    fn provider(&self) -> &'static str {
+        "twilio"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        extract::State,
+        http::{HeaderMap, StatusCode, Uri},
+        routing::post,
+        Form, Router,
+    };
+    use std::collections::HashMap;
+    use tokio::sync::mpsc;
+
+    type Captured = (String, Option<String>, HashMap<String, String>);
+
+    /// Stand-in for Twilio that records each request and answers `status`
+    async fn fake_twilio(status: StatusCode) -> (String, mpsc::UnboundedReceiver<Captured>) {
+        let (tx, rx) = mpsc::unbounded_channel::<Captured>();
+        let app = Router::new()
+            .route(
+                "/*path",
+                post(
+                    move |State(tx): State<mpsc::UnboundedSender<Captured>>,
+                          uri: Uri,
+                          headers: HeaderMap,
+                          Form(form): Form<HashMap<String, String>>| async move {
+                        let auth = headers
+                            .get("authorization")
+                            .and_then(|v| v.to_str().ok())
+                            .map(str::to_string);
+                        let _ = tx.send((uri.path().to_string(), auth, form));
+                        status
+                    },
+                ),
+            )
+            .with_state(tx);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (format!("http://{}/", addr), rx)
+    }
+
+    fn sender(api_base: String) -> TwilioSmsSender {
+        TwilioSmsSender::new(&TwilioConfig {
+            account_sid: "AC123".to_string(),
+            auth_token: "secret".to_string(),
+            from_number: "+15005550006".to_string(),
+            api_base,
+        })
+    }
+
+    #[tokio::test]
+    async fn posts_the_message_to_the_accounts_messages_resource() {
+        let (api_base, mut requests) = fake_twilio(StatusCode::CREATED).await;
+
+        sender(api_base).send("+84901234567", "Your code is: 1234").await.unwrap();
+
+        let (path, auth, form) = requests.recv().await.unwrap();
+        assert_eq!(path, "/2010-04-01/Accounts/AC123/Messages.json");
+        // base64("AC123:secret")
+        assert_eq!(auth.as_deref(), Some("Basic QUMxMjM6c2VjcmV0"));
+        assert_eq!(form["To"], "+84901234567");
+        assert_eq!(form["From"], "+15005550006");
+        assert_eq!(form["Body"], "Your code is: 1234");
+    }
+
+    #[tokio::test]
+    async fn rejected_messages_are_errors() {
+        let (api_base, _requests) = fake_twilio(StatusCode::BAD_REQUEST).await;
+
+        assert!(sender(api_base).send("+84901234567", "code").await.is_err());
+    }
+}
@@ -1,21 +1,35 @@
 use axum_backend::{
+    application::services::{email::EmailService, AuditRetention, InactiveAccounts},
     config::AppConfig,
-    infrastructure::database::{connection::create_pool, connection::run_migrations},
-    presentation::routes::create_router,
-    shared::init_telemetry,
+    infrastructure::database::{
+        connection::create_pool,
+        connection::run_migrations,
+        repositories::{AuditRepositoryImpl, AuthRepositoryImpl},
+    },
+    infrastructure::email::{lettre_service::LettreEmailService, noop_service::NoOpEmailService},
+    presentation::{
+        routes::create_router,
+        server::{bind_all, serve_all_with_shutdown, shutdown_signal},
+    },
+    shared::{init_telemetry, telemetry::set_fingerprint_key, TaskRegistry},
 };
-use axum_prometheus::PrometheusMetricLayer;
-use std::net::SocketAddr;
 
-#[tokio::main]
-async fn main() -> Result<(), Box<dyn std::error::Error>> {
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize telemetry (logging)
     init_telemetry();
 
     // Load configuration
     let config = AppConfig::from_env()?;
     tracing::info!("Configuration loaded successfully");
+    set_fingerprint_key(&config.jwt_secret);
 
+    // Build the runtime by hand so its thread counts come from configuration
+    let runtime = config.runtime.builder().build()?;
+    tracing::info!("Runtime started with {} worker thread(s)", runtime.metrics().num_workers());
+    runtime.block_on(serve(config))
+}
+
+async fn serve(config: AppConfig) -> Result<(), Box<dyn std::error::Error>> {
     // Create database connection pool
     let pool = create_pool(&config.db_config, &config.database_url).await?;
     tracing::info!("Database connection pool created");
@@ -24,41 +38,60 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     run_migrations(&config.database_url).await?;
     tracing::info!("Database migrations completed");
 
-    // Create monitoring layer
-    let (prometheus_layer, metric_handle) = PrometheusMetricLayer::pair();
+    // Create Email Service; with FEATURE_EMAIL off messages are only logged
+    let email_service: std::sync::Arc<dyn EmailService> = if config.features.email {
+        std::sync::Arc::new(LettreEmailService::new(&config.email_config)?)
+    } else {
+        tracing::info!("Email delivery disabled (FEATURE_EMAIL=false)");
+        std::sync::Arc::new(NoOpEmailService::new())
+    };
 
-    // Create Email Service
-    let email_service = std::sync::Arc::new(
-        axum_backend::infrastructure::email::lettre_service::LettreEmailService::new()
-            .expect("Failed to create email service"),
-    );
+    // Background workers register here so shutdown can stop them
+    let tasks = std::sync::Arc::new(TaskRegistry::new());
+    if let Some(retention) = config.audit_retention.retention {
+        let purge = AuditRetention::new(
+            std::sync::Arc::new(AuditRepositoryImpl::new(pool.clone())),
+            retention,
+            config.audit_retention.batch_size,
+        );
+        let interval = config.audit_retention.purge_interval;
+        tasks.spawn("audit_log_purge", move |token| purge.run(interval, token));
+    }
+    if let Some(disable_after) = config.inactivity.disable_after {
+        let mut inactive = InactiveAccounts::new(
+            std::sync::Arc::new(AuthRepositoryImpl::new(pool.clone())),
+            std::sync::Arc::new(AuditRepositoryImpl::new(pool.clone())),
+            disable_after,
+            config.inactivity.batch_size,
+        );
+        if let Some(warn_before) = config.inactivity.warn_before {
+            inactive = inactive.with_warning(email_service.clone(), warn_before);
+        }
+        let interval = config.inactivity.check_interval;
+        tasks.spawn("inactive_account_disable", move |token| inactive.run(interval, token));
+    }
 
     // Create application router
-    let app = create_router(
-        pool,
-        config.jwt_secret.clone(),
-        config.jwt_access_expiry,
-        config.jwt_refresh_expiry,
-        config.jwt_issuer.clone(),
-        config.jwt_audience.clone(),
-        config.confirm_code_expiry,
-        config.cookie_secure,
-        config.rate_limit_per_second,
-        config.rate_limit_burst_size,
-        prometheus_layer,
-        metric_handle,
-        email_service,
-    );
+    let app = create_router(pool, &config, email_service, tasks.clone());
 
-    // Parse server address
-    let addr: SocketAddr = config.server_address().parse()?;
-    tracing::info!("Starting server on {}", addr);
+    // Start one listener per address
+    let addrs = config.listen_addresses()?;
+    let listeners = bind_all(&addrs)?;
+    for listener in &listeners {
+        tracing::info!("Server listening on {}", listener.local_addr()?);
+    }
 
-    // Start server
-    let listener = tokio::net::TcpListener::bind(addr).await?;
-    tracing::info!("Server listening on {}", addr);
+    serve_all_with_shutdown(
+        listeners,
+        app,
+        config.http_keep_alive_timeout,
+        config.shutdown_timeout,
+        shutdown_signal(),
+    )
+    .await;
 
-    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
+    tracing::info!("Stopping {} background task(s)", tasks.len());
+    tasks.shutdown(config.shutdown_timeout).await;
 
     Ok(())
 }
@@ -1,23 +1,45 @@
 use crate::{
     application::{
+        commands::{
+            PhoneVerificationError, RefreshError, RefreshTokenCommand, RevokeSessionCommand,
+            SendPhoneCodeCommand, SessionError, VerifyPhoneCommand,
+        },
         dto::auth::{
-            AuthResponse, ForgotPasswordRequest, LoginRequest, LogoutRequest, RegisterRequest,
-            RegisterResponse, SetPasswordRequest, VerifyEmailRequest,
+            AuthResponse, ForgotPasswordRequest, LoginRequest, LogoutRequest, RefreshTokenRequest,
+            RegisterRequest, SendPhoneCodeRequest, SessionDto, SetPasswordRequest, TokenDelivery,
+            VerifyEmailRequest, VerifyEmailResponse, VerifyPhoneRequest,
         },
+        dto::UserResponseDto,
+        queries::ListSessionsQuery,
         use_cases::{
-            ForgotPasswordUseCase, LoginUseCase, LogoutUseCase, RegisterUseCase,
-            SetPasswordUseCase, VerifyEmailUseCase,
+            auth::{
+                forgot_password::ForgotPasswordError, register::RegisterError,
+                resend_code::ResendConfirmCodeError, set_password::SetPasswordError,
+            },
+            ForgotPasswordUseCase, GetUserUseCase, LoginError, LoginUseCase, LogoutError,
+            LogoutUseCase, RegisterUseCase, SetPasswordUseCase, VerifyEmailUseCase,
+        },
+    },
+    domain::{
+        repositories::{user_repository::UserRepository, AuthRepository},
+        value_objects::UserRole,
+    },
+    infrastructure::database::transaction::RequestTransaction,
+    presentation::{
+        middleware::{
+            auth::{bearer_role, AuthState},
+            ClientIp, JsonBody, OptionalJsonBody, Tx,
         },
+        responses::{user_location, ApiResponse},
     },
-    domain::repositories::AuthRepository,
-    presentation::responses::ApiResponse,
-    shared::utils::jwt::Claims,
+    shared::{errors::log_internal_error, i18n::Locale, utils::jwt::Claims, AppError},
 };
+use askama::Template;
 use axum::{
-    extract::State,
-    http::StatusCode,
-    response::{IntoResponse, Response},
-    Extension, Json,
+    extract::{rejection::FormRejection, Path, Query, State},
+    http::{HeaderMap, StatusCode},
+    response::{Html, IntoResponse, Response},
+    Extension, Form, Json,
 };
 use axum_extra::extract::cookie::{Cookie, SameSite};
 use axum_extra::extract::CookieJar;
@@ -25,10 +47,24 @@ use std::sync::Arc;
 use time::Duration;
 use validator::Validate; // fast dependency check: do I have time crate? axum-extra uses time.
 
+/// Header choosing where login and refresh return tokens: `cookie`, `body`
+/// or `both`
+pub const TOKEN_DELIVERY_HEADER: &str = "x-token-delivery";
+
 /// Runtime cookie security configuration driven by environment.
 #[derive(Debug, Clone)]
 pub struct CookieConfig {
     pub secure: bool,
+    /// Used when a request does not send `X-Token-Delivery`
+    pub token_delivery: TokenDelivery,
+}
+
+/// Who may register; with registration closed only admins and holders of
+/// an invitation can
+#[derive(Clone)]
+pub struct RegistrationGate {
+    pub open: bool,
+    pub auth: AuthState,
 }
 
 #[derive(Debug)]
@@ -36,12 +72,29 @@ pub enum AuthError {
     ValidationError(String),
     RegisterError(String),
     LoginError(String),
+    Conflict(String),
+    /// Rendered as 409 with code `USER_ALREADY_EXISTS`; the message never
+    /// echoes the address
+    UserAlreadyExists,
+    /// Rendered as 403 with code `REGISTRATION_CLOSED`
+    RegistrationClosed,
     LogoutError(String),
     Unauthorized(String),
+    NotFound(String),
     VerifyEmailError(String),
     SetPasswordError(String),
     ForgotPasswordError(String),
     ResendCodeError(String),
+    PhoneVerificationError(String),
+    /// A dependency the request needs is not configured or reachable
+    ServiceUnavailable(String),
+    /// Rendered as a generic 500; the cause is only logged
+    Internal(anyhow::Error),
+    /// Rendered as 429 with `Retry-After`
+    TooManyRequests {
+        message: String,
+        retry_after_secs: u64,
+    },
 }
 
 impl IntoResponse for AuthError {
@@ -50,12 +103,39 @@ impl IntoResponse for AuthError {
             AuthError::ValidationError(msg) => (StatusCode::BAD_REQUEST, msg),
             AuthError::RegisterError(msg) => (StatusCode::BAD_REQUEST, msg),
             AuthError::LoginError(msg) => (StatusCode::UNAUTHORIZED, msg),
+            AuthError::Conflict(msg) => (StatusCode::CONFLICT, msg),
+            AuthError::UserAlreadyExists => {
+                let body = Json(serde_json::json!({
+                    "success": false,
+                    "error": RegisterError::EmailAlreadyExists.to_string(),
+                    "code": "USER_ALREADY_EXISTS",
+                }));
+                return (StatusCode::CONFLICT, body).into_response();
+            },
+            AuthError::RegistrationClosed => {
+                let body = Json(serde_json::json!({
+                    "success": false,
+                    "error": "Registration is closed",
+                    "code": "REGISTRATION_CLOSED",
+                }));
+                return (StatusCode::FORBIDDEN, body).into_response();
+            },
             AuthError::LogoutError(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg),
             AuthError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg),
+            AuthError::NotFound(msg) => (StatusCode::NOT_FOUND, msg),
             AuthError::VerifyEmailError(msg) => (StatusCode::BAD_REQUEST, msg),
             AuthError::SetPasswordError(msg) => (StatusCode::BAD_REQUEST, msg),
             AuthError::ForgotPasswordError(msg) => (StatusCode::BAD_REQUEST, msg),
             AuthError::ResendCodeError(msg) => (StatusCode::BAD_REQUEST, msg),
+            AuthError::PhoneVerificationError(msg) => (StatusCode::BAD_REQUEST, msg),
+            AuthError::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg),
+            AuthError::Internal(e) => {
+                log_internal_error(&e);
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error".to_string())
+            },
+            AuthError::TooManyRequests { message, retry_after_secs } => {
+                return AppError::TooManyRequests { message, retry_after_secs }.into_response();
+            },
         };
 
         let body = Json(serde_json::json!({
@@ -73,25 +153,45 @@ impl IntoResponse for AuthError {
     path = "/api/auth/register",
     request_body = RegisterRequest,
     responses(
-        (status = 201, description = "User registered successfully", body = RegisterResponseWrapper),
-        (status = 400, description = "Validation error or registration failed", body = ErrorResponseWrapper)
+        (status = 201, description = "User registered successfully", body = RegisterResponseWrapper,
+            headers(("Location" = String, description = "Path of the new user"))),
+        (status = 400, description = "Validation error, unusable invitation or registration failed", body = ErrorResponseWrapper),
+        (status = 403, description = "Registration is closed and the caller is neither an admin nor invited", body = ErrorResponseWrapper),
+        (status = 409, description = "An account with this email already exists, or is being registered", body = ErrorResponseWrapper)
     ),
     tag = "auth"
 )]
 pub async fn register<R: AuthRepository>(
     State(use_case): State<Arc<RegisterUseCase<R>>>,
-    Json(payload): Json<RegisterRequest>,
-) -> Result<(StatusCode, Json<ApiResponse<RegisterResponse>>), AuthError> {
+    Extension(gate): Extension<Arc<RegistrationGate>>,
+    headers: HeaderMap,
+    locale: Locale,
+    JsonBody(payload): JsonBody<RegisterRequest>,
+) -> Result<impl IntoResponse, AuthError> {
+    let invited = payload.invite_token.is_some();
+    if !gate.open && !invited && bearer_role(&gate.auth, &headers).await != Some(UserRole::Admin) {
+        return Err(AuthError::RegistrationClosed);
+    }
+
     // Validate input
     payload.validate().map_err(|e| AuthError::ValidationError(e.to_string()))?;
 
     // Execute use case
     let response = use_case
-        .execute(payload.email, payload.name)
+        .execute(payload.email, payload.name, locale, payload.invite_token)
         .await
-        .map_err(|e| AuthError::RegisterError(e.to_string()))?;
-
-    Ok((StatusCode::CREATED, Json(ApiResponse::success(response))))
+        .map_err(|e| match e {
+            RegisterError::EmailAlreadyExists => AuthError::UserAlreadyExists,
+            RegisterError::RegistrationInProgress => AuthError::Conflict(e.to_string()),
+            RegisterError::InvalidName(msg) => AuthError::ValidationError(msg),
+            _ => AuthError::RegisterError(e.to_string()),
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        user_location(&response.user.id),
+        Json(ApiResponse::success(response)),
+    ))
 }
 
 /// Login user
@@ -101,26 +201,79 @@ pub async fn register<R: AuthRepository>(
     request_body = LoginRequest,
     responses(
         (status = 200, description = "User logged in successfully", body = AuthResponseWrapper),
-        (status = 401, description = "Invalid credentials", body = ErrorResponseWrapper)
+        (status = 401, description = "Invalid credentials", body = ErrorResponseWrapper),
+        (status = 429, description = "Account locked after too many failed logins", body = ErrorResponseWrapper)
+    ),
+    params(
+        ("X-Token-Delivery" = Option<String>, Header, description = "cookie, body or both; defaults to TOKEN_DELIVERY")
     ),
     tag = "auth"
 )]
 pub async fn login<R: AuthRepository>(
     State(use_case): State<Arc<LoginUseCase<R>>>,
     Extension(cookie_config): Extension<Arc<CookieConfig>>,
+    ClientIp(client_ip): ClientIp,
+    headers: HeaderMap,
     jar: CookieJar,
-    Json(payload): Json<LoginRequest>,
+    JsonBody(payload): JsonBody<LoginRequest>,
 ) -> Result<(CookieJar, Json<ApiResponse<AuthResponse>>), AuthError> {
     // Validate input
     payload.validate().map_err(|e| AuthError::ValidationError(e.to_string()))?;
+    let delivery = token_delivery(&headers, &cookie_config)?;
 
     // Execute use case
-    let response = use_case
-        .execute(payload.email, payload.password, payload.code)
-        .await
-        .map_err(|e| AuthError::LoginError(e.to_string()))?;
+    let result = use_case.execute(payload.email, payload.password, payload.code, client_ip).await;
+    let response = result.map_err(|e| {
+        tracing::warn!(%client_ip, "Login failed: {}", e);
+        match e {
+            LoginError::SessionLimitReached => AuthError::Conflict(e.to_string()),
+            LoginError::AccountLocked { retry_after_secs } => {
+                AuthError::TooManyRequests { message: e.to_string(), retry_after_secs }
+            },
+            _ => AuthError::LoginError(e.to_string()),
+        }
+    })?;
+
+    Ok(deliver_tokens(jar, delivery, response, &cookie_config))
+}
+
+/// Delivery the request asked for, else the configured default
+fn token_delivery(headers: &HeaderMap, config: &CookieConfig) -> Result<TokenDelivery, AuthError> {
+    match headers.get(TOKEN_DELIVERY_HEADER) {
+        Some(value) => value.to_str().ok().and_then(TokenDelivery::parse).ok_or_else(|| {
+            AuthError::ValidationError("X-Token-Delivery must be cookie, body or both".to_string())
+        }),
+        None => Ok(config.token_delivery),
+    }
+}
+
+/// Return issued tokens as cookies, in the body, or both
+fn deliver_tokens(
+    jar: CookieJar,
+    delivery: TokenDelivery,
+    mut response: AuthResponse,
+    cookie_config: &CookieConfig,
+) -> (CookieJar, Json<ApiResponse<AuthResponse>>) {
+    let jar = match delivery {
+        TokenDelivery::Body => jar,
+        TokenDelivery::Cookie | TokenDelivery::Both => {
+            set_auth_cookies(jar, &response, cookie_config)
+        },
+    };
+    if delivery == TokenDelivery::Cookie {
+        // Empty tokens are left out of the JSON
+        response.access_token.clear();
+        response.refresh_token.clear();
+    }
+    (jar, Json(ApiResponse::success(response)))
+}
 
-    // Set HttpOnly cookies — secure flag driven by runtime config
+/// Set the HttpOnly token cookies — secure flag driven by runtime config
+fn set_auth_cookies(
+    jar: CookieJar,
+    response: &AuthResponse,
+    cookie_config: &CookieConfig,
+) -> CookieJar {
     let access_cookie = Cookie::build(("access_token", response.access_token.clone()))
         .http_only(true)
         .path("/")
@@ -137,18 +290,64 @@ pub async fn login<R: AuthRepository>(
         .max_age(Duration::days(7))
         .build();
 
-    let jar = jar.add(access_cookie).add(refresh_cookie);
-    Ok((jar, Json(ApiResponse::success(response))))
+    jar.add(access_cookie).add(refresh_cookie)
+}
+
+/// Exchange a refresh token for new tokens. Browser clients can send no body
+/// and rely on the `refresh_token` cookie; the new tokens are set as cookies
+/// too. The presented token stops working.
+#[utoipa::path(
+    post,
+    path = "/api/auth/refresh",
+    request_body(content = RefreshTokenRequest, description = "Optional when the refresh_token cookie is set"),
+    responses(
+        (status = 200, description = "Tokens refreshed", body = AuthResponseWrapper),
+        (status = 400, description = "No refresh token given", body = ErrorResponseWrapper),
+        (status = 401, description = "Refresh token invalid, expired or already used", body = ErrorResponseWrapper)
+    ),
+    params(
+        ("X-Token-Delivery" = Option<String>, Header, description = "cookie, body or both; defaults to TOKEN_DELIVERY")
+    ),
+    tag = "auth"
+)]
+pub async fn refresh<R: AuthRepository>(
+    State(command): State<Arc<RefreshTokenCommand<R>>>,
+    Extension(cookie_config): Extension<Arc<CookieConfig>>,
+    headers: HeaderMap,
+    jar: CookieJar,
+    payload: Option<JsonBody<RefreshTokenRequest>>,
+) -> Result<(CookieJar, Json<ApiResponse<AuthResponse>>), AuthError> {
+    let delivery = token_delivery(&headers, &cookie_config)?;
+    let from_body = payload.and_then(|JsonBody(p)| p.refresh_token).filter(|t| !t.is_empty());
+    let refresh_token = from_body
+        .or_else(|| jar.get("refresh_token").map(|c| c.value().to_string()))
+        .ok_or_else(|| AuthError::ValidationError("Refresh token is required".to_string()))?;
+
+    let response = command.execute(&refresh_token).await.map_err(|e| match e {
+        RefreshError::RepositoryError(_) | RefreshError::TokenCreationError(_) => {
+            AuthError::Internal(anyhow::anyhow!("Token refresh failed: {}", e))
+        },
+        _ => AuthError::Unauthorized(e.to_string()),
+    })?;
+
+    Ok(deliver_tokens(jar, delivery, response, &cookie_config))
 }
 
-/// Logout user (revoke refresh token)
+/// Logout user (revoke refresh token). Idempotent: with no body the
+/// `refresh_token` cookie is revoked if sent, and a token that is unknown or
+/// already revoked is not an error; the cookies are cleared either way. A
+/// body that is sent must be valid JSON, so a mistyped `logout_all` is
+/// refused instead of ending only the current session.
 #[utoipa::path(
     post,
     path = "/api/auth/logout",
-    request_body = LogoutRequest,
+    request_body(content = LogoutRequest, description = "Optional; defaults to ending the session in the refresh_token cookie"),
     responses(
         (status = 200, description = "Logged out successfully", body = StringResponseWrapper),
+        (status = 400, description = "Malformed JSON body", body = ErrorResponseWrapper),
         (status = 401, description = "Unauthorized", body = ErrorResponseWrapper),
+        (status = 415, description = "Body sent without a JSON content type", body = ErrorResponseWrapper),
+        (status = 422, description = "Body of the wrong shape", body = ErrorResponseWrapper),
         (status = 500, description = "Logout failed", body = ErrorResponseWrapper)
     ),
     tag = "auth",
@@ -160,19 +359,20 @@ pub async fn logout<R: AuthRepository>(
     State(use_case): State<Arc<LogoutUseCase<R>>>,
     jar: CookieJar,
     claims: Claims,
-    Json(payload): Json<LogoutRequest>,
+    OptionalJsonBody(payload): OptionalJsonBody<LogoutRequest>,
 ) -> Result<(CookieJar, Json<ApiResponse<String>>), AuthError> {
     let user_id = claims
         .sub
         .parse()
         .map_err(|_| AuthError::Unauthorized("Invalid user ID".to_string()))?;
+    let payload = payload.unwrap_or_default();
 
     // Determine refresh token from payload or cookie
-    let refresh_token = if let Some(rt) = &payload.refresh_token {
-        Some(rt.clone())
-    } else {
-        jar.get("refresh_token").map(|c| c.value().to_string())
-    };
+    let refresh_token = payload
+        .refresh_token
+        .filter(|t| !t.is_empty())
+        .or_else(|| jar.get("refresh_token").map(|c| c.value().to_string()))
+        .filter(|t| !t.is_empty());
 
     if payload.logout_all {
         // Logout from all devices
@@ -181,15 +381,11 @@ pub async fn logout<R: AuthRepository>(
             .await
             .map_err(|e| AuthError::LogoutError(e.to_string()))?;
     } else if let Some(token) = refresh_token {
-        // Logout from current device
-        use_case
-            .execute(&token)
-            .await
-            .map_err(|e| AuthError::LogoutError(e.to_string()))?;
-    } else {
-        return Err(AuthError::ValidationError(
-            "Either refresh_token or logout_all must be provided".to_string(),
-        ));
+        // Logout from current device; a session already ended stays ended
+        match use_case.execute(&token).await {
+            Ok(()) | Err(LogoutError::TokenNotFound) => {},
+            Err(e) => return Err(AuthError::LogoutError(e.to_string())),
+        }
     }
 
     // Clear cookies by setting expired cookies
@@ -210,33 +406,195 @@ pub async fn logout<R: AuthRepository>(
     Ok((jar, Json(ApiResponse::success("Logged out successfully".to_string()))))
 }
 
+/// List the caller's active sessions, oldest first
+#[utoipa::path(
+    get,
+    path = "/api/auth/sessions",
+    responses(
+        (status = 200, description = "Active sessions", body = SessionListResponseWrapper),
+        (status = 401, description = "Unauthorized", body = ErrorResponseWrapper)
+    ),
+    tag = "auth",
+    security(
+        ("jwt_token" = [])
+    )
+)]
+pub async fn list_sessions<R: AuthRepository>(
+    State(query): State<Arc<ListSessionsQuery<R>>>,
+    claims: Claims,
+) -> Result<Json<ApiResponse<Vec<SessionDto>>>, AuthError> {
+    let user_id = claims
+        .sub
+        .parse()
+        .map_err(|_| AuthError::Unauthorized("Invalid user ID".to_string()))?;
+
+    let sessions = query.execute(user_id).await.map_err(|e| AuthError::Internal(e.into()))?;
+
+    Ok(Json(ApiResponse::success(sessions)))
+}
+
+/// Revoke one of the caller's sessions. Sessions of other users are
+/// reported as not found.
+#[utoipa::path(
+    delete,
+    path = "/api/auth/sessions/{id}",
+    params(
+        ("id" = String, Path, description = "Session ID")
+    ),
+    responses(
+        (status = 200, description = "Session revoked", body = StringResponseWrapper),
+        (status = 401, description = "Unauthorized", body = ErrorResponseWrapper),
+        (status = 404, description = "No such session for this user", body = ErrorResponseWrapper)
+    ),
+    tag = "auth",
+    security(
+        ("jwt_token" = [])
+    )
+)]
+pub async fn revoke_session<R: AuthRepository>(
+    State(command): State<Arc<RevokeSessionCommand<R>>>,
+    claims: Claims,
+    Path(session_id): Path<String>,
+) -> Result<Json<ApiResponse<String>>, AuthError> {
+    let user_id = claims
+        .sub
+        .parse()
+        .map_err(|_| AuthError::Unauthorized("Invalid user ID".to_string()))?;
+    let not_found = || AuthError::NotFound(SessionError::NotFound.to_string());
+    let session_id = session_id.parse().map_err(|_| not_found())?;
+
+    command.execute(user_id, session_id).await.map_err(|e| match e {
+        SessionError::NotFound => not_found(),
+        SessionError::RepositoryError(_) => AuthError::Internal(e.into()),
+    })?;
+
+    Ok(Json(ApiResponse::success("Session revoked".to_string())))
+}
+
+/// Get the user the access token was issued to, or 404 if they have since
+/// been deleted. Routed under `token_middleware`, which checks only the token.
+#[utoipa::path(
+    get,
+    path = "/api/auth/me",
+    responses(
+        (status = 200, description = "Current user", body = UserResponseWrapper),
+        (status = 401, description = "Unauthorized", body = ErrorResponseWrapper),
+        (status = 404, description = "User no longer exists", body = ErrorResponseWrapper)
+    ),
+    tag = "auth",
+    security(
+        ("jwt_token" = [])
+    )
+)]
+pub async fn me<U: UserRepository>(
+    State(use_case): State<Arc<GetUserUseCase<U>>>,
+    claims: Claims,
+) -> Result<Json<ApiResponse<UserResponseDto>>, AppError> {
+    let user = use_case.execute(&claims.sub).await?;
+
+    Ok(Json(ApiResponse::success(UserResponseDto::from(user))))
+}
+
 /// Verify email
 #[utoipa::path(
     post,
     path = "/api/auth/verify",
     request_body = VerifyEmailRequest,
     responses(
-        (status = 200, description = "Email verified successfully", body = StringResponseWrapper),
+        (status = 200, description = "Email verified successfully", body = VerifyEmailResponseWrapper),
         (status = 400, description = "Verification failed", body = ErrorResponseWrapper)
     ),
     tag = "auth"
 )]
 pub async fn verify_email<R: AuthRepository>(
     State(use_case): State<Arc<VerifyEmailUseCase<R>>>,
-    Json(payload): Json<VerifyEmailRequest>,
-) -> Result<Json<ApiResponse<String>>, AuthError> {
+    JsonBody(payload): JsonBody<VerifyEmailRequest>,
+) -> Result<Json<ApiResponse<VerifyEmailResponse>>, AuthError> {
     // Validate input
     payload.validate().map_err(|e| AuthError::ValidationError(e.to_string()))?;
 
     // Execute use case
-    let message = use_case
+    let response = use_case
         .execute(payload.email, payload.code)
         .await
         .map_err(|e| AuthError::VerifyEmailError(e.to_string()))?;
 
-    Ok(Json(ApiResponse::success(message)))
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Token of a verification link, as its query string or the confirm page's form
+#[derive(Debug, serde::Deserialize, utoipa::IntoParams, utoipa::ToSchema)]
+pub struct VerifyLinkQuery {
+    /// Token from the confirmation email
+    pub token: String,
+}
+
+/// Page a verification link opens, whose button posts the token back
+#[derive(askama::Template)]
+#[template(path = "verify_link.html")]
+struct VerifyLinkPage {
+    token: String,
+    locale: Locale,
+}
+
+impl VerifyLinkPage {
+    fn t(&self, key: &'static str) -> &'static str {
+        crate::shared::i18n::t(self.locale, key)
+    }
+}
+
+/// Open the link in the confirmation email.
+///
+/// This only shows a page asking to confirm; mail scanners and browsers
+/// fetch links ahead of the user, so a GET must not verify the address.
+#[utoipa::path(
+    get,
+    path = "/api/auth/verify-link",
+    params(
+        VerifyLinkQuery
+    ),
+    responses(
+        (status = 200, description = "Page confirming the address with a POST", content_type = "text/html")
+    ),
+    tag = "auth"
+)]
+pub async fn verify_email_link_page(
+    locale: Locale,
+    Query(query): Query<VerifyLinkQuery>,
+) -> Result<Html<String>, AppError> {
+    let page = VerifyLinkPage { token: query.token, locale };
+    page.render().map(Html).map_err(|e| AppError::Internal(e.into()))
+}
+
+/// Verify email with the token of the link in the confirmation email
+#[utoipa::path(
+    post,
+    path = "/api/auth/verify-link",
+    request_body(content = VerifyLinkQuery, content_type = "application/x-www-form-urlencoded"),
+    responses(
+        (status = 200, description = "Email verified successfully", body = VerifyEmailResponseWrapper),
+        (status = 400, description = "Link invalid or expired", body = ErrorResponseWrapper)
+    ),
+    tag = "auth"
+)]
+pub async fn verify_email_link<R: AuthRepository>(
+    State(use_case): State<Arc<VerifyEmailUseCase<R>>>,
+    form: Result<Form<VerifyLinkQuery>, FormRejection>,
+) -> Result<Json<ApiResponse<VerifyEmailResponse>>, AuthError> {
+    let Form(form) = form.map_err(|e| AuthError::ValidationError(e.body_text()))?;
+    let response = use_case
+        .execute_link(&form.token)
+        .await
+        .map_err(|e| AuthError::VerifyEmailError(e.to_string()))?;
+
+    Ok(Json(ApiResponse::success(response)))
 }
 
+/// Builds the set-password use case on the request's transaction, so the
+/// new password and its history entry commit or roll back together
+pub type SetPasswordFactory<R> =
+    Arc<dyn Fn(RequestTransaction) -> SetPasswordUseCase<R> + Send + Sync>;
+
 /// Set password
 #[utoipa::path(
     post,
@@ -244,26 +602,124 @@ pub async fn verify_email<R: AuthRepository>(
     request_body = SetPasswordRequest,
     responses(
         (status = 200, description = "Password set successfully", body = StringResponseWrapper),
-        (status = 400, description = "Failed to set password", body = ErrorResponseWrapper)
+        (status = 400, description = "Failed to set password", body = ErrorResponseWrapper),
+        (status = 429, description = "Password changed too recently", body = ErrorResponseWrapper)
     ),
     tag = "auth"
 )]
 pub async fn set_password<R: AuthRepository>(
-    State(use_case): State<Arc<SetPasswordUseCase<R>>>,
-    Json(payload): Json<SetPasswordRequest>,
+    State(build): State<SetPasswordFactory<R>>,
+    Tx(tx): Tx,
+    JsonBody(payload): JsonBody<SetPasswordRequest>,
 ) -> Result<Json<ApiResponse<String>>, AuthError> {
     // Validate input
     payload.validate().map_err(|e| AuthError::ValidationError(e.to_string()))?;
 
     // Execute use case
-    let message = use_case
-        .execute(payload.email, payload.code, payload.password)
-        .await
-        .map_err(|e| AuthError::SetPasswordError(e.to_string()))?;
+    let use_case = build(tx);
+    let message = use_case.execute(payload.email, payload.code, payload.password).await.map_err(
+        |e| match e {
+            SetPasswordError::ChangedTooRecently { retry_after_secs } => {
+                AuthError::TooManyRequests { message: e.to_string(), retry_after_secs }
+            },
+            SetPasswordError::RepositoryError(_) | SetPasswordError::PasswordHashError(_) => {
+                AuthError::Internal(anyhow::anyhow!("Setting password failed: {}", e))
+            },
+            _ => AuthError::SetPasswordError(e.to_string()),
+        },
+    )?;
 
     Ok(Json(ApiResponse::success(message)))
 }
 
+/// Add or replace the caller's phone number and text it a verification code
+#[utoipa::path(
+    post,
+    path = "/api/auth/phone",
+    request_body = SendPhoneCodeRequest,
+    responses(
+        (status = 200, description = "Verification code sent", body = StringResponseWrapper),
+        (status = 400, description = "Invalid phone number", body = ErrorResponseWrapper),
+        (status = 429, description = "A code was sent too recently", body = ErrorResponseWrapper),
+        (status = 503, description = "Text messaging is not configured or the text could not be sent", body = ErrorResponseWrapper)
+    ),
+    tag = "auth",
+    security(
+        ("jwt_token" = [])
+    )
+)]
+pub async fn send_phone_code<R: AuthRepository>(
+    State(command): State<Arc<SendPhoneCodeCommand<R>>>,
+    claims: Claims,
+    JsonBody(payload): JsonBody<SendPhoneCodeRequest>,
+) -> Result<Json<ApiResponse<String>>, AuthError> {
+    payload.validate().map_err(|e| AuthError::ValidationError(e.to_string()))?;
+
+    command
+        .execute(subject_id(&claims)?, payload.phone)
+        .await
+        .map_err(phone_verification_error)?;
+
+    Ok(Json(ApiResponse::success("Verification code sent to your phone.".to_string())))
+}
+
+/// Verify the caller's phone number with the code texted to it
+#[utoipa::path(
+    post,
+    path = "/api/auth/phone/verify",
+    request_body = VerifyPhoneRequest,
+    responses(
+        (status = 200, description = "Phone verified", body = StringResponseWrapper),
+        (status = 400, description = "Invalid or expired code", body = ErrorResponseWrapper)
+    ),
+    tag = "auth",
+    security(
+        ("jwt_token" = [])
+    )
+)]
+pub async fn verify_phone<R: AuthRepository>(
+    State(command): State<Arc<VerifyPhoneCommand<R>>>,
+    claims: Claims,
+    JsonBody(payload): JsonBody<VerifyPhoneRequest>,
+) -> Result<Json<ApiResponse<String>>, AuthError> {
+    payload.validate().map_err(|e| AuthError::ValidationError(e.to_string()))?;
+
+    command
+        .execute(subject_id(&claims)?, payload.code)
+        .await
+        .map_err(phone_verification_error)?;
+
+    Ok(Json(ApiResponse::success("Phone number verified.".to_string())))
+}
+
+fn subject_id(claims: &Claims) -> Result<uuid::Uuid, AuthError> {
+    uuid::Uuid::parse_str(&claims.sub)
+        .map_err(|_| AuthError::Unauthorized("Invalid token subject".to_string()))
+}
+
+fn phone_verification_error(e: PhoneVerificationError) -> AuthError {
+    match e {
+        PhoneVerificationError::TooManyRequests { retry_after_secs } => {
+            AuthError::TooManyRequests { message: e.to_string(), retry_after_secs }
+        },
+        PhoneVerificationError::SmsUnavailable => AuthError::ServiceUnavailable(e.to_string()),
+        PhoneVerificationError::UserNotFound => AuthError::Unauthorized(e.to_string()),
+        PhoneVerificationError::InvalidPhone
+        | PhoneVerificationError::InvalidCode
+        | PhoneVerificationError::CodeExpired => AuthError::PhoneVerificationError(e.to_string()),
+        // Provider errors can name the account they were sent from
+        PhoneVerificationError::SmsError(detail) => {
+            tracing::error!("Failed to text phone verification code: {}", detail);
+            AuthError::ServiceUnavailable(
+                "Could not send the verification code. Please try again later.".to_string(),
+            )
+        },
+        PhoneVerificationError::RepositoryError(detail) => {
+            AuthError::Internal(anyhow::anyhow!("Phone verification failed: {}", detail))
+        },
+    }
+}
+
 /// Forgot password
 #[utoipa::path(
     post,
@@ -271,22 +727,36 @@ pub async fn set_password<R: AuthRepository>(
     request_body = ForgotPasswordRequest,
     responses(
         (status = 200, description = "Confirmation code sent", body = StringResponseWrapper),
-        (status = 400, description = "Invalid email or user not found", body = ErrorResponseWrapper)
+        (status = 400, description = "Invalid email or user not found", body = ErrorResponseWrapper),
+        (status = 429, description = "A code was sent too recently", body = ErrorResponseWrapper),
+        (status = 503, description = "The code could not be delivered", body = ErrorResponseWrapper)
     ),
     tag = "auth"
 )]
 pub async fn forgot_password<R: AuthRepository>(
     State(use_case): State<Arc<ForgotPasswordUseCase<R>>>,
-    Json(payload): Json<ForgotPasswordRequest>,
+    JsonBody(payload): JsonBody<ForgotPasswordRequest>,
 ) -> Result<Json<ApiResponse<String>>, AuthError> {
     // Validate input
     payload.validate().map_err(|e| AuthError::ValidationError(e.to_string()))?;
 
     // Execute use case
-    let message = use_case
-        .execute(payload.email)
-        .await
-        .map_err(|e| AuthError::ForgotPasswordError(e.to_string()))?;
+    let message = use_case.execute(payload.email, payload.channel).await.map_err(|e| match e {
+        ForgotPasswordError::TooManyRequests { retry_after_secs } => {
+            AuthError::TooManyRequests { message: e.to_string(), retry_after_secs }
+        },
+        // Provider errors can name the account they were sent from
+        ForgotPasswordError::SmsError(detail) | ForgotPasswordError::EmailError(detail) => {
+            tracing::error!("Failed to deliver password reset code: {}", detail);
+            AuthError::ServiceUnavailable(
+                "Could not send the confirmation code. Please try again later.".to_string(),
+            )
+        },
+        ForgotPasswordError::RepositoryError(detail) => {
+            AuthError::Internal(anyhow::anyhow!("Forgot password failed: {}", detail))
+        },
+        _ => AuthError::ForgotPasswordError(e.to_string()),
+    })?;
 
     Ok(Json(ApiResponse::success(message)))
 }
@@ -298,22 +768,25 @@ pub async fn forgot_password<R: AuthRepository>(
     request_body = ResendConfirmCodeRequest,
     responses(
         (status = 200, description = "Confirmation code resent", body = StringResponseWrapper),
-        (status = 400, description = "Invalid email or already verified", body = ErrorResponseWrapper)
+        (status = 400, description = "Invalid email or already verified", body = ErrorResponseWrapper),
+        (status = 429, description = "A code was sent too recently", body = ErrorResponseWrapper)
     ),
     tag = "auth"
 )]
 pub async fn resend_code<R: AuthRepository>(
     State(use_case): State<Arc<crate::application::use_cases::ResendConfirmCodeUseCase<R>>>,
-    Json(payload): Json<crate::application::dto::auth::ResendConfirmCodeRequest>,
+    JsonBody(payload): JsonBody<crate::application::dto::auth::ResendConfirmCodeRequest>,
 ) -> Result<Json<ApiResponse<String>>, AuthError> {
     // Validate input
     payload.validate().map_err(|e| AuthError::ValidationError(e.to_string()))?;
 
     // Execute use case
-    let message = use_case
-        .execute(payload.email)
-        .await
-        .map_err(|e| AuthError::ResendCodeError(e.to_string()))?;
+    let message = use_case.execute(payload.email).await.map_err(|e| match e {
+        ResendConfirmCodeError::TooManyRequests { retry_after_secs } => {
+            AuthError::TooManyRequests { message: e.to_string(), retry_after_secs }
+        },
+        _ => AuthError::ResendCodeError(e.to_string()),
+    })?;
 
     Ok(Json(ApiResponse::success(message)))
 }
@@ -1,6 +1,7 @@
 use crate::infrastructure::monitoring::SystemMetrics;
 use crate::infrastructure::SystemMonitor;
-use axum::{Extension, Json};
+use crate::presentation::responses::ApiResponse;
+use axum::{extract::State, Extension, Json};
 use std::sync::Arc;
 
 pub async fn system_health(
@@ -8,3 +9,23 @@ pub async fn system_health(
 ) -> Json<SystemMetrics> {
     Json(monitor.get_metrics().await)
 }
+
+/// The configuration the server started with, secrets redacted
+#[utoipa::path(
+    get,
+    path = "/api/admin/config",
+    responses(
+        (status = 200, description = "Effective configuration, secrets redacted", body = ConfigResponseWrapper),
+        (status = 401, description = "Unauthorized", body = ErrorResponseWrapper),
+        (status = 403, description = "Caller is not an admin", body = ErrorResponseWrapper)
+    ),
+    tag = "health",
+    security(
+        ("jwt_token" = [])
+    )
+)]
+pub async fn effective_config(
+    State(config): State<Arc<serde_json::Value>>,
+) -> Json<ApiResponse<serde_json::Value>> {
+    Json(ApiResponse::success(config.as_ref().clone()))
+}
@@ -6,7 +6,12 @@ use crate::{
         },
     },
     domain::repositories::user_repository::UserRepository,
-    presentation::responses::ApiResponse,
+    infrastructure::database::transaction::RequestTransaction,
+    presentation::{
+        middleware::{JsonBody, Tx},
+        responses::ApiResponse,
+    },
+    shared::utils::jwt::Claims,
 };
 use axum::{
     extract::{Path, State},
@@ -42,6 +47,11 @@ pub async fn get_user_role<R: UserRepository + 'static>(
     Ok(Json(ApiResponse::success(role_response)))
 }
 
+/// Builds the role update use case on the request's transaction, so the
+/// role change and its audit entry commit or roll back together
+pub type UpdateUserRoleFactory<R> =
+    Arc<dyn Fn(RequestTransaction) -> UpdateUserRoleUseCase<R> + Send + Sync>;
+
 /// Update user role by ID
 #[utoipa::path(
     put,
@@ -55,18 +65,22 @@ pub async fn get_user_role<R: UserRepository + 'static>(
         (status = 200, description = "User role updated successfully", body = RoleResponseWrapper),
         (status = 400, description = "Invalid user ID or role", body = ErrorResponseWrapper),
         (status = 404, description = "User not found", body = ErrorResponseWrapper),
-        (status = 401, description = "Unauthorized", body = ErrorResponseWrapper)
+        (status = 401, description = "Unauthorized", body = ErrorResponseWrapper),
+        (status = 403, description = "Caller is not an admin", body = ErrorResponseWrapper)
     ),
     security(
         ("jwt_token" = [])
     )
 )]
 pub async fn update_user_role<R: UserRepository + 'static>(
-    State(use_case): State<Arc<UpdateUserRoleUseCase<R>>>,
+    State(build): State<UpdateUserRoleFactory<R>>,
+    Tx(tx): Tx,
+    claims: Claims,
     Path(user_id): Path<String>,
-    Json(payload): Json<UpdateRoleRequest>,
+    JsonBody(payload): JsonBody<UpdateRoleRequest>,
 ) -> Result<Json<ApiResponse<RoleResponse>>, RoleApiError> {
-    let role_response = use_case.execute(&user_id, &payload.role).await?;
+    let actor_id = uuid::Uuid::parse_str(&claims.sub).ok();
+    let role_response = build(tx).execute(&user_id, &payload.role, actor_id).await?;
     Ok(Json(ApiResponse::success(role_response)))
 }
 
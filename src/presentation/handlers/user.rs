@@ -1,33 +1,71 @@
 use crate::{
     application::{
-        dto::{CreateUserDto, UpdateUserDto, UserResponseDto},
+        commands::{CreateInvitationCommand, DeactivateUsersCommand, ForcePasswordResetCommand},
+        dto::{
+            CreateInvitationDto, CreateUserDto, DeactivateUsersDto, DeactivateUsersResponseDto,
+            InvitationResponseDto, UpdateUserDto, UserResponseDto,
+        },
+        queries,
+        services::AvatarRenderer,
         use_cases::{
             CreateUserUseCase, GetUserUseCase, ImportUsersUseCase, ListUsersUseCase,
             UpdateUserUseCase,
         },
     },
-    domain::repositories::{user_repository::UserRepository, AuthRepository},
-    presentation::responses::ApiResponse,
-    shared::AppError,
+    domain::{
+        repositories::{
+            user_repository::{UserFilter, UserRepository},
+            AuthRepository,
+        },
+        value_objects::UserRole,
+    },
+    presentation::{
+        middleware::{minimal_response, JsonBody, ReturnPreference, ValidatedQuery},
+        responses::{user_location, ApiResponse},
+    },
+    shared::{utils::jwt::Claims, AppError},
 };
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
-    response::IntoResponse,
-    Json,
+    body::Body,
+    extract::{Path, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
 };
 use serde::Deserialize;
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 use utoipa::{IntoParams, ToSchema};
+use validator::Validate;
 
 // ... (keep existing code)
 
-/// Import users from CSV
+/// CSV file the import endpoint reads, from `IMPORT_CSV_PATH`
+#[derive(Debug, Clone)]
+pub struct ImportSource(pub PathBuf);
+
+/// Query parameters for a user import
+#[derive(Debug, Deserialize, Validate, ToSchema, IntoParams)]
+pub struct ImportUsersQuery {
+    /// Only check the file and report what an import would do
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Import in the background and answer 202 with the job to poll
+    #[serde(default)]
+    pub background: bool,
+}
+
+/// Import users from CSV. With `dry_run=true` nothing is written; the
+/// response reports what the import would do instead. With
+/// `background=true` the import runs on after the response, which carries
+/// the job whose status can be polled.
 #[utoipa::path(
     post,
     path = "/api/users/import",
+    params(ImportUsersQuery),
     responses(
-        (status = 200, description = "Users imported successfully", body = StringResponseWrapper),
+        (status = 200, description = "Users imported successfully; with dry_run, the ImportReportResponseWrapper report", body = StringResponseWrapper),
+        (status = 202, description = "Background import started; Location is its status", body = ImportJobResponseWrapper),
+        (status = 400, description = "Invalid CSV, or dry_run combined with background", body = ErrorResponseWrapper),
         (status = 500, description = "Internal server error", body = ErrorResponseWrapper)
     ),
     tag = "users",
@@ -37,35 +75,111 @@ use utoipa::{IntoParams, ToSchema};
 )]
 pub async fn import_users<R: AuthRepository>(
     State(use_case): State<Arc<ImportUsersUseCase<R>>>,
-) -> Result<impl IntoResponse, AppError> {
-    let csv_path = "import/users.csv";
-    let csv_data = tokio::fs::read(csv_path)
+    Extension(source): Extension<Arc<ImportSource>>,
+    ValidatedQuery(query): ValidatedQuery<ImportUsersQuery>,
+) -> Result<Response, AppError> {
+    let csv_data = tokio::fs::read(&source.0)
         .await
         .map_err(|e| AppError::Config(format!("Failed to read CSV file: {}", e)))?;
 
+    if query.dry_run && query.background {
+        return Err(AppError::Validation("dry_run and background cannot be combined".to_string()));
+    }
+
+    if query.background {
+        let job = use_case
+            .start(csv_data)
+            .await
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        let location = format!("/api/users/import/{}/status", job.job_id);
+        return Ok((
+            StatusCode::ACCEPTED,
+            [(header::LOCATION, location)],
+            Json(ApiResponse::success(job)),
+        )
+            .into_response());
+    }
+
+    if query.dry_run {
+        let report = use_case
+            .dry_run(&csv_data)
+            .await
+            .map_err(|e| AppError::Validation(e.to_string()))?;
+        return Ok(Json(ApiResponse::success(report)).into_response());
+    }
+
     let count = use_case
         .execute(&csv_data)
         .await
         .map_err(|e| AppError::Validation(e.to_string()))?;
 
-    Ok(Json(ApiResponse::success(format!("Successfully imported {} users", count))))
+    Ok(
+        Json(ApiResponse::success(format!("Successfully imported {} users", count)))
+            .into_response(),
+    )
+}
+
+/// Status of a background user import
+#[utoipa::path(
+    get,
+    path = "/api/users/import/{job_id}/status",
+    params(
+        ("job_id" = String, Path, description = "Job returned when the import started")
+    ),
+    responses(
+        (status = 200, description = "Import job status", body = ImportJobResponseWrapper),
+        (status = 404, description = "Unknown or expired job", body = ErrorResponseWrapper)
+    ),
+    tag = "users",
+    security(
+        ("jwt_token" = [])
+    )
+)]
+pub async fn get_import_status<R: AuthRepository>(
+    State(use_case): State<Arc<ImportUsersUseCase<R>>>,
+    Path(job_id): Path<String>,
+) -> Result<impl IntoResponse, AppError> {
+    let not_found = || AppError::NotFound("Import job not found".to_string());
+    let job_id = uuid::Uuid::parse_str(&job_id).map_err(|_| not_found())?;
+    let job = use_case.status(&job_id).await.ok_or_else(not_found)?;
+    Ok(Json(ApiResponse::success(job)))
 }
 
 /// Query parameters for listing users
-#[derive(Debug, Deserialize, ToSchema, IntoParams)]
+#[derive(Debug, Deserialize, Validate, ToSchema, IntoParams)]
 pub struct ListUsersQuery {
     #[serde(default = "default_page")]
+    #[validate(range(min = 1, message = "Page must be >= 1"))]
     pub page: i64,
-    #[serde(default = "default_page_size")]
-    pub page_size: i64,
+    /// Defaults to `DEFAULT_PAGE_SIZE`; values above `MAX_PAGE_SIZE` are clamped
+    #[validate(range(min = 1, message = "Page size must be >= 1"))]
+    pub page_size: Option<i64>,
 }
 
 fn default_page() -> i64 {
     1
 }
 
-fn default_page_size() -> i64 {
-    10
+/// Query parameters narrowing a user export
+#[derive(Debug, Deserialize, Validate, ToSchema, IntoParams)]
+pub struct ExportUsersQuery {
+    /// Only users with this role
+    pub role: Option<String>,
+    /// Only active (`true`) or inactive (`false`) users
+    pub active: Option<bool>,
+}
+
+impl ExportUsersQuery {
+    fn into_filter(self) -> Result<UserFilter, AppError> {
+        let role = self
+            .role
+            .map(|r| {
+                UserRole::parse(&r)
+                    .ok_or_else(|| AppError::Validation(format!("Invalid role: {}", r)))
+            })
+            .transpose()?;
+        Ok(UserFilter { role, is_active: self.active })
+    }
 }
 
 /// Create a new user
@@ -73,9 +187,16 @@ fn default_page_size() -> i64 {
     post,
     path = "/api/users",
     request_body = CreateUserDto,
+    params(
+        ("Prefer" = Option<String>, Header, description = "`return=minimal` to get 204 and `Location` without a body")
+    ),
     responses(
-        (status = 201, description = "User created successfully", body = UserResponseWrapper),
-        (status = 400, description = "Invalid input", body = ErrorResponseWrapper)
+        (status = 201, description = "User created successfully", body = UserResponseWrapper,
+            headers(("Location" = String, description = "Path of the created user"))),
+        (status = 204, description = "User created; sent for `Prefer: return=minimal`",
+            headers(("Location" = String, description = "Path of the created user"))),
+        (status = 400, description = "Invalid input", body = ErrorResponseWrapper),
+        (status = 409, description = "Email already exists", body = ErrorResponseWrapper)
     ),
     tag = "users",
     security(
@@ -84,12 +205,21 @@ fn default_page_size() -> i64 {
 )]
 pub async fn create_user<R: UserRepository>(
     State(use_case): State<Arc<CreateUserUseCase<R>>>,
-    Json(payload): Json<CreateUserDto>,
-) -> Result<impl IntoResponse, AppError> {
+    prefer: ReturnPreference,
+    JsonBody(payload): JsonBody<CreateUserDto>,
+) -> Result<Response, AppError> {
     let user = use_case.execute(payload).await?;
     let response = UserResponseDto::from(user);
 
-    Ok((StatusCode::CREATED, Json(ApiResponse::success(response))))
+    if prefer == ReturnPreference::Minimal {
+        return Ok(minimal_response(user_location(&response.id)));
+    }
+    Ok((
+        StatusCode::CREATED,
+        user_location(&response.id),
+        Json(ApiResponse::success(response)),
+    )
+        .into_response())
 }
 
 /// Get user by ID
@@ -118,6 +248,40 @@ pub async fn get_user<R: UserRepository>(
     Ok(Json(ApiResponse::success(response)))
 }
 
+/// Get a user's avatar. No uploads exist yet, so this is always the
+/// `AvatarRenderer`'s default image for the email address.
+#[utoipa::path(
+    get,
+    path = "/api/users/{id}/avatar",
+    responses(
+        (status = 200, description = "Avatar image", body = String, content_type = "image/svg+xml"),
+        (status = 404, description = "User not found", body = ErrorResponseWrapper)
+    ),
+    params(
+        ("id" = String, Path, description = "User ID")
+    ),
+    tag = "users",
+    security(
+        ("jwt_token" = [])
+    )
+)]
+pub async fn get_user_avatar<R: UserRepository>(
+    State(use_case): State<Arc<GetUserUseCase<R>>>,
+    Extension(avatars): Extension<Arc<dyn AvatarRenderer>>,
+    Path(user_id): Path<String>,
+) -> Result<Response, AppError> {
+    let user = use_case.execute(&user_id).await?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/svg+xml"),
+            (header::CACHE_CONTROL, "private, max-age=86400"),
+        ],
+        avatars.svg(user.email.as_str()),
+    )
+        .into_response())
+}
+
 /// List users with pagination
 #[utoipa::path(
     get,
@@ -126,7 +290,8 @@ pub async fn get_user<R: UserRepository>(
         ListUsersQuery
     ),
     responses(
-        (status = 200, description = "Users list", body = UserListResponseWrapper)
+        (status = 200, description = "Users list", body = UserListResponseWrapper),
+        (status = 400, description = "Invalid page or page_size", body = ErrorResponseWrapper)
     ),
     tag = "users",
     security(
@@ -135,25 +300,30 @@ pub async fn get_user<R: UserRepository>(
 )]
 pub async fn list_users<R: UserRepository>(
     State(use_case): State<Arc<ListUsersUseCase<R>>>,
-    Query(params): Query<ListUsersQuery>,
+    ValidatedQuery(params): ValidatedQuery<ListUsersQuery>,
 ) -> Result<Json<ApiResponse<Vec<UserResponseDto>>>, AppError> {
-    let users = use_case.execute(params.page, params.page_size).await?;
+    let (users, meta) = use_case.execute(params.page, params.page_size).await?;
     let response: Vec<UserResponseDto> = users.iter().map(UserResponseDto::from).collect();
 
-    Ok(Json(ApiResponse::success(response)))
+    Ok(Json(ApiResponse::success(response).with_meta(meta)))
 }
 
-/// Update user
+/// Partially update user; only fields present in the body change.
+/// `PUT` is accepted as an alias with the same semantics.
 #[utoipa::path(
-    put,
+    patch,
     path = "/api/users/{id}",
     request_body = UpdateUserDto,
     responses(
         (status = 200, description = "User updated successfully", body = UserResponseWrapper),
+        (status = 204, description = "User updated; sent for `Prefer: return=minimal`",
+            headers(("Location" = String, description = "Path of the updated user"))),
+        (status = 400, description = "Invalid field value, or null for a field that cannot be cleared", body = ErrorResponseWrapper),
         (status = 404, description = "User not found", body = ErrorResponseWrapper)
     ),
     params(
-        ("id" = String, Path, description = "User ID")
+        ("id" = String, Path, description = "User ID"),
+        ("Prefer" = Option<String>, Header, description = "`return=minimal` to get 204 and `Location` without a body")
     ),
     tag = "users",
     security(
@@ -163,10 +333,132 @@ pub async fn list_users<R: UserRepository>(
 pub async fn update_user<R: UserRepository>(
     State(use_case): State<Arc<UpdateUserUseCase<R>>>,
     Path(user_id): Path<String>,
-    Json(payload): Json<UpdateUserDto>,
-) -> Result<Json<ApiResponse<UserResponseDto>>, AppError> {
+    prefer: ReturnPreference,
+    JsonBody(payload): JsonBody<UpdateUserDto>,
+) -> Result<Response, AppError> {
     let user = use_case.execute(&user_id, payload).await?;
     let response = UserResponseDto::from(user);
 
-    Ok(Json(ApiResponse::success(response)))
+    if prefer == ReturnPreference::Minimal {
+        return Ok(minimal_response(user_location(&response.id)));
+    }
+    Ok(Json(ApiResponse::success(response)).into_response())
+}
+
+/// Export users as CSV, streamed as rows are read
+#[utoipa::path(
+    get,
+    path = "/api/admin/users/export.csv",
+    params(
+        ExportUsersQuery
+    ),
+    responses(
+        (status = 200, description = "CSV with a header row and one row per user", body = String, content_type = "text/csv"),
+        (status = 400, description = "Invalid filter", body = ErrorResponseWrapper),
+        (status = 403, description = "Caller is not an admin", body = ErrorResponseWrapper)
+    ),
+    tag = "users",
+    security(
+        ("jwt_token" = [])
+    )
+)]
+pub async fn export_users_csv<R: UserRepository>(
+    State(export): State<Arc<queries::ExportUsersQuery<R>>>,
+    ValidatedQuery(query): ValidatedQuery<ExportUsersQuery>,
+) -> Result<Response, AppError> {
+    let filter = query.into_filter()?;
+    let body = Body::from_stream(export.execute(&filter));
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"users.csv\""),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+/// Deactivate a batch of users and revoke all their sessions
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/deactivate",
+    request_body = DeactivateUsersDto,
+    responses(
+        (status = 200, description = "Batch deactivated; unknown ids are listed", body = DeactivateUsersResponseWrapper),
+        (status = 400, description = "Empty, oversized or malformed batch, or it includes the caller", body = ErrorResponseWrapper),
+        (status = 403, description = "Caller is not an admin", body = ErrorResponseWrapper)
+    ),
+    tag = "users",
+    security(
+        ("jwt_token" = [])
+    )
+)]
+pub async fn deactivate_users<R: AuthRepository>(
+    State(command): State<Arc<DeactivateUsersCommand<R>>>,
+    claims: Claims,
+    JsonBody(payload): JsonBody<DeactivateUsersDto>,
+) -> Result<Json<ApiResponse<DeactivateUsersResponseDto>>, AppError> {
+    let actor_id = uuid::Uuid::parse_str(&claims.sub).ok();
+    let result = command.execute(&payload.user_ids, actor_id).await?;
+
+    Ok(Json(ApiResponse::success(result)))
+}
+
+/// Email a user a password reset code on an admin's behalf. The password
+/// they set with it is exempt from the minimum change interval.
+#[utoipa::path(
+    post,
+    path = "/api/admin/users/{id}/password-reset",
+    params(
+        ("id" = String, Path, description = "User ID")
+    ),
+    responses(
+        (status = 200, description = "Reset code sent", body = StringResponseWrapper),
+        (status = 400, description = "Malformed user ID", body = ErrorResponseWrapper),
+        (status = 403, description = "Caller is not an admin", body = ErrorResponseWrapper),
+        (status = 404, description = "User not found", body = ErrorResponseWrapper)
+    ),
+    tag = "users",
+    security(
+        ("jwt_token" = [])
+    )
+)]
+pub async fn force_password_reset<R: AuthRepository>(
+    State(command): State<Arc<ForcePasswordResetCommand<R>>>,
+    claims: Claims,
+    Path(id): Path<String>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let actor_id = uuid::Uuid::parse_str(&claims.sub).ok();
+    command.execute(&id, actor_id).await?;
+
+    Ok(Json(ApiResponse::success("Password reset code sent".to_string())))
+}
+
+/// Invite someone to register, optionally with a role
+#[utoipa::path(
+    post,
+    path = "/api/admin/invitations",
+    request_body = CreateInvitationDto,
+    responses(
+        (status = 201, description = "Invitation created; the token is shown only here", body = InvitationResponseWrapper),
+        (status = 400, description = "Unknown role or lifetime out of range", body = ErrorResponseWrapper),
+        (status = 403, description = "Caller is not an admin", body = ErrorResponseWrapper)
+    ),
+    tag = "users",
+    security(
+        ("jwt_token" = [])
+    )
+)]
+pub async fn create_invitation(
+    State(command): State<Arc<CreateInvitationCommand>>,
+    claims: Claims,
+    JsonBody(payload): JsonBody<CreateInvitationDto>,
+) -> Result<(StatusCode, Json<ApiResponse<InvitationResponseDto>>), AppError> {
+    let actor_id = uuid::Uuid::parse_str(&claims.sub).ok();
+    let invitation = command
+        .execute(payload.role.as_deref(), payload.expires_in_secs, actor_id)
+        .await?;
+
+    Ok((StatusCode::CREATED, Json(ApiResponse::success(invitation))))
 }
@@ -1,17 +1,30 @@
-use crate::shared::utils::jwt::{Claims, JwtManager};
+use crate::{
+    application::services::role::RoleResolver,
+    domain::value_objects::{UserId, UserRole},
+    shared::utils::jwt::{Claims, JwtError, JwtManager},
+};
 use axum::{
     body::Body,
     extract::{Request, State},
-    http::{header, StatusCode},
+    http::{header, HeaderMap, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
     Json,
 };
 use std::sync::Arc;
 
+/// Code for an access token past its expiry; refreshing gets a new one
+pub const TOKEN_EXPIRED: &str = "TOKEN_EXPIRED";
+
+/// Code for an access token that is malformed, forged or otherwise unusable;
+/// refreshing will not help, the user has to log in again
+pub const TOKEN_INVALID: &str = "TOKEN_INVALID";
+
 #[derive(Clone)]
 pub struct AuthState {
     pub jwt_manager: Arc<JwtManager>,
+    /// Resolves the caller's current role; tokens do not carry one
+    pub roles: Arc<RoleResolver>,
 }
 
 pub async fn auth_middleware(
@@ -19,47 +32,120 @@ pub async fn auth_middleware(
     req: Request<Body>,
     next: Next,
 ) -> Result<Response, AuthMiddlewareError> {
-    let jwt_manager = &state.jwt_manager;
     let (mut parts, body) = req.into_parts();
 
-    // 1. Try Authorization header
-    let token = if let Some(auth_header) = parts.headers.get(header::AUTHORIZATION) {
-        auth_header
+    let token = access_token(&parts.headers)?;
+    let (claims, role) = authenticate(&state, &token).await?;
+
+    // Insert claims and role into request extensions for handlers to use
+    parts.extensions.insert(claims);
+    parts.extensions.insert(role);
+
+    let req = Request::from_parts(parts, body);
+    Ok(next.run(req).await)
+}
+
+/// Like `auth_middleware`, but only verifies the token and does not look up
+/// the caller's role. For routes that load the user themselves and answer
+/// for a deleted one; no `UserRole` is inserted, so `require_role` cannot
+/// be layered on top.
+pub async fn token_middleware(
+    State(state): State<AuthState>,
+    mut req: Request<Body>,
+    next: Next,
+) -> Result<Response, AuthMiddlewareError> {
+    let token = access_token(req.headers())?;
+    let claims = verify_access_token(&state, &token)?;
+
+    req.extensions_mut().insert(claims);
+    Ok(next.run(req).await)
+}
+
+/// Cookie carrying the access token for browser clients
+const ACCESS_TOKEN_COOKIE: &str = "access_token";
+
+/// Access token presented with a request.
+///
+/// The `Authorization` header takes precedence: when present it must use
+/// the `Bearer` scheme and its token is the only one considered, even if an
+/// `access_token` cookie is also sent. Without the header the cookie is
+/// used, so API and browser clients authenticate the same way.
+fn access_token(headers: &HeaderMap) -> Result<String, AuthMiddlewareError> {
+    if let Some(value) = headers.get(header::AUTHORIZATION) {
+        return value
             .to_str()
             .ok()
             .and_then(|h| h.strip_prefix("Bearer "))
-            .map(|t| t.to_string())
-    } else {
-        None
-    };
-
-    // 2. If no header, try Cookie
-    let token = if let Some(t) = token {
-        Some(t)
-    } else {
-        parts.headers.get(header::COOKIE).and_then(|c| c.to_str().ok()).and_then(|c| {
-            c.split(';')
-                .find_map(|s| s.trim().strip_prefix("access_token=").map(|token| token.to_string()))
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty())
+            .ok_or(AuthMiddlewareError::InvalidTokenFormat);
+    }
+
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|c| c.to_str().ok())
+        .flat_map(|c| c.split(';'))
+        .find_map(|pair| {
+            let (name, value) = pair.trim().split_once('=')?;
+            (name == ACCESS_TOKEN_COOKIE && !value.is_empty()).then(|| value.to_string())
         })
-    };
+        .ok_or(AuthMiddlewareError::MissingToken)
+}
 
-    let token = token.ok_or(AuthMiddlewareError::MissingToken)?;
+/// Verify an access token and look up its user's current role, so a role
+/// change applies to tokens already issued
+async fn authenticate(
+    state: &AuthState,
+    token: &str,
+) -> Result<(Claims, UserRole), AuthMiddlewareError> {
+    let claims = verify_access_token(state, token)?;
 
-    // Verify token
-    let claims = jwt_manager
-        .verify_token(&token)
+    let user_id = UserId::from_string(&claims.sub)
         .map_err(|e| AuthMiddlewareError::InvalidToken(e.to_string()))?;
+    let role = state
+        .roles
+        .resolve(user_id)
+        .await
+        .map_err(|e| AuthMiddlewareError::Internal(format!("{:?}", e)))?
+        .ok_or_else(|| AuthMiddlewareError::InvalidToken("user no longer exists".to_string()))?;
+
+    Ok((claims, role))
+}
+
+fn verify_access_token(state: &AuthState, token: &str) -> Result<Claims, AuthMiddlewareError> {
+    let claims = state.jwt_manager.verify_token(token).map_err(|e| match e {
+        JwtError::TokenExpired => AuthMiddlewareError::TokenExpired,
+        e => AuthMiddlewareError::InvalidToken(e.to_string()),
+    })?;
 
-    // Check if it's an access token
     if claims.token_type != "access" {
         return Err(AuthMiddlewareError::InvalidTokenType);
     }
+    Ok(claims)
+}
 
-    // Insert claims into request extensions for handlers to use
-    parts.extensions.insert(claims);
+/// Current role of the caller behind an `Authorization: Bearer` access
+/// token, for public routes that do more for authenticated callers. Cookies
+/// are not consulted, so a cross-site request cannot borrow a session.
+pub async fn bearer_role(state: &AuthState, headers: &HeaderMap) -> Option<UserRole> {
+    let token = headers.get(header::AUTHORIZATION)?.to_str().ok()?.strip_prefix("Bearer ")?;
+    authenticate(state, token).await.ok().map(|(_, role)| role)
+}
 
-    let req = Request::from_parts(parts, body);
-    Ok(next.run(req).await)
+/// Reject callers whose current role ranks below `required`; higher roles
+/// pass, see `UserRole::satisfies`. Must run inside `auth_middleware`, e.g.
+/// via `route_layer` on an authenticated router.
+pub async fn require_role(
+    State(required): State<UserRole>,
+    req: Request<Body>,
+    next: Next,
+) -> Result<Response, AuthMiddlewareError> {
+    match req.extensions().get::<UserRole>() {
+        Some(role) if role.satisfies(required) => Ok(next.run(req).await),
+        Some(_) => Err(AuthMiddlewareError::Forbidden),
+        None => Err(AuthMiddlewareError::MissingToken),
+    }
 }
 
 #[derive(Debug)]
@@ -68,37 +154,74 @@ pub enum AuthMiddlewareError {
     InvalidTokenFormat,
     InvalidToken(String),
     InvalidTokenType,
+    TokenExpired,
+    Forbidden,
+    Internal(String),
 }
 
 impl IntoResponse for AuthMiddlewareError {
     fn into_response(self) -> Response {
-        let (status, message) = match self {
+        let (status, message, code) = match self {
             AuthMiddlewareError::MissingToken => {
-                (StatusCode::UNAUTHORIZED, "Missing authorization token")
-            },
-            AuthMiddlewareError::InvalidTokenFormat => {
-                (StatusCode::UNAUTHORIZED, "Invalid token format. Expected: Bearer <token>")
+                (StatusCode::UNAUTHORIZED, "Missing authorization token", None)
             },
+            AuthMiddlewareError::InvalidTokenFormat => (
+                StatusCode::UNAUTHORIZED,
+                "Invalid token format. Expected: Bearer <token>",
+                Some(TOKEN_INVALID),
+            ),
             AuthMiddlewareError::InvalidToken(_) => {
-                (StatusCode::UNAUTHORIZED, "Invalid or expired token")
+                (StatusCode::UNAUTHORIZED, "Invalid token", Some(TOKEN_INVALID))
             },
-            AuthMiddlewareError::InvalidTokenType => {
-                (StatusCode::UNAUTHORIZED, "Invalid token type. Expected access token")
+            AuthMiddlewareError::InvalidTokenType => (
+                StatusCode::UNAUTHORIZED,
+                "Invalid token type. Expected access token",
+                Some(TOKEN_INVALID),
+            ),
+            AuthMiddlewareError::TokenExpired => {
+                (StatusCode::UNAUTHORIZED, "Token expired", Some(TOKEN_EXPIRED))
+            },
+            AuthMiddlewareError::Forbidden => {
+                (StatusCode::FORBIDDEN, "Insufficient permissions", None)
+            },
+            AuthMiddlewareError::Internal(msg) => {
+                tracing::error!("Auth middleware failed: {}", msg);
+                (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error", None)
             },
         };
 
-        let body = Json(serde_json::json!({
+        let mut body = serde_json::json!({
             "success": false,
             "error": message,
-        }));
+        });
+        if let Some(code) = code {
+            body["code"] = code.into();
+        }
+        let body = Json(body);
 
         (status, body).into_response()
     }
 }
 
-// Extractor for Claims from request extensions
+// Extractors for Claims and the caller's role from request extensions
 use axum::{async_trait, extract::FromRequestParts, http::request::Parts};
 
+#[async_trait]
+impl<S> FromRequestParts<S> for UserRole
+where
+    S: Send + Sync,
+{
+    type Rejection = (StatusCode, String);
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<UserRole>()
+            .copied()
+            .ok_or((StatusCode::UNAUTHORIZED, "Unauthorized: No role found".to_string()))
+    }
+}
+
 #[async_trait]
 impl<S> FromRequestParts<S> for Claims
 where
@@ -114,3 +237,28 @@ where
             .ok_or((StatusCode::UNAUTHORIZED, "Unauthorized: No claims found".to_string()))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::get, Extension, Router};
+    use tower::ServiceExt;
+
+    /// Status of a request made as `role` to a route requiring `required`
+    async fn status_as(role: UserRole, required: UserRole) -> StatusCode {
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .route_layer(middleware::from_fn_with_state(required, require_role))
+            .layer(Extension(role));
+        let req = Request::builder().uri("/").body(Body::empty()).unwrap();
+        app.oneshot(req).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn higher_roles_pass_lower_role_checks() {
+        assert_eq!(status_as(UserRole::Admin, UserRole::Editor).await, StatusCode::OK);
+        assert_eq!(status_as(UserRole::Editor, UserRole::Editor).await, StatusCode::OK);
+        assert_eq!(status_as(UserRole::Viewer, UserRole::Editor).await, StatusCode::FORBIDDEN);
+        assert_eq!(status_as(UserRole::Editor, UserRole::Admin).await, StatusCode::FORBIDDEN);
+    }
+}
@@ -0,0 +1,96 @@
+use crate::shared::cache_control::CachePolicy;
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+
+/// Send responses with the `Cache-Control` `policy` calls for, e.g. via
+/// `route_layer(middleware::from_fn_with_state(policy, set_cache_control))`.
+///
+/// Only successful GET and HEAD responses are made cacheable; anything else
+/// under a cacheable policy gets `no-store`. A header the handler set itself
+/// is left alone.
+pub async fn set_cache_control(
+    State(policy): State<CachePolicy>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let readonly = matches!(*req.method(), Method::GET | Method::HEAD);
+    let mut response = next.run(req).await;
+    if response.headers().contains_key(header::CACHE_CONTROL) {
+        return response;
+    }
+
+    let policy =
+        if readonly && response.status().is_success() { policy } else { CachePolicy::NoStore };
+    if let Ok(value) = HeaderValue::from_str(&policy.to_string()) {
+        response.headers_mut().insert(header::CACHE_CONTROL, value);
+    }
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::StatusCode, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    async fn cache_control(app: Router, method: Method, uri: &str) -> Option<String> {
+        let req = Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+        let res = app.oneshot(req).await.unwrap();
+        res.headers()
+            .get(header::CACHE_CONTROL)
+            .map(|v| v.to_str().unwrap().to_string())
+    }
+
+    fn app(policy: CachePolicy) -> Router {
+        Router::new()
+            .route("/ok", get(|| async { "ok" }).post(|| async { "created" }))
+            .route("/missing", get(|| async { StatusCode::NOT_FOUND }))
+            .route(
+                "/own",
+                get(|| async { ([(header::CACHE_CONTROL, "public, max-age=86400")], "own") }),
+            )
+            .layer(middleware::from_fn_with_state(policy, set_cache_control))
+    }
+
+    #[tokio::test]
+    async fn cacheable_policy_applies_only_to_successful_reads() {
+        let app = app(CachePolicy::Public { max_age: 60 });
+
+        assert_eq!(
+            cache_control(app.clone(), Method::GET, "/ok").await.as_deref(),
+            Some("public, max-age=60")
+        );
+        assert_eq!(
+            cache_control(app.clone(), Method::HEAD, "/ok").await.as_deref(),
+            Some("public, max-age=60")
+        );
+        assert_eq!(
+            cache_control(app.clone(), Method::POST, "/ok").await.as_deref(),
+            Some("no-store")
+        );
+        assert_eq!(
+            cache_control(app.clone(), Method::GET, "/missing").await.as_deref(),
+            Some("no-store")
+        );
+        assert_eq!(
+            cache_control(app, Method::GET, "/own").await.as_deref(),
+            Some("public, max-age=86400")
+        );
+    }
+
+    #[tokio::test]
+    async fn no_store_applies_to_every_response() {
+        let app = app(CachePolicy::NoStore);
+
+        assert_eq!(
+            cache_control(app.clone(), Method::GET, "/ok").await.as_deref(),
+            Some("no-store")
+        );
+        assert_eq!(cache_control(app, Method::POST, "/ok").await.as_deref(), Some("no-store"));
+    }
+}
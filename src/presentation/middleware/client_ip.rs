@@ -0,0 +1,180 @@
+use crate::shared::AppError;
+use axum::{
+    async_trait,
+    extract::{ConnectInfo, FromRequestParts},
+    http::{request::Parts, Extensions, HeaderMap, Request},
+};
+use ipnet::IpNet;
+use std::{
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+};
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+const X_REAL_IP: &str = "x-real-ip";
+
+/// Proxies whose forwarding headers are believed.
+///
+/// Installed as a request extension; when absent no proxy is trusted and
+/// the socket peer is always the client.
+#[derive(Debug, Clone, Default)]
+pub struct TrustedProxies(Arc<Vec<IpNet>>);
+
+impl TrustedProxies {
+    pub fn new(networks: Vec<IpNet>) -> Self {
+        Self(Arc::new(networks))
+    }
+
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        self.0.iter().any(|net| net.contains(&ip))
+    }
+
+    /// Work out the originating client for a request received from `peer`.
+    ///
+    /// Forwarding headers are only read when `peer` is trusted. The
+    /// `X-Forwarded-For` chain is walked from the nearest hop outwards and
+    /// the first untrusted address wins, so a client cannot spoof its IP by
+    /// prepending entries.
+    pub fn resolve(&self, peer: IpAddr, headers: &HeaderMap) -> IpAddr {
+        if !self.contains(peer) {
+            return peer;
+        }
+
+        let forwarded: Vec<IpAddr> = headers
+            .get_all(X_FORWARDED_FOR)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|hop| hop.trim().parse().ok())
+            .collect();
+
+        if let Some(client) = forwarded.iter().rev().find(|ip| !self.contains(**ip)) {
+            return *client;
+        }
+        // Every hop is one of ours; the outermost is the best we know
+        if let Some(first) = forwarded.first() {
+            return *first;
+        }
+
+        headers
+            .get(X_REAL_IP)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.trim().parse().ok())
+            .unwrap_or(peer)
+    }
+}
+
+fn peer_addr(extensions: &Extensions) -> Option<IpAddr> {
+    extensions.get::<ConnectInfo<SocketAddr>>().map(|ConnectInfo(addr)| addr.ip())
+}
+
+fn trusted_proxies(extensions: &Extensions) -> TrustedProxies {
+    extensions.get::<TrustedProxies>().cloned().unwrap_or_default()
+}
+
+/// The originating client address, honouring forwarding headers only from
+/// trusted proxies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClientIp(pub IpAddr);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for ClientIp
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let peer = peer_addr(&parts.extensions)
+            .ok_or_else(|| AppError::Internal(anyhow::anyhow!("Missing peer address")))?;
+
+        Ok(Self(trusted_proxies(&parts.extensions).resolve(peer, &parts.headers)))
+    }
+}
+
+/// Rate-limit key built from [`ClientIp`] resolution
+#[derive(Debug, Clone, Default)]
+pub struct ClientIpKeyExtractor {
+    trusted: TrustedProxies,
+}
+
+impl ClientIpKeyExtractor {
+    pub fn new(trusted: TrustedProxies) -> Self {
+        Self { trusted }
+    }
+
+    /// Client address for `req`, or `None` without a recorded peer address
+    pub fn extract<T>(&self, req: &Request<T>) -> Option<IpAddr> {
+        let peer = peer_addr(req.extensions())?;
+        Some(self.trusted.resolve(peer, req.headers()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn proxies(nets: &[&str]) -> TrustedProxies {
+        TrustedProxies::new(nets.iter().map(|n| n.parse().unwrap()).collect())
+    }
+
+    fn headers(pairs: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for (name, value) in pairs {
+            map.append(*name, HeaderValue::from_str(value).unwrap());
+        }
+        map
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn ignores_forwarding_headers_from_untrusted_peer() {
+        let trusted = proxies(&["10.0.0.0/8"]);
+        let spoofed = headers(&[(X_FORWARDED_FOR, "1.2.3.4"), (X_REAL_IP, "5.6.7.8")]);
+
+        assert_eq!(trusted.resolve(ip("203.0.113.9"), &spoofed), ip("203.0.113.9"));
+    }
+
+    #[test]
+    fn uses_nearest_untrusted_hop_from_trusted_peer() {
+        let trusted = proxies(&["10.0.0.0/8"]);
+        // The client prepended a fake address; 198.51.100.7 is what our proxy saw
+        let chain = headers(&[(X_FORWARDED_FOR, "1.2.3.4, 198.51.100.7, 10.0.0.2")]);
+
+        assert_eq!(trusted.resolve(ip("10.0.0.1"), &chain), ip("198.51.100.7"));
+    }
+
+    #[test]
+    fn falls_back_to_real_ip_then_peer_for_trusted_peer() {
+        let trusted = proxies(&["127.0.0.1/32"]);
+
+        let real_ip = headers(&[(X_REAL_IP, "198.51.100.7")]);
+        assert_eq!(trusted.resolve(ip("127.0.0.1"), &real_ip), ip("198.51.100.7"));
+
+        let garbage = headers(&[(X_FORWARDED_FOR, "not-an-ip")]);
+        assert_eq!(trusted.resolve(ip("127.0.0.1"), &garbage), ip("127.0.0.1"));
+    }
+
+    #[test]
+    fn trusts_nothing_by_default() {
+        let chain = headers(&[(X_FORWARDED_FOR, "1.2.3.4")]);
+        assert_eq!(TrustedProxies::default().resolve(ip("10.0.0.1"), &chain), ip("10.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn extractor_reads_peer_and_trusted_proxies_from_extensions() {
+        let mut req = Request::builder().header(X_FORWARDED_FOR, "198.51.100.7").body(()).unwrap();
+        req.extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from(([10, 0, 0, 1], 4000))));
+        req.extensions_mut().insert(proxies(&["10.0.0.0/8"]));
+        let (mut parts, _) = req.into_parts();
+
+        let ClientIp(client) = ClientIp::from_request_parts(&mut parts, &()).await.unwrap();
+
+        assert_eq!(client, ip("198.51.100.7"));
+    }
+}
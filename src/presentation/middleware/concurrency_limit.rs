@@ -0,0 +1,49 @@
+use crate::shared::errors::retry_after_response;
+use axum::{
+    error_handling::HandleErrorLayer,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    BoxError, Json, Router,
+};
+use serde_json::json;
+use tower::{limit::GlobalConcurrencyLimitLayer, load_shed::error::Overloaded, ServiceBuilder};
+
+/// Shed requests are usually retryable almost immediately
+const SHED_RETRY_AFTER_SECS: u64 = 1;
+
+/// Cap in-flight requests across the whole router.
+///
+/// Requests arriving while `max_in_flight` are already being served are
+/// shed immediately with 503 and `Retry-After` instead of queueing, so a
+/// traffic spike cannot grow memory without bound. `Router::layer` clones
+/// the layer onto every route, so the semaphore must be the shared kind.
+pub fn apply_concurrency_limit(router: Router, max_in_flight: usize) -> Router {
+    router.layer(
+        ServiceBuilder::new()
+            .layer(HandleErrorLayer::new(overload_error_handler))
+            .load_shed()
+            .layer(GlobalConcurrencyLimitLayer::new(max_in_flight)),
+    )
+}
+
+async fn overload_error_handler(error: BoxError) -> Response {
+    if error.is::<Overloaded>() {
+        let status = StatusCode::SERVICE_UNAVAILABLE;
+        return retry_after_response(
+            status,
+            json!({
+                "error": "Server is at capacity, please retry shortly",
+                "status": status.as_u16(),
+            }),
+            SHED_RETRY_AFTER_SECS,
+        );
+    }
+
+    tracing::error!("Unhandled middleware error: {}", error);
+    let status = StatusCode::INTERNAL_SERVER_ERROR;
+    (
+        status,
+        Json(json!({ "error": "Internal server error", "status": status.as_u16() })),
+    )
+        .into_response()
+}
@@ -0,0 +1,47 @@
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Json, Router,
+};
+use serde_json::json;
+
+/// Bounds on the headers a request may carry
+#[derive(Debug, Clone, Copy)]
+pub struct HeaderLimits {
+    /// Most header fields allowed
+    pub max_count: usize,
+    /// Most bytes allowed across all header names and values
+    pub max_bytes: usize,
+}
+
+/// Answer requests with more or larger headers than `limits` allow with 431.
+///
+/// hyper already refuses requests with more than 100 header fields, so a
+/// larger `max_count` has no effect.
+pub fn apply_header_limits(router: Router, limits: HeaderLimits) -> Router {
+    router.layer(middleware::from_fn_with_state(limits, header_limit_middleware))
+}
+
+async fn header_limit_middleware(
+    State(limits): State<HeaderLimits>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let headers = req.headers();
+    let bytes: usize = headers.iter().map(|(name, value)| name.as_str().len() + value.len()).sum();
+
+    if headers.len() > limits.max_count || bytes > limits.max_bytes {
+        tracing::warn!("Rejected request with {} headers totalling {} bytes", headers.len(), bytes);
+        let status = StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE;
+        return (
+            status,
+            Json(json!({ "error": "Request headers too large", "status": status.as_u16() })),
+        )
+            .into_response();
+    }
+
+    next.run(req).await
+}
@@ -0,0 +1,83 @@
+use crate::shared::i18n::{localize_message, Locale};
+use axum::{
+    async_trait,
+    body::{to_bytes, Body},
+    extract::{FromRequestParts, Request},
+    http::{header, request::Parts, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use std::convert::Infallible;
+
+/// Error bodies are tiny; anything larger is passed through untouched.
+const MAX_ERROR_BODY_BYTES: usize = 64 * 1024;
+
+fn request_locale(headers: &HeaderMap) -> Locale {
+    headers
+        .get(header::ACCEPT_LANGUAGE)
+        .and_then(|h| h.to_str().ok())
+        .map(Locale::negotiate)
+        .unwrap_or_default()
+}
+
+/// Extracts the caller's preferred locale from `Accept-Language`.
+#[async_trait]
+impl<S> FromRequestParts<S> for Locale
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(request_locale(&parts.headers))
+    }
+}
+
+/// Translate the `error` field of JSON error responses into the locale
+/// requested via `Accept-Language`. Messages without a translation are
+/// left in English.
+pub async fn localize_errors(req: Request<Body>, next: Next) -> Response {
+    let locale = request_locale(req.headers());
+    let response = next.run(req).await;
+
+    let is_error = response.status().is_client_error() || response.status().is_server_error();
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+
+    let too_large = response
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok()?.parse::<usize>().ok())
+        .is_some_and(|len| len > MAX_ERROR_BODY_BYTES);
+
+    if locale == Locale::En || !is_error || !is_json || too_large {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let Ok(bytes) = to_bytes(body, MAX_ERROR_BODY_BYTES).await else {
+        tracing::warn!("Failed to buffer error body for localization");
+        return Response::from_parts(parts, Body::empty());
+    };
+
+    let translated =
+        serde_json::from_slice::<serde_json::Value>(&bytes).ok().and_then(|mut json| {
+            let message = json.get("error")?.as_str()?;
+            json["error"] = localize_message(locale, message)?.into();
+            serde_json::to_vec(&json).ok()
+        });
+
+    match translated {
+        Some(body) => {
+            parts.headers.remove(header::CONTENT_LENGTH);
+            parts
+                .headers
+                .insert(header::CONTENT_LANGUAGE, HeaderValue::from_static(locale.as_str()));
+            Response::from_parts(parts, Body::from(body))
+        },
+        None => Response::from_parts(parts, Body::from(bytes)),
+    }
+}
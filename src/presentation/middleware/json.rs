@@ -0,0 +1,182 @@
+use axum::{
+    async_trait,
+    body::Bytes,
+    extract::{FromRequest, Request},
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use serde_json::error::Category;
+
+/// Code carried by every rejected JSON body
+pub const INVALID_JSON: &str = "INVALID_JSON";
+
+/// JSON request body whose rejections use the standard error envelope.
+///
+/// Malformed bodies get 400 and bodies of the wrong shape 422, both with
+/// code `INVALID_JSON` and the line and column serde stopped at. Failures
+/// reading the body itself (e.g. over the size limit) pass through as is.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonBody<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for JsonBody<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if !is_json_content_type(req.headers()) {
+            return Err(unsupported_media_type());
+        }
+
+        let bytes = Bytes::from_request(req, state).await.map_err(IntoResponse::into_response)?;
+        serde_json::from_slice(&bytes).map(JsonBody).map_err(parse_rejection)
+    }
+}
+
+/// JSON request body that may be left out entirely.
+///
+/// An empty body gives `None` whatever its content type. A non-empty one
+/// is handled as `JsonBody` would, rejections included, so a body sent
+/// with the wrong content type or shape is refused rather than ignored.
+#[derive(Debug, Clone)]
+pub struct OptionalJsonBody<T>(pub Option<T>);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for OptionalJsonBody<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let is_json = is_json_content_type(req.headers());
+        let bytes = Bytes::from_request(req, state).await.map_err(IntoResponse::into_response)?;
+        if bytes.is_empty() {
+            return Ok(Self(None));
+        }
+        if !is_json {
+            return Err(unsupported_media_type());
+        }
+        serde_json::from_slice(&bytes)
+            .map(|value| Self(Some(value)))
+            .map_err(parse_rejection)
+    }
+}
+
+/// 422 for a body of the wrong shape, 400 for anything else unparseable
+fn parse_rejection(e: serde_json::Error) -> Response {
+    let status = match e.classify() {
+        Category::Data => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => StatusCode::BAD_REQUEST,
+    };
+    invalid_json(status, describe(&e))
+}
+
+fn unsupported_media_type() -> Response {
+    invalid_json(
+        StatusCode::UNSUPPORTED_MEDIA_TYPE,
+        "Expected request with `Content-Type: application/json`".to_string(),
+    )
+}
+
+fn is_json_content_type(headers: &HeaderMap) -> bool {
+    let Some(content_type) = headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+    let essence = content_type.split(';').next().unwrap_or_default().trim().to_ascii_lowercase();
+    essence == "application/json"
+        || (essence.starts_with("application/") && essence.ends_with("+json"))
+}
+
+/// Client-facing account of a parse failure. serde quotes the offending
+/// value in type errors, which may be a password, so only messages naming
+/// fields are passed through.
+fn describe(err: &serde_json::Error) -> String {
+    let at = format!("line {}, column {}", err.line(), err.column());
+    match err.classify() {
+        Category::Eof => format!("Request body ended unexpectedly at {}", at),
+        Category::Syntax | Category::Io => format!("Request body is not valid JSON at {}", at),
+        Category::Data => {
+            let message = err.to_string();
+            let reason = message.rsplit_once(" at line ").map_or(message.as_str(), |(r, _)| r);
+            if reason.starts_with("missing field") || reason.starts_with("unknown field") {
+                format!("Invalid request body: {} at {}", reason, at)
+            } else {
+                format!("Invalid request body: unexpected type or value at {}", at)
+            }
+        },
+    }
+}
+
+fn invalid_json(status: StatusCode, message: String) -> Response {
+    let body = Json(serde_json::json!({
+        "success": false,
+        "error": message,
+        "code": INVALID_JSON,
+    }));
+    (status, body).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, serde::Deserialize)]
+    #[allow(dead_code)] // Only deserialized
+    struct Login {
+        email: String,
+        password: String,
+    }
+
+    fn parse_error(body: &str) -> serde_json::Error {
+        serde_json::from_str::<Login>(body).unwrap_err()
+    }
+
+    #[test]
+    fn syntax_errors_report_where_parsing_stopped() {
+        assert_eq!(
+            describe(&parse_error("{\"email\": \"a@b.co\",\n \"password\" 1}")),
+            "Request body is not valid JSON at line 2, column 13"
+        );
+        assert_eq!(
+            describe(&parse_error("{\"email\": ")),
+            "Request body ended unexpectedly at line 1, column 10"
+        );
+    }
+
+    #[test]
+    fn data_errors_name_fields_but_never_echo_values() {
+        assert_eq!(
+            describe(&parse_error("{\"email\": \"a@b.co\"}")),
+            "Invalid request body: missing field `password` at line 1, column 19"
+        );
+        let wrong_type = describe(&parse_error("{\"email\": \"a@b.co\", \"password\": 86753099}"));
+        assert_eq!(
+            wrong_type,
+            "Invalid request body: unexpected type or value at line 1, column 40"
+        );
+        assert!(!wrong_type.contains("86753099"));
+    }
+
+    #[test]
+    fn accepts_json_and_json_suffixed_media_types() {
+        let with = |value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::CONTENT_TYPE, header::HeaderValue::from_static(value));
+            is_json_content_type(&headers)
+        };
+
+        assert!(with("application/json"));
+        assert!(with("Application/JSON; charset=utf-8"));
+        assert!(with("application/merge-patch+json"));
+        assert!(!with("text/plain"));
+        assert!(!with("application/jsonp"));
+        assert!(!is_json_content_type(&HeaderMap::new()));
+    }
+}
@@ -0,0 +1,93 @@
+use crate::{config::metrics::MetricsAuth, shared::utils::hash_token};
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use std::sync::Arc;
+
+/// Rejects `/metrics` scrapes that do not carry the configured credentials.
+pub async fn metrics_auth_middleware(
+    State(auth): State<Arc<MetricsAuth>>,
+    req: Request<Body>,
+    next: Next,
+) -> Response {
+    let authorized = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .is_some_and(|h| is_authorized(&auth, h));
+
+    if !authorized {
+        let challenge = match auth.as_ref() {
+            MetricsAuth::Bearer(_) => "Bearer realm=\"metrics\"",
+            MetricsAuth::Basic { .. } => "Basic realm=\"metrics\"",
+        };
+
+        let body = Json(serde_json::json!({
+            "success": false,
+            "error": "Missing or invalid metrics credentials",
+        }));
+
+        return (StatusCode::UNAUTHORIZED, [(header::WWW_AUTHENTICATE, challenge)], body)
+            .into_response();
+    }
+
+    next.run(req).await
+}
+
+fn is_authorized(auth: &MetricsAuth, header_value: &str) -> bool {
+    match auth {
+        MetricsAuth::Bearer(token) => header_value
+            .strip_prefix("Bearer ")
+            .is_some_and(|presented| secrets_match(presented, token)),
+        MetricsAuth::Basic { username, password } => header_value
+            .strip_prefix("Basic ")
+            .and_then(|encoded| STANDARD.decode(encoded.trim()).ok())
+            .and_then(|decoded| String::from_utf8(decoded).ok())
+            .is_some_and(|decoded| secrets_match(&decoded, &format!("{}:{}", username, password))),
+    }
+}
+
+/// Compare digests rather than the raw strings so the comparison time
+/// does not depend on how long a matching prefix the caller guessed.
+fn secrets_match(presented: &str, expected: &str) -> bool {
+    hash_token(presented) == hash_token(expected)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn basic(credentials: &str) -> String {
+        format!("Basic {}", STANDARD.encode(credentials))
+    }
+
+    #[test]
+    fn bearer_token_must_match_exactly() {
+        let auth = MetricsAuth::Bearer("scrape-secret".to_string());
+
+        assert!(is_authorized(&auth, "Bearer scrape-secret"));
+        assert!(!is_authorized(&auth, "Bearer scrape-secre"));
+        assert!(!is_authorized(&auth, "scrape-secret"));
+        assert!(!is_authorized(&auth, &basic("prometheus:scrape-secret")));
+    }
+
+    #[test]
+    fn basic_credentials_must_match_exactly() {
+        let auth = MetricsAuth::Basic {
+            username: "prometheus".to_string(),
+            password: "s3cret".to_string(),
+        };
+
+        assert!(is_authorized(&auth, &basic("prometheus:s3cret")));
+        assert!(!is_authorized(&auth, &basic("prometheus:wrong")));
+        assert!(!is_authorized(&auth, &basic("other:s3cret")));
+        assert!(!is_authorized(&auth, "Basic not-base64!"));
+        assert!(!is_authorized(&auth, "Bearer s3cret"));
+    }
+}
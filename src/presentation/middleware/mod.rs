@@ -1,6 +1,30 @@
 // Middleware implementations
 pub mod auth;
+pub mod cache_control;
+pub mod client_ip;
+pub mod concurrency_limit;
+pub mod header_limit;
+pub mod i18n;
+pub mod json;
+pub mod metrics_auth;
+pub mod panic;
+pub mod prefer;
+pub mod query;
 pub mod rate_limit;
+pub mod trace_context;
+pub mod transaction;
 
 pub use auth::{auth_middleware, AuthMiddlewareError};
+pub use cache_control::set_cache_control;
+pub use client_ip::{ClientIp, ClientIpKeyExtractor, TrustedProxies};
+pub use concurrency_limit::apply_concurrency_limit;
+pub use header_limit::{apply_header_limits, HeaderLimits};
+pub use i18n::localize_errors;
+pub use json::{JsonBody, OptionalJsonBody};
+pub use metrics_auth::metrics_auth_middleware;
+pub use panic::catch_panic_layer;
+pub use prefer::{minimal_response, ReturnPreference};
+pub use query::ValidatedQuery;
 pub use rate_limit::apply_rate_limit;
+pub use trace_context::trace_context_middleware;
+pub use transaction::{transaction_middleware, Tx};
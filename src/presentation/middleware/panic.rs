@@ -0,0 +1,134 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::json;
+use std::{any::Any, backtrace::Backtrace, cell::RefCell, sync::Once};
+use tower_http::catch_panic::{CatchPanicLayer, ResponseForPanic};
+
+thread_local! {
+    /// Backtrace of the most recent panic on this thread, captured by the hook
+    /// because the unwind payload carries none
+    static LAST_BACKTRACE: RefCell<Option<Backtrace>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// Wrap a panic hook around the existing one so caught panics keep their
+/// backtrace. Safe to call repeatedly; only the first call installs it.
+fn install_backtrace_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = std::panic::take_hook();
+        std::panic::set_hook(Box::new(move |info| {
+            LAST_BACKTRACE.with(|slot| *slot.borrow_mut() = Some(Backtrace::force_capture()));
+            previous(info);
+        }));
+    });
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("non-string panic payload")
+}
+
+/// Turns a handler panic into a JSON 500 and logs it with its backtrace
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PanicResponder;
+
+impl ResponseForPanic for PanicResponder {
+    type ResponseBody = axum::body::Body;
+
+    fn response_for_panic(&mut self, err: Box<dyn Any + Send + 'static>) -> Response {
+        let backtrace = LAST_BACKTRACE
+            .with(|slot| slot.borrow_mut().take())
+            .map(|bt| bt.to_string())
+            .unwrap_or_default();
+
+        tracing::error!(
+            panic.message = panic_message(err.as_ref()),
+            panic.backtrace = %backtrace,
+            "Request handler panicked"
+        );
+
+        let body = Json(json!({
+            "success": false,
+            "error": "Internal server error",
+            "code": "INTERNAL_PANIC",
+        }));
+        (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
+    }
+}
+
+/// Layer that keeps a panicking handler from taking down the connection.
+/// Needs `panic = "unwind"`; under abort the process exits before it runs.
+pub fn catch_panic_layer() -> CatchPanicLayer<PanicResponder> {
+    install_backtrace_hook();
+    CatchPanicLayer::custom(PanicResponder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request, routing::get, Router};
+    use std::sync::{Arc, Mutex};
+    use tower::ServiceExt;
+
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl std::io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[allow(clippy::panic)]
+    async fn boom() -> &'static str {
+        panic!("kaboom")
+    }
+
+    #[tokio::test]
+    async fn panicking_handler_returns_structured_500_and_logs_backtrace() {
+        let logs = Captured::default();
+        let writer = logs.clone();
+        let subscriber =
+            tracing_subscriber::fmt().json().with_writer(move || writer.clone()).finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let app = Router::new()
+            .route("/boom", get(boom))
+            .route("/ok", get(|| async { "fine" }))
+            .layer(catch_panic_layer());
+
+        let response = app
+            .clone()
+            .oneshot(Request::get("/boom").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["success"], false);
+        assert_eq!(body["error"], "Internal server error");
+        assert_eq!(body["code"], "INTERNAL_PANIC");
+
+        let log = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        let entry: serde_json::Value = serde_json::from_str(log.lines().last().unwrap()).unwrap();
+        assert_eq!(entry["fields"]["panic.message"], "kaboom");
+        assert!(!entry["fields"]["panic.backtrace"].as_str().unwrap().is_empty());
+
+        // The service keeps serving after the panic
+        let response = app.oneshot(Request::get("/ok").body(Body::empty()).unwrap()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
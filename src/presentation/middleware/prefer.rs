@@ -0,0 +1,99 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{header::HeaderName, request::Parts, HeaderMap, StatusCode},
+    response::{IntoResponse, IntoResponseParts, Response},
+};
+use std::convert::Infallible;
+
+pub const PREFER: HeaderName = HeaderName::from_static("prefer");
+pub const PREFERENCE_APPLIED: HeaderName = HeaderName::from_static("preference-applied");
+
+/// What a write sends back, per the `return` preference of RFC 7240
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReturnPreference {
+    /// The full resource, as when no preference is sent
+    #[default]
+    Representation,
+    /// Status and `Location` only
+    Minimal,
+}
+
+impl ReturnPreference {
+    /// The first `return=` preference across all `Prefer` headers; unknown
+    /// preferences and parameters are ignored
+    fn from_headers(headers: &HeaderMap) -> Self {
+        headers
+            .get_all(PREFER)
+            .iter()
+            .filter_map(|v| v.to_str().ok())
+            .flat_map(|v| v.split(','))
+            .filter_map(|pref| {
+                let pref = pref.split(';').next().unwrap_or_default();
+                let (name, value) = pref.split_once('=')?;
+                if !name.trim().eq_ignore_ascii_case("return") {
+                    return None;
+                }
+                match value.trim().trim_matches('"').to_ascii_lowercase().as_str() {
+                    "minimal" => Some(Self::Minimal),
+                    "representation" => Some(Self::Representation),
+                    _ => None,
+                }
+            })
+            .next()
+            .unwrap_or_default()
+    }
+}
+
+/// Reads the caller's `return` preference from `Prefer`.
+#[async_trait]
+impl<S> FromRequestParts<S> for ReturnPreference
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        Ok(Self::from_headers(&parts.headers))
+    }
+}
+
+/// 204 with `location`, noting that `return=minimal` was honoured
+pub fn minimal_response(location: impl IntoResponseParts) -> Response {
+    (StatusCode::NO_CONTENT, location, [(PREFERENCE_APPLIED, "return=minimal")]).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    fn preference(values: &[&'static str]) -> ReturnPreference {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append(PREFER, HeaderValue::from_static(value));
+        }
+        ReturnPreference::from_headers(&headers)
+    }
+
+    #[test]
+    fn minimal_is_honoured_among_other_preferences() {
+        assert_eq!(preference(&["return=minimal"]), ReturnPreference::Minimal);
+        assert_eq!(preference(&["respond-async, RETURN = \"Minimal\""]), ReturnPreference::Minimal);
+        assert_eq!(preference(&["wait=10", "return=minimal; foo=bar"]), ReturnPreference::Minimal);
+    }
+
+    #[test]
+    fn anything_else_keeps_the_full_representation() {
+        assert_eq!(preference(&[]), ReturnPreference::Representation);
+        assert_eq!(preference(&["return=representation"]), ReturnPreference::Representation);
+        assert_eq!(
+            preference(&["return=nothing", "handling=lenient"]),
+            ReturnPreference::Representation
+        );
+        assert_eq!(
+            preference(&["return=representation", "return=minimal"]),
+            ReturnPreference::Representation
+        );
+    }
+}
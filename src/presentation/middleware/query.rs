@@ -0,0 +1,105 @@
+use axum::{
+    async_trait,
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use std::collections::BTreeMap;
+use validator::Validate;
+
+/// Code carried by every rejected query string
+pub const INVALID_QUERY: &str = "INVALID_QUERY";
+
+/// Query string deserialized into `T` and checked with its `Validate` rules.
+///
+/// Values that do not parse and values that break a rule both get 400 with
+/// code `INVALID_QUERY` and the messages for each offending parameter under
+/// `fields`, rather than falling back to a default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ValidatedQuery<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequestParts<S> for ValidatedQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let query = parts.uri.query().unwrap_or_default();
+        let value: T = parse(query).map_err(invalid_query)?;
+        value.validate().map_err(|e| invalid_query(field_messages(&e)))?;
+        Ok(ValidatedQuery(value))
+    }
+}
+
+/// Parameter name to the messages explaining what is wrong with it
+type FieldMessages = BTreeMap<String, Vec<String>>;
+
+fn parse<T: DeserializeOwned>(query: &str) -> Result<T, FieldMessages> {
+    let deserializer =
+        serde_urlencoded::Deserializer::new(form_urlencoded::parse(query.as_bytes()));
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let field = match e.path().to_string() {
+            path if path == "." => "query".to_string(),
+            path => path,
+        };
+        BTreeMap::from([(field, vec![e.into_inner().to_string()])])
+    })
+}
+
+fn field_messages(errors: &validator::ValidationErrors) -> FieldMessages {
+    errors
+        .field_errors()
+        .into_iter()
+        .map(|(field, errors)| {
+            (field.to_string(), errors.iter().map(ToString::to_string).collect())
+        })
+        .collect()
+}
+
+fn invalid_query(fields: FieldMessages) -> Response {
+    let body = Json(serde_json::json!({
+        "success": false,
+        "error": "Invalid query parameters",
+        "code": INVALID_QUERY,
+        "fields": fields,
+    }));
+    (StatusCode::BAD_REQUEST, body).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, serde::Deserialize, Validate)]
+    #[allow(dead_code)] // Only deserialized
+    struct Page {
+        #[validate(range(min = 1, message = "must be at least 1"))]
+        page: i64,
+        active: Option<bool>,
+    }
+
+    #[test]
+    fn parse_errors_name_the_parameter() {
+        let fields = parse::<Page>("page=two").unwrap_err();
+        assert_eq!(fields["page"], ["invalid digit found in string"]);
+
+        let fields = parse::<Page>("page=1&active=maybe").unwrap_err();
+        assert!(fields.contains_key("active"), "{:?}", fields);
+    }
+
+    #[test]
+    fn rule_violations_are_listed_per_field() {
+        let page = parse::<Page>("page=0").unwrap();
+        let fields = field_messages(&page.validate().unwrap_err());
+
+        assert_eq!(
+            fields,
+            BTreeMap::from([("page".to_string(), vec!["must be at least 1".to_string()])])
+        );
+    }
+}
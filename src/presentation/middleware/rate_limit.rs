@@ -1,80 +1,100 @@
-use axum::body::Body;
-use axum::http::response::Response;
-use axum::http::{header, StatusCode};
-use axum::Router;
-use std::sync::Arc;
-use tower_governor::{
-    governor::GovernorConfigBuilder, key_extractor::SmartIpKeyExtractor, GovernorError,
-    GovernorLayer,
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{HeaderName, HeaderValue, StatusCode},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    Json, Router,
 };
+use std::{net::IpAddr, sync::Arc, time::Duration};
+
+use crate::shared::{
+    errors::AppError,
+    rate_limiter::{RateLimitAlgorithm, RateLimiter},
+};
+
+use super::client_ip::{ClientIpKeyExtractor, TrustedProxies};
+
+/// Requests a client may send per window
+pub const RATE_LIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+
+/// Requests the client may still send right now
+pub const RATE_LIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+
+/// Seconds until the client's quota is full again
+pub const RATE_LIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+struct ClientRateLimit {
+    limiter: RateLimiter<IpAddr>,
+    key: ClientIpKeyExtractor,
+}
 
 /// Apply rate limiting to a router based on client IP.
 ///
-/// Uses `SmartIpKeyExtractor` which checks `X-Forwarded-For` and `X-Real-IP`
-/// headers before falling back to the peer socket address.
+/// Each client may send `burst_size` requests, regaining one every
+/// `per_second` seconds; `algorithm` decides how bursts within that rate
+/// are treated (see [`RateLimitAlgorithm`]).
+///
+/// Keys on the address resolved by [`ClientIpKeyExtractor`]: forwarding
+/// headers count only when the peer is one of `trusted_proxies`.
 ///
-/// Returns HTTP 429 with JSON body and `Retry-After` header when the limit is exceeded.
-pub fn apply_rate_limit(router: Router, per_second: u64, burst_size: u32) -> Router {
-    // SAFETY: GovernorConfigBuilder only returns None when per_second is 0.
-    // We validate at the config layer that per_second defaults to 2.
-    #[allow(clippy::expect_used)]
-    let config = Arc::new(
-        GovernorConfigBuilder::default()
-            .per_second(per_second)
-            .burst_size(burst_size)
-            .key_extractor(SmartIpKeyExtractor)
-            .error_handler(rate_limit_error_handler)
-            .finish()
-            .expect("GovernorConfig: per_second must be > 0"),
-    );
+/// Every response carries the client's quota in `X-RateLimit-Limit`,
+/// `X-RateLimit-Remaining` and `X-RateLimit-Reset`. Returns HTTP 429 with
+/// `Retry-After` and a matching `retry_after_seconds` body field when the
+/// limit is exceeded.
+pub fn apply_rate_limit(
+    router: Router,
+    per_second: u64,
+    burst_size: u32,
+    algorithm: RateLimitAlgorithm,
+    trusted_proxies: TrustedProxies,
+) -> Router {
+    let window = Duration::from_secs(per_second.max(1)).saturating_mul(burst_size.max(1));
+    let state = Arc::new(ClientRateLimit {
+        limiter: RateLimiter::new(algorithm, burst_size, window),
+        key: ClientIpKeyExtractor::new(trusted_proxies),
+    });
 
-    router.layer(GovernorLayer { config })
+    router.layer(middleware::from_fn_with_state(state, rate_limit))
 }
 
-fn rate_limit_error_handler(error: GovernorError) -> Response<Body> {
-    let (status, body_json) = match &error {
-        GovernorError::TooManyRequests { wait_time, .. } => (
-            StatusCode::TOO_MANY_REQUESTS,
-            serde_json::json!({
-                "success": false,
-                "error": format!("Too many requests. Please try again in {}s.", wait_time)
-            }),
-        ),
-        GovernorError::UnableToExtractKey => (
+async fn rate_limit(
+    State(state): State<Arc<ClientRateLimit>>,
+    req: Request,
+    next: Next,
+) -> Response<Body> {
+    let Some(client) = state.key.extract(&req) else {
+        return (
             StatusCode::INTERNAL_SERVER_ERROR,
-            serde_json::json!({
+            Json(serde_json::json!({
                 "success": false,
                 "error": "Unable to identify client"
-            }),
-        ),
-        GovernorError::Other { code, msg, .. } => (
-            *code,
-            serde_json::json!({
-                "success": false,
-                "error": msg.clone().unwrap_or_else(|| "Rate limit error".to_string())
-            }),
-        ),
+            })),
+        )
+            .into_response();
     };
 
-    let retry_after = match &error {
-        GovernorError::TooManyRequests { wait_time, .. } => Some(wait_time.to_string()),
-        _ => None,
+    let (mut response, quota) = match state.limiter.check(&client) {
+        Ok(quota) => (next.run(req).await, quota),
+        Err(throttled) => {
+            // Round up so a client honouring the header is not refused again
+            let wait_secs = ceil_secs(throttled.retry_after).max(1);
+            let response = AppError::TooManyRequests {
+                message: format!("Too many requests. Please try again in {}s.", wait_secs),
+                retry_after_secs: wait_secs,
+            }
+            .into_response();
+            (response, throttled.quota)
+        },
     };
 
-    let body_bytes = serde_json::to_vec(&body_json).unwrap_or_default();
-
-    let mut builder = Response::builder()
-        .status(status)
-        .header(header::CONTENT_TYPE, "application/json");
-
-    if let Some(secs) = retry_after {
-        builder = builder.header(header::RETRY_AFTER, secs);
-    }
+    let headers = response.headers_mut();
+    headers.insert(RATE_LIMIT_LIMIT, HeaderValue::from(quota.limit));
+    headers.insert(RATE_LIMIT_REMAINING, HeaderValue::from(quota.remaining));
+    headers.insert(RATE_LIMIT_RESET, HeaderValue::from(ceil_secs(quota.reset)));
+    response
+}
 
-    builder.body(Body::from(body_bytes)).unwrap_or_else(|_| {
-        Response::builder()
-            .status(StatusCode::INTERNAL_SERVER_ERROR)
-            .body(Body::from("Internal server error"))
-            .unwrap_or_default()
-    })
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
 }
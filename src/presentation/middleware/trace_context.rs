@@ -0,0 +1,15 @@
+use crate::shared::telemetry::{trace_context::TRACEPARENT, TraceContext};
+use axum::{body::Body, extract::Request, middleware::Next, response::Response};
+
+/// Serve the request as a span of the trace named by its `traceparent`
+/// header, or of a new trace when it has none or it is malformed.
+pub async fn trace_context_middleware(req: Request<Body>, next: Next) -> Response {
+    let ctx = req
+        .headers()
+        .get(TRACEPARENT)
+        .and_then(|h| h.to_str().ok())
+        .and_then(TraceContext::extract)
+        .map_or_else(TraceContext::new_root, |parent| parent.child());
+
+    ctx.scope(next.run(req)).await
+}
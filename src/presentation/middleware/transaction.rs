@@ -0,0 +1,78 @@
+use crate::{
+    infrastructure::database::{transaction::RequestTransaction, DbPool},
+    shared::AppError,
+};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Request, State},
+    http::{request::Parts, Method},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+/// The request's transaction, for building repositories that write in it.
+/// Available inside routes wrapped by `transaction_middleware` for
+/// POST/PUT/PATCH/DELETE requests.
+#[derive(Clone)]
+pub struct Tx(pub RequestTransaction);
+
+#[async_trait]
+impl<S> FromRequestParts<S> for Tx
+where
+    S: Send + Sync,
+{
+    type Rejection = AppError;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<RequestTransaction>().cloned().map(Tx).ok_or_else(|| {
+            AppError::Internal(anyhow::anyhow!("Tx extractor used outside transaction_middleware"))
+        })
+    }
+}
+
+fn is_mutating(method: &Method) -> bool {
+    matches!(*method, Method::POST | Method::PUT | Method::PATCH | Method::DELETE)
+}
+
+/// Open a transaction for each mutating request and hand it to the handler
+/// as `Tx`. A 2xx response commits; anything else, including a failed
+/// extractor, rolls back.
+///
+/// Only repositories built on the `Tx` take part; ones holding the pool
+/// still commit each statement on its own.
+pub async fn transaction_middleware(
+    State(pool): State<DbPool>,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    if !is_mutating(req.method()) {
+        return next.run(req).await;
+    }
+
+    let conn = match pool.get().await {
+        Ok(conn) => conn,
+        Err(e) => {
+            tracing::error!("Failed to get connection for request transaction: {}", e);
+            return AppError::ServiceUnavailable("Database unavailable".to_string())
+                .into_response();
+        },
+    };
+    let tx = match RequestTransaction::begin(conn).await {
+        Ok(tx) => tx,
+        Err(e) => return AppError::Database(e).into_response(),
+    };
+    req.extensions_mut().insert(tx.clone());
+
+    let response = next.run(req).await;
+
+    if response.status().is_success() {
+        if let Err(e) = tx.commit().await {
+            tracing::error!("Failed to commit request transaction: {}", e);
+            return AppError::Database(e).into_response();
+        }
+    } else if let Err(e) = tx.rollback().await {
+        tracing::error!("Failed to roll back request transaction: {}", e);
+    }
+
+    response
+}
@@ -2,6 +2,7 @@ pub mod handlers;
 pub mod middleware;
 pub mod responses;
 pub mod routes;
+pub mod server;
 
 pub use responses::ApiResponse;
 pub use routes::create_router;
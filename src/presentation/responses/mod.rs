@@ -1,9 +1,13 @@
 use crate::application::dto::{
-    auth::{AuthResponse, RegisterResponse},
-    user::UserResponseDto,
+    auth::{AuthResponse, RegisterResponse, SessionDto, VerifyEmailResponse},
+    user::{
+        DeactivateUsersResponseDto, ImportJobDto, ImportReportDto, InvitationResponseDto,
+        UserResponseDto,
+    },
+    PaginationMeta,
 };
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderName, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -18,6 +22,9 @@ pub struct ApiResponse<T> {
     pub data: Option<T>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
+    /// Present on paginated list responses
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<PaginationMeta>,
 }
 
 // Documentation-only concrete response schemas to fix generic resolution issues
@@ -47,6 +54,42 @@ pub struct UserListResponseWrapper {
     pub success: bool,
     pub data: Option<Vec<UserResponseDto>>,
     pub error: Option<String>,
+    pub meta: Option<PaginationMeta>,
+}
+
+#[derive(ToSchema)]
+pub struct DeactivateUsersResponseWrapper {
+    pub success: bool,
+    pub data: Option<DeactivateUsersResponseDto>,
+    pub error: Option<String>,
+}
+
+#[derive(ToSchema)]
+pub struct ImportJobResponseWrapper {
+    pub success: bool,
+    pub data: Option<ImportJobDto>,
+    pub error: Option<String>,
+}
+
+#[derive(ToSchema)]
+pub struct ImportReportResponseWrapper {
+    pub success: bool,
+    pub data: Option<ImportReportDto>,
+    pub error: Option<String>,
+}
+
+#[derive(ToSchema)]
+pub struct InvitationResponseWrapper {
+    pub success: bool,
+    pub data: Option<InvitationResponseDto>,
+    pub error: Option<String>,
+}
+
+#[derive(ToSchema)]
+pub struct SessionListResponseWrapper {
+    pub success: bool,
+    pub data: Option<Vec<SessionDto>>,
+    pub error: Option<String>,
 }
 
 #[derive(ToSchema)]
@@ -56,6 +99,13 @@ pub struct StringResponseWrapper {
     pub error: Option<String>,
 }
 
+#[derive(ToSchema)]
+pub struct VerifyEmailResponseWrapper {
+    pub success: bool,
+    pub data: Option<VerifyEmailResponse>,
+    pub error: Option<String>,
+}
+
 #[derive(ToSchema)]
 pub struct RoleResponseWrapper {
     pub success: bool,
@@ -63,6 +113,14 @@ pub struct RoleResponseWrapper {
     pub error: Option<String>,
 }
 
+#[derive(ToSchema)]
+pub struct ConfigResponseWrapper {
+    pub success: bool,
+    #[schema(value_type = Option<Object>)]
+    pub data: Option<serde_json::Value>,
+    pub error: Option<String>,
+}
+
 #[derive(ToSchema)]
 pub struct ErrorResponseWrapper {
     pub success: bool,
@@ -72,14 +130,25 @@ pub struct ErrorResponseWrapper {
 
 impl<T: Serialize> ApiResponse<T> {
     pub fn success(data: T) -> Self {
-        Self { success: true, data: Some(data), error: None }
+        Self { success: true, data: Some(data), error: None, meta: None }
+    }
+
+    pub fn with_meta(mut self, meta: PaginationMeta) -> Self {
+        self.meta = Some(meta);
+        self
     }
 
     pub fn error(message: impl Into<String>) -> ApiResponse<()> {
-        ApiResponse { success: false, data: None, error: Some(message.into()) }
+        ApiResponse { success: false, data: None, error: Some(message.into()), meta: None }
     }
 }
 
+/// `Location` header pointing at the user `id`, for writes that created or
+/// changed it
+pub fn user_location(id: &str) -> [(HeaderName, String); 1] {
+    [(header::LOCATION, format!("/api/users/{}", id))]
+}
+
 impl<T: Serialize> IntoResponse for ApiResponse<T> {
     fn into_response(self) -> Response {
         let status = if self.success { StatusCode::OK } else { StatusCode::BAD_REQUEST };
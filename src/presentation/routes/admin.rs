@@ -0,0 +1,71 @@
+use crate::presentation::middleware::{
+    auth::{auth_middleware, require_role, AuthState},
+    set_cache_control,
+};
+use crate::{
+    application::{
+        commands::{CreateInvitationCommand, DeactivateUsersCommand, ForcePasswordResetCommand},
+        queries::ExportUsersQuery,
+        services::email::EmailService,
+    },
+    config::AppConfig,
+    domain::value_objects::UserRole,
+    infrastructure::database::{
+        repositories::{
+            AuditRepositoryImpl, AuthRepositoryImpl, InvitationRepositoryImpl, UserRepositoryImpl,
+        },
+        DbPool,
+    },
+    presentation::handlers::{
+        monitoring::effective_config,
+        user::{create_invitation, deactivate_users, export_users_csv, force_password_reset},
+    },
+    shared::cache_control::CachePolicy,
+};
+use axum::{
+    middleware,
+    routing::{get, post},
+    Router,
+};
+use std::sync::Arc;
+
+/// Create admin-only routes
+pub fn admin_routes(
+    pool: DbPool,
+    auth_repo: Arc<AuthRepositoryImpl>,
+    auth_state: AuthState,
+    email_service: Arc<dyn EmailService>,
+    config: &AppConfig,
+) -> Router {
+    let audit_repo = Arc::new(AuditRepositoryImpl::new(pool.clone()));
+    let user_repo = Arc::new(UserRepositoryImpl::new(pool.clone()));
+    let export_users_query = Arc::new(ExportUsersQuery::new(user_repo));
+    let force_password_reset_command = Arc::new(
+        ForcePasswordResetCommand::new(
+            auth_repo.clone(),
+            audit_repo.clone(),
+            email_service,
+            config.confirm_code_expiry,
+        )
+        .with_code_hasher(Arc::new(config.confirmation_code_hasher.clone())),
+    );
+    let deactivate_users_command = Arc::new(DeactivateUsersCommand::new(auth_repo, audit_repo));
+    let create_invitation_command = Arc::new(CreateInvitationCommand::new(
+        Arc::new(InvitationRepositoryImpl::new(pool.clone())),
+        config.invitation_ttl,
+    ));
+    let redacted_config = Arc::new(config.redacted());
+
+    Router::new()
+        .route("/users/export.csv", get(export_users_csv).with_state(export_users_query))
+        .route("/users/deactivate", post(deactivate_users).with_state(deactivate_users_command))
+        .route(
+            "/users/:id/password-reset",
+            post(force_password_reset).with_state(force_password_reset_command),
+        )
+        .route("/invitations", post(create_invitation).with_state(create_invitation_command))
+        .route("/config", get(effective_config).with_state(redacted_config))
+        .route_layer(middleware::from_fn_with_state(UserRole::Admin, require_role))
+        .layer(middleware::from_fn_with_state(auth_state, auth_middleware))
+        .layer(middleware::from_fn_with_state(CachePolicy::NoStore, set_cache_control))
+}
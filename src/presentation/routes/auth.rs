@@ -1,29 +1,54 @@
 use crate::{
+    application::commands::{
+        RefreshTokenCommand, RevokeSessionCommand, SendPhoneCodeCommand, VerifyPhoneCommand,
+    },
+    application::queries::ListSessionsQuery,
     application::use_cases::{
-        ForgotPasswordUseCase, LoginUseCase, LogoutUseCase, RegisterUseCase, SetPasswordUseCase,
+        ForgotPasswordUseCase, GetUserUseCase, LoginUseCase, LogoutUseCase, RegisterUseCase,
         VerifyEmailUseCase,
     },
-    domain::repositories::AuthRepository,
-    presentation::handlers::auth::{self, CookieConfig},
+    domain::repositories::{user_repository::UserRepository, AuthRepository},
+    infrastructure::database::DbPool,
+    presentation::handlers::auth::{self, CookieConfig, RegistrationGate, SetPasswordFactory},
+};
+use axum::{
+    middleware,
+    routing::{delete, get, post},
+    Extension, Router,
 };
-use axum::{middleware, routing::post, Extension, Router};
 use std::sync::Arc;
 
-use crate::presentation::middleware::auth::{auth_middleware, AuthState};
+use crate::presentation::middleware::set_cache_control;
+use crate::presentation::middleware::TrustedProxies;
+use crate::presentation::middleware::{
+    auth::{auth_middleware, token_middleware, AuthState},
+    transaction_middleware,
+};
+use crate::shared::{cache_control::CachePolicy, rate_limiter::RateLimitAlgorithm};
 
 #[allow(clippy::too_many_arguments)]
-pub fn create_auth_routes<R: AuthRepository + 'static>(
+pub fn create_auth_routes<R: AuthRepository + 'static, U: UserRepository + 'static>(
     register_uc: Arc<RegisterUseCase<R>>,
     login_uc: Arc<LoginUseCase<R>>,
     logout_uc: Arc<LogoutUseCase<R>>,
+    list_sessions_query: Arc<ListSessionsQuery<R>>,
+    revoke_session_command: Arc<RevokeSessionCommand<R>>,
+    refresh_command: Arc<RefreshTokenCommand<R>>,
     verify_uc: Arc<VerifyEmailUseCase<R>>,
-    set_password_uc: Arc<SetPasswordUseCase<R>>,
+    set_password_uc: SetPasswordFactory<R>,
+    pool: DbPool,
     forgot_password_uc: Arc<ForgotPasswordUseCase<R>>,
+    send_phone_code_command: Arc<SendPhoneCodeCommand<R>>,
+    verify_phone_command: Arc<VerifyPhoneCommand<R>>,
     resend_code_uc: Arc<crate::application::use_cases::ResendConfirmCodeUseCase<R>>,
-    jwt_manager: Arc<crate::shared::utils::jwt::JwtManager>,
+    me_uc: Arc<GetUserUseCase<U>>,
+    auth_state: AuthState,
     cookie_config: Arc<CookieConfig>,
+    registration: Arc<RegistrationGate>,
     rate_limit_per_second: u64,
     rate_limit_burst_size: u32,
+    rate_limit_algorithm: RateLimitAlgorithm,
+    trusted_proxies: TrustedProxies,
 ) -> Router {
     // Public routes (no authentication required)
     let public_routes = Router::new()
@@ -31,33 +56,61 @@ pub fn create_auth_routes<R: AuthRepository + 'static>(
         .with_state(register_uc)
         .route("/login", post(auth::login::<R>))
         .with_state(login_uc)
+        // Public: the access token may already have expired
+        .route("/refresh", post(auth::refresh::<R>))
+        .with_state(refresh_command)
         .route("/verify", post(auth::verify_email::<R>))
+        .route(
+            "/verify-link",
+            get(auth::verify_email_link_page).post(auth::verify_email_link::<R>),
+        )
         .with_state(verify_uc)
-        .route("/password", post(auth::set_password::<R>))
+        // The password and its history entry are written together
+        .route(
+            "/password",
+            post(auth::set_password::<R>)
+                .route_layer(middleware::from_fn_with_state(pool, transaction_middleware)),
+        )
         .with_state(set_password_uc)
         .route("/forgot-password", post(auth::forgot_password::<R>))
         .with_state(forgot_password_uc)
         .route("/resend-code", post(auth::resend_code::<R>))
         .with_state(resend_code_uc);
 
-    // Create auth state for middleware
-    let auth_state = AuthState { jwt_manager };
-
     // Protected routes (authentication required)
     let protected_routes = Router::new()
         .route("/logout", post(auth::logout::<R>))
         .with_state(logout_uc.clone())
-        .layer(middleware::from_fn_with_state(auth_state, auth_middleware));
+        .route("/sessions", get(auth::list_sessions::<R>))
+        .with_state(list_sessions_query)
+        .route("/sessions/:id", delete(auth::revoke_session::<R>))
+        .with_state(revoke_session_command)
+        .route("/phone", post(auth::send_phone_code::<R>))
+        .with_state(send_phone_code_command)
+        .route("/phone/verify", post(auth::verify_phone::<R>))
+        .with_state(verify_phone_command)
+        .layer(middleware::from_fn_with_state(auth_state.clone(), auth_middleware));
+
+    // A deleted user's token still names them, so /me can answer 404
+    let me_routes = Router::new()
+        .route("/me", get(auth::me::<U>))
+        .with_state(me_uc)
+        .layer(middleware::from_fn_with_state(auth_state, token_middleware));
 
     // Combine routes — attach cookie config and rate limiting
     let router = Router::new()
         .merge(public_routes)
         .merge(protected_routes)
-        .layer(Extension(cookie_config));
+        .merge(me_routes)
+        .layer(Extension(cookie_config))
+        .layer(Extension(registration))
+        .layer(middleware::from_fn_with_state(CachePolicy::NoStore, set_cache_control));
 
     crate::presentation::middleware::rate_limit::apply_rate_limit(
         router,
         rate_limit_per_second,
         rate_limit_burst_size,
+        rate_limit_algorithm,
+        trusted_proxies,
     )
 }
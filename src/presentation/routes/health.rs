@@ -1,5 +1,40 @@
-use axum::{routing::get, Json, Router};
-use serde_json::json;
+use crate::{
+    config::NatsConfig,
+    infrastructure::database::{connection::pending_migrations, DbPool},
+};
+use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
+use diesel_async::RunQueryDsl;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::{future::Future, time::Duration};
+
+/// Dependencies probed by the readiness check
+#[derive(Clone)]
+pub struct HealthState {
+    pub pool: DbPool,
+    pub nats: NatsConfig,
+    /// Upper bound on each dependency check; a hung dependency counts as down
+    pub check_timeout: Duration,
+}
+
+/// Overall readiness. `Degraded` means an optional dependency is down: the
+/// service still takes traffic, with that feature failing open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+impl HealthStatus {
+    fn status_code(self) -> StatusCode {
+        match self {
+            Self::Healthy | Self::Degraded => StatusCode::OK,
+            Self::Unhealthy => StatusCode::SERVICE_UNAVAILABLE,
+        }
+    }
+}
 
 /// Health check endpoint
 #[utoipa::path(
@@ -18,7 +53,122 @@ pub async fn health_check() -> Json<serde_json::Value> {
     }))
 }
 
+/// Readiness probe: reports each dependency as `healthy`, `degraded` when only
+/// an optional one is down, or `unhealthy` when a required one is down or the
+/// schema is behind the migrations embedded in this build. Why a dependency
+/// is down is only logged.
+#[utoipa::path(
+    get,
+    path = "/health/ready",
+    responses(
+        (status = 200, description = "Service can take traffic, possibly degraded", body = Object),
+        (status = 503, description = "A required dependency is unavailable or migrations are pending", body = Object)
+    ),
+    tag = "health"
+)]
+pub async fn readiness_check(State(state): State<HealthState>) -> (StatusCode, Json<Value>) {
+    let limit = state.check_timeout;
+    let (database, migrations, nats) = tokio::join!(
+        within(limit, check_database(&state.pool)),
+        within(limit, async {
+            pending_migrations(&state.pool).await.map_err(|e| e.to_string())
+        }),
+        check_nats(&state.nats, limit),
+    );
+
+    if let Err(e) = &database {
+        tracing::error!("Database readiness check failed: {}", e);
+    }
+    if let Err(e) = &migrations {
+        tracing::error!("Migration readiness check failed: {}", e);
+    }
+
+    let status = if database.is_err()
+        || !matches!(migrations, Ok(0))
+        || (nats.is_err() && state.nats.required)
+    {
+        HealthStatus::Unhealthy
+    } else if nats.is_err() {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Healthy
+    };
+
+    let nats_check = match (&state.nats.url, nats) {
+        (None, _) => json!({ "status": "disabled" }),
+        (Some(_), Ok(())) => json!({ "status": "up", "required": state.nats.required }),
+        (Some(_), Err(_)) => json!({ "status": "down", "required": state.nats.required }),
+    };
+
+    let body = json!({
+        "status": status,
+        "timestamp": chrono::Utc::now().to_rfc3339(),
+        "checks": {
+            "database": match database {
+                Ok(()) => json!({ "status": "up" }),
+                Err(_) => json!({ "status": "down" }),
+            },
+            "migrations": match migrations {
+                Ok(0) => json!({ "status": "up", "pending": 0 }),
+                Ok(pending) => json!({ "status": "pending", "pending": pending }),
+                Err(_) => json!({ "status": "down" }),
+            },
+            "nats": nats_check,
+        },
+    });
+
+    (status.status_code(), Json(body))
+}
+
+/// Run a dependency check, failing it if it does not finish within `limit`
+async fn within<T>(
+    limit: Duration,
+    check: impl Future<Output = Result<T, String>>,
+) -> Result<T, String> {
+    tokio::time::timeout(limit, check)
+        .await
+        .unwrap_or_else(|_| Err(format!("timed out after {}ms", limit.as_millis())))
+}
+
+async fn check_database(pool: &DbPool) -> Result<(), String> {
+    let mut conn = pool.get().await.map_err(|e| e.to_string())?;
+    diesel::sql_query("SELECT 1")
+        .execute(&mut conn)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Ping NATS when configured and publish the result as the `nats_connected` gauge
+#[cfg(feature = "nats")]
+async fn check_nats(config: &NatsConfig, limit: Duration) -> Result<(), String> {
+    let Some(url) = config.url.as_deref() else {
+        return Ok(());
+    };
+
+    let result = within(limit, async {
+        crate::infrastructure::messaging::ping_nats(url, config.ping_timeout)
+            .await
+            .map_err(|e| e.to_string())
+    })
+    .await;
+    metrics::gauge!("nats_connected").set(if result.is_ok() { 1.0 } else { 0.0 });
+
+    if let Err(e) = &result {
+        tracing::error!("NATS readiness check failed: {}", e);
+    }
+    result
+}
+
+/// Builds without the `nats` feature never configure a broker
+#[cfg(not(feature = "nats"))]
+async fn check_nats(_config: &NatsConfig, _limit: Duration) -> Result<(), String> {
+    Ok(())
+}
+
 /// Create health check routes
-pub fn health_routes() -> Router {
-    Router::new().route("/health", get(health_check))
+pub fn health_routes(state: HealthState) -> Router {
+    Router::new()
+        .route("/health", get(health_check))
+        .route("/health/ready", get(readiness_check).with_state(state))
 }
@@ -1,85 +1,164 @@
+pub mod admin;
 pub mod auth;
 pub mod health;
 pub mod users;
 
+pub use admin::admin_routes;
 pub use auth::create_auth_routes;
-pub use health::health_routes;
+pub use health::{health_routes, HealthState};
 pub use users::user_routes;
 
-use crate::infrastructure::SystemMonitor;
+use crate::infrastructure::{monitoring::install_prometheus_recorder, SystemMonitor};
 use crate::{
     application::{
-        dto::auth::{
-            AuthResponse, ForgotPasswordRequest, LoginRequest, LogoutRequest, RefreshTokenRequest,
-            RegisterRequest, ResendConfirmCodeRequest, SetPasswordRequest, UserInfo,
-            VerifyEmailRequest,
+        commands::{
+            RefreshTokenCommand, RevokeSessionCommand, SendPhoneCodeCommand, VerifyPhoneCommand,
+        },
+        dto::{
+            auth::{
+                AuthResponse, ForgotPasswordRequest, LoginRequest, LogoutRequest,
+                RefreshTokenRequest, RegisterRequest, ResendConfirmCodeRequest,
+                SendPhoneCodeRequest, SetPasswordRequest, UserInfo, VerifyEmailRequest,
+                VerifyEmailResponse, VerifyPhoneRequest,
+            },
+            PageSizeLimits,
+        },
+        queries::ListSessionsQuery,
+        services::{
+            events::EventPublisher, lockout::LoginLockout, resend::ResendLimiter,
+            role::RoleResolver, sms::SmsSender, DistributedLock,
         },
         use_cases::{
-            ForgotPasswordUseCase, LoginUseCase, LogoutUseCase, RegisterUseCase,
-            SetPasswordUseCase, VerifyEmailUseCase,
+            ForgotPasswordUseCase, GetUserUseCase, LoginUseCase, LogoutUseCase, RegisterUseCase,
+            SessionLimitPolicy, SetPasswordUseCase, VerifyEmailUseCase,
         },
     },
-    infrastructure::database::{repositories::AuthRepositoryImpl, DbPool},
+    config::{AppConfig, CacheBackend, EventTransport, NatsConfig},
+    domain::repositories::CacheRepository,
+    infrastructure::cache::{InMemoryCacheRepository, MeteredCacheRepository, NoOpCacheRepository},
+    infrastructure::database::{
+        repositories::{
+            AuthRepositoryImpl, InvitationRepositoryImpl, PasswordHistoryRepositoryImpl,
+            UserRepositoryImpl,
+        },
+        DbPool,
+    },
+    infrastructure::email::metered::MeteredEmailService,
+    infrastructure::messaging::{NoOpEventPublisher, PgNotifyEventPublisher},
+    infrastructure::sms::TwilioSmsSender,
+    presentation::handlers::auth::SetPasswordFactory,
+    presentation::middleware::{
+        apply_concurrency_limit, apply_header_limits, auth::AuthState, catch_panic_layer,
+        localize_errors, metrics_auth_middleware, trace_context_middleware, HeaderLimits,
+        TrustedProxies,
+    },
     presentation::responses::{
         AuthResponseWrapper, ErrorResponseWrapper, StringResponseWrapper, UserListResponseWrapper,
         UserResponseWrapper,
     },
-    shared::utils::jwt::JwtManager,
+    shared::{
+        tasks::TaskRegistry,
+        utils::{jwt::JwtManager, verification_link::VerificationLinks},
+    },
 };
 use axum::Router;
-use axum::{routing::get, Extension};
-use axum_prometheus::{metrics_exporter_prometheus::PrometheusHandle, PrometheusMetricLayer};
+use axum::{middleware, routing::get, Extension};
+use axum_prometheus::PrometheusMetricLayer;
 use std::sync::Arc;
 use utoipa::{
-    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    openapi::{
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+        RefOr, Schema,
+    },
     Modify, OpenApi,
 };
+#[cfg(feature = "swagger")]
 use utoipa_swagger_ui::SwaggerUi;
 
 #[derive(OpenApi)]
 #[openapi(
     paths(
         crate::presentation::routes::health::health_check,
+        crate::presentation::routes::health::readiness_check,
         crate::presentation::handlers::auth::register,
         crate::presentation::handlers::auth::login,
         crate::presentation::handlers::auth::logout,
+        crate::presentation::handlers::auth::list_sessions,
+        crate::presentation::handlers::auth::revoke_session,
+        crate::presentation::handlers::auth::refresh,
+        crate::presentation::handlers::auth::me,
         crate::presentation::handlers::auth::verify_email,
+        crate::presentation::handlers::auth::verify_email_link_page,
+        crate::presentation::handlers::auth::verify_email_link,
         crate::presentation::handlers::auth::set_password,
         crate::presentation::handlers::auth::forgot_password,
+        crate::presentation::handlers::auth::send_phone_code,
+        crate::presentation::handlers::auth::verify_phone,
         crate::presentation::handlers::auth::resend_code,
         crate::presentation::handlers::user::create_user,
         crate::presentation::handlers::user::get_user,
+        crate::presentation::handlers::user::get_user_avatar,
         crate::presentation::handlers::user::list_users,
         crate::presentation::handlers::user::update_user,
         crate::presentation::handlers::user::import_users,
+        crate::presentation::handlers::user::get_import_status,
+        crate::presentation::handlers::user::export_users_csv,
+        crate::presentation::handlers::user::deactivate_users,
+        crate::presentation::handlers::user::force_password_reset,
+        crate::presentation::handlers::user::create_invitation,
         crate::presentation::handlers::role::get_user_role,
         crate::presentation::handlers::role::update_user_role,
+        crate::presentation::handlers::monitoring::effective_config,
     ),
     components(
         schemas(
             RegisterRequest,
             LoginRequest,
             LogoutRequest,
+            crate::application::dto::auth::SessionDto,
             ForgotPasswordRequest,
+            crate::application::dto::auth::NotificationChannel,
             ResendConfirmCodeRequest,
             RefreshTokenRequest,
             VerifyEmailRequest,
+            VerifyEmailResponse,
+            crate::presentation::handlers::auth::VerifyLinkQuery,
+            SendPhoneCodeRequest,
+            VerifyPhoneRequest,
             SetPasswordRequest,
             AuthResponse,
             UserInfo,
             crate::application::dto::user::CreateUserDto,
             crate::application::dto::user::UpdateUserDto,
             crate::application::dto::user::UserResponseDto,
+            crate::application::dto::user::DeactivateUsersDto,
+            crate::application::dto::user::DeactivateUsersResponseDto,
+            crate::application::dto::user::CreateInvitationDto,
+            crate::application::dto::user::InvitationResponseDto,
+            crate::application::dto::user::ImportReportDto,
+            crate::application::dto::user::ImportJobDto,
+            crate::application::dto::user::ImportJobState,
+            crate::application::dto::user::ImportRowErrorDto,
+            crate::application::dto::PaginationMeta,
             crate::application::dto::role_dto::UpdateRoleRequest,
             crate::application::dto::role_dto::RoleResponse,
             crate::application::dto::role_dto::RolePermissions,
             crate::presentation::handlers::user::ListUsersQuery,
+            crate::presentation::handlers::user::ExportUsersQuery,
+            crate::presentation::handlers::user::ImportUsersQuery,
             AuthResponseWrapper,
             StringResponseWrapper,
             ErrorResponseWrapper,
             UserResponseWrapper,
             UserListResponseWrapper,
             crate::presentation::responses::RoleResponseWrapper,
+            crate::presentation::responses::SessionListResponseWrapper,
+            crate::presentation::responses::DeactivateUsersResponseWrapper,
+            crate::presentation::responses::InvitationResponseWrapper,
+            crate::presentation::responses::ImportReportResponseWrapper,
+            crate::presentation::responses::ImportJobResponseWrapper,
+            crate::presentation::responses::VerifyEmailResponseWrapper,
+            crate::presentation::responses::ConfigResponseWrapper,
         )
     ),
     modifiers(&SecurityAddon),
@@ -97,6 +176,34 @@ use utoipa_swagger_ui::SwaggerUi;
 )]
 pub struct ApiDoc;
 
+/// OpenAPI document with runtime settings, such as page sizes, filled in
+fn api_doc(config: &AppConfig) -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+    document_page_sizes(&mut doc, config.page_size_limits());
+    doc
+}
+
+/// Show the configured default and maximum on every `page_size` parameter
+fn document_page_sizes(doc: &mut utoipa::openapi::OpenApi, limits: PageSizeLimits) {
+    let params = doc
+        .paths
+        .paths
+        .values_mut()
+        .flat_map(|item| item.operations.values_mut())
+        .filter_map(|op| op.parameters.as_mut())
+        .flatten()
+        .filter(|param| param.name == "page_size");
+
+    for param in params {
+        if let Some(RefOr::T(Schema::Object(schema))) = param.schema.as_mut() {
+            schema.default = Some(limits.default.into());
+            schema.example = Some(limits.default.into());
+            schema.minimum = Some(1.0);
+            schema.maximum = Some(limits.max as f64);
+        }
+    }
+}
+
 struct SecurityAddon;
 
 impl Modify for SecurityAddon {
@@ -112,70 +219,261 @@ impl Modify for SecurityAddon {
     }
 }
 
-/// Create the main application router
-#[allow(clippy::too_many_arguments)]
+/// Create the main application router. Background work started by requests,
+/// such as background imports, runs under `tasks`.
 pub fn create_router(
     pool: DbPool,
-    jwt_secret: String,
-    jwt_access_expiry: i64,
-    jwt_refresh_expiry: i64,
-    jwt_issuer: String,
-    jwt_audience: String,
-    confirm_code_expiry: i64,
-    cookie_secure: bool,
-    rate_limit_per_second: u64,
-    rate_limit_burst_size: u32,
-    prometheus_layer: PrometheusMetricLayer<'static>,
-    metric_handle: PrometheusHandle,
+    config: &AppConfig,
     email_service: Arc<dyn crate::application::services::email::EmailService>,
+    tasks: Arc<TaskRegistry>,
 ) -> Router {
+    // Every send is timed, whichever provider delivers it
+    let email_service: Arc<dyn crate::application::services::email::EmailService> =
+        Arc::new(MeteredEmailService::new(email_service));
+
     // Create repositories
     let auth_repo = Arc::new(AuthRepositoryImpl::new(pool.clone()));
+    // With FEATURE_CACHE off every role lookup goes to the database
+    let cache: Arc<dyn CacheRepository> = match (config.features.cache, config.cache_config.backend)
+    {
+        (false, _) => Arc::new(NoOpCacheRepository::new()),
+        (true, CacheBackend::Memory) => Arc::new(InMemoryCacheRepository::new()),
+        #[cfg(feature = "moka")]
+        (true, CacheBackend::Moka) => Arc::new(
+            crate::infrastructure::cache::MokaCacheRepository::new(config.cache_config.max_entries),
+        ),
+    };
 
     // SAFETY: Called once at startup. A bad JWT secret is unrecoverable — failing
     // here with a clear message is the correct behavior.
     #[allow(clippy::expect_used)]
     let jwt_manager = Arc::new(
         JwtManager::new(
-            jwt_secret,
-            jwt_access_expiry,
-            jwt_refresh_expiry,
-            jwt_issuer,
-            jwt_audience,
+            config.jwt_secret.clone(),
+            config.jwt_access_expiry,
+            config.jwt_refresh_expiry,
+            config.jwt_issuer.clone(),
+            config.jwt_audience.clone(),
         )
-        .expect("Failed to create JwtManager — check JWT_SECRET length (min 32 chars)"),
+        .expect("Failed to create JwtManager — check JWT_SECRET length (min 32 chars)")
+        .with_leeway(config.jwt_leeway)
+        .with_issuer_validation(config.jwt_validate_issuer)
+        .with_accepted_audiences(config.jwt_accepted_audiences.clone())
+        .with_role_expiry(config.jwt_role_expiry.clone()),
     );
 
+    // Shared by resend-code and forgot-password; cleared on successful verification
+    let resend_limiter = Arc::new(ResendLimiter::new(
+        cache.clone(),
+        config.resend_cooldown,
+        config.resend_max_per_hour,
+    ));
+
     // Create use cases
-    let register_uc = Arc::new(RegisterUseCase::new(
+    let code_hasher = Arc::new(config.confirmation_code_hasher.clone());
+    let verification_links = Arc::new(VerificationLinks::new(
+        &config.jwt_secret,
+        config.verification_link_url.clone(),
+        config.verification_link_expiry,
+    ));
+    let register_uc = RegisterUseCase::new(
         auth_repo.clone(),
         email_service.clone(),
-        confirm_code_expiry,
+        config.confirm_code_expiry,
+        config.default_user_role,
+    )
+    .with_code_hasher(code_hasher.clone())
+    .with_verification_links(verification_links.clone())
+    .with_domain_policy(config.email_domain_policy.clone())
+    .with_invitations(Arc::new(InvitationRepositoryImpl::new(pool.clone())))
+    // Lapses on its own if a registration dies holding it
+    .with_lock(Arc::new(
+        DistributedLock::new(cache.clone(), std::time::Duration::from_secs(30))
+            .with_policy(config.lock_policy),
     ));
-    let login_uc = Arc::new(LoginUseCase::new(auth_repo.clone(), jwt_manager.clone()));
+    let register_uc = Arc::new(match &config.disposable_email_domains {
+        Some(domains) => register_uc.with_disposable_domains(domains.clone()),
+        None => register_uc,
+    });
+    let peppers = Arc::new(config.password_peppers.clone());
+    let mut login_uc = LoginUseCase::new(auth_repo.clone(), jwt_manager.clone())
+        .with_peppers(peppers.clone())
+        .with_code_hasher(code_hasher.clone());
+    if let Some(max_failures) = config.lockout.max_failures {
+        login_uc = login_uc.with_lockout(Arc::new(LoginLockout::new(
+            cache.clone(),
+            max_failures,
+            config.lockout.window,
+            config.lockout.duration,
+        )));
+        if config.lockout.alert_email {
+            login_uc =
+                login_uc.with_lockout_alerts(email_service.clone()).with_tasks(tasks.clone());
+        }
+    }
+    let login_uc = Arc::new(match config.max_sessions_per_user {
+        Some(max) if config.session_limit_reject => {
+            login_uc.with_session_limit(max, SessionLimitPolicy::Reject)
+        },
+        Some(max) => login_uc.with_session_limit(max, SessionLimitPolicy::EvictOldest),
+        None => login_uc,
+    });
     let logout_uc = Arc::new(LogoutUseCase::new(auth_repo.clone()));
-    let verify_uc = Arc::new(VerifyEmailUseCase::new(auth_repo.clone()));
-    let set_password_uc = Arc::new(SetPasswordUseCase::new(auth_repo.clone()));
-    let forgot_password_uc = Arc::new(ForgotPasswordUseCase::new(
+    let list_sessions_query = Arc::new(ListSessionsQuery::new(auth_repo.clone()));
+    let revoke_session_command = Arc::new(RevokeSessionCommand::new(auth_repo.clone()));
+    let refresh_command =
+        Arc::new(RefreshTokenCommand::new(auth_repo.clone(), jwt_manager.clone()));
+    let verify_uc = Arc::new(
+        VerifyEmailUseCase::new(auth_repo.clone(), resend_limiter.clone())
+            .with_code_hasher(code_hasher.clone())
+            .with_verification_links(verification_links.clone()),
+    );
+    let set_password_uc: SetPasswordFactory<AuthRepositoryImpl> = {
+        let (history_size, min_change_interval) =
+            (config.password_history_size, config.password_min_change_interval);
+        let (rules, peppers, code_hasher) =
+            (config.password_rules.clone(), peppers.clone(), code_hasher.clone());
+        Arc::new(move |tx| {
+            SetPasswordUseCase::new(
+                Arc::new(AuthRepositoryImpl::in_transaction(tx.clone())),
+                Arc::new(PasswordHistoryRepositoryImpl::in_transaction(tx)),
+                history_size,
+            )
+            .with_min_change_interval(min_change_interval)
+            .with_password_rules(rules.clone())
+            .with_peppers(peppers.clone())
+            .with_code_hasher(code_hasher.clone())
+        })
+    };
+    let forgot_password_uc = ForgotPasswordUseCase::new(
         auth_repo.clone(),
         email_service.clone(),
-        confirm_code_expiry,
-    ));
+        config.confirm_code_expiry,
+        resend_limiter.clone(),
+    )
+    .with_code_hasher(code_hasher.clone());
+    let send_phone_code_command = SendPhoneCodeCommand::new(
+        auth_repo.clone(),
+        config.confirm_code_expiry,
+        resend_limiter.clone(),
+    )
+    .with_code_hasher(code_hasher.clone());
+    // Without Twilio codes are only emailed, and phones cannot be verified
+    let (forgot_password_uc, send_phone_code_command) = match &config.sms_config.twilio {
+        Some(twilio) => {
+            let sms_sender: Arc<dyn SmsSender> = Arc::new(TwilioSmsSender::new(twilio));
+            (
+                forgot_password_uc.with_sms(sms_sender.clone(), config.sms_config.default_channel),
+                send_phone_code_command.with_sms(sms_sender),
+            )
+        },
+        None => (forgot_password_uc, send_phone_code_command),
+    };
+    let verify_phone_command =
+        VerifyPhoneCommand::new(auth_repo.clone()).with_code_hasher(code_hasher.clone());
 
     // Monitoring Setup
     let system_monitor = Arc::new(SystemMonitor::new());
 
+    // SAFETY: Buckets are validated by AppConfig; failing here means the
+    // recorder could not be installed at startup, which is unrecoverable.
+    #[allow(clippy::expect_used)]
+    let metric_handle =
+        install_prometheus_recorder(&config.metrics_config.duration_buckets, &tasks)
+            .expect("Failed to install Prometheus recorder");
+    let prometheus_layer = PrometheusMetricLayer::new();
+
     // Cookie security config driven by COOKIE_SECURE env var (falls back to is_production)
-    let cookie_config =
-        Arc::new(crate::presentation::handlers::auth::CookieConfig { secure: cookie_secure });
+    let cookie_config = Arc::new(crate::presentation::handlers::auth::CookieConfig {
+        secure: config.cookie_secure,
+        token_delivery: config.token_delivery,
+    });
+
+    // Roles are resolved per request (cached), not carried in tokens
+    let auth_state = AuthState {
+        jwt_manager: jwt_manager.clone(),
+        roles: Arc::new(RoleResolver::new(
+            Arc::new(UserRepositoryImpl::new(pool.clone())),
+            Arc::new(MeteredCacheRepository::new(cache.clone(), "role")),
+            config.role_cache_ttl,
+        )),
+    };
+
+    // FEATURE_NATS=false, or a build without the `nats` feature, behaves as
+    // if NATS_URL were unset
+    #[cfg(not(feature = "nats"))]
+    if config.nats_config.url.is_some() {
+        tracing::warn!("NATS_URL is set but this build has no NATS support; ignoring it");
+    }
+    let nats_config = NatsConfig {
+        url: config
+            .nats_config
+            .url
+            .clone()
+            .filter(|_| config.features.nats && cfg!(feature = "nats")),
+        ..config.nats_config.clone()
+    };
+
+    // Domain events go to NATS when NATS_URL is set, otherwise they are only
+    // logged, unless EVENT_TRANSPORT=postgres sends them as notifications
+    let event_publisher: Arc<dyn EventPublisher> = match (config.event_transport, &nats_config.url)
+    {
+        (EventTransport::Postgres, _) => Arc::new(PgNotifyEventPublisher::new(pool.clone())),
+        #[cfg(feature = "nats")]
+        (EventTransport::Nats, Some(url)) => {
+            Arc::new(crate::infrastructure::messaging::NatsEventPublisher::new(
+                url,
+                nats_config.ping_timeout,
+            ))
+        },
+        (EventTransport::Nats, _) => Arc::new(NoOpEventPublisher::new()),
+    };
+
+    // Forwarding headers are believed only from these peers (TRUSTED_PROXIES)
+    let trusted_proxies = TrustedProxies::new(config.trusted_proxies.clone());
+
+    // Metrics are open unless METRICS_BEARER_TOKEN or METRICS_BASIC_* is configured
+    let metrics_routes =
+        Router::new().route("/metrics", get(|| async move { metric_handle.render() }));
+    let metrics_routes = match config.metrics_config.auth.clone() {
+        Some(auth) => metrics_routes
+            .layer(middleware::from_fn_with_state(Arc::new(auth), metrics_auth_middleware)),
+        None => metrics_routes,
+    };
 
-    Router::new()
-        .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
-        .merge(health_routes())
-        .route("/metrics", get(|| async move { metric_handle.render() }))
+    // Swagger UI is off by default in production (ENABLE_SWAGGER); builds
+    // without the `swagger` feature serve only the OpenAPI document
+    let docs_routes = if config.swagger_enabled {
+        #[cfg(feature = "swagger")]
+        let docs = SwaggerUi::new("/swagger-ui")
+            .url("/api-docs/openapi.json", api_doc(config))
+            .into();
+        #[cfg(not(feature = "swagger"))]
+        let docs = {
+            let doc = api_doc(config);
+            Router::new().route("/api-docs/openapi.json", get(|| async move { axum::Json(doc) }))
+        };
+        docs
+    } else {
+        Router::new()
+    };
+
+    let router = Router::new()
+        .merge(docs_routes)
+        .merge(health_routes(HealthState {
+            pool: pool.clone(),
+            nats: nats_config,
+            check_timeout: config.health_check_timeout,
+        }))
+        .merge(metrics_routes)
         .route(
             "/api/admin/system",
-            get(crate::presentation::handlers::monitoring::system_health),
+            get(crate::presentation::handlers::monitoring::system_health).route_layer(
+                middleware::from_fn_with_state(
+                    config.cache_control.system_stats,
+                    crate::presentation::middleware::set_cache_control,
+                ),
+            ),
         )
         .nest(
             "/api/auth",
@@ -183,21 +481,78 @@ pub fn create_router(
                 register_uc,
                 login_uc,
                 logout_uc,
+                list_sessions_query,
+                revoke_session_command,
+                refresh_command,
                 verify_uc,
                 set_password_uc,
-                forgot_password_uc,
-                Arc::new(crate::application::use_cases::ResendConfirmCodeUseCase::new(
-                    auth_repo.clone(),
-                    email_service.clone(),
-                    confirm_code_expiry,
-                )),
-                jwt_manager.clone(),
+                pool.clone(),
+                Arc::new(forgot_password_uc),
+                Arc::new(send_phone_code_command),
+                Arc::new(verify_phone_command),
+                Arc::new(
+                    crate::application::use_cases::ResendConfirmCodeUseCase::new(
+                        auth_repo.clone(),
+                        email_service.clone(),
+                        config.confirm_code_expiry,
+                        resend_limiter,
+                    )
+                    .with_code_hasher(code_hasher)
+                    .with_verification_links(verification_links),
+                ),
+                Arc::new(GetUserUseCase::new(Arc::new(UserRepositoryImpl::new(pool.clone())))),
+                auth_state.clone(),
                 cookie_config,
-                rate_limit_per_second,
-                rate_limit_burst_size,
+                Arc::new(crate::presentation::handlers::auth::RegistrationGate {
+                    open: config.registration_open,
+                    auth: auth_state.clone(),
+                }),
+                config.rate_limit_per_second,
+                config.rate_limit_burst_size,
+                config.rate_limit_algorithm,
+                trusted_proxies.clone(),
+            ),
+        )
+        .nest(
+            "/api/admin",
+            admin_routes(
+                pool.clone(),
+                auth_repo.clone(),
+                auth_state.clone(),
+                email_service.clone(),
+                config,
             ),
         )
-        .nest("/api/users", user_routes(pool, auth_repo, jwt_manager))
+        .nest(
+            "/api/users",
+            user_routes(
+                pool,
+                auth_repo,
+                auth_state,
+                cache,
+                config.page_size_limits(),
+                event_publisher,
+                peppers,
+                config.cache_control.user_detail,
+                config.import_max_concurrency,
+                config.import_csv_path.clone(),
+                tasks,
+            ),
+        )
+        .layer(catch_panic_layer())
+        .layer(middleware::from_fn(localize_errors))
         .layer(prometheus_layer)
         .layer(Extension(system_monitor))
+        .layer(Extension(trusted_proxies))
+        .layer(middleware::from_fn(trace_context_middleware));
+
+    // Checked first so oversized requests never take a concurrency slot
+    let router = apply_concurrency_limit(router, config.max_concurrent_requests);
+    apply_header_limits(
+        router,
+        HeaderLimits {
+            max_count: config.max_request_headers,
+            max_bytes: config.max_request_header_bytes,
+        },
+    )
 }
@@ -1,55 +1,113 @@
-use crate::presentation::middleware::auth::{auth_middleware, AuthState};
+use crate::presentation::middleware::{
+    auth::{auth_middleware, require_role, AuthState},
+    set_cache_control, transaction_middleware,
+};
 use crate::{
+    application::dto::PageSizeLimits,
+    application::services::{
+        events::{EventPublisher, DEFAULT_BATCH_SIZE},
+        import_jobs::{ImportJobs, JOB_TTL},
+        AvatarRenderer,
+    },
     application::use_cases::{
         CreateUserUseCase, GetUserRoleUseCase, GetUserUseCase, ImportUsersUseCase,
         ListUsersUseCase, UpdateUserRoleUseCase, UpdateUserUseCase,
     },
-    infrastructure::database::repositories::{AuthRepositoryImpl, UserRepositoryImpl},
+    domain::{repositories::CacheRepository, value_objects::UserRole},
+    infrastructure::avatar::IdenticonRenderer,
+    infrastructure::database::repositories::{
+        AuditRepositoryImpl, AuthRepositoryImpl, UserRepositoryImpl,
+    },
     infrastructure::database::DbPool,
     presentation::{
-        handlers::role::{get_user_role, update_user_role},
-        handlers::user::{create_user, get_user, import_users, list_users, update_user},
+        handlers::role::{get_user_role, update_user_role, UpdateUserRoleFactory},
+        handlers::user::{
+            create_user, get_import_status, get_user, get_user_avatar, import_users, list_users,
+            update_user, ImportSource,
+        },
     },
-    shared::utils::jwt::JwtManager,
+    shared::{cache_control::CachePolicy, tasks::TaskRegistry, utils::password::Peppers},
 };
 use axum::{
     middleware,
-    routing::{get, post, put},
-    Router,
+    routing::{get, patch, post, put},
+    Extension, Router,
 };
-use std::sync::Arc;
+use std::{path::PathBuf, sync::Arc};
 
 /// Create user-related routes
+#[allow(clippy::too_many_arguments)]
 pub fn user_routes(
     pool: DbPool,
     auth_repo: Arc<AuthRepositoryImpl>,
-    jwt_manager: Arc<JwtManager>,
+    auth_state: AuthState,
+    cache: Arc<dyn CacheRepository>,
+    page_sizes: PageSizeLimits,
+    event_publisher: Arc<dyn EventPublisher>,
+    peppers: Arc<Peppers>,
+    user_detail_cache: CachePolicy,
+    import_concurrency: usize,
+    import_csv_path: PathBuf,
+    tasks: Arc<TaskRegistry>,
 ) -> Router {
-    // Create repository
-    let user_repo = Arc::new(UserRepositoryImpl::new(pool));
+    // Create repositories
+    let user_repo = Arc::new(UserRepositoryImpl::new(pool.clone()));
 
     // Create use cases
     let create_user_uc = Arc::new(CreateUserUseCase::new(user_repo.clone()));
     let get_user_uc = Arc::new(GetUserUseCase::new(user_repo.clone()));
-    let list_users_uc = Arc::new(ListUsersUseCase::new(user_repo.clone()));
+    let list_users_uc = Arc::new(ListUsersUseCase::new(user_repo.clone(), page_sizes));
     let update_user_uc = Arc::new(UpdateUserUseCase::new(user_repo.clone()));
-    let import_users_uc = Arc::new(ImportUsersUseCase::new(auth_repo.clone()));
+    let avatars: Arc<dyn AvatarRenderer> = Arc::new(IdenticonRenderer);
+    let import_users_uc = Arc::new(
+        ImportUsersUseCase::new(auth_repo.clone())
+            .with_peppers(peppers)
+            .with_events(event_publisher.clone(), DEFAULT_BATCH_SIZE)
+            .with_concurrency(import_concurrency)
+            .with_jobs(Arc::new(ImportJobs::new(cache.clone(), JOB_TTL)))
+            .with_tasks(tasks),
+    );
 
     // Role management use cases
     let get_role_uc = Arc::new(GetUserRoleUseCase::new(user_repo.clone()));
-    let update_role_uc = Arc::new(UpdateUserRoleUseCase::new(user_repo.clone()));
-
-    // Create auth state for middleware
-    let auth_state = AuthState { jwt_manager };
+    let update_role_uc: UpdateUserRoleFactory<UserRepositoryImpl> = Arc::new(move |tx| {
+        UpdateUserRoleUseCase::new(
+            Arc::new(UserRepositoryImpl::in_transaction(tx.clone())),
+            Arc::new(AuditRepositoryImpl::in_transaction(tx)),
+            cache.clone(),
+            event_publisher.clone(),
+        )
+    });
 
     Router::new()
         .route("/", post(create_user).with_state(create_user_uc))
         .route("/", get(list_users).with_state(list_users_uc))
-        .route("/import", post(import_users).with_state(import_users_uc))
-        .route("/:id", get(get_user).with_state(get_user_uc))
-        .route("/:id", put(update_user).with_state(update_user_uc))
+        .route(
+            "/import",
+            post(import_users)
+                .with_state(import_users_uc.clone())
+                .layer(Extension(Arc::new(ImportSource(import_csv_path)))),
+        )
+        .route("/import/:job_id/status", get(get_import_status).with_state(import_users_uc))
+        .route(
+            "/:id",
+            get(get_user)
+                .with_state(get_user_uc.clone())
+                .route_layer(middleware::from_fn_with_state(user_detail_cache, set_cache_control)),
+        )
+        .route(
+            "/:id/avatar",
+            get(get_user_avatar).with_state(get_user_uc).layer(Extension(avatars)),
+        )
+        .route("/:id", patch(update_user).put(update_user).with_state(update_user_uc))
         // Role management endpoints
         .route("/:id/role", get(get_user_role).with_state(get_role_uc))
-        .route("/:id/role", put(update_user_role).with_state(update_role_uc))
+        .route(
+            "/:id/role",
+            put(update_user_role)
+                .with_state(update_role_uc)
+                .route_layer(middleware::from_fn_with_state(pool, transaction_middleware))
+                .route_layer(middleware::from_fn_with_state(UserRole::Admin, require_role)),
+        )
         .layer(middleware::from_fn_with_state(auth_state, auth_middleware))
 }
@@ -0,0 +1,339 @@
+use axum::{body::Body, extract::ConnectInfo, http::Request, Router};
+use hyper::body::Incoming;
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo, TokioTimer},
+    server::conn::auto,
+};
+use socket2::{Domain, Protocol, Socket, Type};
+use std::{
+    collections::HashMap,
+    future::Future,
+    io,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::{net::TcpListener, task::JoinSet};
+use tokio_util::sync::CancellationToken;
+use tower::ServiceExt;
+
+/// Pending connections queued per listener before the kernel refuses more
+const LISTEN_BACKLOG: i32 = 1024;
+
+/// Peers of the open connections, by the order they were accepted in
+type Peers = Arc<Mutex<HashMap<u64, SocketAddr>>>;
+
+/// Removes its connection from `Peers` however the connection task ends,
+/// including by panicking or being aborted
+struct PeerGuard {
+    peers: Peers,
+    key: u64,
+}
+
+impl Drop for PeerGuard {
+    fn drop(&mut self) {
+        self.peers.lock().unwrap_or_else(|e| e.into_inner()).remove(&self.key);
+    }
+}
+
+/// Bind a listener on each of `addrs`.
+///
+/// When the list mixes IPv4 and IPv6, IPv6 sockets are made IPv6-only so
+/// `0.0.0.0:P` and `[::]:P` can both be bound. A list without IPv4 keeps the
+/// OS default, under which `[::]` usually accepts IPv4 as well.
+pub fn bind_all(addrs: &[SocketAddr]) -> io::Result<Vec<TcpListener>> {
+    let mixed = addrs.iter().any(SocketAddr::is_ipv4);
+    addrs
+        .iter()
+        .map(|addr| {
+            let socket =
+                Socket::new(Domain::for_address(*addr), Type::STREAM, Some(Protocol::TCP))?;
+            // Matches `TcpListener::bind`, so a restart can reuse the port at once
+            #[cfg(unix)]
+            socket.set_reuse_address(true)?;
+            if addr.is_ipv6() && mixed {
+                socket.set_only_v6(true)?;
+            }
+            socket.set_nonblocking(true)?;
+            socket.bind(&(*addr).into())?;
+            socket.listen(LISTEN_BACKLOG)?;
+            TcpListener::from_std(socket.into())
+        })
+        .collect()
+}
+
+/// Serve `app` on `listener`, closing HTTP/1 connections that sit idle for
+/// longer than `keep_alive_timeout` between requests.
+///
+/// Equivalent to `axum::serve` with `into_make_service_with_connect_info`,
+/// which offers no way to tune connection settings.
+pub async fn serve(listener: TcpListener, app: Router, keep_alive_timeout: Duration) {
+    serve_with_shutdown(listener, app, keep_alive_timeout, Duration::ZERO, std::future::pending())
+        .await
+}
+
+/// Like `serve`, but stops accepting connections once `signal` completes.
+///
+/// Open connections are then asked to close once their current request is
+/// answered. Any still open after `shutdown_timeout` are aborted, and their
+/// peers logged, so one stuck request cannot hold up the exit.
+pub async fn serve_with_shutdown(
+    listener: TcpListener,
+    app: Router,
+    keep_alive_timeout: Duration,
+    shutdown_timeout: Duration,
+    signal: impl Future<Output = ()>,
+) {
+    let mut builder = auto::Builder::new(TokioExecutor::new());
+    // Hyper starts the header timer as soon as it waits for the next request,
+    // so it doubles as the keep-alive idle timeout
+    builder
+        .http1()
+        .timer(TokioTimer::new())
+        .keep_alive(true)
+        .header_read_timeout(keep_alive_timeout);
+
+    let draining = CancellationToken::new();
+    let mut connections = JoinSet::new();
+    let peers = Peers::default();
+    let mut accepted_count = 0u64;
+
+    tokio::pin!(signal);
+    loop {
+        let accepted = tokio::select! {
+            accepted = listener.accept() => accepted,
+            Some(_) = connections.join_next() => continue,
+            () = &mut signal => {
+                tracing::info!("Shutdown signal received, no longer accepting connections");
+                break;
+            },
+        };
+        let (stream, remote_addr) = match accepted {
+            Ok(conn) => conn,
+            Err(e) => {
+                // Usually EMFILE or a reset during the handshake; keep accepting
+                tracing::warn!("Failed to accept connection: {}", e);
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                continue;
+            },
+        };
+
+        let app = app.clone();
+        let service = hyper::service::service_fn(move |mut req: Request<Incoming>| {
+            req.extensions_mut().insert(ConnectInfo(remote_addr));
+            app.clone().oneshot(req.map(Body::new))
+        });
+
+        let builder = builder.clone();
+        let draining = draining.clone();
+        accepted_count += 1;
+        peers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(accepted_count, remote_addr);
+        let guard = PeerGuard { peers: peers.clone(), key: accepted_count };
+        connections.spawn(async move {
+            let _guard = guard;
+            let conn = builder.serve_connection_with_upgrades(TokioIo::new(stream), service);
+            tokio::pin!(conn);
+            let result = tokio::select! {
+                result = conn.as_mut() => result,
+                () = draining.cancelled() => {
+                    conn.as_mut().graceful_shutdown();
+                    conn.await
+                },
+            };
+            if let Err(e) = result {
+                tracing::debug!("Connection from {} closed with error: {}", remote_addr, e);
+            }
+        });
+    }
+
+    draining.cancel();
+    let drained = tokio::time::timeout(shutdown_timeout, async {
+        while connections.join_next().await.is_some() {}
+    })
+    .await;
+
+    if drained.is_err() {
+        let stuck: Vec<String> = peers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .map(ToString::to_string)
+            .collect();
+        tracing::warn!(
+            "Aborting {} connection(s) still open after {:?}: {}",
+            stuck.len(),
+            shutdown_timeout,
+            stuck.join(", ")
+        );
+        connections.shutdown().await;
+    }
+}
+
+/// `serve_with_shutdown` on every listener at once, sharing one `signal`
+pub async fn serve_all_with_shutdown(
+    listeners: Vec<TcpListener>,
+    app: Router,
+    keep_alive_timeout: Duration,
+    shutdown_timeout: Duration,
+    signal: impl Future<Output = ()>,
+) {
+    let stop = CancellationToken::new();
+    let mut servers = JoinSet::new();
+    for listener in listeners {
+        servers.spawn(serve_with_shutdown(
+            listener,
+            app.clone(),
+            keep_alive_timeout,
+            shutdown_timeout,
+            stop.clone().cancelled_owned(),
+        ));
+    }
+
+    signal.await;
+    stop.cancel();
+    while servers.join_next().await.is_some() {}
+}
+
+/// Completes on Ctrl+C, or SIGTERM on Unix
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut stream) => {
+                stream.recv().await;
+            },
+            Err(e) => {
+                tracing::error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            },
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        () = ctrl_c => {},
+        () = terminate => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::routing::get;
+    use std::time::Instant;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[tokio::test]
+    async fn a_stuck_request_is_aborted_once_the_shutdown_timeout_passes() {
+        let app = Router::new().route("/stuck", get(std::future::pending::<&'static str>));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve_with_shutdown(
+            listener,
+            app,
+            Duration::from_secs(60),
+            Duration::from_millis(200),
+            async {
+                let _ = stopped.await;
+            },
+        ));
+
+        let mut client = tokio::net::TcpStream::connect(addr).await.unwrap();
+        client.write_all(b"GET /stuck HTTP/1.1\r\nHost: test\r\n\r\n").await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let started = Instant::now();
+        stop.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(2), server).await.unwrap().unwrap();
+
+        assert!(started.elapsed() >= Duration::from_millis(200));
+        // The aborted connection is closed without a response
+        let mut buf = Vec::new();
+        let read = tokio::time::timeout(Duration::from_secs(1), client.read_to_end(&mut buf));
+        assert!(read.await.unwrap().is_ok());
+        assert!(buf.is_empty());
+    }
+
+    #[tokio::test]
+    async fn serves_ipv6_and_ipv4_listeners_side_by_side() {
+        let app = Router::new().route("/quick", get(|| async { "ok" }));
+        let addrs: Vec<SocketAddr> =
+            ["[::1]:0", "127.0.0.1:0"].iter().map(|a| a.parse().unwrap()).collect();
+        let listeners = bind_all(&addrs).unwrap();
+        let bound: Vec<SocketAddr> = listeners.iter().map(|l| l.local_addr().unwrap()).collect();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve_all_with_shutdown(
+            listeners,
+            app,
+            Duration::from_secs(60),
+            Duration::from_secs(30),
+            async {
+                let _ = stopped.await;
+            },
+        ));
+
+        assert!(bound[0].is_ipv6() && bound[1].is_ipv4());
+        for addr in bound {
+            let mut client = tokio::net::TcpStream::connect(addr).await.unwrap();
+            client.write_all(b"GET /quick HTTP/1.1\r\nHost: test\r\n\r\n").await.unwrap();
+            let mut buf = [0u8; 64];
+            let read = client.read(&mut buf).await.unwrap();
+            assert!(buf[..read].starts_with(b"HTTP/1.1 200"), "{}", addr);
+        }
+
+        stop.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(2), server).await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn a_panicking_connection_task_drops_its_peer() {
+        let peers = Peers::default();
+        peers.lock().unwrap().insert(1, "127.0.0.1:1".parse().unwrap());
+        let guard = PeerGuard { peers: peers.clone(), key: 1 };
+
+        let task = tokio::spawn(async move {
+            let _guard = guard;
+            std::panic::resume_unwind(Box::new("connection task failed"));
+        });
+
+        assert!(task.await.unwrap_err().is_panic());
+        assert!(peers.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn idle_connections_do_not_delay_shutdown() {
+        let app = Router::new().route("/quick", get(|| async { "ok" }));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let server = tokio::spawn(serve_with_shutdown(
+            listener,
+            app,
+            Duration::from_secs(60),
+            Duration::from_secs(30),
+            async {
+                let _ = stopped.await;
+            },
+        ));
+
+        let mut client = tokio::net::TcpStream::connect(addr).await.unwrap();
+        client.write_all(b"GET /quick HTTP/1.1\r\nHost: test\r\n\r\n").await.unwrap();
+        let mut buf = [0u8; 64];
+        assert!(client.read(&mut buf).await.unwrap() > 0);
+
+        stop.send(()).unwrap();
+        tokio::time::timeout(Duration::from_secs(2), server).await.unwrap().unwrap();
+    }
+}
@@ -0,0 +1,90 @@
+use std::fmt;
+
+/// `Cache-Control` a route's responses are sent with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Never stored by browsers or shared caches; for anything carrying
+    /// tokens or account details
+    NoStore,
+    /// Only the requesting client's own cache may keep it, for `max_age`
+    /// seconds
+    Private { max_age: u32 },
+    /// CDNs and other shared caches may keep it too
+    Public { max_age: u32 },
+}
+
+impl CachePolicy {
+    /// Read a header value such as `no-store`, `private, max-age=60` or
+    /// `public, max-age=300`
+    pub fn parse(s: &str) -> Option<Self> {
+        let directives: Vec<String> = s
+            .split(',')
+            .map(|d| d.trim().to_ascii_lowercase())
+            .filter(|d| !d.is_empty())
+            .collect();
+        if directives == ["no-store"] {
+            return Some(Self::NoStore);
+        }
+
+        let mut scope = None;
+        let mut max_age = None;
+        for directive in &directives {
+            match directive.split_once('=') {
+                Some(("max-age", secs)) if max_age.is_none() => max_age = Some(secs.parse().ok()?),
+                None if scope.is_none() && (directive == "private" || directive == "public") => {
+                    scope = Some(directive.as_str())
+                },
+                _ => return None,
+            }
+        }
+        match (scope?, max_age?) {
+            ("public", max_age) => Some(Self::Public { max_age }),
+            (_, max_age) => Some(Self::Private { max_age }),
+        }
+    }
+}
+
+impl fmt::Display for CachePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoStore => write!(f, "no-store"),
+            Self::Private { max_age } => write!(f, "private, max-age={}", max_age),
+            Self::Public { max_age } => write!(f, "public, max-age={}", max_age),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_what_it_renders() {
+        for policy in [
+            CachePolicy::NoStore,
+            CachePolicy::Private { max_age: 60 },
+            CachePolicy::Public { max_age: 0 },
+        ] {
+            assert_eq!(CachePolicy::parse(&policy.to_string()), Some(policy));
+        }
+        assert_eq!(
+            CachePolicy::parse(" Max-Age=30 , PUBLIC "),
+            Some(CachePolicy::Public { max_age: 30 })
+        );
+    }
+
+    #[test]
+    fn rejects_incomplete_or_contradictory_values() {
+        for bad in [
+            "",
+            "private",
+            "max-age=60",
+            "private, public, max-age=60",
+            "private, max-age=-1",
+            "no-store, max-age=60",
+            "public, max-age=60, immutable",
+        ] {
+            assert_eq!(CachePolicy::parse(bad), None, "{:?}", bad);
+        }
+    }
+}
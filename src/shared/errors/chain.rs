@@ -0,0 +1,23 @@
+use std::{backtrace::BacktraceStatus, error::Error};
+
+/// Messages of every error beneath `err`, nearest cause first.
+///
+/// `err` itself is not included; a chain ends at the first error whose
+/// `source()` is `None`.
+pub fn source_chain(err: &(dyn Error + 'static)) -> Vec<String> {
+    std::iter::successors(err.source(), |&e| e.source())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Log an internal error with its full cause chain as a structured field,
+/// and its backtrace when one was captured (`RUST_BACKTRACE=1`).
+pub fn log_internal_error(err: &anyhow::Error) {
+    let causes = source_chain(err.as_ref());
+    let backtrace = err.backtrace();
+    if backtrace.status() == BacktraceStatus::Captured {
+        tracing::error!(error = %err, causes = ?causes, backtrace = %backtrace, "Internal error");
+    } else {
+        tracing::error!(error = %err, causes = ?causes, "Internal error");
+    }
+}
@@ -3,8 +3,15 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
 use serde_json::json;
 
+mod chain;
+mod retry_after;
+
+pub use chain::{log_internal_error, source_chain};
+pub use retry_after::{retry_after_response, RETRY_AFTER_FIELD};
+
 /// Application-wide error type
 #[derive(Debug, thiserror::Error)]
 pub enum AppError {
@@ -23,6 +30,18 @@ pub enum AppError {
     #[error("Forbidden")]
     Forbidden,
 
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    /// A transient condition, e.g. a serialization failure; the client may retry
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
+
+    /// The caller must back off; rendered as 429 with `Retry-After`. Every
+    /// 429, from the rate limiter or a handler, is answered through this.
+    #[error("Too many requests: {message}")]
+    TooManyRequests { message: String, retry_after_secs: u64 },
+
     #[error("Internal server error")]
     Internal(#[from] anyhow::Error),
 
@@ -33,16 +52,24 @@ pub enum AppError {
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
-            AppError::Database(ref e) => {
-                tracing::error!("Database error: {:?}", e);
-                (StatusCode::INTERNAL_SERVER_ERROR, "Database error occurred")
-            },
+            AppError::Database(ref e) => database_error_response(e),
             AppError::NotFound(ref msg) => (StatusCode::NOT_FOUND, msg.as_str()),
             AppError::Validation(ref msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
             AppError::Unauthorized(ref msg) => (StatusCode::UNAUTHORIZED, msg.as_str()),
             AppError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden"),
+            AppError::Conflict(ref msg) => (StatusCode::CONFLICT, msg.as_str()),
+            AppError::ServiceUnavailable(ref msg) => {
+                (StatusCode::SERVICE_UNAVAILABLE, msg.as_str())
+            },
+            AppError::TooManyRequests { message, retry_after_secs } => {
+                return retry_after_response(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    json!({ "success": false, "error": message }),
+                    retry_after_secs,
+                );
+            },
             AppError::Internal(ref e) => {
-                tracing::error!("Internal error: {:?}", e);
+                log_internal_error(e);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error")
             },
             AppError::Config(ref msg) => {
@@ -60,9 +87,123 @@ impl IntoResponse for AppError {
     }
 }
 
+/// Status and client-safe message for a raw Diesel error; constraint names
+/// and SQL never reach the response body.
+fn database_error_response(err: &DieselError) -> (StatusCode, &'static str) {
+    match err {
+        DieselError::NotFound => (StatusCode::NOT_FOUND, "Resource not found"),
+        DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _) => {
+            (StatusCode::CONFLICT, "Resource already exists")
+        },
+        DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _) => {
+            (StatusCode::BAD_REQUEST, "Referenced resource does not exist")
+        },
+        DieselError::DatabaseError(DatabaseErrorKind::SerializationFailure, _) => {
+            (StatusCode::SERVICE_UNAVAILABLE, "Database busy, please retry")
+        },
+        _ => {
+            tracing::error!("Database error: {:?}", err);
+            (StatusCode::INTERNAL_SERVER_ERROR, "Database error occurred")
+        },
+    }
+}
+
 /// Convert from config errors
 impl From<crate::config::app_config::ConfigError> for AppError {
     fn from(err: crate::config::app_config::ConfigError) -> Self {
         AppError::Config(err.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_of(err: AppError) -> StatusCode {
+        err.into_response().status()
+    }
+
+    fn db_error(kind: DatabaseErrorKind) -> AppError {
+        AppError::Database(DieselError::DatabaseError(kind, Box::new("boom".to_string())))
+    }
+
+    #[test]
+    fn database_errors_map_to_specific_statuses() {
+        assert_eq!(status_of(db_error(DatabaseErrorKind::UniqueViolation)), StatusCode::CONFLICT);
+        assert_eq!(
+            status_of(db_error(DatabaseErrorKind::ForeignKeyViolation)),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status_of(db_error(DatabaseErrorKind::SerializationFailure)),
+            StatusCode::SERVICE_UNAVAILABLE
+        );
+        assert_eq!(status_of(AppError::Database(DieselError::NotFound)), StatusCode::NOT_FOUND);
+        assert_eq!(
+            status_of(db_error(DatabaseErrorKind::CheckViolation)),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[tokio::test]
+    async fn too_many_requests_sets_retry_after() {
+        let response =
+            AppError::TooManyRequests { message: "Slow down".into(), retry_after_secs: 30 }
+                .into_response();
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[axum::http::header::RETRY_AFTER], "30");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body, json!({ "success": false, "error": "Slow down", RETRY_AFTER_FIELD: 30 }));
+    }
+
+    #[test]
+    fn internal_errors_log_every_cause_but_answer_generically() {
+        use anyhow::Context;
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Logs(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Logs {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap_or_else(|e| e.into_inner()).extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let root = std::io::Error::new(std::io::ErrorKind::ConnectionReset, "socket reset");
+        let err = Err::<(), _>(root)
+            .context("reading reply from smtp.internal")
+            .context("sending confirmation email")
+            .unwrap_err();
+        assert_eq!(
+            source_chain(err.as_ref()),
+            ["reading reply from smtp.internal", "socket reset"]
+        );
+
+        let logs = Logs::default();
+        let writer = logs.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .with_ansi(false)
+            .finish();
+        let response = tracing::subscriber::with_default(subscriber, || {
+            AppError::Internal(err).into_response()
+        });
+
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        let logged = String::from_utf8(logs.0.lock().unwrap().clone()).unwrap();
+        assert!(logged.contains("error=sending confirmation email"), "{}", logged);
+        assert!(
+            logged.contains(r#"causes=["reading reply from smtp.internal", "socket reset"]"#),
+            "{}",
+            logged
+        );
+    }
+}
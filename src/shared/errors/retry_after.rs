@@ -0,0 +1,55 @@
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde_json::Value;
+
+/// Body field mirroring the `Retry-After` header, for clients that only
+/// read the JSON payload.
+pub const RETRY_AFTER_FIELD: &str = "retry_after_seconds";
+
+/// Build a throttled/unavailable response carrying the same delay in both
+/// the `Retry-After` header and the `retry_after_seconds` body field.
+///
+/// Every response that asks the client to back off (load shedding, and
+/// every 429 via `AppError::TooManyRequests`) goes through here so the two
+/// never drift apart. A non-object `body` is sent unchanged apart from the
+/// header.
+pub fn retry_after_response(
+    status: StatusCode,
+    mut body: Value,
+    retry_after_secs: u64,
+) -> Response {
+    if let Some(fields) = body.as_object_mut() {
+        fields.insert(RETRY_AFTER_FIELD.to_string(), retry_after_secs.into());
+    }
+
+    let mut response = (status, Json(body)).into_response();
+    response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::to_bytes;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn header_and_body_field_carry_the_same_delay() {
+        let response = retry_after_response(
+            StatusCode::TOO_MANY_REQUESTS,
+            json!({ "success": false, "error": "slow down" }),
+            7,
+        );
+
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[header::RETRY_AFTER], "7");
+        let bytes = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body, json!({ "success": false, "error": "slow down", RETRY_AFTER_FIELD: 7 }));
+    }
+}
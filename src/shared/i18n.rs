@@ -0,0 +1,247 @@
+//! Minimal keyed message bundle.
+//!
+//! English is the source language: error responses are produced in English
+//! and translated on the way out, emails are rendered from keys. Any key
+//! without a translation falls back to English.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Es,
+    Vi,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 3] = [Locale::En, Locale::Es, Locale::Vi];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Es => "es",
+            Locale::Vi => "vi",
+        }
+    }
+
+    /// Match a BCP 47 tag on its primary subtag, e.g. `vi-VN` -> `Vi`.
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next().unwrap_or_default();
+        Self::ALL.into_iter().find(|l| l.as_str().eq_ignore_ascii_case(primary))
+    }
+
+    /// Pick the best supported locale from an `Accept-Language` header,
+    /// honouring q-values. Unsupported or malformed headers yield English.
+    pub fn negotiate(accept_language: &str) -> Self {
+        accept_language
+            .split(',')
+            .filter_map(|part| {
+                let mut pieces = part.split(';');
+                let locale = Self::from_tag(pieces.next()?)?;
+                let quality = pieces
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                (quality > 0.0).then_some((locale, quality))
+            })
+            // max_by keeps the last maximum; reverse so the first listed wins ties
+            .rev()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(locale, _)| locale)
+            .unwrap_or_default()
+    }
+}
+
+impl fmt::Display for Locale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// (key, en, es, vi) — an empty string marks a missing translation.
+type Entry = (&'static str, &'static str, &'static str, &'static str);
+
+#[rustfmt::skip]
+static CATALOG: &[Entry] = &[
+    // Errors (looked up by their English text)
+    ("error.invalid_credentials", "Invalid credentials", "Credenciales inválidas", "Thông tin đăng nhập không hợp lệ"),
+    ("error.account_inactive", "User account is inactive", "La cuenta de usuario está inactiva", "Tài khoản người dùng chưa được kích hoạt"),
+    ("error.email_exists", "Email already exists", "El correo electrónico ya existe", "Email đã tồn tại"),
+    ("error.invalid_email", "Invalid email format", "Formato de correo electrónico no válido", "Định dạng email không hợp lệ"),
+    ("error.user_not_found", "User not found", "Usuario no encontrado", "Không tìm thấy người dùng"),
+    ("error.invalid_code", "Invalid confirmation code", "Código de confirmación no válido", "Mã xác nhận không hợp lệ"),
+    ("error.code_expired", "Confirmation code expired", "El código de confirmación ha caducado", "Mã xác nhận đã hết hạn"),
+    ("error.already_verified", "User already verified", "El usuario ya está verificado", "Người dùng đã được xác minh"),
+    ("error.token_not_found", "Token not found", "Token no encontrado", "Không tìm thấy token"),
+    ("error.missing_token", "Missing authorization token", "Falta el token de autorización", "Thiếu token xác thực"),
+    ("error.invalid_token", "Invalid token", "Token no válido", "Token không hợp lệ"),
+    ("error.token_expired", "Token expired", "El token ha caducado", "Token đã hết hạn"),
+    ("error.invalid_token_type", "Invalid token type. Expected access token", "Tipo de token no válido. Se esperaba un token de acceso", "Loại token không hợp lệ. Cần access token"),
+    ("error.invalid_user_id", "Invalid user ID", "ID de usuario no válido", "ID người dùng không hợp lệ"),
+    ("error.name_length", "Name must be between 1 and 255 characters", "El nombre debe tener entre 1 y 255 caracteres", "Tên phải có từ 1 đến 255 ký tự"),
+    ("error.code_length", "Code must be at least 6 characters", "El código debe tener al menos 6 caracteres", "Mã phải có ít nhất 6 ký tự"),
+    ("error.password_length", "Password must be at least 8 characters", "La contraseña debe tener al menos 8 caracteres", "Mật khẩu phải có ít nhất 8 ký tự"),
+    ("error.resource_exists", "Resource already exists", "El recurso ya existe", "Tài nguyên đã tồn tại"),
+    ("error.invalid_reference", "Referenced resource does not exist", "El recurso referenciado no existe", "Tài nguyên được tham chiếu không tồn tại"),
+    ("error.database_busy", "Database busy, please retry", "Base de datos ocupada, inténtalo de nuevo", "Cơ sở dữ liệu đang bận, vui lòng thử lại"),
+    ("error.forbidden", "Insufficient permissions", "Permisos insuficientes", "Không đủ quyền truy cập"),
+    ("error.session_limit", "Maximum number of active sessions reached", "Se alcanzó el número máximo de sesiones activas", "Đã đạt số phiên đăng nhập tối đa"),
+    ("error.refresh_token_required", "Refresh token is required", "El token de actualización es obligatorio", "Cần có refresh token"),
+    ("error.name_not_clearable", "Name cannot be cleared", "El nombre no se puede borrar", "Không thể xóa tên"),
+    ("error.resend_cooldown", "Please wait before requesting another code", "Espera antes de solicitar otro código", "Vui lòng đợi trước khi yêu cầu mã khác"),
+    ("error.password_reused", "Password was used recently, choose a different one", "La contraseña se usó recientemente, elige otra", "Mật khẩu đã được dùng gần đây, hãy chọn mật khẩu khác"),
+    ("error.password_changed_recently", "Password was changed too recently, try again later", "La contraseña se cambió hace muy poco, inténtalo más tarde", "Mật khẩu vừa được thay đổi, vui lòng thử lại sau"),
+    ("error.registration_in_progress", "A registration for this email is already in progress", "Ya hay un registro en curso para este correo electrónico", "Đang có một yêu cầu đăng ký cho email này"),
+    ("error.unsupported_locale", "Unsupported locale", "Idioma no compatible", "Ngôn ngữ không được hỗ trợ"),
+
+    // Email: shared
+    ("email.greeting", "Hello", "Hola", "Xin chào"),
+    ("email.footer.rights", "All rights reserved.", "Todos los derechos reservados.", "Bảo lưu mọi quyền."),
+    ("email.footer.privacy", "Privacy Policy", "Política de privacidad", "Chính sách bảo mật"),
+    ("email.footer.terms", "Terms of Service", "Términos del servicio", "Điều khoản dịch vụ"),
+
+    // Email: welcome
+    ("email.welcome.subject", "Welcome to Axum Backend!", "¡Bienvenido a Axum Backend!", "Chào mừng bạn đến với Axum Backend!"),
+    ("email.welcome.heading", "Welcome Aboard!", "¡Te damos la bienvenida!", "Chào mừng bạn!"),
+    ("email.welcome.greeting", "Hi", "Hola", "Chào"),
+    ("email.welcome.intro", "We are thrilled to have you with us! Your account has been successfully created and verified. You are now ready to explore all the features we have to offer.", "¡Nos alegra mucho tenerte con nosotros! Tu cuenta se ha creado y verificado correctamente. Ya puedes explorar todas las funciones que ofrecemos.", "Chúng tôi rất vui khi có bạn đồng hành! Tài khoản của bạn đã được tạo và xác minh thành công. Giờ đây bạn có thể khám phá mọi tính năng mà chúng tôi cung cấp."),
+    ("email.welcome.cta_intro", "Get started by logging into your dashboard.", "Empieza iniciando sesión en tu panel.", "Hãy bắt đầu bằng cách đăng nhập vào bảng điều khiển."),
+    ("email.welcome.cta", "Go to Dashboard", "Ir al panel", "Đến bảng điều khiển"),
+
+    // Email: confirmation
+    ("email.confirmation.subject", "Confirm your registration", "Confirma tu registro", "Xác nhận đăng ký của bạn"),
+    ("email.confirmation.title", "Confirm Your Email", "Confirma tu correo electrónico", "Xác nhận email của bạn"),
+    ("email.confirmation.heading", "Confirm Your Email Address", "Confirma tu dirección de correo electrónico", "Xác nhận địa chỉ email của bạn"),
+    ("email.confirmation.intro", "Thank you for registering with our service. To complete your account setup and ensure the security of your information, please verify your email address using the code below.", "Gracias por registrarte en nuestro servicio. Para completar la configuración de tu cuenta y garantizar la seguridad de tu información, verifica tu dirección de correo electrónico con el siguiente código.", "Cảm ơn bạn đã đăng ký dịch vụ của chúng tôi. Để hoàn tất thiết lập tài khoản và đảm bảo an toàn cho thông tin của bạn, vui lòng xác minh địa chỉ email bằng mã dưới đây."),
+    ("email.confirmation.link_intro", "Or confirm with one click:", "O confírmalo con un clic:", "Hoặc xác nhận chỉ với một cú nhấp:"),
+    ("email.confirmation.link", "Confirm Email", "Confirmar correo", "Xác nhận email"),
+    ("email.confirmation.outro", "This code will expire in 15 minutes. If you did not request this verification, please ignore this email.", "Este código caducará en 15 minutos. Si no solicitaste esta verificación, ignora este correo.", "Mã này sẽ hết hạn sau 15 phút. Nếu bạn không yêu cầu xác minh này, vui lòng bỏ qua email này."),
+
+    // Email: password reset
+    ("email.password_reset.subject", "Reset your password", "Restablece tu contraseña", "Đặt lại mật khẩu của bạn"),
+    ("email.password_reset.heading", "Reset Your Password", "Restablece tu contraseña", "Đặt lại mật khẩu"),
+    ("email.password_reset.intro", "We received a request to reset your password. Use the code below to complete the process.", "Recibimos una solicitud para restablecer tu contraseña. Usa el siguiente código para completar el proceso.", "Chúng tôi đã nhận được yêu cầu đặt lại mật khẩu của bạn. Hãy dùng mã dưới đây để hoàn tất."),
+    ("email.password_reset.outro", "This code will expire shortly. If you did not request a password reset, please ignore this email or contact support if you have concerns.", "Este código caducará pronto. Si no solicitaste restablecer tu contraseña, ignora este correo o contacta con soporte si tienes dudas.", "Mã này sẽ sớm hết hạn. Nếu bạn không yêu cầu đặt lại mật khẩu, vui lòng bỏ qua email này hoặc liên hệ bộ phận hỗ trợ nếu có thắc mắc."),
+
+    // Email: account locked
+    ("email.account_locked.subject", "Your account was locked", "Tu cuenta ha sido bloqueada", "Tài khoản của bạn đã bị khóa"),
+    ("email.account_locked.heading", "Account Locked", "Cuenta bloqueada", "Tài khoản bị khóa"),
+    ("email.account_locked.intro", "We locked your account after several failed sign-in attempts. It will unlock automatically after a short while.", "Bloqueamos tu cuenta tras varios intentos fallidos de inicio de sesión. Se desbloqueará automáticamente en poco tiempo.", "Chúng tôi đã khóa tài khoản của bạn sau nhiều lần đăng nhập không thành công. Tài khoản sẽ tự động mở khóa sau một thời gian ngắn."),
+    ("email.account_locked.ip", "Last attempt from", "Último intento desde", "Lần thử cuối từ"),
+    ("email.account_locked.time", "Locked at", "Bloqueada el", "Bị khóa lúc"),
+    ("email.account_locked.outro", "If this was not you, someone may be trying to access your account. Consider resetting your password once it unlocks.", "Si no fuiste tú, puede que alguien esté intentando acceder a tu cuenta. Considera restablecer tu contraseña cuando se desbloquee.", "Nếu đó không phải là bạn, có thể ai đó đang cố truy cập tài khoản của bạn. Hãy cân nhắc đặt lại mật khẩu khi tài khoản được mở khóa."),
+
+    // Email: inactivity warning
+    ("email.inactivity_warning.subject", "Your account will be disabled soon", "Tu cuenta se desactivará pronto", "Tài khoản của bạn sắp bị vô hiệu hóa"),
+    ("email.inactivity_warning.heading", "We Miss You", "Te echamos de menos", "Chúng tôi nhớ bạn"),
+    ("email.inactivity_warning.intro", "You have not signed in for a long time. Inactive accounts are disabled to keep them safe.", "Hace mucho que no inicias sesión. Las cuentas inactivas se desactivan para mantenerlas seguras.", "Đã lâu bạn không đăng nhập. Các tài khoản không hoạt động sẽ bị vô hiệu hóa để đảm bảo an toàn."),
+    ("email.inactivity_warning.date", "Disabled on", "Se desactivará el", "Vô hiệu hóa vào"),
+    ("email.inactivity_warning.outro", "Sign in before then to keep your account active.", "Inicia sesión antes de esa fecha para mantener tu cuenta activa.", "Hãy đăng nhập trước thời điểm đó để giữ tài khoản hoạt động."),
+
+    // Page: verification link
+    ("page.verify_link.title", "Confirm Your Email", "Confirma tu correo electrónico", "Xác nhận email của bạn"),
+    ("page.verify_link.intro", "Press the button below to confirm this email address.", "Pulsa el botón de abajo para confirmar esta dirección de correo electrónico.", "Nhấn nút bên dưới để xác nhận địa chỉ email này."),
+    ("page.verify_link.button", "Confirm Email", "Confirmar correo", "Xác nhận email"),
+];
+
+fn pick(entry: &Entry, locale: Locale) -> &'static str {
+    let (_, en, es, vi) = *entry;
+    let text = match locale {
+        Locale::En => en,
+        Locale::Es => es,
+        Locale::Vi => vi,
+    };
+    if text.is_empty() {
+        en
+    } else {
+        text
+    }
+}
+
+/// Translate a catalog key, falling back to English and then to the key itself.
+pub fn t(locale: Locale, key: &str) -> &str {
+    CATALOG
+        .iter()
+        .find(|entry| entry.0 == key)
+        .map_or(key, |entry| pick(entry, locale))
+}
+
+/// Translate an English message produced elsewhere in the app.
+///
+/// Validation errors arrive as `field: message` lines, so each line is
+/// translated on its own and the field name is kept. Returns `None` when
+/// nothing in the message is known.
+pub fn localize_message(locale: Locale, message: &str) -> Option<String> {
+    let lookup = |english: &str| {
+        CATALOG.iter().find(|entry| entry.1 == english).map(|entry| pick(entry, locale))
+    };
+
+    if let Some(text) = lookup(message) {
+        return Some(text.to_string());
+    }
+
+    let mut translated_any = false;
+    let lines: Vec<String> = message
+        .lines()
+        .map(|line| match line.split_once(": ").and_then(|(f, m)| Some((f, lookup(m)?))) {
+            Some((field, text)) => {
+                translated_any = true;
+                format!("{}: {}", field, text)
+            },
+            None => line.to_string(),
+        })
+        .collect();
+
+    translated_any.then(|| lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_tag_matches_primary_subtag() {
+        assert_eq!(Locale::from_tag("vi-VN"), Some(Locale::Vi));
+        assert_eq!(Locale::from_tag("ES"), Some(Locale::Es));
+        assert_eq!(Locale::from_tag("fr"), None);
+    }
+
+    #[test]
+    fn negotiate_prefers_highest_quality_supported_locale() {
+        assert_eq!(Locale::negotiate("fr-FR, vi;q=0.8, es;q=0.9"), Locale::Es);
+        assert_eq!(Locale::negotiate("vi, es"), Locale::Vi);
+        assert_eq!(Locale::negotiate("vi;q=0, es;q=0.5"), Locale::Es);
+        assert_eq!(Locale::negotiate("fr, de"), Locale::En);
+        assert_eq!(Locale::negotiate(""), Locale::En);
+    }
+
+    #[test]
+    fn t_falls_back_to_english_then_key() {
+        assert_eq!(t(Locale::Vi, "email.greeting"), "Xin chào");
+        assert_eq!(t(Locale::En, "email.greeting"), "Hello");
+        assert_eq!(t(Locale::Es, "email.unknown"), "email.unknown");
+    }
+
+    #[test]
+    fn catalog_keys_are_unique_and_english_is_complete() {
+        for (i, entry) in CATALOG.iter().enumerate() {
+            assert!(!entry.1.is_empty(), "{} has no English text", entry.0);
+            assert!(CATALOG[i + 1..].iter().all(|other| other.0 != entry.0), "dup {}", entry.0);
+        }
+    }
+
+    #[test]
+    fn localize_message_translates_whole_and_per_field_messages() {
+        assert_eq!(
+            localize_message(Locale::Es, "Invalid credentials").as_deref(),
+            Some("Credenciales inválidas")
+        );
+        assert_eq!(
+            localize_message(Locale::Vi, "email: Invalid email format").as_deref(),
+            Some("email: Định dạng email không hợp lệ")
+        );
+        assert_eq!(localize_message(Locale::Vi, "Repository error: timeout"), None);
+    }
+}
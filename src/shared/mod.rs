@@ -1,6 +1,11 @@
+pub mod cache_control;
 pub mod errors;
+pub mod i18n;
+pub mod rate_limiter;
+pub mod tasks;
 pub mod telemetry;
 pub mod utils;
 
 pub use errors::AppError;
+pub use tasks::TaskRegistry;
 pub use telemetry::init_telemetry;
@@ -0,0 +1,328 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Keys tracked before idle ones are first swept out on the next check
+const SWEEP_THRESHOLD: usize = 10_000;
+
+/// How requests are counted against a quota of `limit` per `window`.
+///
+/// All three admit the same long-run rate; they differ in how bursts are
+/// treated:
+/// - `TokenBucket`: up to `limit` at once from a full bucket, then one
+///   more every `window / limit`. Bursts are smoothed out.
+/// - `FixedWindow`: up to `limit` per window, counted from the key's first
+///   request. Cheapest, but a client can send `limit` at the end of one
+///   window and `limit` more at the start of the next, so up to twice the
+///   limit can land in quick succession.
+/// - `SlidingWindowLog`: never more than `limit` in any `window`-long span.
+///   Strictest; remembers one timestamp per admitted request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitAlgorithm {
+    #[default]
+    TokenBucket,
+    FixedWindow,
+    SlidingWindowLog,
+}
+
+impl RateLimitAlgorithm {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "token_bucket" => Some(Self::TokenBucket),
+            "fixed_window" => Some(Self::FixedWindow),
+            "sliding_window" | "sliding_window_log" => Some(Self::SlidingWindowLog),
+            _ => None,
+        }
+    }
+}
+
+/// Where a key stands against its quota after a check
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quota {
+    /// Requests admitted per window
+    pub limit: u32,
+    /// Requests that would still be admitted right now
+    pub remaining: u32,
+    /// Time until the quota is back to `limit` if no more requests arrive
+    pub reset: Duration,
+}
+
+/// A refused request: how long until one would be admitted, and the quota
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Throttled {
+    pub retry_after: Duration,
+    pub quota: Quota,
+}
+
+/// Per-key states, and the count that triggers the next sweep of idle ones
+struct Keys<K> {
+    states: HashMap<K, State>,
+    sweep_at: usize,
+}
+
+enum State {
+    Bucket { tokens: f64, updated: Instant },
+    Window { started: Instant, count: u32 },
+    Log(VecDeque<Instant>),
+}
+
+/// Per-key request quota held in process memory.
+///
+/// Each check reads and updates a key's state under one lock, so
+/// concurrent requests can never be admitted past the limit.
+pub struct RateLimiter<K> {
+    algorithm: RateLimitAlgorithm,
+    limit: u32,
+    window: Duration,
+    keys: Mutex<Keys<K>>,
+}
+
+impl<K: Hash + Eq + Clone> RateLimiter<K> {
+    /// Admit `limit` requests per `window` for each key; `limit` is raised
+    /// to at least one
+    pub fn new(algorithm: RateLimitAlgorithm, limit: u32, window: Duration) -> Self {
+        let keys = Keys { states: HashMap::new(), sweep_at: SWEEP_THRESHOLD };
+        Self { algorithm, limit: limit.max(1), window, keys: Mutex::new(keys) }
+    }
+
+    /// Count a request for `key` and return the quota left, or how long
+    /// until one would be admitted
+    pub fn check(&self, key: &K) -> Result<Quota, Throttled> {
+        self.check_at(key, Instant::now())
+    }
+
+    /// `check` as of `now`, which must not go backwards between calls
+    pub fn check_at(&self, key: &K, now: Instant) -> Result<Quota, Throttled> {
+        let mut keys = self.keys.lock().unwrap_or_else(|e| e.into_inner());
+        if keys.states.len() >= keys.sweep_at {
+            keys.states.retain(|_, state| !self.is_idle(state, now));
+            // Wait for the live keys to double before sweeping again, so a
+            // flood of distinct keys costs O(1) per check amortized rather
+            // than a full scan on every one
+            keys.sweep_at = (keys.states.len() * 2).max(SWEEP_THRESHOLD);
+        }
+
+        let state = keys.states.entry(key.clone()).or_insert_with(|| self.fresh_state(now));
+        let admitted = self.admit(state, now);
+        let quota = self.quota(state, now);
+        admitted.map(|()| quota).map_err(|retry_after| Throttled { retry_after, quota })
+    }
+
+    fn fresh_state(&self, now: Instant) -> State {
+        match self.algorithm {
+            RateLimitAlgorithm::TokenBucket => {
+                State::Bucket { tokens: f64::from(self.limit), updated: now }
+            },
+            RateLimitAlgorithm::FixedWindow => State::Window { started: now, count: 0 },
+            RateLimitAlgorithm::SlidingWindowLog => State::Log(VecDeque::new()),
+        }
+    }
+
+    fn admit(&self, state: &mut State, now: Instant) -> Result<(), Duration> {
+        let limit = f64::from(self.limit);
+        match state {
+            State::Bucket { tokens, updated } => {
+                let per_token = self.window.as_secs_f64() / limit;
+                let refilled = now.saturating_duration_since(*updated).as_secs_f64() / per_token;
+                *tokens = (*tokens + refilled).min(limit);
+                *updated = now;
+                if *tokens >= 1.0 {
+                    *tokens -= 1.0;
+                    Ok(())
+                } else {
+                    Err(Duration::from_secs_f64((1.0 - *tokens) * per_token))
+                }
+            },
+            State::Window { started, count } => {
+                if now.saturating_duration_since(*started) >= self.window {
+                    *started = now;
+                    *count = 0;
+                }
+                if *count < self.limit {
+                    *count += 1;
+                    Ok(())
+                } else {
+                    Err((*started + self.window).saturating_duration_since(now))
+                }
+            },
+            State::Log(admitted) => {
+                while admitted
+                    .front()
+                    .is_some_and(|t| now.saturating_duration_since(*t) >= self.window)
+                {
+                    admitted.pop_front();
+                }
+                match admitted.front() {
+                    Some(oldest) if admitted.len() >= self.limit as usize => {
+                        Err((*oldest + self.window).saturating_duration_since(now))
+                    },
+                    _ => {
+                        admitted.push_back(now);
+                        Ok(())
+                    },
+                }
+            },
+        }
+    }
+
+    /// Quota left in `state`, which `admit` has just brought up to `now`
+    fn quota(&self, state: &State, now: Instant) -> Quota {
+        let (remaining, reset) = match state {
+            State::Bucket { tokens, .. } => {
+                let per_token = self.window.as_secs_f64() / f64::from(self.limit);
+                let missing = (f64::from(self.limit) - *tokens).max(0.0);
+                (*tokens as u32, Duration::from_secs_f64(missing * per_token))
+            },
+            State::Window { started, count } => (
+                self.limit.saturating_sub(*count),
+                (*started + self.window).saturating_duration_since(now),
+            ),
+            State::Log(admitted) => (
+                self.limit.saturating_sub(u32::try_from(admitted.len()).unwrap_or(u32::MAX)),
+                admitted
+                    .back()
+                    .map_or(Duration::ZERO, |t| (*t + self.window).saturating_duration_since(now)),
+            ),
+        };
+        Quota { limit: self.limit, remaining, reset }
+    }
+
+    /// Whether forgetting `state` would change no future decision
+    fn is_idle(&self, state: &State, now: Instant) -> bool {
+        let last = match state {
+            State::Bucket { updated, .. } => *updated,
+            State::Window { started, .. } => *started,
+            State::Log(admitted) => match admitted.back() {
+                Some(t) => *t,
+                None => return true,
+            },
+        };
+        now.saturating_duration_since(last) >= self.window
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    /// Requests admitted out of `attempts` sent at `at`
+    fn admitted(limiter: &RateLimiter<&str>, at: Instant, attempts: usize) -> usize {
+        (0..attempts).filter(|_| limiter.check_at(&"client", at).is_ok()).count()
+    }
+
+    #[test]
+    fn fixed_window_admits_a_double_burst_across_the_boundary_token_bucket_does_not() {
+        // Same nominal rate for both: 10 requests per 10 seconds
+        let bucket = RateLimiter::new(RateLimitAlgorithm::TokenBucket, 10, 10 * SECOND);
+        let fixed = RateLimiter::new(RateLimitAlgorithm::FixedWindow, 10, 10 * SECOND);
+        let start = Instant::now();
+        // A quiet first request opens the window, then a burst just before it
+        // closes and another just after
+        let late = start + Duration::from_millis(9_900);
+        let early = start + Duration::from_millis(10_100);
+
+        assert_eq!(admitted(&bucket, start, 1), 1);
+        assert_eq!(admitted(&fixed, start, 1), 1);
+        let through_bucket = admitted(&bucket, late, 20) + admitted(&bucket, early, 20);
+        let through_fixed = admitted(&fixed, late, 20) + admitted(&fixed, early, 20);
+
+        assert_eq!(through_fixed, 19);
+        // The bucket refills to at most its capacity, and 0.2s adds no whole token
+        assert_eq!(through_bucket, 10);
+    }
+
+    #[test]
+    fn sliding_log_never_exceeds_the_limit_in_any_window() {
+        let limiter = RateLimiter::new(RateLimitAlgorithm::SlidingWindowLog, 3, 10 * SECOND);
+        let start = Instant::now();
+
+        assert_eq!(admitted(&limiter, start, 1), 1);
+        assert_eq!(admitted(&limiter, start + 9 * SECOND, 5), 2);
+        // Only the first request has aged out
+        assert_eq!(admitted(&limiter, start + 10 * SECOND, 5), 1);
+        let wait = limiter.check_at(&"client", start + 10 * SECOND).unwrap_err().retry_after;
+        assert_eq!(wait, 9 * SECOND);
+    }
+
+    #[test]
+    fn token_bucket_refills_one_token_per_interval() {
+        let limiter = RateLimiter::new(RateLimitAlgorithm::TokenBucket, 2, 4 * SECOND);
+        let start = Instant::now();
+
+        assert_eq!(admitted(&limiter, start, 3), 2);
+        assert_eq!(limiter.check_at(&"client", start).unwrap_err().retry_after, 2 * SECOND);
+        assert_eq!(admitted(&limiter, start + 2 * SECOND, 3), 1);
+    }
+
+    #[test]
+    fn remaining_quota_counts_down_to_zero() {
+        for algorithm in [
+            RateLimitAlgorithm::TokenBucket,
+            RateLimitAlgorithm::FixedWindow,
+            RateLimitAlgorithm::SlidingWindowLog,
+        ] {
+            let limiter = RateLimiter::new(algorithm, 3, 30 * SECOND);
+            let now = Instant::now();
+
+            let remaining: Vec<u32> =
+                (0..3).map(|_| limiter.check_at(&"client", now).unwrap().remaining).collect();
+            assert_eq!(remaining, [2, 1, 0], "{:?}", algorithm);
+
+            let throttled = limiter.check_at(&"client", now).unwrap_err();
+            assert_eq!(throttled.quota.limit, 3);
+            assert_eq!(throttled.quota.remaining, 0);
+            assert_eq!(throttled.quota.reset, 30 * SECOND, "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn keys_are_limited_independently() {
+        for algorithm in [
+            RateLimitAlgorithm::TokenBucket,
+            RateLimitAlgorithm::FixedWindow,
+            RateLimitAlgorithm::SlidingWindowLog,
+        ] {
+            let limiter = RateLimiter::new(algorithm, 1, SECOND);
+            let now = Instant::now();
+            assert!(limiter.check_at(&"a", now).is_ok());
+            assert!(limiter.check_at(&"a", now).is_err(), "{:?}", algorithm);
+            assert!(limiter.check_at(&"b", now).is_ok(), "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn live_keys_push_the_next_sweep_out_instead_of_rescanning_every_check() {
+        let limiter = RateLimiter::new(RateLimitAlgorithm::FixedWindow, 1, 60 * SECOND);
+        let now = Instant::now();
+        for key in 0..=SWEEP_THRESHOLD {
+            assert!(limiter.check_at(&key, now).is_ok());
+        }
+
+        let keys = limiter.keys.lock().unwrap();
+        // None were idle, so the sweep kept them all and backed off
+        assert_eq!(keys.states.len(), SWEEP_THRESHOLD + 1);
+        assert_eq!(keys.sweep_at, SWEEP_THRESHOLD * 2);
+    }
+
+    #[test]
+    fn parses_algorithm_names() {
+        assert_eq!(
+            RateLimitAlgorithm::parse("token_bucket"),
+            Some(RateLimitAlgorithm::TokenBucket)
+        );
+        assert_eq!(
+            RateLimitAlgorithm::parse(" Fixed_Window "),
+            Some(RateLimitAlgorithm::FixedWindow)
+        );
+        assert_eq!(
+            RateLimitAlgorithm::parse("sliding_window"),
+            Some(RateLimitAlgorithm::SlidingWindowLog)
+        );
+        assert_eq!(RateLimitAlgorithm::parse("leaky"), None);
+    }
+}
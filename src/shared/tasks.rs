@@ -0,0 +1,141 @@
+use std::{future::Future, sync::Mutex, time::Duration};
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+/// Background tasks that must stop when the server shuts down.
+///
+/// Each task is handed a `CancellationToken` and is expected to return
+/// promptly once it is cancelled; `shutdown` cancels them all and waits.
+#[derive(Default)]
+pub struct TaskRegistry {
+    token: CancellationToken,
+    tasks: Mutex<Vec<(&'static str, JoinHandle<()>)>>,
+}
+
+impl TaskRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Spawn `task` on the runtime, passing the token it should watch.
+    /// Tasks that have already finished are dropped from the registry, so
+    /// short-lived tasks such as background imports do not pile up.
+    pub fn spawn<F, Fut>(&self, name: &'static str, task: F)
+    where
+        F: FnOnce(CancellationToken) -> Fut,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        let handle = tokio::spawn(task(self.token.child_token()));
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        tasks.retain(|(_, handle)| !handle.is_finished());
+        tasks.push((name, handle));
+    }
+
+    /// Number of tasks registered and still running
+    pub fn len(&self) -> usize {
+        let mut tasks = self.tasks.lock().unwrap_or_else(|e| e.into_inner());
+        tasks.retain(|(_, handle)| !handle.is_finished());
+        tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cancel every task, then wait up to `timeout` for them to finish.
+    /// Tasks still running after that are aborted; their names are returned.
+    pub async fn shutdown(&self, timeout: Duration) -> Vec<&'static str> {
+        self.token.cancel();
+        let tasks = std::mem::take(&mut *self.tasks.lock().unwrap_or_else(|e| e.into_inner()));
+        let deadline = tokio::time::Instant::now() + timeout;
+
+        let mut aborted = Vec::new();
+        for (name, mut handle) in tasks {
+            match tokio::time::timeout_at(deadline, &mut handle).await {
+                Ok(Ok(())) => tracing::debug!("Background task {} stopped", name),
+                Ok(Err(e)) => tracing::error!("Background task {} failed: {}", name, e),
+                Err(_) => {
+                    handle.abort();
+                    aborted.push(name);
+                },
+            }
+        }
+
+        if !aborted.is_empty() {
+            tracing::warn!(
+                "Aborted background task(s) still running after {:?}: {}",
+                timeout,
+                aborted.join(", ")
+            );
+        }
+        aborted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    };
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    #[tokio::test]
+    async fn shutdown_cancels_and_awaits_registered_tasks() {
+        let registry = TaskRegistry::new();
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let flag = stopped.clone();
+        registry.spawn("ticker", |token| async move {
+            loop {
+                tokio::select! {
+                    _ = token.cancelled() => break,
+                    _ = tokio::time::sleep(Duration::from_millis(10)) => {},
+                }
+            }
+            flag.store(true, Ordering::SeqCst);
+        });
+        assert_eq!(registry.len(), 1);
+
+        let aborted = tokio::time::timeout(Duration::from_secs(1), registry.shutdown(SECOND))
+            .await
+            .unwrap();
+
+        assert!(aborted.is_empty());
+        assert!(stopped.load(Ordering::SeqCst));
+        assert!(registry.is_empty());
+    }
+
+    #[tokio::test]
+    async fn tasks_ignoring_cancellation_are_aborted_at_the_deadline() {
+        let registry = TaskRegistry::new();
+        registry.spawn("stuck", |_token| std::future::pending());
+        registry.spawn("polite", |token| async move { token.cancelled().await });
+
+        let started = std::time::Instant::now();
+        let aborted = registry.shutdown(Duration::from_millis(100)).await;
+
+        assert_eq!(aborted, ["stuck"]);
+        assert!(started.elapsed() >= Duration::from_millis(100));
+        assert!(started.elapsed() < SECOND);
+    }
+
+    #[tokio::test]
+    async fn finished_tasks_are_dropped_from_the_registry() {
+        let registry = TaskRegistry::new();
+        registry.spawn("done", |_token| async {});
+        registry.spawn("waiting", |token| async move { token.cancelled().await });
+
+        tokio::time::timeout(SECOND, async {
+            while registry.len() > 1 {
+                tokio::task::yield_now().await;
+            }
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(registry.shutdown(SECOND).await, Vec::<&str>::new());
+    }
+}
@@ -1,3 +1,9 @@
+pub mod spans;
+pub mod trace_context;
+
+pub use spans::{email_fingerprint, record_outcome, record_user_id, set_fingerprint_key};
+pub use trace_context::TraceContext;
+
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
 
 /// Initialize telemetry (logging and tracing)
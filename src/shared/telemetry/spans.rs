@@ -0,0 +1,180 @@
+//! Helpers for the spans use cases open around `execute`, so traces show
+//! each business step with fields that are safe to export.
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use std::{fmt, sync::OnceLock};
+
+/// Span field holding how the step ended: `ok` or the error variant's name
+pub const OUTCOME: &str = "outcome";
+
+/// Span field holding the id of the user the step acted on
+pub const USER_ID: &str = "user_id";
+
+/// Hex digits of the email digest kept in span fields
+const FINGERPRINT_LEN: usize = 16;
+
+/// Key the fingerprints are computed with
+static FINGERPRINT_KEY: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// Key email fingerprints with one derived from `secret`, so every node
+/// sharing it produces the same values. Only the first call takes effect;
+/// before any, a random key is drawn and fingerprints hold for this process only.
+pub fn set_fingerprint_key(secret: &str) {
+    let mut mac = hmac_sha256(secret.as_bytes());
+    mac.update(b"email-fingerprint");
+    let _ = FINGERPRINT_KEY.set(mac.finalize().into_bytes().to_vec());
+}
+
+/// Stable, non-reversible stand-in for an address in span fields.
+///
+/// The same address (ignoring case and surrounding whitespace) always maps
+/// to the same value, so traces of one user can be correlated without the
+/// address itself leaving the process. The value is an HMAC rather than a
+/// plain hash, so it cannot be matched against guessed addresses without
+/// the server's key.
+pub fn email_fingerprint(email: &str) -> String {
+    let key = FINGERPRINT_KEY.get_or_init(|| {
+        let mut key = vec![0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        key
+    });
+    let mut mac = hmac_sha256(key);
+    mac.update(email.trim().to_lowercase().as_bytes());
+    let mut digest = hex::encode(mac.finalize().into_bytes());
+    digest.truncate(FINGERPRINT_LEN);
+    digest
+}
+
+fn hmac_sha256(key: &[u8]) -> Hmac<Sha256> {
+    // SAFETY: HMAC takes keys of any length, so this cannot fail
+    #[allow(clippy::expect_used)]
+    Hmac::new_from_slice(key).expect("HMAC accepts any key length")
+}
+
+/// Record `outcome` on the current span and hand the result back unchanged.
+///
+/// Errors are recorded by variant name only; their messages can carry
+/// repository details that do not belong in a trace.
+pub fn record_outcome<T, E: fmt::Debug>(result: Result<T, E>) -> Result<T, E> {
+    let outcome = match &result {
+        Ok(_) => "ok".to_string(),
+        Err(e) => variant_name(e),
+    };
+    tracing::Span::current().record(OUTCOME, outcome.as_str());
+    result
+}
+
+/// Record `user_id` on the current span once the user is known
+pub fn record_user_id(user_id: impl fmt::Display) {
+    tracing::Span::current().record(USER_ID, tracing::field::display(user_id));
+}
+
+fn variant_name(error: &impl fmt::Debug) -> String {
+    let debug = format!("{:?}", error);
+    let end = debug.find(|c: char| !c.is_alphanumeric() && c != '_').unwrap_or(debug.len());
+    debug[..end].to_string()
+}
+
+/// Collects the spans opened while a test runs, with their recorded fields
+#[cfg(test)]
+pub(crate) mod capture {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+    };
+    use tracing::{
+        field::{Field, Visit},
+        span::{Attributes, Id, Record},
+        Subscriber,
+    };
+    use tracing_subscriber::{layer::Context, prelude::*, registry::LookupSpan, Layer};
+
+    #[derive(Debug, Clone)]
+    pub struct CapturedSpan {
+        pub name: &'static str,
+        pub fields: HashMap<String, String>,
+    }
+
+    #[derive(Clone, Default)]
+    pub struct SpanCapture {
+        spans: Arc<Mutex<Vec<CapturedSpan>>>,
+        ids: Arc<Mutex<HashMap<Id, usize>>>,
+    }
+
+    struct Fields<'a>(&'a mut HashMap<String, String>);
+
+    impl Visit for Fields<'_> {
+        fn record_str(&mut self, field: &Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    impl SpanCapture {
+        /// Capture spans on this thread until the guard is dropped
+        pub fn install(&self) -> tracing::subscriber::DefaultGuard {
+            tracing_subscriber::registry().with(self.clone()).set_default()
+        }
+
+        pub fn spans(&self) -> Vec<CapturedSpan> {
+            self.spans.lock().unwrap_or_else(|e| e.into_inner()).clone()
+        }
+    }
+
+    impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SpanCapture {
+        fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, _ctx: Context<'_, S>) {
+            let mut fields = HashMap::new();
+            attrs.record(&mut Fields(&mut fields));
+            let mut spans = self.spans.lock().unwrap_or_else(|e| e.into_inner());
+            spans.push(CapturedSpan { name: attrs.metadata().name(), fields });
+            self.ids
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .insert(id.clone(), spans.len() - 1);
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+            let ids = self.ids.lock().unwrap_or_else(|e| e.into_inner());
+            let mut spans = self.spans.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(span) = ids.get(id).and_then(|&i| spans.get_mut(i)) {
+                values.record(&mut Fields(&mut span.fields));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[allow(dead_code)] // Only read through `Debug`
+    #[derive(Debug)]
+    enum StepError {
+        NotFound,
+        Repository(String),
+        Invalid { reason: String },
+    }
+
+    #[test]
+    fn fingerprint_ignores_case_and_hides_the_address() {
+        let fingerprint = email_fingerprint("Jane@Example.com ");
+
+        assert_eq!(fingerprint, email_fingerprint("jane@example.com"));
+        assert_eq!(fingerprint.len(), FINGERPRINT_LEN);
+        assert!(!fingerprint.contains("jane"));
+        assert_ne!(fingerprint, email_fingerprint("john@example.com"));
+        // Not the unkeyed digest anyone could compute from the address
+        assert!(!crate::shared::utils::hash_token("jane@example.com").starts_with(&fingerprint));
+    }
+
+    #[test]
+    fn outcome_names_the_error_variant_without_its_message() {
+        assert_eq!(variant_name(&StepError::NotFound), "NotFound");
+        assert_eq!(variant_name(&StepError::Repository("secret dsn".into())), "Repository");
+        assert_eq!(variant_name(&StepError::Invalid { reason: "x".into() }), "Invalid");
+    }
+}
@@ -0,0 +1,135 @@
+//! W3C Trace Context propagation.
+//!
+//! A request's context is taken from its `traceparent` header, or started
+//! fresh, and made current for everything the request awaits. Work moved to a
+//! spawned task does not inherit it; pass it along with `scope` if needed.
+use opentelemetry::{
+    propagation::TextMapPropagator,
+    trace::{SpanContext, SpanId, TraceContextExt, TraceFlags, TraceId, TraceState},
+    Context,
+};
+use opentelemetry_sdk::propagation::TraceContextPropagator;
+use rand::RngCore;
+use std::{collections::HashMap, future::Future};
+
+/// Header and envelope field carrying the context
+pub const TRACEPARENT: &str = "traceparent";
+
+tokio::task_local! {
+    static CURRENT: TraceContext;
+}
+
+/// One span's position in a distributed trace
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TraceContext {
+    trace_id: TraceId,
+    span_id: SpanId,
+    flags: TraceFlags,
+}
+
+impl TraceContext {
+    /// The first span of a new, sampled trace
+    pub fn new_root() -> Self {
+        let mut trace_id = [0u8; 16];
+        rand::thread_rng().fill_bytes(&mut trace_id);
+        Self {
+            trace_id: TraceId::from_bytes(trace_id),
+            span_id: new_span_id(),
+            flags: TraceFlags::SAMPLED,
+        }
+    }
+
+    /// Read a `traceparent` value with OpenTelemetry's W3C propagator, so
+    /// what is accepted tracks the spec rather than a parser of our own
+    pub fn extract(traceparent: &str) -> Option<Self> {
+        let carrier = HashMap::from([(TRACEPARENT.to_string(), traceparent.to_string())]);
+        let context = TraceContextPropagator::new().extract(&carrier);
+        let span = context.span();
+        let span_context = span.span_context();
+        span_context.is_valid().then(|| Self {
+            trace_id: span_context.trace_id(),
+            span_id: span_context.span_id(),
+            flags: span_context.trace_flags(),
+        })
+    }
+
+    /// This context as a version `00` `traceparent` value
+    pub fn inject(&self) -> String {
+        let span_context =
+            SpanContext::new(self.trace_id, self.span_id, self.flags, true, TraceState::default());
+        let context = Context::new().with_remote_span_context(span_context);
+        let mut carrier = HashMap::new();
+        TraceContextPropagator::new().inject_context(&context, &mut carrier);
+        carrier.remove(TRACEPARENT).unwrap_or_default()
+    }
+
+    /// A new span in the same trace, with this one as its parent
+    pub fn child(&self) -> Self {
+        Self { span_id: new_span_id(), ..*self }
+    }
+
+    pub fn trace_id(&self) -> String {
+        self.trace_id.to_string()
+    }
+
+    /// The context of the request being served, if any
+    pub fn current() -> Option<Self> {
+        CURRENT.try_with(|ctx| *ctx).ok()
+    }
+
+    /// Run `fut` with this as the current context
+    pub async fn scope<F: Future>(self, fut: F) -> F::Output {
+        CURRENT.scope(self, fut).await
+    }
+}
+
+fn new_span_id() -> SpanId {
+    let mut span_id = [0u8; 8];
+    while span_id == [0; 8] {
+        rand::thread_rng().fill_bytes(&mut span_id);
+    }
+    SpanId::from_bytes(span_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    #[test]
+    fn traceparent_round_trips() {
+        let ctx = TraceContext::extract(SAMPLE).unwrap();
+
+        assert_eq!(ctx.inject(), SAMPLE);
+        assert_eq!(ctx.trace_id(), "4bf92f3577b34da6a3ce929d0e0e4736");
+        let child = ctx.child();
+        assert_eq!(child.trace_id(), ctx.trace_id());
+        assert_ne!(child.inject(), SAMPLE);
+    }
+
+    #[test]
+    fn malformed_traceparents_are_rejected() {
+        for bad in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        ] {
+            assert_eq!(TraceContext::extract(bad), None, "{:?}", bad);
+        }
+        // Later versions may append fields
+        assert!(TraceContext::extract(&format!("01{}-extra", &SAMPLE[2..])).is_some());
+    }
+
+    #[tokio::test]
+    async fn context_is_current_only_inside_its_scope() {
+        let ctx = TraceContext::new_root();
+
+        assert_eq!(TraceContext::current(), None);
+        assert_eq!(ctx.scope(async { TraceContext::current() }).await, Some(ctx));
+    }
+}
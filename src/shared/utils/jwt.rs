@@ -1,6 +1,7 @@
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use uuid::Uuid;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -11,7 +12,37 @@ pub struct Claims {
     pub jti: String,        // JWT ID (unique identifier)
     pub token_type: String, // "access" or "refresh"
     pub iss: String,        // Issuer
-    pub aud: String,        // Audience
+    #[serde(with = "audience")]
+    pub aud: Vec<String>, // Audience; a single string or an array in the token
+}
+
+/// `aud` as RFC 7519 allows it: one string or an array of them. A single
+/// audience is written back as a plain string.
+mod audience {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+
+    pub fn serialize<S: Serializer>(aud: &[String], serializer: S) -> Result<S::Ok, S::Error> {
+        match aud {
+            [one] => one.serialize(serializer),
+            many => many.serialize(serializer),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<String>, D::Error> {
+        Ok(match OneOrMany::deserialize(deserializer)? {
+            OneOrMany::One(one) => vec![one],
+            OneOrMany::Many(many) => many,
+        })
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -26,12 +57,37 @@ pub enum JwtError {
     TokenExpired,
 }
 
+/// A signed token together with the `exp` it carries
+#[derive(Debug, Clone)]
+pub struct IssuedToken {
+    pub token: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Token lifetimes, in seconds, replacing the global ones for users of one
+/// role; `None` keeps the global lifetime
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExpiryOverride {
+    pub access: Option<i64>,
+    pub refresh: Option<i64>,
+}
+
+/// Clock skew tolerated on `exp`, `nbf` and `iat` unless configured otherwise
+pub const DEFAULT_LEEWAY_SECS: u64 = 30;
+
 pub struct JwtManager {
     secret: String,
     access_token_expiry: Duration,
     refresh_token_expiry: Duration,
     issuer: String,
+    /// Whether incoming tokens must carry `issuer` as their `iss`
+    validate_issuer: bool,
     audience: String,
+    /// Audiences accepted besides `audience`, which tokens are issued for
+    extra_audiences: Vec<String>,
+    /// Per-role lifetimes, keyed by role name
+    role_expiry: HashMap<String, ExpiryOverride>,
+    leeway: u64,
 }
 
 impl JwtManager {
@@ -54,50 +110,119 @@ impl JwtManager {
             access_token_expiry: Duration::seconds(access_token_expiry),
             refresh_token_expiry: Duration::seconds(refresh_token_expiry),
             issuer,
+            validate_issuer: true,
             audience,
+            extra_audiences: Vec::new(),
+            role_expiry: HashMap::new(),
+            leeway: DEFAULT_LEEWAY_SECS,
         })
     }
 
-    pub fn create_access_token(&self, user_id: Uuid) -> Result<String, JwtError> {
-        let now = Utc::now();
-        let expiry = now + self.access_token_expiry;
+    /// Also accept tokens whose `aud` is any of `audiences`, e.g. tokens
+    /// minted for sibling services. Issued tokens keep the primary audience.
+    pub fn with_accepted_audiences(mut self, audiences: Vec<String>) -> Self {
+        self.extra_audiences = audiences;
+        self
+    }
 
-        let claims = Claims {
-            sub: user_id.to_string(),
-            exp: expiry.timestamp(),
-            iat: now.timestamp(),
-            jti: Uuid::new_v4().to_string(),
-            token_type: "access".to_string(),
-            iss: self.issuer.clone(),
-            aud: self.audience.clone(),
-        };
+    /// Stop checking `iss` against the configured issuer, e.g. while tokens
+    /// from a previous issuer name are still in use. `iss` must still be
+    /// present, and issued tokens keep the configured issuer.
+    pub fn with_issuer_validation(mut self, enabled: bool) -> Self {
+        self.validate_issuer = enabled;
+        self
+    }
 
-        let mut header = Header::new(Algorithm::HS256);
-        header.typ = Some("JWT".to_string());
+    /// Issue tokens for the given roles with their own lifetimes, e.g.
+    /// shorter ones for admins. Applies to the `*_for_role` methods.
+    pub fn with_role_expiry(mut self, role_expiry: HashMap<String, ExpiryOverride>) -> Self {
+        self.role_expiry = role_expiry;
+        self
+    }
 
-        encode(&header, &claims, &EncodingKey::from_secret(self.secret.as_bytes()))
-            .map_err(|e| JwtError::TokenCreation(e.to_string()))
+    /// Tolerate up to `seconds` of clock skew between token issuer and verifier
+    pub fn with_leeway(mut self, seconds: u64) -> Self {
+        self.leeway = seconds;
+        self
+    }
+
+    pub fn create_access_token(&self, user_id: Uuid) -> Result<String, JwtError> {
+        self.issue_access_token(user_id).map(|issued| issued.token)
     }
 
     pub fn create_refresh_token(&self, user_id: Uuid) -> Result<String, JwtError> {
+        self.issue_refresh_token(user_id).map(|issued| issued.token)
+    }
+
+    /// Like `create_access_token`, also returning the expiry embedded in the claims
+    pub fn issue_access_token(&self, user_id: Uuid) -> Result<IssuedToken, JwtError> {
+        self.issue(user_id, "access", self.access_token_expiry)
+    }
+
+    /// Like `create_refresh_token`, also returning the expiry embedded in the claims
+    pub fn issue_refresh_token(&self, user_id: Uuid) -> Result<IssuedToken, JwtError> {
+        self.issue(user_id, "refresh", self.refresh_token_expiry)
+    }
+
+    /// Access token living as long as configured for `role`
+    pub fn issue_access_token_for_role(
+        &self,
+        user_id: Uuid,
+        role: &str,
+    ) -> Result<IssuedToken, JwtError> {
+        self.issue(user_id, "access", self.access_token_expiry_for(role))
+    }
+
+    /// Refresh token living as long as configured for `role`
+    pub fn issue_refresh_token_for_role(
+        &self,
+        user_id: Uuid,
+        role: &str,
+    ) -> Result<IssuedToken, JwtError> {
+        let lifetime = self
+            .role_expiry
+            .get(role)
+            .and_then(|o| o.refresh)
+            .map_or(self.refresh_token_expiry, Duration::seconds);
+        self.issue(user_id, "refresh", lifetime)
+    }
+
+    fn access_token_expiry_for(&self, role: &str) -> Duration {
+        self.role_expiry
+            .get(role)
+            .and_then(|o| o.access)
+            .map_or(self.access_token_expiry, Duration::seconds)
+    }
+
+    fn issue(
+        &self,
+        user_id: Uuid,
+        token_type: &str,
+        lifetime: Duration,
+    ) -> Result<IssuedToken, JwtError> {
         let now = Utc::now();
-        let expiry = now + self.refresh_token_expiry;
+        let expiry = now + lifetime;
 
         let claims = Claims {
             sub: user_id.to_string(),
             exp: expiry.timestamp(),
             iat: now.timestamp(),
             jti: Uuid::new_v4().to_string(),
-            token_type: "refresh".to_string(),
+            token_type: token_type.to_string(),
             iss: self.issuer.clone(),
-            aud: self.audience.clone(),
+            aud: vec![self.audience.clone()],
         };
 
         let mut header = Header::new(Algorithm::HS256);
         header.typ = Some("JWT".to_string());
 
-        encode(&header, &claims, &EncodingKey::from_secret(self.secret.as_bytes()))
-            .map_err(|e| JwtError::TokenCreation(e.to_string()))
+        let token = encode(&header, &claims, &EncodingKey::from_secret(self.secret.as_bytes()))
+            .map_err(|e| JwtError::TokenCreation(e.to_string()))?;
+        // `exp` has whole-second precision; report exactly what was signed
+        let expires_at = DateTime::from_timestamp(claims.exp, 0)
+            .ok_or_else(|| JwtError::TokenCreation("Token expiry out of range".to_string()))?;
+
+        Ok(IssuedToken { token, expires_at })
     }
 
     pub fn verify_token(&self, token: &str) -> Result<Claims, JwtError> {
@@ -106,24 +231,44 @@ impl JwtManager {
         // Strict validation settings
         validation.set_required_spec_claims(&["exp", "sub", "iat", "jti", "iss", "aud"]);
         validation.validate_exp = true;
-        validation.set_issuer(&[&self.issuer]);
-        validation.set_audience(&[&self.audience]);
+        validation.validate_nbf = true;
+        validation.leeway = self.leeway;
+        if self.validate_issuer {
+            validation.set_issuer(&[&self.issuer]);
+        }
+        let mut audiences = vec![self.audience.as_str()];
+        audiences.extend(self.extra_audiences.iter().map(String::as_str));
+        validation.set_audience(&audiences);
+
+        let claims =
+            decode::<Claims>(token, &DecodingKey::from_secret(self.secret.as_bytes()), &validation)
+                .map(|data| data.claims)
+                .map_err(|e| {
+                    if e.to_string().contains("ExpiredSignature") {
+                        JwtError::TokenExpired
+                    } else {
+                        JwtError::InvalidToken(e.to_string())
+                    }
+                })?;
+
+        // jsonwebtoken does not check `iat`; a token from the future means a
+        // skewed issuer clock beyond what we are willing to tolerate
+        let leeway = i64::try_from(self.leeway).unwrap_or(i64::MAX);
+        if claims.iat > Utc::now().timestamp().saturating_add(leeway) {
+            return Err(JwtError::InvalidToken("Token issued in the future".to_string()));
+        }
 
-        decode::<Claims>(token, &DecodingKey::from_secret(self.secret.as_bytes()), &validation)
-            .map(|data| data.claims)
-            .map_err(|e| {
-                if e.to_string().contains("ExpiredSignature") {
-                    JwtError::TokenExpired
-                } else {
-                    JwtError::InvalidToken(e.to_string())
-                }
-            })
+        Ok(claims)
     }
 
     pub fn get_access_token_expiry_seconds(&self) -> i64 {
         self.access_token_expiry.num_seconds()
     }
 
+    pub fn get_access_token_expiry_seconds_for_role(&self, role: &str) -> i64 {
+        self.access_token_expiry_for(role).num_seconds()
+    }
+
     pub fn get_refresh_token_expiry(&self) -> Duration {
         self.refresh_token_expiry
     }
@@ -133,6 +278,144 @@ impl JwtManager {
 mod tests {
     use super::*;
 
+    const SECRET: &str = "test_secret_that_is_long_enough_32chars";
+
+    fn manager(leeway: u64) -> JwtManager {
+        JwtManager::new(
+            SECRET.to_string(),
+            3600,
+            86400,
+            "test-issuer".to_string(),
+            "test-audience".to_string(),
+        )
+        .unwrap()
+        .with_leeway(leeway)
+    }
+
+    /// Sign an access token whose `iat`/`exp` are offset from now by the given seconds
+    fn token_with_times(iat_offset: i64, exp_offset: i64) -> String {
+        token_with(iat_offset, exp_offset, "test-audience")
+    }
+
+    fn token_with(iat_offset: i64, exp_offset: i64, aud: &str) -> String {
+        token_from("test-issuer", iat_offset, exp_offset, aud)
+    }
+
+    fn token_from(iss: &str, iat_offset: i64, exp_offset: i64, aud: &str) -> String {
+        let now = Utc::now().timestamp();
+        let claims = Claims {
+            sub: Uuid::new_v4().to_string(),
+            exp: now + exp_offset,
+            iat: now + iat_offset,
+            jti: Uuid::new_v4().to_string(),
+            token_type: "access".to_string(),
+            iss: iss.to_string(),
+            aud: vec![aud.to_string()],
+        };
+        encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(SECRET.as_bytes()),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn accepts_recently_expired_token_within_leeway() {
+        let token = token_with_times(-3600, -10);
+        assert!(manager(30).verify_token(&token).is_ok());
+    }
+
+    #[test]
+    fn rejects_token_expired_beyond_leeway() {
+        let token = token_with_times(-3600, -60);
+        assert!(matches!(manager(30).verify_token(&token), Err(JwtError::TokenExpired)));
+
+        let token = token_with_times(-3600, -10);
+        assert!(matches!(manager(0).verify_token(&token), Err(JwtError::TokenExpired)));
+    }
+
+    #[test]
+    fn applies_leeway_to_issued_at() {
+        let skewed = token_with_times(10, 3600);
+        assert!(manager(30).verify_token(&skewed).is_ok());
+
+        let future = token_with_times(120, 3600);
+        assert!(matches!(manager(30).verify_token(&future), Err(JwtError::InvalidToken(_))));
+    }
+
+    #[test]
+    fn issuer_must_match_unless_validation_is_disabled() {
+        let matching = token_from("test-issuer", 0, 3600, "test-audience");
+        let mismatched = token_from("legacy-issuer", 0, 3600, "test-audience");
+
+        assert!(manager(0).verify_token(&matching).is_ok());
+        assert!(matches!(manager(0).verify_token(&mismatched), Err(JwtError::InvalidToken(_))));
+
+        let lenient = manager(0).with_issuer_validation(false);
+        assert_eq!(lenient.verify_token(&mismatched).unwrap().iss, "legacy-issuer");
+        assert!(lenient.verify_token(&matching).is_ok());
+    }
+
+    #[test]
+    fn accepts_any_configured_audience() {
+        let jwt_manager = manager(0)
+            .with_accepted_audiences(vec!["billing-api".to_string(), "reports-api".to_string()]);
+
+        for aud in ["test-audience", "billing-api", "reports-api"] {
+            let claims = jwt_manager.verify_token(&token_with(0, 3600, aud)).unwrap();
+            assert_eq!(claims.aud, [aud]);
+        }
+    }
+
+    #[test]
+    fn accepts_an_array_audience_containing_a_configured_one() {
+        let now = Utc::now().timestamp();
+        let claims = serde_json::json!({
+            "sub": Uuid::new_v4().to_string(),
+            "exp": now + 3600,
+            "iat": now,
+            "jti": Uuid::new_v4().to_string(),
+            "token_type": "access",
+            "iss": "test-issuer",
+            "aud": ["reports-api", "test-audience"],
+        });
+        let token = encode(
+            &Header::new(Algorithm::HS256),
+            &claims,
+            &EncodingKey::from_secret(SECRET.as_bytes()),
+        )
+        .unwrap();
+
+        let claims = manager(0).verify_token(&token).unwrap();
+        assert_eq!(claims.aud, ["reports-api", "test-audience"]);
+    }
+
+    #[test]
+    fn issued_tokens_carry_a_single_string_audience() {
+        let claims = Claims {
+            sub: String::new(),
+            exp: 0,
+            iat: 0,
+            jti: String::new(),
+            token_type: "access".to_string(),
+            iss: "test-issuer".to_string(),
+            aud: vec!["test-audience".to_string()],
+        };
+        assert_eq!(serde_json::to_value(&claims).unwrap()["aud"], "test-audience");
+    }
+
+    #[test]
+    fn rejects_audience_outside_the_configured_list() {
+        let jwt_manager = manager(0).with_accepted_audiences(vec!["billing-api".to_string()]);
+
+        let result = jwt_manager.verify_token(&token_with(0, 3600, "unknown-api"));
+        assert!(matches!(result, Err(JwtError::InvalidToken(_))));
+        // Only the primary audience is accepted when none are added
+        let result = manager(0).verify_token(&token_with(0, 3600, "billing-api"));
+        assert!(matches!(result, Err(JwtError::InvalidToken(_))));
+    }
+
     #[test]
     fn test_create_and_verify_access_token() {
         let jwt_manager = JwtManager::new(
@@ -151,7 +434,7 @@ mod tests {
         assert_eq!(claims.sub, user_id.to_string());
         assert_eq!(claims.token_type, "access");
         assert_eq!(claims.iss, "test-issuer");
-        assert_eq!(claims.aud, "test-audience");
+        assert_eq!(claims.aud, ["test-audience"]);
     }
 
     #[test]
@@ -172,6 +455,44 @@ mod tests {
         assert_eq!(claims.sub, user_id.to_string());
         assert_eq!(claims.token_type, "refresh");
         assert_eq!(claims.iss, "test-issuer");
-        assert_eq!(claims.aud, "test-audience");
+        assert_eq!(claims.aud, ["test-audience"]);
+    }
+
+    #[test]
+    fn issued_expiry_matches_signed_claims() {
+        let jwt_manager = manager(0);
+        let user_id = Uuid::new_v4();
+
+        for issued in
+            [jwt_manager.issue_access_token(user_id), jwt_manager.issue_refresh_token(user_id)]
+        {
+            let issued = issued.unwrap();
+            let claims = jwt_manager.verify_token(&issued.token).unwrap();
+            assert_eq!(issued.expires_at.timestamp(), claims.exp);
+        }
+    }
+
+    #[test]
+    fn role_expiry_shortens_admin_tokens_and_falls_back_for_others() {
+        let jwt_manager = manager(0).with_role_expiry(HashMap::from([(
+            "admin".to_string(),
+            ExpiryOverride { access: Some(900), refresh: None },
+        )]));
+        let user_id = Uuid::new_v4();
+        let exp = |issued: Result<IssuedToken, JwtError>| {
+            jwt_manager.verify_token(&issued.unwrap().token).unwrap().exp - Utc::now().timestamp()
+        };
+
+        let admin_access = exp(jwt_manager.issue_access_token_for_role(user_id, "admin"));
+        let viewer_access = exp(jwt_manager.issue_access_token_for_role(user_id, "viewer"));
+        assert!((899..=900).contains(&admin_access), "{}", admin_access);
+        assert!((3599..=3600).contains(&viewer_access), "{}", viewer_access);
+        assert!(admin_access < viewer_access);
+        assert_eq!(jwt_manager.get_access_token_expiry_seconds_for_role("admin"), 900);
+        assert_eq!(jwt_manager.get_access_token_expiry_seconds_for_role("viewer"), 3600);
+
+        // No refresh override for admins, so the global lifetime applies
+        let admin_refresh = exp(jwt_manager.issue_refresh_token_for_role(user_id, "admin"));
+        assert!((86399..=86400).contains(&admin_refresh), "{}", admin_refresh);
     }
 }
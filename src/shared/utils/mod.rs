@@ -1,5 +1,7 @@
+pub mod code_hash;
 pub mod jwt;
 pub mod password;
+pub mod verification_link;
 
 use chrono::{DateTime, Utc};
 use rand::rngs::OsRng;
@@ -1,8 +1,9 @@
 use argon2::{
     password_hash::{PasswordHash, PasswordHasher as _, PasswordVerifier, SaltString},
-    Argon2,
+    Algorithm, Argon2, KeyId, Params, ParamsBuilder, Version,
 };
 use rand::rngs::OsRng;
+use std::fmt;
 
 #[derive(Debug, thiserror::Error)]
 pub enum PasswordError {
@@ -14,6 +15,71 @@ pub enum PasswordError {
 
     #[error("Invalid password")]
     InvalidPassword,
+
+    #[error("Invalid pepper '{0}': expected id:secret with an id of 1-8 bytes")]
+    InvalidPepper(String),
+}
+
+/// Application-wide secret fed to Argon2 alongside each password. The id is
+/// stored in the hash (`keyid=`) so the right pepper is used to verify it.
+#[derive(Clone)]
+pub struct Pepper {
+    id: KeyId,
+    secret: Vec<u8>,
+}
+
+impl fmt::Debug for Pepper {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Pepper").field("id", &self.id).finish_non_exhaustive()
+    }
+}
+
+/// The pepper new hashes use, plus retired ones still accepted on verify.
+/// Empty when peppering is off.
+#[derive(Debug, Clone, Default)]
+pub struct Peppers {
+    current: Option<Pepper>,
+    previous: Vec<Pepper>,
+}
+
+impl Peppers {
+    /// Parse a comma-separated `id:secret` list; the first entry is current.
+    pub fn parse(raw: &str) -> Result<Self, PasswordError> {
+        let mut peppers = raw
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| {
+                let invalid = || {
+                    PasswordError::InvalidPepper(
+                        entry.split(':').next().unwrap_or_default().to_string(),
+                    )
+                };
+                let (id, secret) = entry.split_once(':').ok_or_else(invalid)?;
+                if id.is_empty() || secret.is_empty() {
+                    return Err(invalid());
+                }
+                let id = KeyId::new(id.as_bytes()).map_err(|_| invalid())?;
+                Ok(Pepper { id, secret: secret.as_bytes().to_vec() })
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .into_iter();
+
+        Ok(Self { current: peppers.next(), previous: peppers.collect() })
+    }
+
+    /// Ids of the configured peppers, current first; the secrets stay private
+    pub fn ids(&self) -> Vec<String> {
+        self.current
+            .iter()
+            .chain(&self.previous)
+            .map(|p| String::from_utf8_lossy(p.id.as_bytes()).into_owned())
+            .collect()
+    }
+
+    fn find(&self, id: &[u8]) -> Option<&Pepper> {
+        self.current.iter().chain(&self.previous).find(|p| p.id.as_bytes() == id)
+    }
 }
 
 pub struct PasswordManager;
@@ -21,8 +87,27 @@ pub struct PasswordManager;
 impl PasswordManager {
     /// Hash a password using Argon2
     pub fn hash(password: &str) -> Result<String, PasswordError> {
+        Self::hash_peppered(password, &Peppers::default())
+    }
+
+    /// Hash a password using Argon2 keyed with the current pepper, if any
+    pub fn hash_peppered(password: &str, peppers: &Peppers) -> Result<String, PasswordError> {
         let salt = SaltString::generate(&mut OsRng);
-        let argon2 = Argon2::default();
+        let hash_error = |e: argon2::Error| PasswordError::HashError(e.to_string());
+
+        let argon2 = match &peppers.current {
+            Some(pepper) => {
+                let params = ParamsBuilder::new().keyid(pepper.id).build().map_err(hash_error)?;
+                Argon2::new_with_secret(
+                    &pepper.secret,
+                    Algorithm::default(),
+                    Version::default(),
+                    params,
+                )
+                .map_err(hash_error)?
+            },
+            None => Argon2::default(),
+        };
 
         argon2
             .hash_password(password.as_bytes(), &salt)
@@ -32,17 +117,50 @@ impl PasswordManager {
 
     /// Verify a password against a hash
     pub fn verify(password: &str, hash: &str) -> Result<bool, PasswordError> {
-        let parsed_hash =
-            PasswordHash::new(hash).map_err(|e| PasswordError::VerifyError(e.to_string()))?;
+        Self::verify_peppered(password, hash, &Peppers::default())
+    }
+
+    /// Verify a password against a hash made with any known pepper, or none.
+    /// A hash naming a pepper that is no longer configured is an error.
+    pub fn verify_peppered(
+        password: &str,
+        hash: &str,
+        peppers: &Peppers,
+    ) -> Result<bool, PasswordError> {
+        let verify_error = |e: String| PasswordError::VerifyError(e);
+        let parsed_hash = PasswordHash::new(hash).map_err(|e| verify_error(e.to_string()))?;
+        let params = Params::try_from(&parsed_hash).map_err(|e| verify_error(e.to_string()))?;
 
         // Use the parameters from the hash itself for verification (standard practice)
-        let argon2 = Argon2::default();
+        let argon2 = if params.keyid().is_empty() {
+            Argon2::default()
+        } else {
+            let pepper = peppers
+                .find(params.keyid())
+                .ok_or_else(|| verify_error("hash uses an unknown pepper".to_string()))?;
+            Argon2::new_with_secret(
+                &pepper.secret,
+                Algorithm::default(),
+                Version::default(),
+                params,
+            )
+            .map_err(|e| verify_error(e.to_string()))?
+        };
 
         match argon2.verify_password(password.as_bytes(), &parsed_hash) {
             Ok(_) => Ok(true),
             Err(_) => Ok(false),
         }
     }
+
+    /// True when `hash` was not made with the current pepper, so it should be
+    /// replaced the next time the plaintext is at hand
+    pub fn needs_rehash(hash: &str, peppers: &Peppers) -> bool {
+        let current = peppers.current.as_ref().map_or(&[][..], |p| p.id.as_bytes());
+        PasswordHash::new(hash)
+            .and_then(|parsed| Params::try_from(&parsed))
+            .is_ok_and(|params| params.keyid() != current)
+    }
 }
 
 #[cfg(test)]
@@ -57,4 +175,42 @@ mod tests {
         assert!(PasswordManager::verify(password, &hash).unwrap());
         assert!(!PasswordManager::verify("wrong_password", &hash).unwrap());
     }
+
+    #[test]
+    fn peppered_hash_verifies_only_with_the_pepper() {
+        let peppers = Peppers::parse("v1:pepper-secret").unwrap();
+        let hash = PasswordManager::hash_peppered("my_secure_password", &peppers).unwrap();
+
+        assert!(PasswordManager::verify_peppered("my_secure_password", &hash, &peppers).unwrap());
+        assert!(!PasswordManager::verify_peppered("wrong_password", &hash, &peppers).unwrap());
+        assert!(PasswordManager::verify("my_secure_password", &hash).is_err());
+
+        let wrong_secret = Peppers::parse("v1:other-secret").unwrap();
+        assert!(
+            !PasswordManager::verify_peppered("my_secure_password", &hash, &wrong_secret).unwrap()
+        );
+    }
+
+    #[test]
+    fn rotated_pepper_still_verifies_and_flags_rehash() {
+        let old = Peppers::parse("v1:old-secret").unwrap();
+        let rotated = Peppers::parse("v2:new-secret, v1:old-secret").unwrap();
+        let hash = PasswordManager::hash_peppered("my_secure_password", &old).unwrap();
+
+        assert!(PasswordManager::verify_peppered("my_secure_password", &hash, &rotated).unwrap());
+        assert!(PasswordManager::needs_rehash(&hash, &rotated));
+        assert!(!PasswordManager::needs_rehash(&hash, &old));
+
+        let plain = PasswordManager::hash("my_secure_password").unwrap();
+        assert!(PasswordManager::needs_rehash(&plain, &rotated));
+        assert!(!PasswordManager::needs_rehash(&plain, &Peppers::default()));
+    }
+
+    #[test]
+    fn rejects_malformed_pepper_lists() {
+        for raw in ["no-secret", ":secret", "v1:", "much-too-long-id:secret"] {
+            assert!(matches!(Peppers::parse(raw), Err(PasswordError::InvalidPepper(_))), "{}", raw);
+        }
+        assert!(Peppers::parse("").unwrap().current.is_none());
+    }
 }
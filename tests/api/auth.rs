@@ -1,5 +1,6 @@
 /// Integration tests for authentication endpoints
 use crate::common::*;
+use axum_backend::domain::value_objects::BreachedPasswords;
 use reqwest::StatusCode;
 use serde_json::json;
 use serial_test::serial;
@@ -21,6 +22,40 @@ async fn test_register_success() {
     // Note: token might be empty in JSON if using cookies, check design
 }
 
+#[tokio::test]
+#[serial]
+async fn register_location_points_at_the_new_user() {
+    let server = TestServer::new().await;
+    let email = unique_email("reg_loc");
+
+    let res = server
+        .client
+        .post(format!("{}/api/auth/register", server.base_url))
+        .json(&json!({ "email": email, "name": "Located" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let location = res.headers()[reqwest::header::LOCATION].to_str().unwrap().to_string();
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(location, format!("/api/users/{}", body["data"]["user"]["id"].as_str().unwrap()));
+
+    let viewer = unique_email("reg_loc_viewer");
+    server.register_user(&viewer, "Viewer", TEST_PASSWORD).await;
+    let token = server.login_user(&viewer, TEST_PASSWORD).await;
+    let fetched = server
+        .client
+        .get(format!("{}{}", server.base_url, location))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(fetched.status(), StatusCode::OK);
+    let fetched: serde_json::Value = fetched.json().await.unwrap();
+    assert_eq!(fetched["data"]["email"], email);
+}
+
 #[tokio::test]
 #[serial]
 async fn test_register_duplicate_email() {
@@ -31,7 +66,7 @@ async fn test_register_duplicate_email() {
     assert_success(&server.register_user(&email, "User 1", TEST_PASSWORD).await);
 
     // Second (Fail)
-    let res = server
+    let response = server
         .client
         .post(format!("{}/api/auth/register", server.base_url))
         .json(&json!({
@@ -41,12 +76,13 @@ async fn test_register_duplicate_email() {
         }))
         .send()
         .await
-        .unwrap()
-        .json()
-        .await
         .unwrap();
 
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let res: serde_json::Value = response.json().await.unwrap();
     assert_error(&res);
+    assert_eq!(res["code"], "USER_ALREADY_EXISTS");
+    assert_eq!(res["error"], "Email already exists");
 }
 
 #[tokio::test]
@@ -72,6 +108,145 @@ async fn test_register_invalid_email() {
     assert_error(&res);
 }
 
+#[tokio::test]
+#[serial]
+async fn register_sanitizes_and_bounds_the_name() {
+    let server = TestServer::new().await;
+    let register = |name: String| {
+        server
+            .client
+            .post(format!("{}/api/auth/register", server.base_url))
+            .json(&json!({ "email": unique_email("reg_name"), "name": name }))
+            .send()
+    };
+
+    let res = register("  Lan\u{0}\u{7} Tran\n".to_string()).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["data"]["user"]["name"], "Lan Tran");
+
+    for name in ["   ".to_string(), "\u{1}\t\u{1b}".to_string(), "a".repeat(256)] {
+        let res = register(name.clone()).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{:?}", name);
+        assert_error(&res.json().await.unwrap());
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn registration_is_limited_to_the_configured_email_domains() {
+    use axum_backend::domain::value_objects::EmailDomainPolicy;
+
+    let server = TestServer::with_config(|config| {
+        config.email_domain_policy =
+            EmailDomainPolicy::new(["*.corp.test"], ["contractors.corp.test"]).unwrap();
+    })
+    .await;
+    let register = |domain: &str| {
+        let email = format!("dom_{}@{}", uuid::Uuid::new_v4(), domain);
+        server
+            .client
+            .post(format!("{}/api/auth/register", server.base_url))
+            .json(&json!({ "email": email, "name": "Domain User" }))
+            .send()
+    };
+
+    assert_eq!(register("eu.corp.test").await.unwrap().status(), StatusCode::CREATED);
+
+    for domain in ["test.com", "corp.test", "contractors.corp.test"] {
+        let res = register(domain).await.unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{}", domain);
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["error"], format!("Registration is not open to addresses at {}", domain));
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn concurrent_registrations_of_one_address_create_one_user() {
+    let server = TestServer::with_config(|config| {
+        config.lock_policy = axum_backend::application::services::LockPolicy::Strict;
+    })
+    .await;
+    let email = unique_email("racing");
+    let attempts = (0..5).map(|_| {
+        server
+            .client
+            .post(format!("{}/api/auth/register", server.base_url))
+            .json(&json!({ "email": email, "name": "Racer" }))
+            .send()
+    });
+
+    let statuses: Vec<StatusCode> = futures::future::join_all(attempts)
+        .await
+        .into_iter()
+        .map(|res| res.unwrap().status())
+        .collect();
+
+    assert_eq!(statuses.iter().filter(|s| **s == StatusCode::CREATED).count(), 1);
+    // The rest either found the lock taken or the user already there
+    assert!(statuses.iter().all(|s| *s == StatusCode::CREATED || *s == StatusCode::CONFLICT));
+}
+
+#[tokio::test]
+#[serial]
+async fn registration_refuses_disposable_email_providers() {
+    use axum_backend::domain::value_objects::DisposableDomains;
+    use std::sync::Arc;
+
+    let server = TestServer::with_config(|config| {
+        config.disposable_email_domains =
+            Some(Arc::new(DisposableDomains::parse("mailinator.com\nyopmail.com")));
+    })
+    .await;
+    let register = |email: String| {
+        server
+            .client
+            .post(format!("{}/api/auth/register", server.base_url))
+            .json(&json!({ "email": email, "name": "Throwaway" }))
+            .send()
+    };
+
+    let res = register(format!("tmp_{}@mailinator.com", uuid::Uuid::new_v4())).await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["error"], "Disposable email addresses are not accepted");
+
+    let res = register(unique_email("not_disposable")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+}
+
+#[tokio::test]
+#[serial]
+async fn closed_registration_admits_only_admins() {
+    let closed = TestServer::with_config(|config| config.registration_open = false).await;
+    let admin = unique_email("closed_admin");
+    closed.create_admin(&admin, TEST_PASSWORD).await;
+    let register = |token: Option<String>| {
+        let request = closed
+            .client
+            .post(format!("{}/api/auth/register", closed.base_url))
+            .json(&json!({ "email": unique_email("closed_new"), "name": "New User" }));
+        match token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        }
+        .send()
+    };
+
+    let res = register(None).await.unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["success"], false);
+    assert_eq!(body["code"], "REGISTRATION_CLOSED");
+
+    let forged = register(Some("not-a-token".to_string())).await.unwrap();
+    assert_eq!(forged.status(), StatusCode::FORBIDDEN);
+
+    let admin_token = closed.login_user(&admin, TEST_PASSWORD).await;
+    assert_eq!(register(Some(admin_token)).await.unwrap().status(), StatusCode::CREATED);
+}
+
 #[tokio::test]
 #[serial]
 async fn test_set_password_weak_password() {
@@ -132,6 +307,104 @@ async fn test_login_success() {
     assert!(!token.is_empty());
 }
 
+#[tokio::test]
+#[serial]
+async fn login_reports_token_type_and_expiry_timestamps_from_claims() {
+    let server = TestServer::new().await;
+    let email = unique_email("login_expiry");
+    server.register_user(&email, "Expiry User", TEST_PASSWORD).await;
+
+    let (status, body) = server.login_response(&email, TEST_PASSWORD).await;
+
+    assert_eq!(status, StatusCode::OK);
+    let data = &body["data"];
+    assert_eq!(data["token_type"], "Bearer");
+    let jwt = test_jwt_manager();
+    for (token, expires_at) in
+        [("access_token", "access_token_expires_at"), ("refresh_token", "refresh_token_expires_at")]
+    {
+        let claims = jwt.verify_token(data[token].as_str().unwrap()).unwrap();
+        let expires_at =
+            chrono::DateTime::parse_from_rfc3339(data[expires_at].as_str().unwrap()).unwrap();
+        assert_eq!(expires_at.timestamp(), claims.exp, "{}", token);
+    }
+    let access_exp =
+        chrono::DateTime::parse_from_rfc3339(data["access_token_expires_at"].as_str().unwrap())
+            .unwrap();
+    let issued_at = access_exp.timestamp() - data["expires_in"].as_i64().unwrap();
+    assert!((issued_at - chrono::Utc::now().timestamp()).abs() <= 5);
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_tokens_use_the_role_specific_expiry() {
+    let server = TestServer::with_config(|config| {
+        config.jwt_role_expiry.insert(
+            "admin".to_string(),
+            axum_backend::shared::utils::jwt::ExpiryOverride { access: Some(600), refresh: None },
+        );
+    })
+    .await;
+    let admin = unique_email("rexp_admin");
+    let viewer = unique_email("rexp_viewer");
+    server.register_user(&admin, "Admin", TEST_PASSWORD).await;
+    server.register_user(&viewer, "Viewer", TEST_PASSWORD).await;
+    server.set_user_role(&admin, "admin").await;
+
+    let jwt = test_jwt_manager();
+    let access_lifetime = |body: &serde_json::Value| {
+        let claims = jwt.verify_token(body["data"]["access_token"].as_str().unwrap()).unwrap();
+        assert_eq!(claims.exp - claims.iat, body["data"]["expires_in"].as_i64().unwrap());
+        claims.exp - claims.iat
+    };
+    let (_, admin_body) = server.login_response(&admin, TEST_PASSWORD).await;
+    let (_, viewer_body) = server.login_response(&viewer, TEST_PASSWORD).await;
+
+    assert_eq!(access_lifetime(&admin_body), 600);
+    assert_eq!(access_lifetime(&viewer_body), 3600);
+}
+
+#[tokio::test]
+#[serial]
+async fn expired_and_invalid_access_tokens_get_distinct_codes() {
+    let server = TestServer::new().await;
+    let email = unique_email("tok_codes");
+    server.register_user(&email, "Token Codes", TEST_PASSWORD).await;
+    let (_, body) = server.login_response(&email, TEST_PASSWORD).await;
+    let user_id: uuid::Uuid = body["data"]["user"]["id"].as_str().unwrap().parse().unwrap();
+    // Past the server's leeway
+    let expired = test_jwt_manager_with_expiry(-600).create_access_token(user_id).unwrap();
+
+    for (token, code) in [(expired.as_str(), "TOKEN_EXPIRED"), ("not.a.jwt", "TOKEN_INVALID")] {
+        let res = server
+            .client
+            .get(format!("{}/api/auth/sessions", server.base_url))
+            .bearer_auth(token)
+            .send()
+            .await
+            .unwrap();
+
+        assert_eq!(res.status(), StatusCode::UNAUTHORIZED, "{}", code);
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_error(&body);
+        assert_eq!(body["code"], code);
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn seeded_user_can_log_in_without_the_email_flow() {
+    let server = TestServer::new().await;
+    let email = unique_email("seeded");
+
+    let user = seed_user(&server.pool, &email, "Seeded User", TEST_PASSWORD).await;
+
+    let (status, body) = server.login_response(&email, TEST_PASSWORD).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["user"]["id"], user.id.to_string());
+    assert_eq!(body["data"]["user"]["name"], "Seeded User");
+}
+
 #[tokio::test]
 #[serial]
 async fn test_login_wrong_credentials() {
@@ -167,6 +440,128 @@ async fn test_login_wrong_credentials() {
     assert_error(&res);
 }
 
+/// Fail two logins for a fresh user on a server that locks after two,
+/// returning the user's email and the locking response
+async fn lock_out(server: &TestServer, prefix: &str) -> (String, StatusCode, serde_json::Value) {
+    let email = unique_email(prefix);
+    server.register_user(&email, "Locked User", TEST_PASSWORD).await;
+
+    let (status, _) = server.login_response(&email, "WrongPassword1!").await;
+    assert_eq!(status, 401);
+    let (status, body) = server.login_response(&email, "WrongPassword1!").await;
+    (email, status, body)
+}
+
+#[tokio::test]
+#[serial]
+async fn lockout_emails_the_locking_ip_and_time_when_enabled() {
+    let server = TestServer::with_config(|config| {
+        config.lockout.max_failures = Some(2);
+        config.lockout.alert_email = true;
+    })
+    .await;
+    let before = chrono::Utc::now() - chrono::Duration::seconds(1);
+
+    let (email, status, body) = lock_out(&server, "lock_on").await;
+
+    assert_eq!(status, 429, "{}", body);
+    assert_error(&body);
+    // The alert is sent in the background
+    let mut alert = None;
+    for _ in 0..50 {
+        alert = server.outbox.last_lockout_alert(&email);
+        if alert.is_some() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    let (ip, locked_at) = alert.expect("no lockout alert");
+    assert!(ip.is_loopback(), "{}", ip);
+    assert!(locked_at >= before && locked_at <= chrono::Utc::now(), "{}", locked_at);
+
+    // Locked even with the right password
+    let (status, _) = server.login_response(&email, TEST_PASSWORD).await;
+    assert_eq!(status, 429);
+}
+
+#[tokio::test]
+#[serial]
+async fn unknown_addresses_lock_out_like_registered_ones() {
+    let server = TestServer::with_config(|config| config.lockout.max_failures = Some(2)).await;
+    let (_, registered, _) = lock_out(&server, "lock_known").await;
+
+    let unknown = unique_email("lock_unknown");
+    let (first, _) = server.login_response(&unknown, "WrongPassword1!").await;
+    let (second, body) = server.login_response(&unknown, "WrongPassword1!").await;
+
+    assert_eq!(first, 401);
+    assert_eq!(second, registered, "{}", body);
+    assert_eq!(second, 429);
+}
+
+#[tokio::test]
+#[serial]
+async fn lockout_sends_no_email_when_alerts_are_disabled() {
+    let server = TestServer::with_config(|config| {
+        config.lockout.max_failures = Some(2);
+        config.lockout.alert_email = false;
+    })
+    .await;
+
+    let (email, status, body) = lock_out(&server, "lock_off").await;
+
+    assert_eq!(status, 429, "{}", body);
+    assert_eq!(server.outbox.last_lockout_alert(&email), None);
+}
+
+#[tokio::test]
+#[serial]
+async fn login_rejects_malformed_json_with_the_error_envelope() {
+    let server = TestServer::new().await;
+    let post = |body: &'static str, content_type: &'static str| {
+        server
+            .client
+            .post(format!("{}/api/auth/login", server.base_url))
+            .header("Content-Type", content_type)
+            .body(body)
+            .send()
+    };
+
+    let res = post("{\"email\": \"jane@example.com\",\n \"password\" }", "application/json")
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["success"], false);
+    assert_eq!(body["code"], "INVALID_JSON");
+    assert_eq!(body["error"], "Request body is not valid JSON at line 2, column 13");
+
+    let res = post(
+        "{\"email\": \"jane@example.com\", \"password\": 1234567890}",
+        "application/json",
+    )
+    .await
+    .unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["code"], "INVALID_JSON");
+    assert!(!body["error"].as_str().unwrap().contains("1234567890"));
+
+    let res = post("{\"password\": \"hunter2\"}", "application/json").await.unwrap();
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(
+        body["error"],
+        "Invalid request body: missing field `email` at line 1, column 23"
+    );
+
+    let res = post("email=jane@example.com", "application/x-www-form-urlencoded")
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["code"], "INVALID_JSON");
+}
+
 // ============================================================================
 // Protected Resources
 // ============================================================================
@@ -203,6 +598,112 @@ async fn test_list_users() {
     assert_eq!(res_unauth.status(), StatusCode::UNAUTHORIZED);
 }
 
+#[tokio::test]
+#[serial]
+async fn list_users_applies_configured_default_page_size() {
+    let server = TestServer::with_config(|config| config.default_page_size = 2).await;
+    let email = unique_email("list_default");
+    server.register_user(&email, "Default User", TEST_PASSWORD).await;
+    for i in 0..2 {
+        seed_user(
+            &server.pool,
+            &unique_email(&format!("list_default_{}", i)),
+            "Row",
+            TEST_PASSWORD,
+        )
+        .await;
+    }
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    let res: serde_json::Value = server
+        .client
+        .get(format!("{}/api/users", server.base_url))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    assert_success(&res);
+    assert_eq!(res["meta"]["page_size"], 2);
+    assert_eq!(res["data"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+#[serial]
+async fn list_users_clamps_oversized_page_size() {
+    let server = TestServer::new().await;
+    let email = unique_email("list_clamp");
+
+    server.register_user(&email, "Clamp User", TEST_PASSWORD).await;
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    let res = server.list_users(&token, 1, 1_000_000).await;
+    assert_success(&res);
+    assert_eq!(res["meta"]["page"], 1);
+    assert_eq!(res["meta"]["page_size"], 100);
+    assert!(res["meta"]["total"].as_i64().unwrap() >= 1);
+    assert!(res["data"].as_array().unwrap().len() <= 100);
+}
+
+#[tokio::test]
+#[serial]
+async fn list_users_rejects_non_positive_page_or_page_size() {
+    let server = TestServer::new().await;
+    let email = unique_email("list_invalid");
+
+    server.register_user(&email, "Invalid Page User", TEST_PASSWORD).await;
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    for (page, page_size, field) in [
+        ("0", "10", "page"),
+        ("-3", "10", "page"),
+        ("1", "0", "page_size"),
+        ("1", "-1", "page_size"),
+    ] {
+        let res = server
+            .client
+            .get(format!("{}/api/users", server.base_url))
+            .bearer_auth(&token)
+            .query(&[("page", page), ("page_size", page_size)])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "page={page} page_size={page_size}");
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["code"], "INVALID_QUERY");
+        assert_eq!(body["fields"].as_object().unwrap().len(), 1, "{}", body);
+        assert!(body["fields"][field][0].as_str().unwrap().contains(">= 1"), "{}", body);
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn list_users_rejects_non_numeric_pagination_with_field_errors() {
+    let server = TestServer::new().await;
+    let email = unique_email("list_nan");
+
+    server.register_user(&email, "NaN Page User", TEST_PASSWORD).await;
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    for (param, value) in [("page", "two"), ("page_size", "ten"), ("page", "1.5")] {
+        let res = server
+            .client
+            .get(format!("{}/api/users", server.base_url))
+            .bearer_auth(&token)
+            .query(&[(param, value)])
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST, "{param}={value}");
+        let body: serde_json::Value = res.json().await.unwrap();
+        assert_eq!(body["code"], "INVALID_QUERY");
+        assert!(body["fields"][param].is_array(), "{}", body);
+    }
+}
+
 #[tokio::test]
 #[serial]
 async fn test_concurrent_registrations() {
@@ -311,6 +812,191 @@ async fn test_forgot_password_flow() {
     assert_error(&old_login_res);
 }
 
+/// Request a reset code for `email`; returns it for `set_password_with`
+async fn forgot_password_code(server: &TestServer, email: &str) -> String {
+    let response = post_email(server, "forgot-password", email).await;
+    assert_eq!(response.status(), StatusCode::OK);
+    server.get_confirmation_code(email).await
+}
+
+async fn set_password_with(
+    server: &TestServer,
+    email: &str,
+    code: &str,
+    password: &str,
+) -> (StatusCode, serde_json::Value) {
+    let response = server
+        .client
+        .post(format!("{}/api/auth/password", server.base_url))
+        .json(&json!({ "email": email, "code": code, "password": password }))
+        .send()
+        .await
+        .unwrap();
+    (response.status(), response.json().await.unwrap())
+}
+
+#[tokio::test]
+#[serial]
+async fn password_reset_rejects_recently_used_passwords() {
+    let server = TestServer::with_config(|config| {
+        config.resend_cooldown = std::time::Duration::ZERO;
+        config.password_history_size = 2;
+    })
+    .await;
+    let email = unique_email("pw_history");
+    server.register_user(&email, "History User", "FirstPass123!").await;
+
+    let code = forgot_password_code(&server, &email).await;
+    let (status, body) = set_password_with(&server, &email, &code, "FirstPass123!").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Password was used recently, choose a different one");
+
+    // The rejected attempt leaves the code usable
+    let (status, _) = set_password_with(&server, &email, &code, "SecondPass123!").await;
+    assert_eq!(status, StatusCode::OK);
+
+    let code = forgot_password_code(&server, &email).await;
+    let (status, _) = set_password_with(&server, &email, &code, "FirstPass123!").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST, "still within the last two passwords");
+    let (status, _) = set_password_with(&server, &email, &code, "ThirdPass123!").await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Two newer passwords have pushed the first one out of the history
+    let code = forgot_password_code(&server, &email).await;
+    let (status, _) = set_password_with(&server, &email, &code, "FirstPass123!").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+#[serial]
+async fn password_is_unchanged_when_its_history_cannot_be_recorded() {
+    use diesel_async::SimpleAsyncConnection;
+
+    let server = TestServer::with_config(|config| {
+        config.resend_cooldown = std::time::Duration::ZERO;
+    })
+    .await;
+    let email = unique_email("pw_nohist");
+    server.register_user(&email, "History User", "FirstPass123!").await;
+    let before = server.get_password_hash(&email).await;
+    let code = forgot_password_code(&server, &email).await;
+
+    let mut conn = server.pool.get().await.unwrap();
+    conn.batch_execute(
+        "CREATE FUNCTION reject_history() RETURNS trigger AS $$ \
+         BEGIN RAISE EXCEPTION 'password_history unavailable'; END; $$ LANGUAGE plpgsql; \
+         CREATE TRIGGER reject_history BEFORE INSERT ON password_history \
+         FOR EACH ROW EXECUTE FUNCTION reject_history();",
+    )
+    .await
+    .unwrap();
+
+    let (status, body) = set_password_with(&server, &email, &code, "SecondPass123!").await;
+    assert_eq!(status, StatusCode::INTERNAL_SERVER_ERROR);
+    assert!(!body.to_string().contains("password_history"), "{}", body);
+    assert_eq!(server.get_password_hash(&email).await, before);
+    // The code was not consumed either
+    assert_eq!(server.get_confirmation_code(&email).await, code);
+}
+
+#[tokio::test]
+#[serial]
+async fn password_change_within_min_interval_is_refused() {
+    let server = TestServer::with_config(|config| {
+        config.resend_cooldown = std::time::Duration::ZERO;
+        config.password_min_change_interval = std::time::Duration::from_secs(3600);
+    })
+    .await;
+    let email = unique_email("pw_interval");
+    // Setting the first password is not a change and is always allowed
+    server.register_user(&email, "Interval User", "FirstPass123!").await;
+
+    let code = forgot_password_code(&server, &email).await;
+    let response = server
+        .client
+        .post(format!("{}/api/auth/password", server.base_url))
+        .json(&json!({ "email": email, "code": code, "password": "SecondPass123!" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    let retry_after: u64 = response.headers()["retry-after"].to_str().unwrap().parse().unwrap();
+    assert!((3590..=3600).contains(&retry_after), "{}", retry_after);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["error"], "Password was changed too recently, try again later");
+}
+
+#[tokio::test]
+#[serial]
+async fn password_change_after_min_interval_succeeds() {
+    use axum_backend::infrastructure::database::schema::users;
+    use diesel::prelude::*;
+    use diesel_async::RunQueryDsl;
+
+    let server = TestServer::with_config(|config| {
+        config.resend_cooldown = std::time::Duration::ZERO;
+        config.password_min_change_interval = std::time::Duration::from_secs(3600);
+    })
+    .await;
+    let email = unique_email("pw_interval_ok");
+    server.register_user(&email, "Interval User", "FirstPass123!").await;
+    let mut conn = server.pool.get().await.unwrap();
+    diesel::update(users::table.filter(users::email.eq(&email)))
+        .set(users::password_changed_at.eq(chrono::Utc::now() - chrono::Duration::hours(2)))
+        .execute(&mut conn)
+        .await
+        .unwrap();
+
+    let code = forgot_password_code(&server, &email).await;
+    let (status, _) = set_password_with(&server, &email, &code, "SecondPass123!").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert!(!server.login_user(&email, "SecondPass123!").await.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_forced_reset_is_exempt_from_the_min_interval() {
+    let server = TestServer::with_config(|config| {
+        config.password_min_change_interval = std::time::Duration::from_secs(3600);
+    })
+    .await;
+    let admin = unique_email("forced_admin");
+    server.register_user(&admin, "Admin", TEST_PASSWORD).await;
+    server.set_user_role(&admin, "admin").await;
+    let admin_token = server.login_user(&admin, TEST_PASSWORD).await;
+    let email = unique_email("forced_user");
+    // Sets the first password just now, well within the interval
+    let user = server.register_user(&email, "Forced User", "FirstPass123!").await;
+    let user_id = user["data"]["user"]["id"].as_str().unwrap().to_string();
+
+    let response = server
+        .client
+        .post(format!("{}/api/admin/users/{}/password-reset", server.base_url, user_id))
+        .bearer_auth(&admin_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let code = server.get_confirmation_code(&email).await;
+    let (status, body) = set_password_with(&server, &email, &code, "SecondPass123!").await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let actions: Vec<String> = server
+        .audit_entries_for(user_id.parse().unwrap())
+        .await
+        .into_iter()
+        .map(|(action, _, _)| action)
+        .collect();
+    assert!(actions.iter().any(|a| a == "user.password_reset_forced"), "{:?}", actions);
+
+    // The exemption is spent once the password is set
+    let code = forgot_password_code(&server, &email).await;
+    let (status, _) = set_password_with(&server, &email, &code, "ThirdPass123!").await;
+    assert_eq!(status, StatusCode::TOO_MANY_REQUESTS);
+}
+
 // ============================================================================
 // Resend Confirm Code Flow Tests
 // ============================================================================
@@ -372,6 +1058,198 @@ async fn test_resend_code_flow() {
     assert_success(&verify_res);
 }
 
+async fn verify(server: &TestServer, email: &str) -> serde_json::Value {
+    let code = server.get_confirmation_code(email).await;
+    server
+        .client
+        .post(format!("{}/api/auth/verify", server.base_url))
+        .json(&json!({ "email": email, "code": code }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap()
+}
+
+/// Token of the verification link last emailed to `email`
+fn link_token(server: &TestServer, email: &str) -> String {
+    let link = server.outbox.last_link(email).expect("confirmation email carries a link");
+    link.split_once("token=").unwrap().1.to_string()
+}
+
+/// Submit the confirm page of the verification link last emailed to `email`
+async fn open_verification_link(server: &TestServer, email: &str) -> reqwest::Response {
+    server
+        .client
+        .post(format!("{}/api/auth/verify-link", server.base_url))
+        .form(&[("token", link_token(server, email))])
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn fetching_a_verification_link_only_shows_its_confirm_page() {
+    let server = TestServer::new().await;
+    let email = unique_email("link_prefetch");
+    register_unverified(&server, &email).await;
+    let token = link_token(&server, &email);
+
+    let page = server
+        .client
+        .get(format!("{}/api/auth/verify-link", server.base_url))
+        .query(&[("token", &token)])
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(page.status(), StatusCode::OK);
+    assert!(page.headers()["content-type"].to_str().unwrap().starts_with("text/html"));
+    let html = page.text().await.unwrap();
+    assert!(html.contains("method=\"post\""));
+    assert!(html.contains(&format!("value=\"{}\"", token)));
+
+    // The GET verified nothing, so submitting the page still does
+    let by_link = open_verification_link(&server, &email).await;
+    let body: serde_json::Value = by_link.json().await.unwrap();
+    assert_eq!(body["data"]["verified"], true);
+}
+
+#[tokio::test]
+#[serial]
+async fn verification_link_outlives_an_expired_code() {
+    let server = TestServer::with_config(|config| {
+        config.confirm_code_expiry = 1;
+        config.verification_link_expiry = 3600;
+    })
+    .await;
+    let email = unique_email("link_late");
+    register_unverified(&server, &email).await;
+    tokio::time::sleep(std::time::Duration::from_millis(1_100)).await;
+
+    let by_code = verify(&server, &email).await;
+    assert_eq!(by_code["error"], "Confirmation code expired");
+
+    let by_link = open_verification_link(&server, &email).await;
+    assert_eq!(by_link.status(), StatusCode::OK);
+    let body: serde_json::Value = by_link.json().await.unwrap();
+    assert_eq!(body["data"]["verified"], true);
+}
+
+#[tokio::test]
+#[serial]
+async fn expired_verification_link_is_refused_while_the_code_still_works() {
+    let server = TestServer::with_config(|config| {
+        config.confirm_code_expiry = 3600;
+        config.verification_link_expiry = 1;
+    })
+    .await;
+    let email = unique_email("link_expired");
+    register_unverified(&server, &email).await;
+    tokio::time::sleep(std::time::Duration::from_millis(1_100)).await;
+
+    let by_link = open_verification_link(&server, &email).await;
+    assert_eq!(by_link.status(), StatusCode::BAD_REQUEST);
+    let body: serde_json::Value = by_link.json().await.unwrap();
+    assert_eq!(body["error"], "Verification link expired");
+
+    assert_success(&verify(&server, &email).await);
+}
+
+async fn post_email(server: &TestServer, path: &str, email: &str) -> reqwest::Response {
+    server
+        .client
+        .post(format!("{}/api/auth/{}", server.base_url, path))
+        .json(&json!({ "email": email }))
+        .send()
+        .await
+        .unwrap()
+}
+
+async fn register_unverified(server: &TestServer, email: &str) {
+    server
+        .client
+        .post(format!("{}/api/auth/register", server.base_url))
+        .json(&json!({ "email": email, "name": "Cooldown" }))
+        .send()
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+#[serial]
+async fn resend_within_cooldown_is_throttled_with_retry_after() {
+    let server = TestServer::new().await;
+    let email = unique_email("resend_twice");
+    register_unverified(&server, &email).await;
+
+    assert_eq!(post_email(&server, "resend-code", &email).await.status(), StatusCode::OK);
+    let throttled = post_email(&server, "resend-code", &email).await;
+
+    assert_eq!(throttled.status(), StatusCode::TOO_MANY_REQUESTS);
+    let header: u64 = throttled.headers()["retry-after"].to_str().unwrap().parse().unwrap();
+    let body: serde_json::Value = throttled.json().await.unwrap();
+    assert_eq!(body["retry_after_seconds"], header);
+    assert!((1..=60).contains(&header), "{}", header);
+    assert_error(&body);
+}
+
+#[tokio::test]
+#[serial]
+async fn verifying_resets_the_resend_cooldown() {
+    let server = TestServer::new().await;
+    let email = unique_email("resend_reset");
+    register_unverified(&server, &email).await;
+    assert_eq!(post_email(&server, "resend-code", &email).await.status(), StatusCode::OK);
+
+    assert_success(&verify(&server, &email).await);
+
+    // Would be inside the cooldown started by resend-code above
+    let res = post_email(&server, "forgot-password", &email).await;
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+#[serial]
+async fn verify_reports_password_setup_required_for_new_account() {
+    let server = TestServer::new().await;
+    let email = unique_email("verify_new");
+    server
+        .client
+        .post(format!("{}/api/auth/register", server.base_url))
+        .json(&json!({ "email": email, "name": "New" }))
+        .send()
+        .await
+        .unwrap();
+
+    let res = verify(&server, &email).await;
+
+    assert_success(&res);
+    assert_eq!(res["data"], json!({ "verified": true, "requires_password_setup": true }));
+}
+
+#[tokio::test]
+#[serial]
+async fn verify_reports_no_password_setup_when_password_exists() {
+    let server = TestServer::new().await;
+    let email = unique_email("verify_existing");
+    server.register_user(&email, "Existing", TEST_PASSWORD).await;
+    // Issues a fresh code to an account that already has a password
+    server
+        .client
+        .post(format!("{}/api/auth/forgot-password", server.base_url))
+        .json(&json!({ "email": email }))
+        .send()
+        .await
+        .unwrap();
+
+    let res = verify(&server, &email).await;
+
+    assert_success(&res);
+    assert_eq!(res["data"], json!({ "verified": true, "requires_password_setup": false }));
+}
+
 // ============================================================================
 // Full Authentication Flow Tests
 // ============================================================================
@@ -505,11 +1383,8 @@ async fn test_login_with_code_flow() {
 
     assert_eq!(res.status(), StatusCode::OK);
 
-    // 4. Login with Code (Skip Set Password)
-    // Code should still be valid because `VerifyEmail` does NOT clear it in some flows,
-    // OR we might need to regenerat it.
-    // Based on previous analysis: `Login` clears the code.
-
+    // 4. Login with the same code is refused: until a password exists the
+    // code is reserved for password setup
     let res = server
         .client
         .post(format!("{}/api/auth/login", server.base_url))
@@ -521,9 +1396,386 @@ async fn test_login_with_code_flow() {
         .await
         .expect("Failed to send login request");
 
-    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
 
-    // Check for cookie
-    let cookie = res.cookies().find(|c| c.name() == "access_token");
-    assert!(cookie.is_some(), "Access token cookie should be present after code login");
+    // 5. Which it still completes
+    let res = server
+        .client
+        .post(format!("{}/api/auth/password", server.base_url))
+        .json(&json!({ "email": email, "code": code, "password": TEST_PASSWORD }))
+        .send()
+        .await
+        .expect("Failed to send set password request");
+
+    assert_success(&res.json().await.unwrap());
+}
+
+async fn login_with_code(server: &TestServer, email: &str, code: &str) -> StatusCode {
+    server
+        .client
+        .post(format!("{}/api/auth/login", server.base_url))
+        .json(&json!({ "email": email, "code": code }))
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+#[serial]
+async fn a_code_used_to_verify_cannot_be_replayed_to_log_in() {
+    let server = TestServer::new().await;
+    let email = unique_email("replay");
+    server.register_user(&email, "Replay User", TEST_PASSWORD).await;
+
+    // A fresh code for a user who already has a password
+    assert!(post_email(&server, "forgot-password", &email).await.status().is_success());
+    let code = server.get_confirmation_code(&email).await;
+    assert_success(&verify(&server, &email).await);
+
+    assert_eq!(login_with_code(&server, &email, &code).await, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn a_login_code_works_exactly_once() {
+    let server = TestServer::new().await;
+    let email = unique_email("once");
+    server.register_user(&email, "Once User", TEST_PASSWORD).await;
+
+    assert!(post_email(&server, "forgot-password", &email).await.status().is_success());
+    let code = server.get_confirmation_code(&email).await;
+
+    let (first, second) = tokio::join!(
+        login_with_code(&server, &email, &code),
+        login_with_code(&server, &email, &code)
+    );
+    let mut statuses = [first, second];
+    statuses.sort();
+    assert_eq!(statuses, [StatusCode::OK, StatusCode::UNAUTHORIZED]);
+    assert_eq!(login_with_code(&server, &email, &code).await, StatusCode::UNAUTHORIZED);
+}
+
+// ============================================================================
+// Session Limits
+// ============================================================================
+
+async fn login_refresh_token(server: &TestServer, email: &str) -> String {
+    let (status, body) = server.login_response(email, TEST_PASSWORD).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    body["data"]["refresh_token"].as_str().unwrap().to_string()
+}
+
+/// Log in and return the access and refresh tokens
+async fn login_tokens(server: &TestServer, email: &str) -> (String, String) {
+    let (status, body) = server.login_response(email, TEST_PASSWORD).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    let token = |name: &str| body["data"][name].as_str().unwrap().to_string();
+    (token("access_token"), token("refresh_token"))
+}
+
+async fn list_sessions(server: &TestServer, token: &str) -> Vec<serde_json::Value> {
+    let body: serde_json::Value = server
+        .client
+        .get(format!("{}/api/auth/sessions", server.base_url))
+        .bearer_auth(token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_success(&body);
+    body["data"].as_array().unwrap().clone()
+}
+
+async fn revoke_session(server: &TestServer, token: &str, id: &str) -> StatusCode {
+    server
+        .client
+        .delete(format!("{}/api/auth/sessions/{}", server.base_url, id))
+        .bearer_auth(token)
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+#[tokio::test]
+#[serial]
+async fn users_list_and_revoke_their_own_sessions() {
+    let server = TestServer::new().await;
+    let email = unique_email("sessions");
+    server.register_user(&email, "Session User", TEST_PASSWORD).await;
+    let (token, refresh) = login_tokens(&server, &email).await;
+
+    // register_user's login plus this one, oldest first
+    let sessions = list_sessions(&server, &token).await;
+    assert_eq!(sessions.len(), 2, "{:?}", sessions);
+    let newest = sessions[1]["id"].as_str().unwrap().to_string();
+
+    assert_eq!(revoke_session(&server, &token, &newest).await, StatusCode::OK);
+
+    assert!(server.is_refresh_token_revoked(&refresh).await);
+    let remaining = list_sessions(&server, &token).await;
+    assert_eq!(remaining.len(), 1);
+    assert_ne!(remaining[0]["id"], newest.as_str());
+    // Already revoked, or not a session id at all
+    assert_eq!(revoke_session(&server, &token, &newest).await, StatusCode::NOT_FOUND);
+    assert_eq!(revoke_session(&server, &token, "not-a-uuid").await, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[serial]
+async fn users_cannot_revoke_another_users_session() {
+    let server = TestServer::new().await;
+    let alice = unique_email("sess_alice");
+    let bob = unique_email("sess_bob");
+    server.register_user(&alice, "Alice", TEST_PASSWORD).await;
+    server.register_user(&bob, "Bob", TEST_PASSWORD).await;
+    let (alice_token, _) = login_tokens(&server, &alice).await;
+    let (bob_token, bob_refresh) = login_tokens(&server, &bob).await;
+
+    let bob_sessions = list_sessions(&server, &bob_token).await;
+    let alice_sessions = list_sessions(&server, &alice_token).await;
+    assert!(alice_sessions.iter().all(|s| !bob_sessions.contains(s)));
+
+    for session in &bob_sessions {
+        let id = session["id"].as_str().unwrap();
+        assert_eq!(revoke_session(&server, &alice_token, id).await, StatusCode::NOT_FOUND);
+    }
+
+    assert!(!server.is_refresh_token_revoked(&bob_refresh).await);
+    assert_eq!(list_sessions(&server, &bob_token).await, bob_sessions);
+}
+
+#[tokio::test]
+#[serial]
+async fn login_beyond_session_cap_evicts_oldest_session() {
+    let server = TestServer::with_config(|config| config.max_sessions_per_user = Some(2)).await;
+    let email = unique_email("session_evict");
+    server.register_user(&email, "Session User", TEST_PASSWORD).await;
+
+    // register_user already logged in once; that session is the oldest
+    let second = login_refresh_token(&server, &email).await;
+    let third = login_refresh_token(&server, &email).await;
+    let fourth = login_refresh_token(&server, &email).await;
+
+    assert!(server.is_refresh_token_revoked(&second).await);
+    assert!(!server.is_refresh_token_revoked(&third).await);
+    assert!(!server.is_refresh_token_revoked(&fourth).await);
+}
+
+#[tokio::test]
+#[serial]
+async fn login_beyond_session_cap_is_rejected_when_configured() {
+    let server = TestServer::with_config(|config| {
+        config.max_sessions_per_user = Some(1);
+        config.session_limit_reject = true;
+    })
+    .await;
+    let email = unique_email("session_reject");
+    server.register_user(&email, "Session User", TEST_PASSWORD).await;
+
+    let (status, body) = server.login_response(&email, TEST_PASSWORD).await;
+
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(body["error"], "Maximum number of active sessions reached");
+}
+
+async fn get_me(server: &TestServer, token: Option<&str>) -> reqwest::Response {
+    let request = server.client.get(format!("{}/api/auth/me", server.base_url));
+    let request = match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    };
+    request.send().await.unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn me_returns_the_token_owner_without_sensitive_fields() {
+    let server = TestServer::new().await;
+    let email = unique_email("me_user");
+    let user = seed_user(&server.pool, &email, "Me User", TEST_PASSWORD).await;
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    let response = get_me(&server, Some(&token)).await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["data"]["id"], user.id.to_string());
+    assert_eq!(body["data"]["email"], email);
+    assert_eq!(body["data"]["name"], "Me User");
+    assert!(body["data"].get("password_hash").is_none());
+    assert!(body["data"].get("confirmation_code").is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn me_requires_authentication() {
+    let server = TestServer::new().await;
+
+    assert_eq!(get_me(&server, None).await.status(), StatusCode::UNAUTHORIZED);
+    let response = get_me(&server, Some("not-a-token")).await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["code"], "TOKEN_INVALID");
+}
+
+#[tokio::test]
+#[serial]
+async fn me_is_not_found_once_the_user_is_deleted_whether_or_not_their_role_is_cached() {
+    let server = TestServer::new().await;
+    let cached = unique_email("me_cached");
+    let uncached = unique_email("me_uncached");
+    seed_user(&server.pool, &cached, "Cached User", TEST_PASSWORD).await;
+    seed_user(&server.pool, &uncached, "Uncached User", TEST_PASSWORD).await;
+    let cached_token = server.login_user(&cached, TEST_PASSWORD).await;
+    let uncached_token = server.login_user(&uncached, TEST_PASSWORD).await;
+    // Only this one has its role cached before the delete
+    assert_eq!(get_me(&server, Some(&cached_token)).await.status(), StatusCode::OK);
+
+    cleanup_test_user(&server.pool, &cached).await;
+    cleanup_test_user(&server.pool, &uncached).await;
+
+    for token in [&cached_token, &uncached_token] {
+        assert_eq!(get_me(&server, Some(token)).await.status(), StatusCode::NOT_FOUND);
+    }
+    // Other protected routes still need the user to exist
+    let response = server
+        .client
+        .get(format!("{}/api/auth/sessions", server.base_url))
+        .bearer_auth(&uncached_token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+#[serial]
+async fn login_rehashes_password_with_the_current_pepper() {
+    let server = TestServer::with_config(|config| {
+        config.password_peppers =
+            axum_backend::shared::utils::password::Peppers::parse("v2:new-pepper").unwrap();
+    })
+    .await;
+    let email = unique_email("pepper_rehash");
+    // Seeded directly, so hashed without any pepper
+    seed_user(&server.pool, &email, "Pepper User", TEST_PASSWORD).await;
+    let before = server.get_password_hash(&email).await.unwrap();
+    assert!(!before.contains("keyid="));
+
+    let (status, body) = server.login_response(&email, TEST_PASSWORD).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+
+    let after = server.get_password_hash(&email).await.unwrap();
+    assert_ne!(after, before);
+    assert!(after.contains("keyid="), "{}", after);
+    let (status, body) = server.login_response(&email, TEST_PASSWORD).await;
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(server.get_password_hash(&email).await.unwrap(), after);
+}
+
+#[tokio::test]
+#[serial]
+async fn hashed_codes_still_verify_while_the_column_holds_only_a_digest() {
+    use axum_backend::shared::utils::code_hash::CodeHasher;
+
+    let hasher = CodeHasher::new(Some("test_code_hash_key_at_least_32_bytes"));
+    let keyed = hasher.clone();
+    let server =
+        TestServer::with_config(move |config| config.confirmation_code_hasher = keyed).await;
+    let email = unique_email("hashed");
+
+    // Registration, verification and password setup all run on the digest
+    assert_success(&server.register_user(&email, "Hashed User", TEST_PASSWORD).await);
+
+    assert!(post_email(&server, "forgot-password", &email).await.status().is_success());
+    let code = server.get_confirmation_code(&email).await;
+    let stored = server.get_stored_confirmation_code(&email).await;
+    assert_ne!(stored, code);
+    assert_eq!(stored, hasher.stored_form(&code));
+
+    // The stored digest is not itself a usable code
+    assert_eq!(login_with_code(&server, &email, &stored).await, StatusCode::UNAUTHORIZED);
+    assert_eq!(login_with_code(&server, &email, &code).await, StatusCode::OK);
+}
+
+#[tokio::test]
+#[serial]
+async fn password_reset_rejects_the_email_as_password_when_enabled() {
+    let server = TestServer::with_config(|config| {
+        config.resend_cooldown = std::time::Duration::ZERO;
+        config.password_rules.reject_email = true;
+    })
+    .await;
+    let email = unique_email("pw_email");
+    server.register_user(&email, "Email User", TEST_PASSWORD).await;
+    let local_part = email.split('@').next().unwrap().to_uppercase();
+
+    let code = forgot_password_code(&server, &email).await;
+    for password in [local_part.as_str(), email.as_str()] {
+        let (status, body) = set_password_with(&server, &email, &code, password).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", password);
+        assert_eq!(body["error"], "Password must not be the same as your email address");
+    }
+
+    let (status, _) = set_password_with(&server, &email, &code, "Unrelated123!").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+#[serial]
+async fn password_reset_rejects_breached_passwords_when_enabled() {
+    let breached = "Breached-Pass-1";
+    let server = TestServer::with_config(|config| {
+        config.resend_cooldown = std::time::Duration::ZERO;
+        config.password_rules.breached =
+            Some(std::sync::Arc::new(BreachedPasswords::parse(breached)));
+    })
+    .await;
+    let email = unique_email("pw_breach");
+    server.register_user(&email, "Breach User", TEST_PASSWORD).await;
+
+    let code = forgot_password_code(&server, &email).await;
+    let (status, body) = set_password_with(&server, &email, &code, breached).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Password has appeared in a data breach, choose a different one");
+
+    let (status, _) = set_password_with(&server, &email, &code, "Unbreached-Pass-1").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+#[serial]
+async fn refresh_storage_failures_do_not_reveal_the_cause() {
+    use diesel_async::SimpleAsyncConnection;
+
+    let server = TestServer::new().await;
+    let email = unique_email("refresh_down");
+    server.register_user(&email, "Refresh", TEST_PASSWORD).await;
+    let refresh_token = login_refresh_token(&server, &email).await;
+
+    let mut conn = server.pool.get().await.unwrap();
+    conn.batch_execute(
+        "CREATE FUNCTION reject_tokens() RETURNS trigger AS $$ \
+         BEGIN RAISE EXCEPTION 'refresh_tokens is read-only'; END; $$ LANGUAGE plpgsql; \
+         CREATE TRIGGER reject_tokens BEFORE INSERT OR UPDATE ON refresh_tokens \
+         FOR EACH ROW EXECUTE FUNCTION reject_tokens();",
+    )
+    .await
+    .unwrap();
+
+    let res = server
+        .client
+        .post(format!("{}/api/auth/refresh", server.base_url))
+        .json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body, json!({ "success": false, "error": "Internal server error" }));
 }
@@ -0,0 +1,94 @@
+/// Integration tests for per-route Cache-Control headers
+use crate::common::*;
+use axum_backend::shared::cache_control::CachePolicy;
+use reqwest::StatusCode;
+use serial_test::serial;
+
+fn cache_control(res: &reqwest::Response) -> Option<String> {
+    res.headers().get("cache-control").map(|v| v.to_str().unwrap().to_string())
+}
+
+#[tokio::test]
+#[serial]
+async fn configured_policies_apply_to_cacheable_gets() {
+    let server = TestServer::with_config(|c| {
+        c.cache_control.user_detail = CachePolicy::Public { max_age: 120 };
+        c.cache_control.system_stats = CachePolicy::Private { max_age: 7 };
+    })
+    .await;
+    let admin = unique_email("cache_admin");
+    server.create_admin(&admin, TEST_PASSWORD).await;
+    let token = server.login_user(&admin, TEST_PASSWORD).await;
+    let me: serde_json::Value = server
+        .client
+        .get(format!("{}/api/auth/me", server.base_url))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    let id = me["data"]["id"].as_str().unwrap();
+
+    let user = server
+        .client
+        .get(format!("{}/api/users/{}", server.base_url, id))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(user.status(), StatusCode::OK);
+    assert_eq!(cache_control(&user).as_deref(), Some("public, max-age=120"));
+
+    let stats = server
+        .client
+        .get(format!("{}/api/admin/system", server.base_url))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(stats.status(), StatusCode::OK);
+    assert_eq!(cache_control(&stats).as_deref(), Some("private, max-age=7"));
+
+    let missing = server
+        .client
+        .get(format!("{}/api/users/{}", server.base_url, uuid::Uuid::new_v4()))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(missing.status(), StatusCode::NOT_FOUND);
+    assert_eq!(cache_control(&missing).as_deref(), Some("no-store"), "errors are never cached");
+}
+
+#[tokio::test]
+#[serial]
+async fn auth_and_admin_endpoints_are_never_stored() {
+    let server = TestServer::new().await;
+    let admin = unique_email("nostore_admin");
+    server.create_admin(&admin, TEST_PASSWORD).await;
+
+    let login = server
+        .client
+        .post(format!("{}/api/auth/login", server.base_url))
+        .json(&serde_json::json!({ "email": admin, "password": TEST_PASSWORD }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(login.status(), StatusCode::OK);
+    assert_eq!(cache_control(&login).as_deref(), Some("no-store"));
+    let token = server.login_user(&admin, TEST_PASSWORD).await;
+
+    for path in ["/api/auth/me", "/api/admin/users/export.csv"] {
+        let res = server
+            .client
+            .get(format!("{}{}", server.base_url, path))
+            .bearer_auth(&token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK, "{}", path);
+        assert_eq!(cache_control(&res).as_deref(), Some("no-store"), "{}", path);
+    }
+}
@@ -0,0 +1,47 @@
+use crate::common::*;
+use reqwest::StatusCode;
+use serde_json::json;
+use serial_test::serial;
+
+/// Allow a single auth request per client before throttling
+async fn strict_server(trusted_proxies: &str) -> TestServer {
+    let trusted = axum_backend::config::parse_trusted_proxies(trusted_proxies).unwrap();
+    TestServer::with_config(move |config| {
+        config.rate_limit_per_second = 60;
+        config.rate_limit_burst_size = 1;
+        config.trusted_proxies = trusted;
+    })
+    .await
+}
+
+async fn login_from(server: &TestServer, forwarded_for: &str) -> StatusCode {
+    server
+        .client
+        .post(format!("{}/api/auth/login", server.base_url))
+        .header("X-Forwarded-For", forwarded_for)
+        .json(&json!({ "email": "nobody@example.com", "password": "wrong-password" }))
+        .send()
+        .await
+        .expect("Failed to send login request")
+        .status()
+}
+
+#[tokio::test]
+#[serial]
+async fn trusted_proxy_forwarding_gives_each_client_its_own_limit() {
+    let server = strict_server("127.0.0.1").await;
+
+    assert_ne!(login_from(&server, "198.51.100.1").await, StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(login_from(&server, "198.51.100.1").await, StatusCode::TOO_MANY_REQUESTS);
+    assert_ne!(login_from(&server, "198.51.100.2").await, StatusCode::TOO_MANY_REQUESTS);
+}
+
+#[tokio::test]
+#[serial]
+async fn untrusted_forwarding_headers_cannot_dodge_the_limit() {
+    let server = strict_server("").await;
+
+    assert_ne!(login_from(&server, "198.51.100.1").await, StatusCode::TOO_MANY_REQUESTS);
+    // A fresh spoofed address is ignored; the socket peer is still throttled
+    assert_eq!(login_from(&server, "198.51.100.2").await, StatusCode::TOO_MANY_REQUESTS);
+}
@@ -83,6 +83,84 @@ async fn test_cookie_auth_missing_cookie() {
     assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
 }
 
+fn cookie(res: &reqwest::Response, name: &str) -> Option<String> {
+    res.cookies().find(|c| c.name() == name).map(|c| c.value().to_string())
+}
+
+async fn refresh(server: &TestServer, body: Option<serde_json::Value>) -> reqwest::Response {
+    let request = server.client.post(format!("{}/api/auth/refresh", server.base_url));
+    let request = match body {
+        Some(body) => request.json(&body),
+        None => request,
+    };
+    request.send().await.unwrap()
+}
+
+#[tokio::test]
+async fn test_cookie_only_client_refreshes_silently() {
+    let server = TestServer::new().await;
+    let email = unique_email("cookie_refresh");
+    server.register_user(&email, "Refresh User", TEST_PASSWORD).await;
+    let login_res = server
+        .client
+        .post(format!("{}/api/auth/login", server.base_url))
+        .json(&json!({ "email": email, "password": TEST_PASSWORD }))
+        .send()
+        .await
+        .unwrap();
+    let old_refresh = cookie(&login_res, "refresh_token").expect("Login should set refresh cookie");
+
+    // No body at all: the refresh token comes from the cookie jar
+    let res = refresh(&server, None).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let new_access = cookie(&res, "access_token").expect("Refresh should set access cookie");
+    let new_refresh = cookie(&res, "refresh_token").expect("Refresh should set refresh cookie");
+    assert_ne!(new_refresh, old_refresh);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["data"]["access_token"], new_access.as_str());
+
+    // The jar now holds the new tokens, which keep working
+    assert_eq!(server.get_users_list_raw().await.status(), StatusCode::OK);
+    assert_eq!(refresh(&server, None).await.status(), StatusCode::OK);
+
+    // The rotated-out token is spent
+    let replay = refresh(&server, Some(json!({ "refresh_token": old_refresh }))).await;
+    assert_eq!(replay.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_refresh_accepts_a_body_token_and_requires_one() {
+    let server = TestServer::new().await;
+    let email = unique_email("body_refresh");
+    server.register_user(&email, "Refresh User", TEST_PASSWORD).await;
+    let (_, login) = server.login_response(&email, TEST_PASSWORD).await;
+    let refresh_token = login["data"]["refresh_token"].as_str().unwrap();
+
+    // A client without cookies sends the token in the body
+    let raw_client = reqwest::Client::new();
+    let url = format!("{}/api/auth/refresh", server.base_url);
+    let res = raw_client
+        .post(&url)
+        .json(&json!({ "refresh_token": refresh_token }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // An access token is not a refresh token
+    let access_token = login["data"]["access_token"].as_str().unwrap();
+    let res = raw_client
+        .post(&url)
+        .json(&json!({ "refresh_token": access_token }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+
+    let res = raw_client.post(&url).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
 // Extension to TestServer for raw requests if needed
 impl TestServer {
     pub async fn get_users_list_raw(&self) -> reqwest::Response {
@@ -94,3 +172,199 @@ impl TestServer {
             .unwrap()
     }
 }
+
+/// Log in with an optional `X-Token-Delivery`, returning the cookie names set
+/// (sorted) and whether the body carried the tokens
+async fn login_delivery(
+    server: &TestServer,
+    email: &str,
+    header: Option<&str>,
+) -> (StatusCode, Vec<String>, bool) {
+    let request = reqwest::Client::new()
+        .post(format!("{}/api/auth/login", server.base_url))
+        .json(&json!({ "email": email, "password": TEST_PASSWORD }));
+    let request = match header {
+        Some(value) => request.header("X-Token-Delivery", value),
+        None => request,
+    };
+    let res = request.send().await.unwrap();
+    let status = res.status();
+    let mut cookies: Vec<String> = res.cookies().map(|c| c.name().to_string()).collect();
+    cookies.sort();
+    let body: serde_json::Value = res.json().await.unwrap();
+    let in_body =
+        body["data"]["access_token"].is_string() && body["data"]["refresh_token"].is_string();
+    (status, cookies, in_body)
+}
+
+#[tokio::test]
+async fn token_delivery_header_picks_where_tokens_go() {
+    let server = TestServer::new().await;
+    let email = unique_email("delivery");
+    server.register_user(&email, "Delivery User", TEST_PASSWORD).await;
+    let both = vec!["access_token".to_string(), "refresh_token".to_string()];
+
+    assert_eq!(
+        login_delivery(&server, &email, None).await,
+        (StatusCode::OK, both.clone(), true)
+    );
+    assert_eq!(
+        login_delivery(&server, &email, Some("both")).await,
+        (StatusCode::OK, both.clone(), true)
+    );
+    assert_eq!(
+        login_delivery(&server, &email, Some("cookie")).await,
+        (StatusCode::OK, both, false)
+    );
+    assert_eq!(
+        login_delivery(&server, &email, Some("body")).await,
+        (StatusCode::OK, vec![], true)
+    );
+
+    let res = reqwest::Client::new()
+        .post(format!("{}/api/auth/login", server.base_url))
+        .header("X-Token-Delivery", "carrier-pigeon")
+        .json(&json!({ "email": email, "password": TEST_PASSWORD }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn configured_token_delivery_applies_to_login_and_refresh() {
+    let server = TestServer::with_config(|config| {
+        config.token_delivery = axum_backend::application::dto::auth::TokenDelivery::Cookie;
+    })
+    .await;
+    let email = unique_email("delivery_cfg");
+    server.register_user(&email, "Delivery User", TEST_PASSWORD).await;
+
+    let (status, cookies, in_body) = login_delivery(&server, &email, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(cookies.contains(&"refresh_token".to_string()));
+    assert!(!in_body, "cookie delivery must keep tokens out of the body");
+
+    // The shared client's jar holds the refresh cookie from registration
+    let res = refresh(&server, None).await;
+    assert_eq!(res.status(), StatusCode::OK);
+    assert!(cookie(&res, "access_token").is_some());
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert!(body["data"].get("access_token").is_none());
+    assert!(body["data"].get("refresh_token").is_none());
+    assert!(body["data"]["expires_in"].is_number());
+}
+
+#[tokio::test]
+async fn bearer_header_takes_precedence_over_the_cookie() {
+    let server = TestServer::new().await;
+    let email = unique_email("auth_order");
+    server.register_user(&email, "Auth Order", TEST_PASSWORD).await;
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+    // No cookie store, so only what each case sets is sent
+    let raw_client = reqwest::Client::new();
+    let sessions = |header: Option<&str>, cookie: Option<&str>| {
+        let mut request = raw_client.get(format!("{}/api/auth/sessions", server.base_url));
+        if let Some(token) = header {
+            request = request.bearer_auth(token);
+        }
+        if let Some(token) = cookie {
+            request = request.header("Cookie", format!("theme=dark; access_token={}", token));
+        }
+        request.send()
+    };
+
+    assert_eq!(sessions(Some(&token), None).await.unwrap().status(), StatusCode::OK);
+    assert_eq!(sessions(None, Some(&token)).await.unwrap().status(), StatusCode::OK);
+    assert_eq!(sessions(Some(&token), Some("garbage")).await.unwrap().status(), StatusCode::OK);
+
+    let res = sessions(Some("garbage"), Some(&token)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["code"], "TOKEN_INVALID");
+
+    let res = sessions(None, None).await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["error"], "Missing authorization token");
+}
+
+#[tokio::test]
+async fn cookie_only_logout_revokes_the_cookie_session_and_can_be_repeated() {
+    let server = TestServer::new().await;
+    let email = unique_email("logout_cookie");
+    server.register_user(&email, "Cookie Logout", TEST_PASSWORD).await;
+    let (_, body) = server.login_response(&email, TEST_PASSWORD).await;
+    let refresh_token = body["data"]["refresh_token"].as_str().unwrap().to_string();
+    let access_token = body["data"]["access_token"].as_str().unwrap().to_string();
+    let logout = |cookies: String| {
+        reqwest::Client::new()
+            .post(format!("{}/api/auth/logout", server.base_url))
+            .bearer_auth(&access_token)
+            .header("Cookie", cookies)
+            .send()
+    };
+
+    // No body at all, only the cookie a browser would send
+    let res = logout(format!("refresh_token={}", refresh_token)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(cookie(&res, "refresh_token").as_deref(), Some(""));
+    assert!(server.is_refresh_token_revoked(&refresh_token).await);
+
+    let res = logout(format!("refresh_token={}", refresh_token)).await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn logout_without_any_refresh_token_still_clears_cookies() {
+    let server = TestServer::new().await;
+    let email = unique_email("logout_none");
+    server.register_user(&email, "Tokenless Logout", TEST_PASSWORD).await;
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    let res = reqwest::Client::new()
+        .post(format!("{}/api/auth/logout", server.base_url))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::OK);
+    assert_eq!(cookie(&res, "access_token").as_deref(), Some(""));
+    assert_eq!(cookie(&res, "refresh_token").as_deref(), Some(""));
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_success(&body);
+}
+
+#[tokio::test]
+async fn logout_all_with_the_wrong_content_type_is_refused() {
+    let server = TestServer::new().await;
+    let email = unique_email("logout_mistyped");
+    server.register_user(&email, "Mistyped Logout", TEST_PASSWORD).await;
+    let (_, body) = server.login_response(&email, TEST_PASSWORD).await;
+    let access_token = body["data"]["access_token"].as_str().unwrap().to_string();
+    let refresh_token = body["data"]["refresh_token"].as_str().unwrap().to_string();
+    let logout = |body: &'static str, content_type: &'static str| {
+        reqwest::Client::new()
+            .post(format!("{}/api/auth/logout", server.base_url))
+            .bearer_auth(&access_token)
+            .header("Content-Type", content_type)
+            .body(body)
+            .send()
+    };
+
+    let res = logout("{\"logout_all\": true}", "text/plain").await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["code"], "INVALID_JSON");
+
+    let res = logout("{\"logout_all\": \"yes\"}", "application/json").await.unwrap();
+    assert_eq!(res.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let res = logout("{\"logout_all\": ", "application/json").await.unwrap();
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+    assert!(!server.is_refresh_token_revoked(&refresh_token).await, "no session ended");
+
+    // An empty body is still no body, whatever it claims to be
+    let res = logout("", "text/plain").await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
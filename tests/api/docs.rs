@@ -0,0 +1,81 @@
+/// Integration tests for the API documentation routes
+use crate::common::*;
+use reqwest::StatusCode;
+use serial_test::serial;
+
+async fn status(server: &TestServer, path: &str) -> StatusCode {
+    server
+        .client
+        .get(format!("{}{}", server.base_url, path))
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+#[cfg(feature = "swagger")]
+#[tokio::test]
+#[serial]
+async fn swagger_is_served_when_enabled() {
+    let server = TestServer::new().await;
+
+    assert_eq!(status(&server, "/api-docs/openapi.json").await, StatusCode::OK);
+    assert_eq!(status(&server, "/swagger-ui/").await, StatusCode::OK);
+}
+
+#[tokio::test]
+#[serial]
+async fn swagger_is_absent_when_disabled() {
+    let server = TestServer::with_config(|config| config.swagger_enabled = false).await;
+
+    assert_eq!(status(&server, "/api-docs/openapi.json").await, StatusCode::NOT_FOUND);
+    assert_eq!(status(&server, "/swagger-ui/").await, StatusCode::NOT_FOUND);
+    assert_eq!(status(&server, "/swagger-ui").await, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+#[serial]
+async fn openapi_shows_configured_page_sizes() {
+    let server = TestServer::with_config(|config| {
+        config.default_page_size = 25;
+        config.max_page_size = 50;
+    })
+    .await;
+
+    let doc: serde_json::Value = server
+        .client
+        .get(format!("{}/api-docs/openapi.json", server.base_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let params = doc["paths"]["/api/users"]["get"]["parameters"].as_array().unwrap();
+    let page_size = params.iter().find(|p| p["name"] == "page_size").unwrap();
+    assert_eq!(page_size["schema"]["default"], 25);
+    assert_eq!(page_size["schema"]["maximum"], 50.0);
+}
+
+#[tokio::test]
+#[serial]
+async fn openapi_documents_the_admin_config_dump() {
+    let server = TestServer::new().await;
+
+    let doc: serde_json::Value = server
+        .client
+        .get(format!("{}/api-docs/openapi.json", server.base_url))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    let responses = &doc["paths"]["/api/admin/config"]["get"]["responses"];
+    assert_eq!(
+        responses["200"]["content"]["application/json"]["schema"]["$ref"],
+        "#/components/schemas/ConfigResponseWrapper"
+    );
+}
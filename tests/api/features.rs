@@ -0,0 +1,88 @@
+/// Integration tests for FEATURE_* subsystem toggles
+use crate::common::*;
+use reqwest::StatusCode;
+use serde_json::json;
+use serial_test::serial;
+use uuid::Uuid;
+
+async fn put_role(server: &TestServer, token: &str, target: Uuid, role: &str) -> StatusCode {
+    server
+        .client
+        .put(format!("{}/api/users/{}/role", server.base_url, target))
+        .bearer_auth(token)
+        .json(&json!({ "role": role }))
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+/// Register a viewer, let the server resolve (and possibly cache) their role,
+/// then promote them behind the server's back and report the next response
+async fn status_after_out_of_band_promotion(server: &TestServer, prefix: &str) -> StatusCode {
+    let email = unique_email(prefix);
+    let user = server.register_user(&email, "Toggle", TEST_PASSWORD).await;
+    let user_id: Uuid = user["data"]["user"]["id"].as_str().unwrap().parse().unwrap();
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    assert_eq!(put_role(server, &token, user_id, "viewer").await, StatusCode::FORBIDDEN);
+    server.set_user_role(&email, "admin").await;
+    put_role(server, &token, user_id, "viewer").await
+}
+
+#[tokio::test]
+#[serial]
+async fn disabling_cache_reads_roles_from_the_database_on_every_request() {
+    let cached = TestServer::new().await;
+    assert_eq!(
+        status_after_out_of_band_promotion(&cached, "cache_on").await,
+        StatusCode::FORBIDDEN,
+        "cached role should still be served"
+    );
+
+    let uncached = TestServer::with_config(|config| config.features.cache = false).await;
+    assert_eq!(status_after_out_of_band_promotion(&uncached, "cache_off").await, StatusCode::OK);
+}
+
+#[tokio::test]
+#[serial]
+async fn disabling_nats_skips_the_broker_even_when_configured_as_required() {
+    let server = TestServer::with_config(|config| {
+        // Nothing listens here; with the feature on readiness would fail
+        config.nats_config.url = Some("nats://127.0.0.1:1".to_string());
+        config.nats_config.required = true;
+        config.features.nats = false;
+    })
+    .await;
+
+    let response = server.readiness_check().await;
+
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "healthy");
+    assert_eq!(body["checks"]["nats"]["status"], "disabled");
+}
+
+/// Builds without the `nats` and `moka` cargo features ignore a configured
+/// broker and still cache roles in memory
+#[cfg(not(any(feature = "nats", feature = "moka")))]
+#[tokio::test]
+#[serial]
+async fn app_runs_without_the_nats_and_moka_cargo_features() {
+    let server = TestServer::with_config(|config| {
+        config.nats_config.url = Some("nats://127.0.0.1:1".to_string());
+        config.nats_config.required = true;
+    })
+    .await;
+
+    let response = server.readiness_check().await;
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["checks"]["nats"]["status"], "disabled");
+
+    assert_eq!(
+        status_after_out_of_band_promotion(&server, "no_features").await,
+        StatusCode::FORBIDDEN,
+        "roles are still cached in memory"
+    );
+}
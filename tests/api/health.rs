@@ -11,3 +11,136 @@ async fn test_health_check() {
     let body: serde_json::Value = response.json().await.unwrap();
     assert_eq!(body["status"], "healthy");
 }
+
+#[tokio::test]
+#[serial]
+async fn readiness_reports_database_up_and_nats_disabled_by_default() {
+    let server = TestServer::new().await;
+
+    let response = server.readiness_check().await;
+
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "healthy");
+    assert_eq!(body["checks"]["database"]["status"], "up");
+    assert_eq!(body["checks"]["migrations"]["status"], "up");
+    assert_eq!(body["checks"]["migrations"]["pending"], 0);
+    assert_eq!(body["checks"]["nats"]["status"], "disabled");
+}
+
+#[tokio::test]
+#[serial]
+async fn readiness_fails_while_migrations_are_pending() {
+    let server = TestServer::without_migrations().await;
+
+    let response = server.readiness_check().await;
+
+    assert_eq!(response.status(), 503);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "unhealthy");
+    assert_eq!(body["checks"]["database"]["status"], "up");
+    assert_eq!(body["checks"]["migrations"]["status"], "pending");
+    assert!(body["checks"]["migrations"]["pending"].as_u64().unwrap() > 0);
+}
+
+#[cfg(feature = "nats")]
+#[tokio::test]
+#[serial]
+async fn readiness_is_degraded_when_only_optional_nats_is_down() {
+    let server = TestServer::with_config(|config| {
+        config.nats_config.url = Some(unreachable_nats_url());
+        config.nats_config.required = false;
+    })
+    .await;
+
+    let response = server.readiness_check().await;
+
+    assert_eq!(response.status(), 200);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "degraded");
+    assert_eq!(body["checks"]["database"]["status"], "up");
+    assert_eq!(body["checks"]["migrations"]["status"], "up");
+    assert_eq!(body["checks"]["nats"]["status"], "down");
+    assert_eq!(body["checks"]["nats"]["required"], false);
+    assert_eq!(
+        body["checks"]["nats"],
+        serde_json::json!({ "status": "down", "required": false })
+    );
+}
+
+#[cfg(feature = "nats")]
+#[tokio::test]
+#[serial]
+async fn readiness_fails_when_required_nats_is_down() {
+    let server = TestServer::with_config(|config| {
+        config.nats_config.url = Some(unreachable_nats_url());
+        config.nats_config.required = true;
+    })
+    .await;
+
+    let response = server.readiness_check().await;
+
+    assert_eq!(response.status(), 503);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "unhealthy");
+    assert_eq!(body["checks"]["database"]["status"], "up");
+    assert_eq!(body["checks"]["nats"]["status"], "down");
+
+    let metrics = server
+        .client
+        .get(format!("{}/metrics", server.base_url))
+        .send()
+        .await
+        .unwrap()
+        .text()
+        .await
+        .unwrap();
+    assert!(metrics.contains("nats_connected 0"), "broker gauge should report disconnected");
+}
+
+#[cfg(feature = "nats")]
+#[tokio::test]
+#[serial]
+async fn readiness_gives_up_on_a_hung_dependency() {
+    let server = TestServer::with_config(|config| {
+        config.nats_config.url = Some(silent_nats_url());
+        config.nats_config.required = true;
+        config.nats_config.ping_timeout = std::time::Duration::from_secs(30);
+        config.health_check_timeout = std::time::Duration::from_millis(300);
+    })
+    .await;
+
+    let started = std::time::Instant::now();
+    let response = server.readiness_check().await;
+
+    assert!(started.elapsed() < std::time::Duration::from_secs(5), "{:?}", started.elapsed());
+    assert_eq!(response.status(), 503);
+    let body: serde_json::Value = response.json().await.unwrap();
+    assert_eq!(body["status"], "unhealthy");
+    assert_eq!(body["checks"]["database"]["status"], "up");
+    assert_eq!(body["checks"]["nats"]["status"], "down");
+    assert!(body["checks"]["nats"].get("error").is_none());
+}
+
+/// A "NATS server" that accepts connections and never answers
+#[cfg(feature = "nats")]
+fn silent_nats_url() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let mut held = Vec::new();
+        for stream in listener.incoming().flatten() {
+            held.push(stream);
+        }
+    });
+    format!("nats://{}", addr)
+}
+
+/// A loopback address with nothing listening on it
+#[cfg(feature = "nats")]
+fn unreachable_nats_url() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+    format!("nats://{}", addr)
+}
@@ -0,0 +1,92 @@
+/// Integration tests for localized responses
+use crate::common::*;
+use reqwest::{header::HeaderMap, StatusCode};
+use serde_json::{json, Value};
+use serial_test::serial;
+
+async fn login_with_language(server: &TestServer, language: &str) -> (HeaderMap, Value) {
+    let response = server
+        .client
+        .post(format!("{}/api/auth/login", server.base_url))
+        .header("Accept-Language", language)
+        .json(&json!({ "email": unique_email("i18n_nobody"), "password": "wrong-password" }))
+        .send()
+        .await
+        .expect("Failed to send login request");
+    let headers = response.headers().clone();
+    let body: Value = response.json().await.expect("Failed to parse login response");
+    (headers, body)
+}
+
+#[tokio::test]
+#[serial]
+async fn error_messages_follow_accept_language() {
+    let server = TestServer::new().await;
+
+    let (headers, body) = login_with_language(&server, "vi-VN,vi;q=0.9,en;q=0.8").await;
+
+    assert_error(&body);
+    assert_eq!(body["error"], "Thông tin đăng nhập không hợp lệ");
+    assert_eq!(headers["content-language"], "vi");
+}
+
+#[tokio::test]
+#[serial]
+async fn error_messages_fall_back_to_english_for_unsupported_locale() {
+    let server = TestServer::new().await;
+
+    let (headers, body) = login_with_language(&server, "fr-FR").await;
+
+    assert_eq!(body["error"], "Invalid credentials");
+    assert!(headers.get("content-language").is_none());
+}
+
+#[tokio::test]
+#[serial]
+async fn validation_errors_are_translated_per_field() {
+    let server = TestServer::new().await;
+
+    let body: Value = server
+        .client
+        .post(format!("{}/api/auth/register", server.base_url))
+        .header("Accept-Language", "es")
+        .json(&json!({ "email": "not-an-email", "name": "Ana" }))
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+
+    assert_error(&body);
+    assert_eq!(body["error"], "email: Formato de correo electrónico no válido");
+}
+
+#[tokio::test]
+#[serial]
+async fn registration_stores_requested_locale_for_emails() {
+    let server = TestServer::new().await;
+    let email = unique_email("i18n_locale");
+
+    let res = server
+        .client
+        .post(format!("{}/api/auth/register", server.base_url))
+        .header("Accept-Language", "vi")
+        .json(&json!({ "email": email, "name": "Lan" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    assert_eq!(server.get_user_locale(&email).await, "vi");
+
+    let default_email = unique_email("i18n_default");
+    server
+        .client
+        .post(format!("{}/api/auth/register", server.base_url))
+        .json(&json!({ "email": default_email, "name": "Sam" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(server.get_user_locale(&default_email).await, "en");
+}
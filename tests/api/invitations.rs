@@ -0,0 +1,111 @@
+/// Integration tests for admin invitations and invite-only registration
+use crate::common::*;
+use reqwest::StatusCode;
+use serde_json::{json, Value};
+use serial_test::serial;
+
+/// A server with registration closed, and an admin's access token for it
+async fn invite_only_server() -> (TestServer, String) {
+    let server = TestServer::with_config(|config| config.registration_open = false).await;
+    let admin = unique_email("inviter");
+    server.create_admin(&admin, TEST_PASSWORD).await;
+    let token = server.login_user(&admin, TEST_PASSWORD).await;
+    (server, token)
+}
+
+async fn invite(server: &TestServer, token: &str, body: Value) -> (StatusCode, Value) {
+    let res = server
+        .client
+        .post(format!("{}/api/admin/invitations", server.base_url))
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    let status = res.status();
+    (status, res.json().await.unwrap())
+}
+
+/// Register anonymously; a fresh client so no admin cookie rides along
+async fn register_invited(
+    server: &TestServer,
+    email: &str,
+    invite_token: &str,
+) -> (StatusCode, Value) {
+    let res = reqwest::Client::new()
+        .post(format!("{}/api/auth/register", server.base_url))
+        .json(&json!({ "email": email, "name": "Invitee", "invite_token": invite_token }))
+        .send()
+        .await
+        .unwrap();
+    let status = res.status();
+    (status, res.json().await.unwrap())
+}
+
+#[tokio::test]
+#[serial]
+async fn an_invitation_registers_one_user_with_its_role() {
+    let (server, admin_token) = invite_only_server().await;
+
+    let (status, body) = invite(&server, &admin_token, json!({ "role": "editor" })).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["data"]["role"], "editor");
+    let invite_token = body["data"]["token"].as_str().unwrap().to_string();
+
+    let invitee = unique_email("invitee");
+    let (status, _) = register_invited(&server, &invitee, &invite_token).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(server.get_user_role(&invitee).await, "editor");
+
+    // Single use, even for a different address
+    let (status, body) = register_invited(&server, &unique_email("second"), &invite_token).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Invitation is invalid, expired or already used");
+}
+
+#[tokio::test]
+#[serial]
+async fn expired_and_unknown_invitations_are_refused() {
+    let (server, admin_token) = invite_only_server().await;
+    let (_, body) = invite(&server, &admin_token, json!({ "expires_in_secs": 1 })).await;
+    let invite_token = body["data"]["token"].as_str().unwrap().to_string();
+    tokio::time::sleep(std::time::Duration::from_millis(1_100)).await;
+
+    for token in [invite_token.as_str(), "made-up-token"] {
+        let (status, _) = register_invited(&server, &unique_email("late"), token).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", token);
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn a_made_up_invitation_is_refused_before_the_email_is_checked() {
+    let (server, _) = invite_only_server().await;
+    let taken = unique_email("taken");
+    server.create_admin(&taken, TEST_PASSWORD).await;
+
+    // Not 409, which would confirm the address has an account
+    let (status, body) = register_invited(&server, &taken, "made-up-token").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["error"], "Invitation is invalid, expired or already used");
+}
+
+#[tokio::test]
+#[serial]
+async fn only_admins_can_invite_and_only_to_known_roles() {
+    let (server, admin_token) = invite_only_server().await;
+
+    let (status, _) = invite(&server, &admin_token, json!({ "role": "owner" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = invite(&server, &admin_token, json!({})).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(body["data"]["role"].is_null());
+
+    let open = TestServer::new().await;
+    let viewer = unique_email("viewer");
+    open.register_user(&viewer, "Viewer", TEST_PASSWORD).await;
+    let viewer_token = open.login_user(&viewer, TEST_PASSWORD).await;
+    let (status, _) = invite(&open, &viewer_token, json!({})).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -1,4 +1,5 @@
 use crate::common::*;
+use axum_backend::config::{MetricsAuth, REDACTED};
 use reqwest::StatusCode;
 
 #[tokio::test]
@@ -50,3 +51,166 @@ async fn test_system_health_endpoint() {
     let total_mem = json["total_memory"].as_u64().expect("total_memory is not u64");
     assert!(total_mem > 0, "Total memory should be positive");
 }
+
+#[tokio::test]
+async fn metrics_rejects_scrape_without_configured_bearer_token() {
+    let server = TestServer::with_config(|config| {
+        config.metrics_config.auth = Some(MetricsAuth::Bearer("scrape-secret".to_string()));
+    })
+    .await;
+    let url = format!("{}/metrics", server.base_url);
+
+    let missing = server.client.get(&url).send().await.expect("Failed to execute request");
+    assert_eq!(missing.status(), StatusCode::UNAUTHORIZED);
+    assert!(missing.headers().contains_key("www-authenticate"));
+
+    let wrong = server
+        .client
+        .get(&url)
+        .bearer_auth("not-the-secret")
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(wrong.status(), StatusCode::UNAUTHORIZED);
+
+    let ok = server
+        .client
+        .get(&url)
+        .bearer_auth("scrape-secret")
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(ok.status(), StatusCode::OK);
+    assert!(ok.text().await.expect("Failed to get response text").contains("# TYPE"));
+}
+
+#[tokio::test]
+async fn metrics_accepts_configured_basic_credentials() {
+    let server = TestServer::with_config(|config| {
+        config.metrics_config.auth = Some(MetricsAuth::Basic {
+            username: "prometheus".to_string(),
+            password: "s3cret".to_string(),
+        });
+    })
+    .await;
+    let url = format!("{}/metrics", server.base_url);
+
+    let wrong = server
+        .client
+        .get(&url)
+        .basic_auth("prometheus", Some("wrong"))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(wrong.status(), StatusCode::UNAUTHORIZED);
+
+    let ok = server
+        .client
+        .get(&url)
+        .basic_auth("prometheus", Some("s3cret"))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(ok.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn metrics_auth_does_not_affect_other_routes() {
+    let server = TestServer::with_config(|config| {
+        config.metrics_config.auth = Some(MetricsAuth::Bearer("scrape-secret".to_string()));
+    })
+    .await;
+
+    let response = server.health_check().await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn metrics_use_configured_duration_buckets() {
+    let server = TestServer::new().await;
+
+    let _ = server.health_check().await;
+
+    let text = server
+        .client
+        .get(format!("{}/metrics", server.base_url))
+        .send()
+        .await
+        .expect("Failed to execute request")
+        .text()
+        .await
+        .expect("Failed to get response text");
+
+    assert!(
+        text.contains("axum_http_requests_duration_seconds_bucket{"),
+        "duration histogram missing from metrics output"
+    );
+    for bucket in TEST_DURATION_BUCKETS {
+        assert!(text.contains(&format!("le=\"{}\"", bucket)), "bucket {} not rendered", bucket);
+    }
+    assert!(!text.contains("le=\"0.005\""), "default buckets should not be rendered");
+}
+
+#[tokio::test]
+async fn admin_config_dump_shows_settings_but_redacts_secrets() {
+    let mut jwt_secret = String::new();
+    let server = TestServer::with_config(|config| {
+        jwt_secret = config.jwt_secret.clone();
+        config.database_url = config.database_url.replacen("://", "://dump:db-secret@", 1);
+        config.email_config.smtp_password = "smtp-secret".to_string();
+        config.metrics_config.auth = Some(MetricsAuth::Basic {
+            username: "scraper".to_string(),
+            password: "scrape-secret".to_string(),
+        });
+        config.max_page_size = 77;
+    })
+    .await;
+    let admin = unique_email("config_admin");
+    server.create_admin(&admin, TEST_PASSWORD).await;
+    let token = server.login_user(&admin, TEST_PASSWORD).await;
+
+    let response = server
+        .client
+        .get(format!("{}/api/admin/config", server.base_url))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status(), StatusCode::OK);
+    let text = response.text().await.unwrap();
+    let body: serde_json::Value = serde_json::from_str(&text).unwrap();
+    assert_eq!(body["success"], true);
+    let json = &body["data"];
+
+    for secret in [jwt_secret.as_str(), "db-secret", "smtp-secret", "scrape-secret"] {
+        assert!(!text.contains(secret), "{} leaked", secret);
+    }
+    assert_eq!(json["jwt"]["secret"], REDACTED);
+    assert_eq!(json["email"]["smtp_password"], REDACTED);
+    assert_eq!(json["metrics"]["auth"]["basic"]["username"], "scraper");
+    assert!(json["database"]["url"]
+        .as_str()
+        .unwrap()
+        .contains(&format!("dump:{}@", REDACTED)));
+    assert_eq!(json["jwt"]["issuer"], "test-issuer");
+    assert_eq!(json["pagination"]["max_page_size"], 77);
+    assert_eq!(json["sms"]["twilio"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn admin_config_dump_is_admin_only() {
+    let server = TestServer::new().await;
+    let email = unique_email("config_viewer");
+    server.register_user(&email, "Viewer", TEST_PASSWORD).await;
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    let response = server
+        .client
+        .get(format!("{}/api/admin/config", server.base_url))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
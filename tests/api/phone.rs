@@ -0,0 +1,193 @@
+/// Integration tests for phone verification and SMS code delivery
+use crate::common::*;
+use axum::{extract::State, routing::post, Form, Router};
+use axum_backend::config::TwilioConfig;
+use reqwest::StatusCode;
+use serde_json::json;
+use serial_test::serial;
+use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+type Texts = mpsc::UnboundedReceiver<HashMap<String, String>>;
+
+/// Stand-in for Twilio's API that accepts every message and hands over its
+/// form fields
+async fn fake_twilio() -> (TwilioConfig, Texts) {
+    let (tx, rx) = mpsc::unbounded_channel();
+    let app = Router::new()
+        .route(
+            "/*path",
+            post(
+                |State(tx): State<mpsc::UnboundedSender<HashMap<String, String>>>,
+                 Form(form): Form<HashMap<String, String>>| async move {
+                    let _ = tx.send(form);
+                    axum::http::StatusCode::CREATED
+                },
+            ),
+        )
+        .with_state(tx);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let config = TwilioConfig {
+        account_sid: "AC123".to_string(),
+        auth_token: "secret".to_string(),
+        from_number: "+15005550006".to_string(),
+        api_base: format!("http://{}", addr),
+    };
+    (config, rx)
+}
+
+async fn post_json(
+    server: &TestServer,
+    path: &str,
+    token: &str,
+    body: serde_json::Value,
+) -> StatusCode {
+    server
+        .client
+        .post(format!("{}{}", server.base_url, path))
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+/// The code at the end of a texted message
+fn code_in(text: &HashMap<String, String>) -> String {
+    text["Body"].rsplit(' ').next().unwrap().to_string()
+}
+
+#[tokio::test]
+#[serial]
+async fn verified_phone_receives_password_reset_codes() {
+    let (twilio, mut texts) = fake_twilio().await;
+    let server = TestServer::with_config(|config| {
+        config.sms_config.twilio = Some(twilio);
+        // The phone code and the reset code come out of one resend budget
+        config.resend_cooldown = std::time::Duration::ZERO;
+    })
+    .await;
+    let email = unique_email("phone");
+    server.register_user(&email, "Phone", TEST_PASSWORD).await;
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    let status =
+        post_json(&server, "/api/auth/phone", &token, json!({ "phone": "+84 (90) 123-4567" }))
+            .await;
+    assert_eq!(status, StatusCode::OK);
+    let text = texts.recv().await.unwrap();
+    assert_eq!(text["To"], "+84901234567");
+
+    let wrong =
+        post_json(&server, "/api/auth/phone/verify", &token, json!({ "code": "nope-nope" }));
+    assert_eq!(wrong.await, StatusCode::BAD_REQUEST);
+    let verify = json!({ "code": code_in(&text) });
+    assert_eq!(
+        post_json(&server, "/api/auth/phone/verify", &token, verify).await,
+        StatusCode::OK
+    );
+
+    let me: serde_json::Value = server
+        .client
+        .get(format!("{}/api/auth/me", server.base_url))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(me["data"]["phone"], "+84901234567");
+    assert_eq!(me["data"]["phone_verified"], true);
+
+    let res = server
+        .client
+        .post(format!("{}/api/auth/forgot-password", server.base_url))
+        .json(&json!({ "email": email, "channel": "sms" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+    let reset = texts.recv().await.unwrap();
+    assert_eq!(reset["To"], "+84901234567");
+    assert!(reset["Body"].starts_with("Your password reset code is: "));
+}
+
+#[tokio::test]
+#[serial]
+async fn phone_verification_needs_sms_and_a_valid_number() {
+    let server = TestServer::new().await;
+    let email = unique_email("phone_off");
+    server.register_user(&email, "Phone", TEST_PASSWORD).await;
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    let invalid = post_json(&server, "/api/auth/phone", &token, json!({ "phone": "0901234567" }));
+    assert_eq!(invalid.await, StatusCode::BAD_REQUEST);
+    let valid = post_json(&server, "/api/auth/phone", &token, json!({ "phone": "+84901234567" }));
+    assert_eq!(valid.await, StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+#[serial]
+async fn undeliverable_reset_texts_do_not_reveal_provider_errors() {
+    let (twilio, mut texts) = fake_twilio().await;
+    let unreachable = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let dead_base = format!("http://{}", unreachable.local_addr().unwrap());
+    drop(unreachable);
+    let server = TestServer::with_config(|config| {
+        config.sms_config.twilio = Some(TwilioConfig { api_base: dead_base, ..twilio.clone() });
+        config.resend_cooldown = std::time::Duration::ZERO;
+    })
+    .await;
+    let email = unique_email("phone_down");
+    server.register_user(&email, "Phone", TEST_PASSWORD).await;
+    server.set_verified_phone(&email, "+84901234567").await;
+
+    let res = server
+        .client
+        .post(format!("{}/api/auth/forgot-password", server.base_url))
+        .json(&json!({ "email": email, "channel": "sms" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = res.text().await.unwrap();
+    assert!(!body.contains(&twilio.account_sid), "{}", body);
+    assert!(!body.contains("127.0.0.1"), "{}", body);
+    assert!(texts.try_recv().is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn undeliverable_phone_codes_do_not_reveal_provider_errors() {
+    let (twilio, _texts) = fake_twilio().await;
+    let unreachable = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let dead_base = format!("http://{}", unreachable.local_addr().unwrap());
+    drop(unreachable);
+    let server = TestServer::with_config(|config| {
+        config.sms_config.twilio = Some(TwilioConfig { api_base: dead_base, ..twilio.clone() });
+    })
+    .await;
+    let email = unique_email("phone_nosms");
+    server.register_user(&email, "Phone", TEST_PASSWORD).await;
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    let res = server
+        .client
+        .post(format!("{}/api/auth/phone", server.base_url))
+        .bearer_auth(&token)
+        .json(&json!({ "phone": "+84901234567" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+    let body = res.text().await.unwrap();
+    assert!(!body.contains(&twilio.account_sid), "{}", body);
+    assert!(!body.contains("127.0.0.1"), "{}", body);
+}
@@ -0,0 +1,241 @@
+/// Integration tests for role management endpoints
+use crate::common::*;
+use axum_backend::domain::value_objects::UserRole;
+use reqwest::StatusCode;
+use serde_json::json;
+use serial_test::serial;
+use uuid::Uuid;
+
+fn user_id(register_response: &serde_json::Value) -> Uuid {
+    register_response["data"]["user"]["id"]
+        .as_str()
+        .and_then(|id| id.parse().ok())
+        .unwrap()
+}
+
+async fn put_role(server: &TestServer, token: &str, target: Uuid, role: &str) -> StatusCode {
+    server
+        .client
+        .put(format!("{}/api/users/{}/role", server.base_url, target))
+        .bearer_auth(token)
+        .json(&json!({ "role": role }))
+        .send()
+        .await
+        .unwrap()
+        .status()
+}
+
+/// Register a user and promote them to admin before their first request
+async fn register_admin(server: &TestServer, prefix: &str) -> (Uuid, String) {
+    let email = unique_email(prefix);
+    let admin = server.register_user(&email, "Admin", TEST_PASSWORD).await;
+    server.set_user_role(&email, "admin").await;
+    (user_id(&admin), server.login_user(&email, TEST_PASSWORD).await)
+}
+
+#[tokio::test]
+#[serial]
+async fn role_change_writes_audit_entry_with_actor() {
+    let server = TestServer::new().await;
+    let (admin_id, token) = register_admin(&server, "role_admin").await;
+    let target_email = unique_email("role_target");
+    let target = server.register_user(&target_email, "Target", TEST_PASSWORD).await;
+
+    assert_eq!(put_role(&server, &token, user_id(&target), "editor").await, StatusCode::OK);
+
+    let entries = server.audit_entries_for(user_id(&target)).await;
+    assert_eq!(
+        entries,
+        vec![(
+            "user.role_changed".to_string(),
+            Some(admin_id),
+            json!({ "old_role": "viewer", "new_role": "editor" })
+        )]
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn setting_the_current_role_is_not_audited() {
+    let server = TestServer::new().await;
+    let (admin_id, token) = register_admin(&server, "role_same").await;
+
+    assert_eq!(put_role(&server, &token, admin_id, "admin").await, StatusCode::OK);
+
+    assert!(server.audit_entries_for(admin_id).await.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn failed_audit_leaves_the_role_unchanged() {
+    use diesel_async::SimpleAsyncConnection;
+
+    let server = TestServer::new().await;
+    let (_, token) = register_admin(&server, "role_noaudit").await;
+    let target_email = unique_email("role_victim");
+    let target = server.register_user(&target_email, "Target", TEST_PASSWORD).await;
+
+    let mut conn = server.pool.get().await.unwrap();
+    conn.batch_execute(
+        "CREATE FUNCTION reject_audit() RETURNS trigger AS $$ \
+         BEGIN RAISE EXCEPTION 'audit_logs unavailable'; END; $$ LANGUAGE plpgsql; \
+         CREATE TRIGGER reject_audit BEFORE INSERT ON audit_logs \
+         FOR EACH ROW EXECUTE FUNCTION reject_audit();",
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        put_role(&server, &token, user_id(&target), "editor").await,
+        StatusCode::INTERNAL_SERVER_ERROR
+    );
+    assert_eq!(server.get_user_role(&target_email).await, "viewer");
+    assert!(server.audit_entries_for(user_id(&target)).await.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn non_admin_cannot_change_roles() {
+    let server = TestServer::new().await;
+    let email = unique_email("role_viewer");
+    let viewer = server.register_user(&email, "Viewer", TEST_PASSWORD).await;
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    assert_eq!(
+        put_role(&server, &token, user_id(&viewer), "admin").await,
+        StatusCode::FORBIDDEN
+    );
+    assert!(server.audit_entries_for(user_id(&viewer)).await.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn role_change_applies_to_existing_access_token_on_next_request() {
+    assert_role_changes_reach_existing_token(&TestServer::new().await).await;
+}
+
+#[cfg(feature = "moka")]
+#[tokio::test]
+#[serial]
+async fn role_cache_invalidation_works_with_the_moka_backend() {
+    let server = TestServer::with_config(|config| {
+        config.cache_config.backend = axum_backend::config::CacheBackend::Moka;
+    })
+    .await;
+    assert_role_changes_reach_existing_token(&server).await;
+}
+
+async fn assert_role_changes_reach_existing_token(server: &TestServer) {
+    let (_, admin_token) = register_admin(server, "role_granter").await;
+    let email = unique_email("role_promoted");
+    let user = server.register_user(&email, "Promoted", TEST_PASSWORD).await;
+    let user_id = user_id(&user);
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    // Caches the viewer role for this user
+    assert_eq!(put_role(server, &token, user_id, "viewer").await, StatusCode::FORBIDDEN);
+
+    assert_eq!(put_role(server, &admin_token, user_id, "admin").await, StatusCode::OK);
+    assert_eq!(put_role(server, &token, user_id, "admin").await, StatusCode::OK);
+
+    assert_eq!(put_role(server, &admin_token, user_id, "viewer").await, StatusCode::OK);
+    assert_eq!(put_role(server, &token, user_id, "admin").await, StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+#[serial]
+async fn registration_assigns_the_configured_default_role() {
+    let server = TestServer::with_config(|config| {
+        config.default_user_role = UserRole::Editor;
+    })
+    .await;
+    let email = unique_email("role_default");
+
+    server.register_user(&email, "Editor", TEST_PASSWORD).await;
+
+    assert_eq!(server.get_user_role(&email).await, "editor");
+}
+
+#[tokio::test]
+#[serial]
+async fn registration_defaults_to_viewer() {
+    let server = TestServer::new().await;
+    let email = unique_email("role_viewer");
+
+    server.register_user(&email, "Viewer", TEST_PASSWORD).await;
+
+    assert_eq!(server.get_user_role(&email).await, "viewer");
+}
+
+/// A broker that acknowledges every connection and forwards each published
+/// `(subject, payload)`
+#[cfg(feature = "nats")]
+fn recording_nats() -> (String, tokio::sync::mpsc::UnboundedReceiver<(String, String)>) {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let url = format!("nats://{}", listener.local_addr().unwrap());
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let listener = tokio::net::TcpListener::from_std(listener).unwrap();
+        while let Ok((socket, _)) = listener.accept().await {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let (reader, mut writer) = socket.into_split();
+                let mut reader = BufReader::new(reader);
+                writer.write_all(b"INFO {}\r\n").await.unwrap();
+                let mut line = String::new();
+                while reader.read_line(&mut line).await.unwrap_or(0) > 0 {
+                    let parts: Vec<&str> = line.split_whitespace().collect();
+                    match parts.as_slice() {
+                        ["PUB", subject, len] => {
+                            let mut payload = vec![0u8; len.parse::<usize>().unwrap() + 2];
+                            reader.read_exact(&mut payload).await.unwrap();
+                            payload.truncate(payload.len() - 2);
+                            let payload = String::from_utf8(payload).unwrap();
+                            let _ = tx.send((subject.to_string(), payload));
+                        },
+                        ["PING"] => writer.write_all(b"PONG\r\n").await.unwrap(),
+                        _ => {},
+                    }
+                    line.clear();
+                }
+            });
+        }
+    });
+    (url, rx)
+}
+
+#[cfg(feature = "nats")]
+#[tokio::test]
+#[serial]
+async fn role_change_event_continues_the_requests_trace() {
+    let (url, mut published) = recording_nats();
+    let server = TestServer::with_config(|config| config.nats_config.url = Some(url)).await;
+    let (_, token) = register_admin(&server, "trace_admin").await;
+    let target = server.register_user(&unique_email("trace_tgt"), "Target", TEST_PASSWORD).await;
+    let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    let res = server
+        .client
+        .put(format!("{}/api/users/{}/role", server.base_url, user_id(&target)))
+        .bearer_auth(&token)
+        .header("traceparent", traceparent)
+        .json(&json!({ "role": "editor" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let (subject, payload) = published.recv().await.unwrap();
+    assert_eq!(subject, "events.v2.user.role_changed");
+    let event: serde_json::Value = serde_json::from_str(&payload).unwrap();
+    assert_eq!(event["new_role"], "editor");
+    // Same trace, with the server's span as the parent
+    let sent = event["traceparent"].as_str().unwrap();
+    let fields: Vec<&str> = sent.split('-').collect();
+    assert_eq!(fields[..2], ["00", "4bf92f3577b34da6a3ce929d0e0e4736"]);
+    assert_ne!(fields[2], "00f067aa0ba902b7");
+    assert_eq!(fields[3], "01");
+}
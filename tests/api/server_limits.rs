@@ -0,0 +1,196 @@
+use crate::common::*;
+use reqwest::StatusCode;
+use serial_test::serial;
+#[cfg(feature = "nats")]
+use std::time::Duration;
+
+/// A "NATS server" that accepts connections and never answers, so each
+/// readiness probe holds its request slot until the ping times out
+#[cfg(feature = "nats")]
+fn silent_nats_url() -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        let mut held = Vec::new();
+        for stream in listener.incoming().flatten() {
+            held.push(stream);
+        }
+    });
+    format!("nats://{}", addr)
+}
+
+#[cfg(feature = "nats")]
+#[tokio::test]
+#[serial]
+async fn requests_beyond_concurrency_limit_get_503() {
+    let server = TestServer::with_config(|config| {
+        config.max_concurrent_requests = 1;
+        config.nats_config.url = Some(silent_nats_url());
+        config.nats_config.ping_timeout = Duration::from_secs(1);
+    })
+    .await;
+
+    let slow = {
+        let client = reqwest::Client::new();
+        let url = format!("{}/health/ready", server.base_url);
+        tokio::spawn(async move { client.get(url).send().await.unwrap().status() })
+    };
+    tokio::time::sleep(Duration::from_millis(200)).await;
+
+    let shed = server.health_check().await;
+    assert_eq!(shed.status(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(shed.headers()["retry-after"], "1");
+    let body: serde_json::Value = shed.json().await.unwrap();
+    assert_eq!(body["status"], 503);
+    assert_eq!(body["retry_after_seconds"], 1);
+
+    // The in-flight request was served, and capacity frees up afterwards
+    assert_ne!(slow.await.unwrap(), StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(server.health_check().await.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+#[serial]
+async fn rate_limited_response_reports_retry_after_in_header_and_body() {
+    let server = TestServer::with_config(|config| {
+        config.rate_limit_per_second = 60;
+        config.rate_limit_burst_size = 1;
+    })
+    .await;
+    let login = || {
+        server
+            .client
+            .post(format!("{}/api/auth/login", server.base_url))
+            .json(&serde_json::json!({ "email": "nobody@example.com", "password": "wrong" }))
+            .send()
+    };
+
+    login().await.unwrap();
+    let throttled = login().await.unwrap();
+
+    assert_eq!(throttled.status(), StatusCode::TOO_MANY_REQUESTS);
+    let header: u64 = throttled.headers()["retry-after"].to_str().unwrap().parse().unwrap();
+    let body: serde_json::Value = throttled.json().await.unwrap();
+    assert_eq!(body["retry_after_seconds"], header);
+    assert!(header > 0);
+    assert_eq!(body["success"], false);
+}
+
+#[tokio::test]
+#[serial]
+async fn rate_limit_headers_count_down_the_remaining_quota() {
+    let server = TestServer::with_config(|config| {
+        config.rate_limit_per_second = 60;
+        config.rate_limit_burst_size = 3;
+    })
+    .await;
+    let login = || {
+        server
+            .client
+            .post(format!("{}/api/auth/login", server.base_url))
+            .json(&serde_json::json!({ "email": "nobody@example.com", "password": "wrong" }))
+            .send()
+    };
+    let header = |res: &reqwest::Response, name: &str| -> u64 {
+        res.headers()[name].to_str().unwrap().parse().unwrap()
+    };
+
+    let mut remaining = Vec::new();
+    for _ in 0..3 {
+        let res = login().await.unwrap();
+        assert_ne!(res.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(header(&res, "x-ratelimit-limit"), 3);
+        assert!(header(&res, "x-ratelimit-reset") <= 180);
+        remaining.push(header(&res, "x-ratelimit-remaining"));
+    }
+    assert_eq!(remaining, [2, 1, 0]);
+
+    let throttled = login().await.unwrap();
+    assert_eq!(throttled.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(header(&throttled, "x-ratelimit-remaining"), 0);
+    assert!(header(&throttled, "x-ratelimit-reset") >= header(&throttled, "retry-after"));
+}
+
+#[tokio::test]
+#[serial]
+async fn sliding_window_algorithm_can_be_selected() {
+    let server = TestServer::with_config(|config| {
+        config.rate_limit_per_second = 60;
+        config.rate_limit_burst_size = 2;
+        config.rate_limit_algorithm =
+            axum_backend::shared::rate_limiter::RateLimitAlgorithm::SlidingWindowLog;
+    })
+    .await;
+    let login = || {
+        server
+            .client
+            .post(format!("{}/api/auth/login", server.base_url))
+            .json(&serde_json::json!({ "email": "nobody@example.com", "password": "wrong" }))
+            .send()
+    };
+
+    assert_ne!(login().await.unwrap().status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_ne!(login().await.unwrap().status(), StatusCode::TOO_MANY_REQUESTS);
+    let throttled = login().await.unwrap();
+
+    assert_eq!(throttled.status(), StatusCode::TOO_MANY_REQUESTS);
+    // Two requests per two-minute window, counted from the first
+    let header: u64 = throttled.headers()["retry-after"].to_str().unwrap().parse().unwrap();
+    assert!((119..=120).contains(&header), "{}", header);
+}
+
+#[tokio::test]
+#[serial]
+async fn simultaneous_requests_admit_exactly_the_burst() {
+    let server = TestServer::with_config(|config| {
+        config.rate_limit_per_second = 60;
+        config.rate_limit_burst_size = 5;
+    })
+    .await;
+    let logins = (0..30).map(|_| {
+        server
+            .client
+            .post(format!("{}/api/auth/login", server.base_url))
+            .json(&serde_json::json!({ "email": "nobody@example.com", "password": "wrong" }))
+            .send()
+    });
+
+    let statuses = futures::future::join_all(logins).await;
+
+    let admitted = statuses
+        .into_iter()
+        .filter(|res| res.as_ref().unwrap().status() != StatusCode::TOO_MANY_REQUESTS)
+        .count();
+    assert_eq!(admitted, 5);
+}
+
+#[tokio::test]
+#[serial]
+async fn requests_with_too_many_or_too_large_headers_get_431() {
+    let server = TestServer::with_config(|config| {
+        config.max_request_headers = 20;
+        config.max_request_header_bytes = 4096;
+    })
+    .await;
+    let url = format!("{}/health", server.base_url);
+    let client = reqwest::Client::new();
+
+    let mut many = reqwest::header::HeaderMap::new();
+    for i in 0..30 {
+        many.insert(
+            reqwest::header::HeaderName::try_from(format!("x-filler-{}", i)).unwrap(),
+            "1".parse().unwrap(),
+        );
+    }
+    let res = client.get(&url).headers(many).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["status"], 431);
+
+    let res = client.get(&url).header("x-filler", "a".repeat(5000)).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::REQUEST_HEADER_FIELDS_TOO_LARGE);
+
+    // Ordinary requests stay within both limits
+    let res = client.get(&url).header("x-filler", "a".repeat(1000)).send().await.unwrap();
+    assert_eq!(res.status(), StatusCode::OK);
+}
@@ -0,0 +1,70 @@
+/// Integration tests for the per-request transaction middleware
+use crate::common::*;
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    middleware,
+    routing::post,
+    Router,
+};
+use axum_backend::{
+    domain::{entities::AuditEntry, repositories::AuditRepository},
+    infrastructure::database::{repositories::AuditRepositoryImpl, DbPool},
+    presentation::middleware::{transaction_middleware, Tx},
+};
+use serde_json::json;
+use serial_test::serial;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+/// Records an audit entry against the target in the path through the
+/// request's transaction, then answers with `status`
+fn audit_then(status: StatusCode) -> Router<DbPool> {
+    Router::new().route(
+        "/:target",
+        post(
+            move |Tx(tx): Tx, axum::extract::Path(target): axum::extract::Path<Uuid>| async move {
+                let entry = AuditEntry::new(None, "tx.probe", Some(target), json!({}));
+                AuditRepositoryImpl::in_transaction(tx).record(&entry).await.unwrap();
+                status
+            },
+        ),
+    )
+}
+
+fn probe_app(pool: &DbPool) -> Router {
+    Router::new()
+        .nest("/ok", audit_then(StatusCode::CREATED))
+        .nest("/fail", audit_then(StatusCode::UNPROCESSABLE_ENTITY))
+        .layer(middleware::from_fn_with_state(pool.clone(), transaction_middleware))
+        .with_state(pool.clone())
+}
+
+async fn post_to(app: Router, uri: String) -> StatusCode {
+    let req = Request::post(uri).body(Body::empty()).unwrap();
+    app.oneshot(req).await.unwrap().status()
+}
+
+#[tokio::test]
+#[serial]
+async fn writes_are_rolled_back_when_the_handler_fails() {
+    let server = TestServer::new().await;
+    let target = Uuid::new_v4();
+
+    let status = post_to(probe_app(&server.pool), format!("/fail/{}", target)).await;
+
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(server.audit_entries_for(target).await.is_empty());
+}
+
+#[tokio::test]
+#[serial]
+async fn writes_are_committed_when_the_handler_succeeds() {
+    let server = TestServer::new().await;
+    let target = Uuid::new_v4();
+
+    let status = post_to(probe_app(&server.pool), format!("/ok/{}", target)).await;
+
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(server.audit_entries_for(target).await.len(), 1);
+}
@@ -0,0 +1,583 @@
+/// Integration tests for user management endpoints
+use crate::common::*;
+use axum::response::IntoResponse;
+use axum_backend::{
+    domain::{entities::User, repositories::user::RepositoryError, Email, UserRepository},
+    infrastructure::database::repositories::UserRepositoryImpl,
+    shared::AppError,
+};
+use reqwest::StatusCode;
+use serde_json::json;
+use serial_test::serial;
+
+#[tokio::test]
+#[serial]
+async fn creating_a_user_with_a_taken_email_is_a_conflict() {
+    let server = TestServer::new().await;
+    let creator = unique_email("dup_creator");
+    server.register_user(&creator, "Creator", TEST_PASSWORD).await;
+    let token = server.login_user(&creator, TEST_PASSWORD).await;
+    let email = unique_email("dup_created");
+
+    let mut statuses = Vec::new();
+    for _ in 0..2 {
+        let res = server
+            .client
+            .post(format!("{}/api/users", server.base_url))
+            .bearer_auth(&token)
+            .json(&json!({ "email": email, "name": "Duplicate" }))
+            .send()
+            .await
+            .unwrap();
+        statuses.push(res.status());
+    }
+
+    assert_eq!(statuses, vec![StatusCode::CREATED, StatusCode::CONFLICT]);
+}
+
+#[tokio::test]
+#[serial]
+async fn created_user_location_points_at_the_fetchable_user() {
+    let server = TestServer::new().await;
+    let creator = unique_email("loc_creator");
+    server.register_user(&creator, "Creator", TEST_PASSWORD).await;
+    let token = server.login_user(&creator, TEST_PASSWORD).await;
+
+    let res = server
+        .client
+        .post(format!("{}/api/users", server.base_url))
+        .bearer_auth(&token)
+        .json(&json!({ "email": unique_email("loc_created"), "name": "Located" }))
+        .send()
+        .await
+        .unwrap();
+
+    assert_eq!(res.status(), StatusCode::CREATED);
+    let location = res.headers()[reqwest::header::LOCATION].to_str().unwrap().to_string();
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(location, format!("/api/users/{}", body["data"]["id"].as_str().unwrap()));
+
+    let fetched = server
+        .client
+        .get(format!("{}{}", server.base_url, location))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(fetched.status(), StatusCode::OK);
+    let fetched: serde_json::Value = fetched.json().await.unwrap();
+    assert_eq!(fetched["data"], body["data"]);
+}
+
+#[tokio::test]
+#[serial]
+async fn prefer_return_minimal_skips_the_body_on_create_and_update() {
+    let server = TestServer::new().await;
+    let creator = unique_email("minimal_creator");
+    server.register_user(&creator, "Creator", TEST_PASSWORD).await;
+    let token = server.login_user(&creator, TEST_PASSWORD).await;
+
+    let created = server
+        .client
+        .post(format!("{}/api/users", server.base_url))
+        .bearer_auth(&token)
+        .header("Prefer", "return=minimal")
+        .json(&json!({ "email": unique_email("minimal_created"), "name": "Minimal" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(created.status(), StatusCode::NO_CONTENT);
+    assert_eq!(created.headers()["preference-applied"], "return=minimal");
+    let location = created.headers()[reqwest::header::LOCATION].to_str().unwrap().to_string();
+    assert!(created.text().await.unwrap().is_empty());
+
+    let updated = server
+        .client
+        .patch(format!("{}{}", server.base_url, location))
+        .bearer_auth(&token)
+        .header("Prefer", "return=minimal")
+        .json(&json!({ "name": "Renamed" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(updated.status(), StatusCode::NO_CONTENT);
+    assert_eq!(updated.headers()[reqwest::header::LOCATION], location.as_str());
+    assert!(updated.text().await.unwrap().is_empty());
+
+    let fetched: serde_json::Value = server
+        .client
+        .get(format!("{}{}", server.base_url, location))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap()
+        .json()
+        .await
+        .unwrap();
+    assert_eq!(fetched["data"]["name"], "Renamed");
+}
+
+#[tokio::test]
+#[serial]
+async fn prefer_return_representation_keeps_the_full_body() {
+    let server = TestServer::new().await;
+    let creator = unique_email("repr_creator");
+    server.register_user(&creator, "Creator", TEST_PASSWORD).await;
+    let token = server.login_user(&creator, TEST_PASSWORD).await;
+
+    let created = server
+        .client
+        .post(format!("{}/api/users", server.base_url))
+        .bearer_auth(&token)
+        .header("Prefer", "return=representation")
+        .json(&json!({ "email": unique_email("repr_created"), "name": "Full" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(created.status(), StatusCode::CREATED);
+    assert!(created.headers().get("preference-applied").is_none());
+    let body: serde_json::Value = created.json().await.unwrap();
+    assert_eq!(body["data"]["name"], "Full");
+
+    let updated = server
+        .client
+        .patch(format!(
+            "{}/api/users/{}",
+            server.base_url,
+            body["data"]["id"].as_str().unwrap()
+        ))
+        .bearer_auth(&token)
+        .json(&json!({ "name": "Still Full" }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(updated.status(), StatusCode::OK);
+    let body: serde_json::Value = updated.json().await.unwrap();
+    assert_eq!(body["data"]["name"], "Still Full");
+}
+
+#[tokio::test]
+#[serial]
+async fn unique_violation_from_database_maps_to_conflict() {
+    let server = TestServer::new().await;
+    let repo = UserRepositoryImpl::new(server.pool.clone());
+    let email = Email::parse(unique_email("dup_repo")).unwrap();
+
+    repo.save(&User::new(email.clone(), "First".to_string()).unwrap())
+        .await
+        .unwrap();
+    // A different id with the same email skips the upsert and hits the unique index
+    let err = repo.save(&User::new(email, "Second".to_string()).unwrap()).await.unwrap_err();
+
+    assert!(matches!(err, RepositoryError::DuplicateEmail(_)), "{:?}", err);
+    assert_eq!(AppError::from(err).into_response().status().as_u16(), 409);
+}
+
+async fn patch_user(
+    server: &TestServer,
+    token: &str,
+    id: &str,
+    body: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    let res = server
+        .client
+        .patch(format!("{}/api/users/{}", server.base_url, id))
+        .bearer_auth(token)
+        .json(&body)
+        .send()
+        .await
+        .unwrap();
+    (res.status(), res.json().await.unwrap())
+}
+
+#[tokio::test]
+#[serial]
+async fn patch_changes_only_the_fields_sent() {
+    let server = TestServer::new().await;
+    let email = unique_email("patch_user");
+    let user = server.register_user(&email, "Original Name", TEST_PASSWORD).await;
+    let id = user["data"]["user"]["id"].as_str().unwrap().to_string();
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    let (status, body) = patch_user(&server, &token, &id, json!({ "locale": "vi" })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["name"], "Original Name");
+    assert_eq!(body["data"]["email"], email);
+    assert_eq!(server.get_user_locale(&email).await, "vi");
+
+    let (status, body) = patch_user(&server, &token, &id, json!({ "name": "Renamed" })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["name"], "Renamed");
+    assert_eq!(server.get_user_locale(&email).await, "vi", "locale must be untouched");
+}
+
+#[tokio::test]
+#[serial]
+async fn patch_null_clears_only_where_allowed() {
+    let server = TestServer::new().await;
+    let email = unique_email("patch_null");
+    let user = server.register_user(&email, "Keeps Name", TEST_PASSWORD).await;
+    let id = user["data"]["user"]["id"].as_str().unwrap().to_string();
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+    patch_user(&server, &token, &id, json!({ "locale": "es" })).await;
+
+    let (status, _) = patch_user(&server, &token, &id, json!({ "name": null })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = patch_user(&server, &token, &id, json!({ "locale": null })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"]["name"], "Keeps Name");
+    assert_eq!(server.get_user_locale(&email).await, "en");
+}
+
+async fn export_csv(server: &TestServer, token: &str, query: &str) -> reqwest::Response {
+    server
+        .client
+        .get(format!("{}/api/admin/users/export.csv{}", server.base_url, query))
+        .bearer_auth(token)
+        .send()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+#[serial]
+async fn admin_export_streams_all_users_as_csv_without_credentials() {
+    let server = TestServer::new().await;
+    let admin = unique_email("export_admin");
+    server.register_user(&admin, "Admin", TEST_PASSWORD).await;
+    server.set_user_role(&admin, "admin").await;
+    for i in 0..5 {
+        seed_user(&server.pool, &unique_email(&format!("export_{}", i)), "Row", TEST_PASSWORD)
+            .await;
+    }
+    let token = server.login_user(&admin, TEST_PASSWORD).await;
+
+    let response = export_csv(&server, &token, "").await;
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["content-type"], "text/csv; charset=utf-8");
+    let csv = response.text().await.unwrap();
+    let mut reader = csv::Reader::from_reader(csv.as_bytes());
+    let headers: Vec<String> = reader.headers().unwrap().iter().map(String::from).collect();
+    assert_eq!(
+        headers,
+        [
+            "id",
+            "email",
+            "name",
+            "role",
+            "is_active",
+            "email_verified",
+            "locale",
+            "last_login",
+            "created_at",
+            "updated_at"
+        ]
+    );
+    assert_eq!(reader.records().count(), 6);
+    assert!(!csv.contains("password") && !csv.contains("$argon2"));
+}
+
+#[tokio::test]
+#[serial]
+async fn export_applies_role_and_active_filters() {
+    let server = TestServer::new().await;
+    let admin = unique_email("export_filter");
+    server.register_user(&admin, "Admin", TEST_PASSWORD).await;
+    server.set_user_role(&admin, "admin").await;
+    seed_user(&server.pool, &unique_email("export_viewer"), "Viewer", TEST_PASSWORD).await;
+    let token = server.login_user(&admin, TEST_PASSWORD).await;
+
+    let admins = export_csv(&server, &token, "?role=admin&active=true")
+        .await
+        .text()
+        .await
+        .unwrap();
+    let inactive = export_csv(&server, &token, "?active=false").await.text().await.unwrap();
+
+    assert_eq!(admins.lines().count(), 2);
+    assert!(admins.contains(&admin));
+    assert_eq!(inactive.lines().count(), 1, "header only");
+    assert_eq!(
+        export_csv(&server, &token, "?role=owner").await.status(),
+        StatusCode::BAD_REQUEST
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn export_is_admin_only() {
+    let server = TestServer::new().await;
+    let email = unique_email("export_viewer");
+    server.register_user(&email, "Viewer", TEST_PASSWORD).await;
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    assert_eq!(export_csv(&server, &token, "").await.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+#[serial]
+async fn avatar_falls_back_to_a_stable_identicon() {
+    let server = TestServer::new().await;
+    let email = unique_email("avatar");
+    let user = server.register_user(&email, "Avatar User", TEST_PASSWORD).await;
+    let id = user["data"]["user"]["id"].as_str().unwrap().to_string();
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    let mut bodies = Vec::new();
+    for _ in 0..2 {
+        let res = server
+            .client
+            .get(format!("{}/api/users/{}/avatar", server.base_url, id))
+            .bearer_auth(&token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()["content-type"], "image/svg+xml");
+        bodies.push(res.text().await.unwrap());
+    }
+
+    assert_eq!(bodies[0], bodies[1]);
+    assert!(bodies[0].starts_with("<svg"));
+}
+
+async fn deactivate(
+    server: &TestServer,
+    token: &str,
+    user_ids: Vec<String>,
+) -> (StatusCode, serde_json::Value) {
+    let res = server
+        .client
+        .post(format!("{}/api/admin/users/deactivate", server.base_url))
+        .bearer_auth(token)
+        .json(&json!({ "user_ids": user_ids }))
+        .send()
+        .await
+        .unwrap();
+    (res.status(), res.json().await.unwrap())
+}
+
+#[tokio::test]
+#[serial]
+async fn bulk_deactivation_flips_is_active_and_revokes_sessions() {
+    let server = TestServer::new().await;
+    let admin = unique_email("bulk_admin");
+    server.register_user(&admin, "Admin", TEST_PASSWORD).await;
+    server.set_user_role(&admin, "admin").await;
+    let token = server.login_user(&admin, TEST_PASSWORD).await;
+
+    let mut targets = Vec::new();
+    for i in 0..3 {
+        let email = unique_email(&format!("bulk_{}", i));
+        let user = server.register_user(&email, "Target", TEST_PASSWORD).await;
+        let id = user["data"]["user"]["id"].as_str().unwrap().to_string();
+        let (_, login) = server.login_response(&email, TEST_PASSWORD).await;
+        let refresh = login["data"]["refresh_token"].as_str().unwrap().to_string();
+        targets.push((email, id, refresh));
+    }
+    let unknown = uuid::Uuid::new_v4().to_string();
+
+    let mut ids: Vec<String> = targets.iter().map(|(_, id, _)| id.clone()).collect();
+    ids.push(unknown.clone());
+    let (status, body) = deactivate(&server, &token, ids).await;
+
+    assert_eq!(status, StatusCode::OK, "{}", body);
+    assert_eq!(body["data"]["deactivated"].as_array().unwrap().len(), 3);
+    assert_eq!(body["data"]["not_found"], json!([unknown]));
+    for (email, id, refresh) in &targets {
+        assert!(!server.is_user_active(email).await);
+        assert!(server.is_refresh_token_revoked(refresh).await);
+        let audit = server.audit_entries_for(id.parse().unwrap()).await;
+        assert!(audit.iter().any(|(action, _, _)| action == "user.deactivated"), "{:?}", audit);
+    }
+    assert!(server.is_user_active(&admin).await);
+}
+
+#[tokio::test]
+#[serial]
+async fn deactivated_users_cannot_refresh_their_sessions() {
+    let server = TestServer::new().await;
+    let admin = unique_email("deact_admin");
+    server.register_user(&admin, "Admin", TEST_PASSWORD).await;
+    server.set_user_role(&admin, "admin").await;
+    let token = server.login_user(&admin, TEST_PASSWORD).await;
+    let email = unique_email("deact_user");
+    let user = server.register_user(&email, "Target", TEST_PASSWORD).await;
+    let id = user["data"]["user"]["id"].as_str().unwrap().to_string();
+    let (_, login) = server.login_response(&email, TEST_PASSWORD).await;
+    let refresh = login["data"]["refresh_token"].as_str().unwrap().to_string();
+
+    let (status, _) = deactivate(&server, &token, vec![id]).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let res = server
+        .client
+        .post(format!("{}/api/auth/refresh", server.base_url))
+        .json(&json!({ "refresh_token": refresh }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::UNAUTHORIZED);
+    let (status, _) = server.login_response(&email, TEST_PASSWORD).await;
+    assert_ne!(status, StatusCode::OK, "a deactivated user cannot log back in either");
+}
+
+#[tokio::test]
+#[serial]
+async fn bulk_deactivation_rejects_unsafe_batches() {
+    let server = TestServer::new().await;
+    let admin = unique_email("bulk_guard");
+    let admin_user = server.register_user(&admin, "Admin", TEST_PASSWORD).await;
+    let admin_id = admin_user["data"]["user"]["id"].as_str().unwrap().to_string();
+    server.set_user_role(&admin, "admin").await;
+    let token = server.login_user(&admin, TEST_PASSWORD).await;
+
+    let oversized = (0..101).map(|_| uuid::Uuid::new_v4().to_string()).collect();
+    let (status, _) = deactivate(&server, &token, oversized).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = deactivate(&server, &token, vec![admin_id]).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(server.is_user_active(&admin).await);
+
+    let viewer = unique_email("bulk_viewer");
+    server.register_user(&viewer, "Viewer", TEST_PASSWORD).await;
+    let viewer_token = server.login_user(&viewer, TEST_PASSWORD).await;
+    let res = server
+        .client
+        .post(format!("{}/api/admin/users/deactivate", server.base_url))
+        .bearer_auth(&viewer_token)
+        .json(&json!({ "user_ids": [] }))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::FORBIDDEN);
+}
+
+/// CSV in the temp dir for a server's import endpoint to read, via
+/// `IMPORT_CSV_PATH`; removed on drop
+struct ImportFile(std::path::PathBuf);
+
+impl ImportFile {
+    fn write(csv: &str) -> Self {
+        let path = std::env::temp_dir().join(format!("import_{}.csv", uuid::Uuid::new_v4()));
+        std::fs::write(&path, csv).unwrap();
+        Self(path)
+    }
+}
+
+impl Drop for ImportFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn background_import_status_can_be_polled_to_completion() {
+    let emails: Vec<_> = (0..3).map(|i| unique_email(&format!("imp_bg{}", i))).collect();
+    let file = ImportFile::write(
+        &emails.iter().fold("email,name,password\n".to_string(), |csv, email| {
+            csv + &format!("{},Imported,secret123\n", email)
+        }),
+    );
+    let server = TestServer::with_config(|config| config.import_csv_path = file.0.clone()).await;
+    let admin = unique_email("imp_admin");
+    server.register_user(&admin, "Importer", TEST_PASSWORD).await;
+    let token = server.login_user(&admin, TEST_PASSWORD).await;
+
+    let res = server
+        .client
+        .post(format!("{}/api/users/import?background=true", server.base_url))
+        .bearer_auth(&token)
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(res.status(), StatusCode::ACCEPTED);
+    let location = res.headers()[reqwest::header::LOCATION].to_str().unwrap().to_string();
+    let body: serde_json::Value = res.json().await.unwrap();
+    assert_eq!(body["data"]["total"], 3);
+    assert_eq!(
+        location,
+        format!("/api/users/import/{}/status", body["data"]["job_id"].as_str().unwrap())
+    );
+
+    let mut job = serde_json::Value::Null;
+    for _ in 0..100 {
+        job = server
+            .client
+            .get(format!("{}{}", server.base_url, location))
+            .bearer_auth(&token)
+            .send()
+            .await
+            .unwrap()
+            .json::<serde_json::Value>()
+            .await
+            .unwrap()["data"]
+            .clone();
+        if job["state"] != "running" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+
+    assert_eq!(job["state"], "completed", "{}", job);
+    assert_eq!((job["processed"].clone(), job["total"].clone()), (json!(3), json!(3)));
+    let repo = UserRepositoryImpl::new(server.pool.clone());
+    for email in &emails {
+        let email = Email::parse(email).unwrap();
+        assert!(
+            repo.find_by_email(&email).await.unwrap().is_some(),
+            "{} was not imported",
+            email
+        );
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn unknown_import_jobs_are_not_found() {
+    let server = TestServer::new().await;
+    let email = unique_email("imp_unknown");
+    server.register_user(&email, "Importer", TEST_PASSWORD).await;
+    let token = server.login_user(&email, TEST_PASSWORD).await;
+
+    for job_id in [uuid::Uuid::new_v4().to_string(), "not-a-job".to_string()] {
+        let res = server
+            .client
+            .get(format!("{}/api/users/import/{}/status", server.base_url, job_id))
+            .bearer_auth(&token)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::NOT_FOUND, "{}", job_id);
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn last_login_advances_with_each_login() {
+    let server = TestServer::new().await;
+    let email = unique_email("last_login");
+    let user = server.register_user(&email, "Returning", TEST_PASSWORD).await;
+    let id = user["data"]["user"]["id"].as_str().unwrap().to_string();
+
+    let fetch_last_login = |token: String| {
+        let url = format!("{}/api/users/{}", server.base_url, id);
+        let client = server.client.clone();
+        async move {
+            let body: serde_json::Value =
+                client.get(url).bearer_auth(token).send().await.unwrap().json().await.unwrap();
+            let last_login = body["data"]["last_login"].as_str().unwrap().to_string();
+            chrono::DateTime::parse_from_rfc3339(&last_login).unwrap()
+        }
+    };
+
+    let first = fetch_last_login(server.login_user(&email, TEST_PASSWORD).await).await;
+    tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    let second = fetch_last_login(server.login_user(&email, TEST_PASSWORD).await).await;
+
+    assert!(second > first, "{} should be after {}", second, first);
+}
@@ -2,8 +2,19 @@ mod common;
 
 mod api {
     pub mod auth;
+    pub mod cache_control;
+    pub mod client_ip;
     pub mod cookie_auth;
+    pub mod docs;
+    pub mod features;
     pub mod health;
+    pub mod i18n;
+    pub mod invitations;
     pub mod monitoring;
+    pub mod phone;
     pub mod preflight;
+    pub mod roles;
+    pub mod server_limits;
+    pub mod transactions;
+    pub mod users;
 }
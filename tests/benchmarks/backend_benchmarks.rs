@@ -159,7 +159,8 @@ fn bench_core_jwt_creation(c: &mut Criterion) {
         604800,
         "benchmark-issuer".to_string(),
         "benchmark-audience".to_string(),
-    );
+    )
+    .unwrap();
     let mut group = c.benchmark_group("core_jwt_operations");
     group.measurement_time(Duration::from_secs(5));
 
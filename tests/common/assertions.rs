@@ -4,9 +4,8 @@ use serde_json::Value;
 
 /// Assert response is successful
 pub fn assert_success(response: &Value) {
-    assert_eq!(
+    assert!(
         response["success"].as_bool().unwrap_or(false),
-        true,
         "Response was not successful: {:?}",
         response
     );
@@ -14,9 +13,8 @@ pub fn assert_success(response: &Value) {
 
 /// Assert response has error
 pub fn assert_error(response: &Value) {
-    assert_eq!(
-        response["success"].as_bool().unwrap_or(true),
-        false,
+    assert!(
+        !response["success"].as_bool().unwrap_or(true),
         "Response should have failed: {:?}",
         response
     );
@@ -0,0 +1,50 @@
+#![allow(dead_code)]
+
+use axum_backend::config::DatabaseConfig;
+use axum_backend::infrastructure::database::{
+    connection::{create_pool, run_migrations},
+    DbPool,
+};
+use diesel_async::RunQueryDsl;
+
+use crate::common::mock::MockPostgres;
+
+/// Migrated ephemeral database for exercising repositories directly,
+/// without a router or HTTP client in the way. Dropped with the value.
+pub struct TestDb {
+    pub pool: DbPool,
+    pub url: String,
+    _mock_db: MockPostgres,
+}
+
+impl TestDb {
+    pub async fn new() -> Self {
+        dotenvy::dotenv().ok();
+
+        let mock_db = MockPostgres::new().await;
+        let url = mock_db.connection_string.clone();
+        let db_config = DatabaseConfig {
+            max_connections: 5,
+            min_connections: 1,
+            connect_timeout: std::time::Duration::from_secs(30),
+            idle_timeout: std::time::Duration::from_secs(600),
+            max_lifetime: std::time::Duration::from_secs(1800),
+        };
+        let pool = create_pool(&db_config, &url)
+            .await
+            .expect("Failed to create test database pool");
+
+        {
+            let mut conn = pool.get().await.expect("Failed to get connection for setup");
+            let _ = diesel::sql_query("CREATE EXTENSION IF NOT EXISTS \"uuid-ossp\";")
+                .execute(&mut conn)
+                .await;
+            let _ = diesel::sql_query("CREATE EXTENSION IF NOT EXISTS \"pgcrypto\";")
+                .execute(&mut conn)
+                .await;
+        }
+        run_migrations(&url).await.expect("Failed to run migrations");
+
+        Self { pool, url, _mock_db: mock_db }
+    }
+}
@@ -1,6 +1,10 @@
 #![allow(dead_code)]
 
-use axum_backend::infrastructure::database::DbPool;
+use axum_backend::{
+    domain::{entities::User, Email, UserRepository},
+    infrastructure::database::{repositories::UserRepositoryImpl, DbPool},
+    shared::utils::password::PasswordManager,
+};
 
 /// Generate a unique test email
 pub fn unique_email(prefix: &str) -> String {
@@ -20,6 +24,27 @@ pub fn unique_name(prefix: &str) -> String {
 /// Test password
 pub const TEST_PASSWORD: &str = "TestPassword@123";
 
+/// Insert a verified, active user with `password` straight into the
+/// database, skipping the register/verify/set-password HTTP round trips
+/// and email. Log in with `TestServer::login_user` as usual.
+pub async fn seed_user(pool: &DbPool, email: &str, name: &str, password: &str) -> User {
+    let password = password.to_string();
+    let hash = tokio::task::spawn_blocking(move || PasswordManager::hash(&password))
+        .await
+        .expect("Password hashing task panicked")
+        .expect("Failed to hash seed password");
+
+    let mut user = User::new(Email::parse(email).expect("Invalid seed email"), name.to_string())
+        .expect("Invalid seed user");
+    user.verify_email();
+    user.set_password(hash);
+
+    UserRepositoryImpl::new(pool.clone())
+        .save(&user)
+        .await
+        .expect("Failed to seed user")
+}
+
 /// Clean up test data (optional, for specific tests)
 pub async fn cleanup_test_user(pool: &DbPool, email: &str) {
     use diesel::prelude::*;
@@ -1,8 +1,12 @@
 pub mod assertions;
+pub mod db;
 pub mod factories;
 pub mod mock;
+pub mod outbox;
 pub mod server;
 
 pub use assertions::*;
+#[allow(unused_imports)]
+pub use db::*;
 pub use factories::*;
 pub use server::*;
@@ -0,0 +1,65 @@
+#![allow(dead_code)]
+
+use async_trait::async_trait;
+use axum_backend::application::services::email::{EmailService, EmailType, Recipient};
+use axum_backend::shared::errors::AppError;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::Mutex;
+
+/// Email service that sends nothing and remembers the last code and
+/// verification link mailed to each address, since the database may only
+/// hold a hash of the code and does not hold links at all
+#[derive(Default)]
+pub struct Outbox {
+    codes: Mutex<HashMap<String, String>>,
+    links: Mutex<HashMap<String, String>>,
+    lockout_alerts: Mutex<HashMap<String, (IpAddr, DateTime<Utc>)>>,
+    inactivity_warnings: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl Outbox {
+    pub fn last_code(&self, email: &str) -> Option<String> {
+        self.codes.lock().unwrap().get(email).cloned()
+    }
+
+    pub fn last_link(&self, email: &str) -> Option<String> {
+        self.links.lock().unwrap().get(email).cloned()
+    }
+
+    /// Locking IP and time of the last lockout alert mailed to `email`
+    pub fn last_lockout_alert(&self, email: &str) -> Option<(IpAddr, DateTime<Utc>)> {
+        self.lockout_alerts.lock().unwrap().get(email).copied()
+    }
+
+    /// Disable time announced by the last inactivity warning mailed to `email`
+    pub fn last_inactivity_warning(&self, email: &str) -> Option<DateTime<Utc>> {
+        self.inactivity_warnings.lock().unwrap().get(email).copied()
+    }
+}
+
+#[async_trait]
+impl EmailService for Outbox {
+    async fn send(&self, recipient: Recipient, email_type: EmailType) -> Result<(), AppError> {
+        match email_type {
+            EmailType::Confirmation(code, link) | EmailType::ConfirmationResent(code, link) => {
+                if let Some(link) = link {
+                    self.links.lock().unwrap().insert(recipient.email.clone(), link);
+                }
+                self.codes.lock().unwrap().insert(recipient.email, code);
+            },
+            EmailType::PasswordReset(code) => {
+                self.codes.lock().unwrap().insert(recipient.email, code);
+            },
+            EmailType::AccountLocked(ip, locked_at) => {
+                self.lockout_alerts.lock().unwrap().insert(recipient.email, (ip, locked_at));
+            },
+            EmailType::InactivityWarning(disable_at) => {
+                self.inactivity_warnings.lock().unwrap().insert(recipient.email, disable_at);
+            },
+            EmailType::Welcome(_) => {},
+        }
+        Ok(())
+    }
+}
@@ -1,42 +1,162 @@
 #![allow(dead_code)]
 
-use axum_backend::infrastructure::database::connection::create_pool;
-use axum_backend::infrastructure::database::schema::users;
-use axum_backend::presentation::routes::create_router;
-use axum_prometheus::{metrics_exporter_prometheus::PrometheusHandle, PrometheusMetricLayer};
+use axum_backend::infrastructure::database::schema::{audit_logs, refresh_tokens, users};
+use axum_backend::infrastructure::database::{connection::create_pool, DbPool};
+use axum_backend::presentation::{routes::create_router, server::serve};
+use axum_backend::shared::utils::jwt::JwtManager;
 use diesel::prelude::*;
 use diesel_async::{AsyncConnection, AsyncPgConnection, RunQueryDsl};
 use reqwest::Client;
 use serde_json::{json, Value};
 use std::net::SocketAddr;
-use std::sync::OnceLock;
+use std::sync::Arc;
 use tokio::net::TcpListener;
 
-static PROMETHEUS_COMPONENTS: OnceLock<(PrometheusMetricLayer, PrometheusHandle)> = OnceLock::new();
+use crate::common::{mock::MockPostgres, outbox::Outbox};
+use axum_backend::config::{
+    AppConfig, CacheConfig, DatabaseConfig, EmailConfig, Features, MetricsConfig, NatsConfig,
+};
 
-use crate::common::mock::MockPostgres;
-use axum_backend::config::DatabaseConfig;
+/// Request duration buckets shared by every test server
+pub const TEST_DURATION_BUCKETS: &[f64] = &[0.002, 0.02, 0.2, 2.0, 20.0];
+
+fn test_jwt_secret() -> String {
+    std::env::var("JWT_SECRET")
+        .unwrap_or_else(|_| "test_secret_must_be_at_least_32_bytes_long".to_string())
+}
+
+/// Verifier for tokens issued by a server running `test_config`
+pub fn test_jwt_manager() -> JwtManager {
+    test_jwt_manager_with_expiry(3600)
+}
+
+/// Issuer of tokens a `test_config` server accepts, minting access tokens
+/// that expire `access_secs` from now; negative values are already expired
+pub fn test_jwt_manager_with_expiry(access_secs: i64) -> JwtManager {
+    JwtManager::new(
+        test_jwt_secret(),
+        access_secs,
+        86400,
+        "test-issuer".into(),
+        "test-audience".into(),
+    )
+    .expect("Failed to create test JwtManager")
+}
+
+/// Baseline configuration for test servers
+fn test_config(db_url: &str, db_config: DatabaseConfig) -> AppConfig {
+    AppConfig {
+        database_url: db_url.to_string(),
+        server_host: "127.0.0.1".to_string(),
+        server_port: 0,
+        bind_addresses: Vec::new(),
+        http_keep_alive_timeout: std::time::Duration::from_secs(75),
+        max_concurrent_requests: 1024,
+        shutdown_timeout: std::time::Duration::from_secs(30),
+        max_request_headers: 100,
+        max_request_header_bytes: 32 * 1024,
+        runtime: Default::default(),
+        jwt_secret: test_jwt_secret(),
+        jwt_access_expiry: 3600,
+        jwt_refresh_expiry: 86400,
+        jwt_role_expiry: std::collections::HashMap::new(),
+        jwt_issuer: "test-issuer".to_string(),
+        jwt_validate_issuer: true,
+        jwt_audience: "test-audience".to_string(),
+        jwt_accepted_audiences: Vec::new(),
+        jwt_leeway: axum_backend::shared::utils::jwt::DEFAULT_LEEWAY_SECS,
+        confirm_code_expiry: 60,
+        verification_link_expiry: 86400,
+        verification_link_url: "http://localhost/api/auth/verify-link".to_string(),
+        confirmation_code_hasher: Default::default(),
+        rust_log: "info".to_string(),
+        is_production: false,
+        token_delivery: Default::default(),
+        cookie_secure: false,
+        swagger_enabled: true,
+        rate_limit_per_second: 10_000, // high enough to never trigger in tests
+        rate_limit_burst_size: 100_000, // high enough to never trigger in tests
+        rate_limit_algorithm: Default::default(),
+        trusted_proxies: Vec::new(),
+        default_page_size: 10,
+        max_page_size: 100,
+        max_sessions_per_user: None,
+        session_limit_reject: false,
+        password_history_size: 5,
+        password_min_change_interval: std::time::Duration::ZERO,
+        password_peppers: Default::default(),
+        password_rules: Default::default(),
+        registration_open: true,
+        invitation_ttl: std::time::Duration::from_secs(7 * 24 * 60 * 60),
+        default_user_role: Default::default(),
+        email_domain_policy: Default::default(),
+        disposable_email_domains: None,
+        role_cache_ttl: std::time::Duration::from_secs(300),
+        resend_cooldown: std::time::Duration::from_secs(60),
+        resend_max_per_hour: 5,
+        health_check_timeout: std::time::Duration::from_secs(2),
+        features: Features::default(),
+        db_config,
+        cache_config: CacheConfig::default(),
+        lock_policy: Default::default(),
+        cache_control: Default::default(),
+        audit_retention: Default::default(),
+        inactivity: Default::default(),
+        lockout: Default::default(),
+        import_max_concurrency: 4,
+        import_csv_path: std::env::temp_dir().join("axum_backend_import_users.csv"),
+        // The Prometheus recorder is process-global, so every test server
+        // must agree on buckets; these are distinct from the defaults so
+        // tests can tell they were applied.
+        metrics_config: MetricsConfig {
+            duration_buckets: TEST_DURATION_BUCKETS.to_vec(),
+            ..MetricsConfig::default()
+        },
+        nats_config: NatsConfig::default(),
+        event_transport: Default::default(),
+        sms_config: Default::default(),
+        email_config: EmailConfig::default(),
+    }
+}
 
 /// Test server instance
 pub struct TestServer {
     pub addr: SocketAddr,
     pub client: Client,
     pub base_url: String,
+    /// Pool on the server's database, for exercising repositories directly
+    pub pool: DbPool,
+    /// Codes mailed by the server, unless it sends real email
+    pub outbox: Arc<Outbox>,
     pub _mock_db: Option<MockPostgres>,
 }
 
 impl TestServer {
     /// Create a new test server instance
     pub async fn new() -> Self {
-        Self::build(false).await
+        Self::build(false, true, |_| {}).await
     }
 
     /// Create a new test server instance with real email service
     pub async fn new_with_real_email() -> Self {
-        Self::build(true).await
+        Self::build(true, true, |_| {}).await
+    }
+
+    /// Create a new test server instance after adjusting the default test config
+    pub async fn with_config(configure: impl FnOnce(&mut AppConfig)) -> Self {
+        Self::build(false, true, configure).await
+    }
+
+    /// Create a test server whose database has never been migrated
+    pub async fn without_migrations() -> Self {
+        Self::build(false, false, |_| {}).await
     }
 
-    async fn build(use_real_email: bool) -> Self {
+    async fn build(
+        use_real_email: bool,
+        migrate: bool,
+        configure: impl FnOnce(&mut AppConfig),
+    ) -> Self {
         // 1. Initialize Infrastructure (Standalone)
         dotenvy::dotenv().ok();
 
@@ -45,14 +165,6 @@ impl TestServer {
         let db_url = mock_db.connection_string.clone();
         let mock_db = Some(mock_db);
 
-        // Determine critical params, fallback if needed
-        let jwt_secret = std::env::var("JWT_SECRET")
-            .unwrap_or_else(|_| "test_secret_must_be_at_least_32_bytes_long".to_string());
-        let jwt_access_expiry = 3600;
-        let jwt_refresh_expiry = 86400;
-        let jwt_issuer = "test-issuer".to_string();
-        let jwt_audience = "test-audience".to_string();
-
         // 2. Create Database Pool
         // Allow overriding via env vars for load testing
         let max_connections = std::env::var("DB_MAX_CONNECTIONS")
@@ -88,43 +200,30 @@ impl TestServer {
         }
 
         // 3. Run Migrations (Idempotent)
-        axum_backend::infrastructure::database::connection::run_migrations(&db_url)
-            .await
-            .expect("Failed to run migrations");
-
-        // 4. Monitoring Setup (Singleton for tests to avoid panic)
-        let (prometheus_layer, metric_handle) =
-            PROMETHEUS_COMPONENTS.get_or_init(|| PrometheusMetricLayer::pair()).clone();
-
-        // 5. Create Router
-        let email_service: std::sync::Arc<
-            dyn axum_backend::application::services::email::EmailService,
-        > = if use_real_email {
-            std::sync::Arc::new(
-                axum_backend::infrastructure::email::lettre_service::LettreEmailService::new()
+        if migrate {
+            axum_backend::infrastructure::database::connection::run_migrations(&db_url)
+                .await
+                .expect("Failed to run migrations");
+        }
+
+        // 4. Create Router
+        let outbox = Arc::new(Outbox::default());
+        let email_service: Arc<dyn axum_backend::application::services::email::EmailService> =
+            if use_real_email {
+                Arc::new(
+                    axum_backend::infrastructure::email::lettre_service::LettreEmailService::new(
+                        &EmailConfig::from_env().expect("Invalid SMTP configuration"),
+                    )
                     .expect("Failed to create real email service"),
-            )
-        } else {
-            std::sync::Arc::new(
-                axum_backend::infrastructure::email::noop_service::NoOpEmailService::new(),
-            )
-        };
+                )
+            } else {
+                outbox.clone()
+            };
 
-        let app = create_router(
-            pool,
-            jwt_secret,
-            jwt_access_expiry,
-            jwt_refresh_expiry,
-            jwt_issuer,
-            jwt_audience,
-            60,      // confirm_code_expiry
-            false,   // cookie_secure
-            10_000,  // rate_limit_per_second — high enough to never trigger in tests
-            100_000, // rate_limit_burst_size — high enough to never trigger in tests
-            prometheus_layer,
-            metric_handle,
-            email_service,
-        );
+        let mut config = test_config(&db_url, db_config);
+        configure(&mut config);
+
+        let app = create_router(pool.clone(), &config, email_service, Arc::default());
 
         // 5. Bind to Random Port
         let listener = TcpListener::bind("127.0.0.1:0").await.expect("Failed to bind test server");
@@ -132,11 +231,8 @@ impl TestServer {
         let base_url = format!("http://{}", addr);
 
         // 6. Spawn Server Background Task
-        tokio::spawn(async move {
-            axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
-                .await
-                .expect("Test server failed");
-        });
+        let keep_alive_timeout = config.http_keep_alive_timeout;
+        tokio::spawn(serve(listener, app, keep_alive_timeout));
 
         // 7. Wait for readiness
         tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
@@ -148,12 +244,23 @@ impl TestServer {
                 .build()
                 .expect("Failed to build test client"),
             base_url,
+            pool,
+            outbox,
             _mock_db: mock_db,
         }
     }
 
-    /// Get confirmation code from DB
+    /// The last code mailed to `email_addr`. Servers sending real email are
+    /// read from the DB instead, which only works while codes are unhashed.
     pub async fn get_confirmation_code(&self, email_addr: &str) -> String {
+        if let Some(code) = self.outbox.last_code(email_addr) {
+            return code;
+        }
+        self.get_stored_confirmation_code(email_addr).await
+    }
+
+    /// The confirmation code column as stored in the DB
+    pub async fn get_stored_confirmation_code(&self, email_addr: &str) -> String {
         let db_url = &self._mock_db.as_ref().expect("Mock DB not initialized").connection_string;
         let mut conn = AsyncPgConnection::establish(db_url).await.expect("Failed to connect to DB");
 
@@ -167,6 +274,156 @@ impl TestServer {
         code.expect("Confirmation code not found")
     }
 
+    /// Get a user's stored password hash from DB
+    pub async fn get_password_hash(&self, email_addr: &str) -> Option<String> {
+        let db_url = &self._mock_db.as_ref().expect("Mock DB not initialized").connection_string;
+        let mut conn = AsyncPgConnection::establish(db_url).await.expect("Failed to connect to DB");
+
+        users::table
+            .filter(users::email.eq(email_addr))
+            .select(users::password_hash)
+            .first(&mut conn)
+            .await
+            .expect("Failed to query user")
+    }
+
+    /// Get a user's stored locale from DB
+    pub async fn get_user_locale(&self, email_addr: &str) -> String {
+        let db_url = &self._mock_db.as_ref().expect("Mock DB not initialized").connection_string;
+        let mut conn = AsyncPgConnection::establish(db_url).await.expect("Failed to connect to DB");
+
+        users::table
+            .filter(users::email.eq(email_addr))
+            .select(users::locale)
+            .first(&mut conn)
+            .await
+            .expect("Failed to query user")
+    }
+
+    /// Get a user's stored role from DB
+    pub async fn get_user_role(&self, email_addr: &str) -> String {
+        let db_url = &self._mock_db.as_ref().expect("Mock DB not initialized").connection_string;
+        let mut conn = AsyncPgConnection::establish(db_url).await.expect("Failed to connect to DB");
+
+        users::table
+            .filter(users::email.eq(email_addr))
+            .select(users::role)
+            .first(&mut conn)
+            .await
+            .expect("Failed to query user")
+    }
+
+    /// Get a user's stored `is_active` flag from DB
+    pub async fn is_user_active(&self, email_addr: &str) -> bool {
+        let db_url = &self._mock_db.as_ref().expect("Mock DB not initialized").connection_string;
+        let mut conn = AsyncPgConnection::establish(db_url).await.expect("Failed to connect to DB");
+
+        users::table
+            .filter(users::email.eq(email_addr))
+            .select(users::is_active)
+            .first(&mut conn)
+            .await
+            .expect("Failed to query user")
+    }
+
+    /// Whether the stored refresh token has been revoked
+    pub async fn is_refresh_token_revoked(&self, token: &str) -> bool {
+        let db_url = &self._mock_db.as_ref().expect("Mock DB not initialized").connection_string;
+        let mut conn = AsyncPgConnection::establish(db_url).await.expect("Failed to connect to DB");
+
+        let revoked_at: Option<chrono::DateTime<chrono::Utc>> = refresh_tokens::table
+            .filter(refresh_tokens::token_hash.eq(axum_backend::shared::utils::hash_token(token)))
+            .select(refresh_tokens::revoked_at)
+            .first(&mut conn)
+            .await
+            .expect("Refresh token not found");
+
+        revoked_at.is_some()
+    }
+
+    /// Overwrite a user's role directly in the DB. Bypasses the role cache, so
+    /// call it before the user makes an authenticated request.
+    pub async fn set_user_role(&self, email_addr: &str, role: &str) {
+        let db_url = &self._mock_db.as_ref().expect("Mock DB not initialized").connection_string;
+        let mut conn = AsyncPgConnection::establish(db_url).await.expect("Failed to connect to DB");
+
+        diesel::update(users::table.filter(users::email.eq(email_addr)))
+            .set(users::role.eq(role))
+            .execute(&mut conn)
+            .await
+            .expect("Failed to update user role");
+    }
+
+    /// Mark `phone` as a user's verified number without texting a code
+    pub async fn set_verified_phone(&self, email_addr: &str, phone: &str) {
+        let db_url = &self._mock_db.as_ref().expect("Mock DB not initialized").connection_string;
+        let mut conn = AsyncPgConnection::establish(db_url).await.expect("Failed to connect to DB");
+
+        diesel::update(users::table.filter(users::email.eq(email_addr)))
+            .set((users::phone.eq(phone), users::phone_verified.eq(true)))
+            .execute(&mut conn)
+            .await
+            .expect("Failed to update user phone");
+    }
+
+    /// Insert an active admin directly in the DB, for servers whose
+    /// registration is closed
+    pub async fn create_admin(&self, email_addr: &str, password: &str) {
+        let hash = axum_backend::shared::utils::password::PasswordManager::hash(password)
+            .expect("Failed to hash password");
+        let db_url = &self._mock_db.as_ref().expect("Mock DB not initialized").connection_string;
+        let mut conn = AsyncPgConnection::establish(db_url).await.expect("Failed to connect to DB");
+
+        diesel::insert_into(users::table)
+            .values((
+                users::id.eq(uuid::Uuid::new_v4()),
+                users::email.eq(email_addr),
+                users::name.eq("Admin"),
+                users::password_hash.eq(hash),
+                users::role.eq("admin"),
+                users::is_active.eq(true),
+                users::email_verified.eq(true),
+            ))
+            .execute(&mut conn)
+            .await
+            .expect("Failed to insert admin");
+    }
+
+    /// Audit entries recorded against `target_id`, oldest first, as
+    /// `(action, actor_id, details)`
+    pub async fn audit_entries_for(
+        &self,
+        target_id: uuid::Uuid,
+    ) -> Vec<(String, Option<uuid::Uuid>, Value)> {
+        let db_url = &self._mock_db.as_ref().expect("Mock DB not initialized").connection_string;
+        let mut conn = AsyncPgConnection::establish(db_url).await.expect("Failed to connect to DB");
+
+        audit_logs::table
+            .filter(audit_logs::target_id.eq(target_id))
+            .order(audit_logs::created_at.asc())
+            .select((audit_logs::action, audit_logs::actor_id, audit_logs::details))
+            .load(&mut conn)
+            .await
+            .expect("Failed to query audit logs")
+    }
+
+    /// Log in and return the raw status and JSON body
+    pub async fn login_response(
+        &self,
+        email: &str,
+        password: &str,
+    ) -> (reqwest::StatusCode, Value) {
+        let response = self
+            .client
+            .post(format!("{}/api/auth/login", self.base_url))
+            .json(&json!({ "email": email, "password": password }))
+            .send()
+            .await
+            .expect("Failed to send login request");
+        let status = response.status();
+        (status, response.json().await.expect("Failed to parse login response"))
+    }
+
     /// Register a test user (Full Flow: Register -> Verify -> SetPassword -> Login)
     pub async fn register_user(&self, email: &str, name: &str, password: &str) -> Value {
         // 1. Register
@@ -289,6 +546,15 @@ impl TestServer {
             .expect("Failed to send health check request")
     }
 
+    /// Get readiness probe
+    pub async fn readiness_check(&self) -> reqwest::Response {
+        self.client
+            .get(format!("{}/health/ready", self.base_url))
+            .send()
+            .await
+            .expect("Failed to send readiness request")
+    }
+
     /// List users with authentication
     pub async fn list_users(&self, token: &str, page: u32, page_size: u32) -> Value {
         self.client
@@ -1,7 +1,9 @@
 use crate::common::server::TestServer;
 use axum_backend::{
     application::services::email::{EmailService, EmailType, Recipient},
+    config::EmailConfig,
     infrastructure::email::lettre_service::LettreEmailService,
+    shared::i18n::Locale,
 };
 use std::sync::Arc;
 
@@ -26,13 +28,18 @@ async fn test_service_send_welcome_email() {
     }
 
     // 2. Create Service
-    let email_service =
-        LettreEmailService::new().map(Arc::new).expect("Failed to create email service");
+    let config = EmailConfig::from_env().expect("Invalid SMTP configuration");
+    let email_service = LettreEmailService::new(&config)
+        .map(Arc::new)
+        .expect("Failed to create email service");
 
     // 3. Define Recipient (Self-send for testing)
     let to_email = std::env::var("SMTP_FROM").unwrap_or_else(|_| "test@example.com".to_string());
-    let recipient =
-        Recipient { email: to_email.clone(), name: "Integration Test User".to_string() };
+    let recipient = Recipient {
+        email: to_email.clone(),
+        name: "Integration Test User".to_string(),
+        locale: Locale::default(),
+    };
 
     println!("Sending email to: {}", to_email);
 
@@ -1,3 +1,4 @@
+use super::pushgateway::{push_if_configured, PerformanceMetrics};
 use crate::common::*;
 use rand::Rng;
 use serde_json::json;
@@ -87,6 +88,7 @@ impl StressMetrics {
 }
 
 // Stats for detailed analysis (Spike Test)
+#[allow(dead_code)]
 struct LoadTestStats {
     total_success: usize,
     total_failures: usize,
@@ -272,6 +274,18 @@ async fn spike_load_test() {
     );
 
     generate_report(&mut stats, avg, p95);
+    push_if_configured(
+        "spike",
+        &PerformanceMetrics {
+            total_requests: stats.total_requests(),
+            success_rate: stats.success_rate(),
+            throughput_rps: stats.total_requests() as f64 / stats.duration.as_secs_f64(),
+            avg_latency_ms: avg,
+            p95_latency_ms: p95,
+            p99_latency_ms: p99,
+        },
+    )
+    .await;
 
     // Soft assertion for load test
     if stats.total_failures > stats.total_requests() / 2 {
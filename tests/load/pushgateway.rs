@@ -0,0 +1,155 @@
+//! Push load test results to a Prometheus Pushgateway so CI dashboards can
+//! track regressions across runs. Enabled by setting `LOAD_TEST_PUSHGATEWAY_URL`.
+use std::fmt::Write;
+
+/// Gateway base URL, e.g. `http://pushgateway:9091`; pushing is skipped when unset
+pub const PUSHGATEWAY_ENV: &str = "LOAD_TEST_PUSHGATEWAY_URL";
+
+/// Pushgateway job the results are grouped under
+const JOB: &str = "axum_backend_load_test";
+
+/// Headline numbers from one load test run
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerformanceMetrics {
+    pub total_requests: usize,
+    /// Percentage of requests that succeeded, 0-100
+    pub success_rate: f64,
+    pub throughput_rps: f64,
+    pub avg_latency_ms: f64,
+    pub p95_latency_ms: u128,
+    pub p99_latency_ms: u128,
+}
+
+impl PerformanceMetrics {
+    /// Render as Prometheus text exposition format, one gauge per field
+    pub fn to_prometheus_text(&self) -> String {
+        let gauges: [(&str, &str, String); 6] = [
+            ("load_test_requests_total", "Requests sent", self.total_requests.to_string()),
+            (
+                "load_test_success_rate_percent",
+                "Requests that succeeded",
+                self.success_rate.to_string(),
+            ),
+            (
+                "load_test_throughput_rps",
+                "Requests per second",
+                self.throughput_rps.to_string(),
+            ),
+            (
+                "load_test_latency_avg_ms",
+                "Mean latency of successful requests",
+                self.avg_latency_ms.to_string(),
+            ),
+            (
+                "load_test_latency_p95_ms",
+                "95th percentile latency",
+                self.p95_latency_ms.to_string(),
+            ),
+            (
+                "load_test_latency_p99_ms",
+                "99th percentile latency",
+                self.p99_latency_ms.to_string(),
+            ),
+        ];
+
+        let mut body = String::new();
+        for (name, help, value) in gauges {
+            let _ = writeln!(body, "# HELP {} {}", name, help);
+            let _ = writeln!(body, "# TYPE {} gauge", name);
+            let _ = writeln!(body, "{} {}", name, value);
+        }
+        body
+    }
+}
+
+/// Replace this test's metrics group on the gateway
+pub async fn push(
+    client: &reqwest::Client,
+    gateway_url: &str,
+    test_name: &str,
+    metrics: &PerformanceMetrics,
+) -> Result<(), String> {
+    let url =
+        format!("{}/metrics/job/{}/test/{}", gateway_url.trim_end_matches('/'), JOB, test_name);
+    let response = client
+        .put(url)
+        .header(reqwest::header::CONTENT_TYPE, "text/plain; version=0.0.4")
+        .body(metrics.to_prometheus_text())
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("pushgateway responded {}", response.status()))
+    }
+}
+
+/// Push when `LOAD_TEST_PUSHGATEWAY_URL` is set; a failed push is reported
+/// but does not fail the load test
+pub async fn push_if_configured(test_name: &str, metrics: &PerformanceMetrics) {
+    let Ok(gateway_url) = std::env::var(PUSHGATEWAY_ENV) else {
+        return;
+    };
+
+    match push(&reqwest::Client::new(), &gateway_url, test_name, metrics).await {
+        Ok(()) => println!("📤 Metrics pushed to {}", gateway_url),
+        Err(e) => println!("⚠️  Failed to push metrics to {}: {}", gateway_url, e),
+    }
+}
+
+#[tokio::test]
+async fn push_sends_text_format_to_the_gateway() {
+    use axum::{body::Bytes, extract::State, http::Uri, routing::put, Router};
+    use tokio::sync::mpsc;
+
+    let (tx, mut rx) = mpsc::unbounded_channel::<(String, String)>();
+    let gateway = Router::new()
+        .route(
+            "/*path",
+            put(
+                |State(tx): State<mpsc::UnboundedSender<(String, String)>>,
+                 uri: Uri,
+                 body: Bytes| async move {
+                    let _ = tx.send((
+                        uri.path().to_string(),
+                        String::from_utf8_lossy(&body).into_owned(),
+                    ));
+                },
+            ),
+        )
+        .with_state(tx);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, gateway).await });
+
+    let metrics = PerformanceMetrics {
+        total_requests: 1200,
+        success_rate: 99.5,
+        throughput_rps: 40.0,
+        avg_latency_ms: 12.25,
+        p95_latency_ms: 48,
+        p99_latency_ms: 97,
+    };
+    push(&reqwest::Client::new(), &format!("http://{}/", addr), "spike", &metrics)
+        .await
+        .unwrap();
+
+    let (path, body) = rx.recv().await.unwrap();
+    assert_eq!(path, "/metrics/job/axum_backend_load_test/test/spike");
+    let samples: Vec<&str> = body.lines().filter(|l| !l.starts_with('#')).collect();
+    assert_eq!(
+        samples,
+        [
+            "load_test_requests_total 1200",
+            "load_test_success_rate_percent 99.5",
+            "load_test_throughput_rps 40",
+            "load_test_latency_avg_ms 12.25",
+            "load_test_latency_p95_ms 48",
+            "load_test_latency_p99_ms 97",
+        ]
+    );
+    assert!(body.contains("# TYPE load_test_latency_p99_ms gauge\n"));
+    assert!(body.ends_with('\n'));
+}
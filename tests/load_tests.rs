@@ -1,5 +1,7 @@
+#[allow(unused_imports)]
 mod common;
 
 mod load {
     pub mod load_tests;
+    pub mod pushgateway;
 }
@@ -0,0 +1,43 @@
+/// Retention purge of `audit_logs` against a real database
+use crate::common::*;
+use axum_backend::{
+    application::services::AuditRetention,
+    domain::{entities::AuditEntry, repositories::AuditRepository},
+    infrastructure::database::{repositories::AuditRepositoryImpl, schema::audit_logs},
+};
+use chrono::{Duration, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use std::sync::Arc;
+use uuid::Uuid;
+
+fn entry(age: Duration, legal_hold: bool) -> AuditEntry {
+    let mut entry = AuditEntry::new(None, "user.deactivated", None, serde_json::json!({}));
+    entry.created_at = Utc::now() - age;
+    entry.legal_hold = legal_hold;
+    entry
+}
+
+#[tokio::test]
+async fn purge_removes_expired_entries_but_keeps_recent_and_held_ones() {
+    let db = TestDb::new().await;
+    let repo = Arc::new(AuditRepositoryImpl::new(db.pool.clone()));
+    let expired: Vec<AuditEntry> = (0..5).map(|_| entry(Duration::days(100), false)).collect();
+    let recent = entry(Duration::days(10), false);
+    let held = entry(Duration::days(400), true);
+    for e in expired.iter().chain([&recent, &held]) {
+        repo.record(e).await.unwrap();
+    }
+
+    // Batches of two take three statements to clear five entries
+    let retention = AuditRetention::new(repo, std::time::Duration::from_secs(90 * 86_400), 2);
+    assert_eq!(retention.purge(Utc::now()).await.unwrap(), 5);
+
+    let mut conn = db.pool.get().await.unwrap();
+    let mut remaining: Vec<Uuid> =
+        audit_logs::table.select(audit_logs::id).load(&mut conn).await.unwrap();
+    remaining.sort();
+    let mut kept = vec![recent.id, held.id];
+    kept.sort();
+    assert_eq!(remaining, kept);
+}
@@ -0,0 +1,195 @@
+/// Data-layer tests for `AuthRepositoryImpl` against a real database
+use crate::common::*;
+use axum_backend::{
+    domain::{
+        entities::RefreshToken,
+        repositories::{AuthRepository, AuthRepositoryError, PasswordHistoryRepository},
+        value_objects::{PhoneNumber, UserId, UserRole},
+    },
+    infrastructure::database::repositories::{AuthRepositoryImpl, PasswordHistoryRepositoryImpl},
+};
+use chrono::{Duration, Utc};
+use uuid::Uuid;
+
+async fn user_id(repo: &AuthRepositoryImpl, prefix: &str) -> Uuid {
+    let user = repo
+        .create_user(
+            &unique_email(prefix),
+            "Token User",
+            None,
+            None,
+            None,
+            "en",
+            UserRole::default(),
+        )
+        .await
+        .unwrap();
+    *user.id.as_uuid()
+}
+
+async fn issue(repo: &AuthRepositoryImpl, user_id: Uuid, hash: &str) {
+    let token = RefreshToken::new(user_id, hash.to_string(), Utc::now() + Duration::hours(1));
+    repo.save_refresh_token(&token).await.unwrap();
+}
+
+#[tokio::test]
+async fn create_user_rejects_duplicate_email() {
+    let db = TestDb::new().await;
+    let repo = AuthRepositoryImpl::new(db.pool.clone());
+    let email = unique_email("repo_dup");
+
+    repo.create_user(&email, "First", None, None, None, "en", UserRole::default())
+        .await
+        .unwrap();
+    let err = repo
+        .create_user(&email, "Second", None, None, None, "en", UserRole::default())
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, AuthRepositoryError::EmailAlreadyExists), "{:?}", err);
+}
+
+#[tokio::test]
+async fn revoking_a_token_removes_it_from_the_active_set() {
+    let db = TestDb::new().await;
+    let repo = AuthRepositoryImpl::new(db.pool.clone());
+    let user = user_id(&repo, "repo_revoke").await;
+    issue(&repo, user, "hash-a").await;
+    issue(&repo, user, "hash-b").await;
+
+    repo.revoke_refresh_token("hash-a").await.unwrap();
+
+    let active: Vec<_> = repo
+        .list_active_refresh_tokens(user)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.token_hash)
+        .collect();
+    assert_eq!(active, vec!["hash-b"]);
+    assert!(repo.find_refresh_token("hash-a").await.unwrap().unwrap().revoked_at.is_some());
+    assert!(matches!(
+        repo.revoke_refresh_token("hash-a").await,
+        Err(AuthRepositoryError::TokenNotFound)
+    ));
+}
+
+#[tokio::test]
+async fn revoke_all_only_touches_that_users_tokens() {
+    let db = TestDb::new().await;
+    let repo = AuthRepositoryImpl::new(db.pool.clone());
+    let alice = user_id(&repo, "repo_alice").await;
+    let bob = user_id(&repo, "repo_bob").await;
+    issue(&repo, alice, "alice-1").await;
+    issue(&repo, alice, "alice-2").await;
+    issue(&repo, bob, "bob-1").await;
+
+    repo.revoke_all_user_tokens(alice).await.unwrap();
+
+    assert!(repo.list_active_refresh_tokens(alice).await.unwrap().is_empty());
+    assert_eq!(repo.list_active_refresh_tokens(bob).await.unwrap().len(), 1);
+    assert_eq!(repo.cleanup_expired_tokens().await.unwrap(), 2, "revoked tokens are purged");
+}
+
+#[tokio::test]
+async fn concurrent_saves_never_exceed_the_session_limit() {
+    let db = TestDb::new().await;
+    let repo = std::sync::Arc::new(AuthRepositoryImpl::new(db.pool.clone()));
+    let rejecting = user_id(&repo, "repo_cap_rej").await;
+    let evicting = user_id(&repo, "repo_cap_evict").await;
+
+    let save_all = |user: Uuid, evict_oldest: bool| {
+        futures::future::join_all((0..8).map(|i| {
+            let repo = repo.clone();
+            async move {
+                let hash = format!("{}-{}", user, i);
+                let token = RefreshToken::new(user, hash, Utc::now() + Duration::hours(1));
+                repo.save_refresh_token_within_limit(&token, 2, evict_oldest).await.unwrap()
+            }
+        }))
+    };
+
+    let saved = save_all(rejecting, false).await;
+    assert_eq!(saved.iter().filter(|s| s.is_some()).count(), 2, "{:?}", saved);
+    assert_eq!(repo.list_active_refresh_tokens(rejecting).await.unwrap().len(), 2);
+
+    let saved = save_all(evicting, true).await;
+    assert!(saved.iter().all(Option::is_some), "{:?}", saved);
+    assert_eq!(saved.iter().flatten().sum::<usize>(), 6);
+    assert_eq!(repo.list_active_refresh_tokens(evicting).await.unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn password_history_keeps_only_the_newest_hashes() {
+    let db = TestDb::new().await;
+    let repo = AuthRepositoryImpl::new(db.pool.clone());
+    let history = PasswordHistoryRepositoryImpl::new(db.pool.clone());
+    let user = UserId::from_uuid(user_id(&repo, "repo_history").await);
+
+    for hash in ["hash-1", "hash-2", "hash-3"] {
+        history.record(user, hash, 2).await.unwrap();
+    }
+
+    assert_eq!(history.recent(user, 5).await.unwrap(), vec!["hash-3", "hash-2"]);
+    assert_eq!(history.recent(user, 1).await.unwrap(), vec!["hash-3"]);
+}
+
+#[tokio::test]
+async fn a_confirmation_code_is_consumed_only_while_valid_and_only_once() {
+    let db = TestDb::new().await;
+    let repo = AuthRepositoryImpl::new(db.pool.clone());
+    let fresh = Utc::now() + Duration::minutes(10);
+    let live = repo
+        .create_user(
+            &unique_email("repo_code"),
+            "Code",
+            None,
+            Some("live".into()),
+            Some(fresh),
+            "en",
+            UserRole::default(),
+        )
+        .await
+        .unwrap();
+    let stale = repo
+        .create_user(
+            &unique_email("repo_stale"),
+            "Stale",
+            None,
+            Some("stale".into()),
+            Some(Utc::now() - Duration::minutes(1)),
+            "en",
+            UserRole::default(),
+        )
+        .await
+        .unwrap();
+    let live_id = *live.id.as_uuid();
+
+    assert!(!repo.consume_confirmation_code(live_id, "wrong").await.unwrap());
+    assert!(!repo.consume_confirmation_code(*stale.id.as_uuid(), "stale").await.unwrap());
+    assert!(repo.consume_confirmation_code(live_id, "live").await.unwrap());
+    assert!(!repo.consume_confirmation_code(live_id, "live").await.unwrap());
+
+    let stored = repo.find_by_email(live.email.as_str()).await.unwrap().unwrap();
+    assert_eq!(stored.confirmation_code, None);
+    assert_eq!(stored.confirmation_code_expires_at, None);
+}
+
+#[tokio::test]
+async fn a_phone_code_verifies_only_while_valid_and_only_once() {
+    let db = TestDb::new().await;
+    let repo = AuthRepositoryImpl::new(db.pool.clone());
+    let id = user_id(&repo, "repo_phone").await;
+    let mut user = repo.find_by_id(id).await.unwrap().unwrap();
+    let phone = PhoneNumber::parse("+84901234567").unwrap();
+    user.set_phone(phone, "texted".into(), Utc::now() + Duration::minutes(10));
+    repo.update_user(&user).await.unwrap();
+
+    assert!(!repo.consume_phone_code(id, "wrong").await.unwrap());
+    assert!(repo.consume_phone_code(id, "texted").await.unwrap());
+    assert!(!repo.consume_phone_code(id, "texted").await.unwrap());
+
+    let stored = repo.find_by_id(id).await.unwrap().unwrap();
+    assert_eq!(stored.verified_phone().map(PhoneNumber::as_str), Some("+84901234567"));
+    assert_eq!(stored.phone_code, None);
+}
@@ -0,0 +1,70 @@
+/// Publishing events as Postgres notifications
+use crate::common::*;
+use axum_backend::{
+    application::services::events::{publish_event, EventPublisher},
+    domain::{
+        events::{v2::UserRoleChanged, EventEnvelope},
+        value_objects::UserRole,
+    },
+    infrastructure::messaging::{pg_notify::MAX_NOTIFY_PAYLOAD, PgNotifyEventPublisher},
+};
+use futures::StreamExt;
+use tokio::sync::mpsc;
+use tokio_postgres::{AsyncMessage, NoTls};
+
+/// A separate session `LISTEN`ing on `channel`, forwarding each notification.
+/// The session lasts as long as the returned client.
+async fn listen(
+    url: &str,
+    channel: &str,
+) -> (tokio_postgres::Client, mpsc::UnboundedReceiver<(String, String)>) {
+    let (client, mut connection) = tokio_postgres::connect(url, NoTls).await.unwrap();
+    let (tx, rx) = mpsc::unbounded_channel();
+    tokio::spawn(async move {
+        let mut messages = futures::stream::poll_fn(move |cx| connection.poll_message(cx));
+        while let Some(Ok(message)) = messages.next().await {
+            if let AsyncMessage::Notification(n) = message {
+                let _ = tx.send((n.channel().to_string(), n.payload().to_string()));
+            }
+        }
+    });
+    client.batch_execute(&format!("LISTEN \"{}\"", channel)).await.unwrap();
+    (client, rx)
+}
+
+#[tokio::test]
+async fn published_events_reach_a_listening_session() {
+    let db = TestDb::new().await;
+    let publisher = PgNotifyEventPublisher::new(db.pool.clone());
+    let subject = "events.v2.user.role_changed";
+
+    let (_session, mut received) = listen(&db.url, subject).await;
+
+    let event = UserRoleChanged {
+        user_id: uuid::Uuid::new_v4(),
+        old_role: UserRole::Viewer,
+        new_role: UserRole::Admin,
+        actor_id: None,
+        occurred_at: chrono::Utc::now(),
+    };
+    publish_event(&publisher, &event).await.unwrap();
+
+    let (channel, payload) =
+        tokio::time::timeout(std::time::Duration::from_secs(5), received.recv())
+            .await
+            .unwrap()
+            .unwrap();
+    assert_eq!(channel, subject);
+    let envelope: EventEnvelope<UserRoleChanged> = serde_json::from_str(&payload).unwrap();
+    assert_eq!(envelope.event, event);
+}
+
+#[tokio::test]
+async fn payloads_over_the_notification_limit_are_refused() {
+    let db = TestDb::new().await;
+    let publisher = PgNotifyEventPublisher::new(db.pool.clone());
+
+    let payload = vec![b'x'; MAX_NOTIFY_PAYLOAD + 1];
+    assert!(publisher.publish("events.v2.test", payload).await.is_err());
+    assert!(publisher.publish("events.v2.test", b"{}".to_vec()).await.is_ok());
+}
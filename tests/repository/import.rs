@@ -0,0 +1,40 @@
+/// CSV imports against a real database and a deliberately small pool
+use crate::common::*;
+use axum_backend::{
+    application::use_cases::ImportUsersUseCase,
+    config::DatabaseConfig,
+    domain::repositories::AuthRepository,
+    infrastructure::database::{connection::create_pool, repositories::AuthRepositoryImpl},
+};
+use std::{sync::Arc, time::Duration};
+
+#[tokio::test]
+async fn large_import_stays_within_a_small_pool() {
+    let db = TestDb::new().await;
+    // Two connections and a short wait: creations outnumbering the pool
+    // would time out waiting for one instead of queueing behind the limit
+    let small = DatabaseConfig {
+        max_connections: 2,
+        min_connections: 1,
+        connect_timeout: Duration::from_secs(1),
+        ..DatabaseConfig::default()
+    };
+    let pool = create_pool(&small, &db.url).await.unwrap();
+    let repo = Arc::new(AuthRepositoryImpl::new(pool));
+
+    let emails: Vec<_> = (0..20).map(|i| unique_email(&format!("imp{}", i))).collect();
+    let csv = emails.iter().fold("email,name,password\n".to_string(), |csv, email| {
+        csv + &format!("{},Imported,secret123\n", email)
+    });
+
+    let count = ImportUsersUseCase::new(repo.clone())
+        .with_concurrency(2)
+        .execute(csv.as_bytes())
+        .await
+        .unwrap();
+
+    assert_eq!(count, emails.len());
+    for email in &emails {
+        assert!(repo.find_by_email(email).await.unwrap().is_some(), "{} was not created", email);
+    }
+}
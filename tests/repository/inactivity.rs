@@ -0,0 +1,141 @@
+/// Disabling inactive accounts against a real database
+use crate::common::{outbox::Outbox, *};
+use axum_backend::{
+    application::services::InactiveAccounts,
+    domain::{repositories::AuthRepository, value_objects::UserRole},
+    infrastructure::database::{
+        repositories::{AuditRepositoryImpl, AuthRepositoryImpl},
+        schema::users,
+        DbPool,
+    },
+};
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel_async::RunQueryDsl;
+use std::sync::Arc;
+use uuid::Uuid;
+
+const DAY: std::time::Duration = std::time::Duration::from_secs(86_400);
+
+/// Active user with `role` who signed up at `created_at` and last logged in
+/// at `last_login`
+async fn seed(
+    repo: &AuthRepositoryImpl,
+    pool: &DbPool,
+    prefix: &str,
+    role: UserRole,
+    created_at: DateTime<Utc>,
+    last_login: Option<DateTime<Utc>>,
+) -> (String, Uuid) {
+    let email = unique_email(prefix);
+    let user = repo.create_user(&email, "Seeded", None, None, None, "en", role).await.unwrap();
+    let id = *user.id.as_uuid();
+    let mut conn = pool.get().await.unwrap();
+    diesel::update(users::table.filter(users::id.eq(id)))
+        .set((
+            users::is_active.eq(true),
+            users::created_at.eq(created_at),
+            users::last_login.eq(last_login),
+        ))
+        .execute(&mut conn)
+        .await
+        .unwrap();
+    (email, id)
+}
+
+#[tokio::test]
+async fn only_accounts_idle_past_the_limit_are_disabled() {
+    let db = TestDb::new().await;
+    let repo = Arc::new(AuthRepositoryImpl::new(db.pool.clone()));
+    let now = Utc::now();
+    let long_ago = now - Duration::days(400);
+    let viewer = UserRole::default();
+
+    let stale_login = seed(&repo, &db.pool, "idle_login", viewer, long_ago, Some(long_ago)).await;
+    let never_logged_in = seed(&repo, &db.pool, "idle_never", viewer, long_ago, None).await;
+    let recent_login =
+        seed(&repo, &db.pool, "idle_recent", viewer, long_ago, Some(now - Duration::days(1))).await;
+    let new_signup = seed(&repo, &db.pool, "idle_new", viewer, now - Duration::days(1), None).await;
+    let admin = seed(&repo, &db.pool, "idle_admin", UserRole::Admin, long_ago, None).await;
+
+    // One per batch, so disabling takes several transactions
+    let inactive = InactiveAccounts::new(
+        repo.clone(),
+        Arc::new(AuditRepositoryImpl::new(db.pool.clone())),
+        DAY * 365,
+        1,
+    );
+    let mut disabled = inactive.disable_stale(now).await.unwrap();
+
+    disabled.sort();
+    let mut expected = vec![stale_login.1, never_logged_in.1];
+    expected.sort();
+    assert_eq!(disabled, expected);
+    for ((email, id), active) in [
+        (&stale_login, false),
+        (&never_logged_in, false),
+        (&recent_login, true),
+        (&new_signup, true),
+        (&admin, true),
+    ] {
+        let user = repo.find_by_id(*id).await.unwrap().unwrap();
+        assert_eq!(user.is_active, active, "{}", email);
+    }
+}
+
+#[tokio::test]
+async fn warned_accounts_are_disabled_a_full_warning_period_later() {
+    let db = TestDb::new().await;
+    let repo = Arc::new(AuthRepositoryImpl::new(db.pool.clone()));
+    let now = Utc::now();
+    let long_ago = now - Duration::days(400);
+    let viewer = UserRole::default();
+
+    let stale = seed(&repo, &db.pool, "warn_stale", viewer, long_ago, Some(long_ago)).await;
+    let nearly_stale = now - Duration::days(351) - Duration::minutes(30);
+    let nearly = seed(&repo, &db.pool, "warn_nearly", viewer, long_ago, Some(nearly_stale)).await;
+    let returning = seed(&repo, &db.pool, "warn_back", viewer, long_ago, None).await;
+    let recent =
+        seed(&repo, &db.pool, "warn_recent", viewer, long_ago, Some(now - Duration::days(1))).await;
+
+    let outbox = Arc::new(Outbox::default());
+    let inactive = InactiveAccounts::new(
+        repo.clone(),
+        Arc::new(AuditRepositoryImpl::new(db.pool.clone())),
+        DAY * 365,
+        2,
+    )
+    .with_warning(outbox.clone(), DAY * 14);
+
+    assert_eq!(inactive.warn_upcoming(now).await.unwrap(), 3);
+    assert_eq!(inactive.warn_upcoming(now).await.unwrap(), 0, "warned once");
+    for (email, _) in [&stale, &nearly, &returning] {
+        assert_eq!(
+            outbox.last_inactivity_warning(email).map(|at| at.timestamp()),
+            Some((now + Duration::days(14)).timestamp()),
+            "{}",
+            email
+        );
+    }
+    assert_eq!(outbox.last_inactivity_warning(&recent.0), None);
+
+    // Even the long-idle account keeps the whole warning period
+    assert!(inactive.disable_stale(now).await.unwrap().is_empty());
+
+    // Logging in clears the warning, even if the account then goes idle again
+    repo.update_last_login(returning.1).await.unwrap();
+    let mut conn = db.pool.get().await.unwrap();
+    diesel::update(users::table.filter(users::id.eq(returning.1)))
+        .set(users::last_login.eq(Some(long_ago)))
+        .execute(&mut conn)
+        .await
+        .unwrap();
+    let mut disabled = inactive.disable_stale(now + Duration::days(14)).await.unwrap();
+
+    disabled.sort();
+    let mut expected = vec![stale.1, nearly.1];
+    expected.sort();
+    assert_eq!(disabled, expected);
+    let user = repo.find_by_id(returning.1).await.unwrap().unwrap();
+    assert!(user.is_active, "disabled without a fresh warning");
+}
@@ -0,0 +1,41 @@
+/// Data-layer tests for `InvitationRepositoryImpl` against a real database
+use crate::common::*;
+use axum_backend::{
+    domain::{entities::Invitation, repositories::InvitationRepository, value_objects::UserRole},
+    infrastructure::database::repositories::InvitationRepositoryImpl,
+};
+use chrono::{Duration, Utc};
+
+#[tokio::test]
+async fn an_invitation_can_be_claimed_once_until_released() {
+    let db = TestDb::new().await;
+    let repo = InvitationRepositoryImpl::new(db.pool.clone());
+    let invitation = Invitation::new(
+        "claim-hash".to_string(),
+        Some(UserRole::Editor),
+        None,
+        Utc::now() + Duration::hours(1),
+    );
+    repo.create(&invitation).await.unwrap();
+
+    let claimed = repo.claim("claim-hash").await.unwrap().unwrap();
+    assert_eq!(claimed.id, invitation.id);
+    assert_eq!(claimed.role, Some(UserRole::Editor));
+    assert!(claimed.used_at.is_some());
+    assert!(repo.claim("claim-hash").await.unwrap().is_none());
+
+    repo.release(invitation.id).await.unwrap();
+    assert!(repo.claim("claim-hash").await.unwrap().is_some());
+}
+
+#[tokio::test]
+async fn expired_invitations_cannot_be_claimed() {
+    let db = TestDb::new().await;
+    let repo = InvitationRepositoryImpl::new(db.pool.clone());
+    let expired =
+        Invitation::new("expired-hash".to_string(), None, None, Utc::now() - Duration::seconds(1));
+    repo.create(&expired).await.unwrap();
+
+    assert!(repo.claim("expired-hash").await.unwrap().is_none());
+    assert!(repo.claim("unknown-hash").await.unwrap().is_none());
+}
@@ -0,0 +1,96 @@
+/// Data-layer tests for `UserRepositoryImpl` against a real database
+use crate::common::*;
+use axum_backend::{
+    domain::{
+        entities::User, repositories::UserChanges, value_objects::UserId, Email, UserRepository,
+    },
+    infrastructure::database::repositories::UserRepositoryImpl,
+};
+
+fn new_user(prefix: &str) -> User {
+    User::new(Email::parse(unique_email(prefix)).unwrap(), "Repo User".to_string()).unwrap()
+}
+
+#[tokio::test]
+async fn save_then_find_by_id_and_email_round_trips_all_fields() {
+    let db = TestDb::new().await;
+    let repo = UserRepositoryImpl::new(db.pool.clone());
+    let mut user = new_user("repo_find");
+    user.verify_email();
+    user.set_password("hash".to_string());
+
+    let saved = repo.save(&user).await.unwrap();
+
+    let by_id = repo.find_by_id(saved.id).await.unwrap().unwrap();
+    let by_email = repo.find_by_email(&user.email).await.unwrap().unwrap();
+    for found in [by_id, by_email] {
+        assert_eq!(found.id, user.id);
+        assert_eq!(found.email, user.email);
+        assert_eq!(found.name, "Repo User");
+        assert_eq!(found.password_hash.as_deref(), Some("hash"));
+        assert!(found.is_active && found.is_email_verified);
+        assert_eq!(found.locale, "en");
+    }
+    assert!(repo.exists_by_email(&user.email).await.unwrap());
+}
+
+#[tokio::test]
+async fn missing_users_are_none_not_errors() {
+    let db = TestDb::new().await;
+    let repo = UserRepositoryImpl::new(db.pool.clone());
+
+    assert!(repo.find_by_id(UserId::new()).await.unwrap().is_none());
+    let email = Email::parse(unique_email("repo_missing")).unwrap();
+    assert!(repo.find_by_email(&email).await.unwrap().is_none());
+    assert!(!repo.exists_by_email(&email).await.unwrap());
+    assert!(repo.patch(UserId::new(), &UserChanges::default()).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn update_overwrites_the_stored_row() {
+    let db = TestDb::new().await;
+    let repo = UserRepositoryImpl::new(db.pool.clone());
+    let mut user = repo.save(&new_user("repo_update")).await.unwrap();
+
+    user.update_name("Renamed".to_string()).unwrap();
+    repo.update(&user).await.unwrap();
+
+    assert_eq!(repo.find_by_id(user.id).await.unwrap().unwrap().name, "Renamed");
+}
+
+#[tokio::test]
+async fn patch_writes_only_the_given_columns() {
+    let db = TestDb::new().await;
+    let repo = UserRepositoryImpl::new(db.pool.clone());
+    let user = repo.save(&new_user("repo_patch")).await.unwrap();
+
+    let changes = UserChanges { locale: Some("vi".to_string()), ..Default::default() };
+    let patched = repo.patch(user.id, &changes).await.unwrap().unwrap();
+
+    assert_eq!(patched.locale, "vi");
+    assert_eq!(patched.name, user.name);
+    assert_eq!(patched.email, user.email);
+    assert!(patched.updated_at > user.updated_at);
+
+    // An empty patch is a read and leaves updated_at alone
+    let unchanged = repo.patch(user.id, &UserChanges::default()).await.unwrap().unwrap();
+    assert_eq!(unchanged.updated_at, patched.updated_at);
+}
+
+#[tokio::test]
+async fn list_count_and_delete() {
+    let db = TestDb::new().await;
+    let repo = UserRepositoryImpl::new(db.pool.clone());
+    let first = repo.save(&new_user("repo_list_a")).await.unwrap();
+    let second = repo.save(&new_user("repo_list_b")).await.unwrap();
+
+    assert_eq!(repo.count().await.unwrap(), 2);
+    let page = repo.list_paginated(1, 0).await.unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].id, second.id, "newest first");
+
+    assert!(repo.delete(first.id).await.unwrap());
+    assert!(!repo.delete(first.id).await.unwrap(), "second delete finds nothing");
+    assert!(repo.find_by_id(first.id).await.unwrap().is_none());
+    assert_eq!(repo.count().await.unwrap(), 1);
+}
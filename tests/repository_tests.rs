@@ -0,0 +1,12 @@
+#[allow(unused_imports)]
+mod common;
+
+mod repository {
+    pub mod audit;
+    pub mod auth;
+    pub mod events;
+    pub mod import;
+    pub mod inactivity;
+    pub mod invitations;
+    pub mod users;
+}